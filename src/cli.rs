@@ -1,17 +1,43 @@
 use crate::writer::Writer;
 use clap::{Args, Parser, Subcommand};
-use inline_colorization::{color_bright_green, color_bright_red, color_reset};
+use inline_colorization::{
+    color_bright_green,
+    color_bright_red,
+    color_bright_yellow,
+    color_reset,
+};
 mod td_table;
 use std::path::Path;
+use std::time::{Duration, Instant};
 use td_table::TdTable;
 use tendrils_core::{
     ActionLog,
+    AutoProfileLog,
+    CheckFinding,
+    ConditionLog,
+    ConfigIssue,
+    ConfigIssueKind,
+    Diagnosis,
+    ConfigFormat,
+    ConfigType,
+    CopyMethod,
+    CopyProgress,
+    FsoType,
+    GetConfigError,
+    GetTendrilsRepoError,
+    HostCondition,
     InvalidTendrilError,
     ListLog,
+    ParseErrorLocation,
+    RepoReports,
+    SetupError,
+    Severity,
     TendrilActionError,
     TendrilActionSuccess,
     TendrilLog,
+    TendrilMode,
     TendrilReport,
+    UniPath,
 };
 
 /// A CLI tool for managing tendrils
@@ -30,6 +56,57 @@ pub(crate) enum TendrilsSubcommands {
         about_subcommand: AboutSubcommands,
     },
 
+    /// Checks the config files for unknown keys and other likely mistakes,
+    /// and checks the tendrils for duplicate remotes, and nested locals or
+    /// remotes, that would make the outcome of the actions depend on their
+    /// order
+    Check {
+        #[clap(flatten)]
+        path_args: PathArgs,
+
+        #[clap(flatten)]
+        filter_args: FilterArgs,
+
+        /// Treats warnings as errors
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Manages the config file of the Tendrils repo
+    Config {
+        #[command(subcommand)]
+        config_subcommand: ConfigSubcommands,
+    },
+
+    /// Checks the environment and the Tendrils repo for problems such as
+    /// invalid config files, unset variables, broken symlinks and folders
+    /// that cannot be written to, and suggests how to fix them
+    Doctor {
+        #[clap(flatten)]
+        path_args: PathArgs,
+    },
+
+    /// Rewrites the config file of the Tendrils repo in a canonical form
+    Fmt {
+        #[clap(flatten)]
+        path_args: PathArgs,
+
+        /// Only checks whether the file is formatted, without changing it.
+        /// Returns a non-zero exit code if it is not
+        #[arg(long)]
+        check: bool,
+
+        /// Sorts the tendrils, automatic profiles, profile groups and
+        /// variables by name
+        #[arg(long)]
+        sort_keys: bool,
+
+        /// Allows the comments in a JSONC config file to be removed when it
+        /// is rewritten. Comments in TOML config files are always kept
+        #[arg(long)]
+        drop_comments: bool,
+    },
+
     /// Initializes a new Tendrils repo in the current directory
     Init {
         /// Ignores errors due to a non-empty folder
@@ -70,6 +147,16 @@ pub(crate) enum TendrilsSubcommands {
         filter_args: FilterArgs,
     },
 
+    /// Replaces the symlinks at their various locations that do not point to
+    /// the tendrils in the Tendrils repo, such as after the repo is moved
+    Relink {
+        #[clap(flatten)]
+        action_args: ActionArgs,
+
+        #[clap(flatten)]
+        filter_args: FilterArgs,
+    },
+
     /// Lists extended info about the tendrils
     List {
         #[clap(flatten)]
@@ -91,8 +178,25 @@ pub(crate) enum TendrilsSubcommands {
     /// Gets the default Tendrils repo path if it is defined
     Path,
 
-    /// Gets the default Tendrils profiles if they are defined
-    Profiles,
+    /// Gets the default Tendrils profiles if they are defined, and shows
+    /// which automatic profiles are active on this host
+    Profiles {
+        #[clap(flatten)]
+        path_args: PathArgs,
+    },
+
+    /// Shows which Tendrils repo the other commands will use, and why
+    Repo {
+        #[clap(flatten)]
+        path_args: PathArgs,
+    },
+
+    /// Prints the JSON Schema for a config file, for editor autocompletion
+    /// and validation
+    Schema {
+        #[command(subcommand)]
+        schema_subcommand: SchemaSubcommands,
+    },
 }
 
 #[derive(Subcommand, Clone, Debug, Eq, PartialEq)]
@@ -104,6 +208,52 @@ pub(crate) enum AboutSubcommands {
     Acknowledgements,
 }
 
+#[derive(Subcommand, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ConfigSubcommands {
+    /// Converts the repo config file to another format. Unknown keys are
+    /// not carried over, and included files are left as-is
+    Convert {
+        /// The format to convert to
+        /// [possible values: json, jsonc, toml]
+        #[arg(value_parser = parse_config_format)]
+        format: ConfigFormat,
+
+        #[clap(flatten)]
+        path_args: PathArgs,
+
+        /// Allows the comments in a JSONC or TOML config file to be removed
+        /// when it is converted
+        #[arg(long)]
+        drop_comments: bool,
+    },
+
+    /// Rewrites the repo config file in the latest layout and sets its
+    /// version. Unknown keys are not carried over, and included files are
+    /// left as-is
+    Migrate {
+        /// Migrates the global-config.json file instead
+        #[arg(long, conflicts_with_all = ["path", "no_discovery", "drop_comments"])]
+        global: bool,
+
+        #[clap(flatten)]
+        path_args: PathArgs,
+
+        /// Allows the comments in a JSONC or TOML config file to be removed
+        /// when it is rewritten
+        #[arg(long)]
+        drop_comments: bool,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum SchemaSubcommands {
+    /// Print the schema for the tendrils.json file and its included files
+    Repo,
+
+    /// Print the schema for the global-config.json file
+    Global,
+}
+
 #[derive(Args, Clone, Debug, Eq, PartialEq)]
 pub(crate) struct ActionArgs {
     #[clap(flatten)]
@@ -117,13 +267,41 @@ pub(crate) struct ActionArgs {
     /// Ignores type mismatches and forces the operation
     #[arg(short, long)]
     pub force: bool,
+
+    /// Stops before performing any actions if the check command finds any
+    /// problems, including warnings
+    #[arg(long)]
+    pub strict: bool,
 }
 
 #[derive(Args, Clone, Debug, Eq, PartialEq)]
 pub(crate) struct PathArgs {
-    /// Explicitly sets the path to the Tendrils repo
+    /// Explicitly sets the path to the Tendrils repo. Several paths can be
+    /// given to use each of them
+    #[arg(long, num_args = 1..)]
+    pub path: Vec<String>,
+
+    /// Only checks the current directory for a Tendrils repo, instead of
+    /// also searching its parent folders, before using the default repo
     #[arg(long)]
-    pub path: Option<String>,
+    pub no_discovery: bool,
+}
+
+/// Indicates why a particular Tendrils repo is used
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum RepoSource {
+    /// The repo was explicitly given with `--path`
+    Given,
+
+    /// The current directory is a Tendrils repo
+    CurrentDir,
+
+    /// The repo is a parent folder of the current directory, `cd`
+    ParentDir { cd: UniPath },
+
+    /// No repo was found in the current directory (or in any of its parent
+    /// folders, if they were searched), so the default repo is used
+    Default { searched_parents: bool },
 }
 
 #[derive(Args, Clone, Debug, Eq, PartialEq)]
@@ -139,10 +317,174 @@ pub(crate) struct FilterArgs {
     /// Explicitly sets the list of profiles to filter for. Globs accepted.
     #[arg(short, long, num_args = ..)]
     pub profiles: Option<Vec<String>>,
+
+    /// List of locals to exclude. Globs accepted.
+    #[arg(long, num_args = ..)]
+    pub exclude_locals: Vec<String>,
+
+    /// List of remotes to exclude. Globs accepted.
+    #[arg(long, num_args = ..)]
+    pub exclude_remotes: Vec<String>,
+
+    /// List of profiles to exclude. Globs accepted.
+    #[arg(long, num_args = ..)]
+    pub exclude_profiles: Vec<String>,
+
+    /// List of tendril modes to filter for
+    /// [possible values: dir-merge, dir-overwrite, link, hardlink]
+    #[arg(long, num_args = .., value_parser = parse_tendril_mode)]
+    pub modes: Vec<TendrilMode>,
+
+    /// List of file system object types to filter for
+    /// [possible values: file, dir, symfile, symdir, broken]
+    #[arg(long, num_args = .., value_parser = parse_fso_type)]
+    pub types: Vec<FsoType>,
+}
+
+fn parse_tendril_mode(value: &str) -> Result<TendrilMode, String> {
+    match value {
+        "dir-merge" => Ok(TendrilMode::DirMerge),
+        "dir-overwrite" => Ok(TendrilMode::DirOverwrite),
+        "link" => Ok(TendrilMode::Link),
+        "hardlink" => Ok(TendrilMode::Hardlink),
+        _ => Err(String::from(
+            "Expected one of: dir-merge, dir-overwrite, link, hardlink"
+        )),
+    }
+}
+
+fn parse_config_format(value: &str) -> Result<ConfigFormat, String> {
+    match value {
+        "json" => Ok(ConfigFormat::Json),
+        "jsonc" => Ok(ConfigFormat::Jsonc),
+        "toml" => Ok(ConfigFormat::Toml),
+        _ => Err(String::from("Expected one of: json, jsonc, toml")),
+    }
+}
+
+fn parse_fso_type(value: &str) -> Result<FsoType, String> {
+    match value {
+        "file" => Ok(FsoType::File),
+        "dir" => Ok(FsoType::Dir),
+        "symfile" => Ok(FsoType::SymFile),
+        "symdir" => Ok(FsoType::SymDir),
+        "broken" => Ok(FsoType::BrokenSym),
+        _ => Err(String::from(
+            "Expected one of: file, dir, symfile, symdir, broken"
+        )),
+    }
 }
 
 pub(crate) const CLEAR_LINE: &str = "r\x1b[2K\x1b[0G";
 
+/// The number of characters between the brackets of a [`ProgressBar`].
+const PROGRESS_BAR_WIDTH: usize = 20;
+
+/// The minimum time between redraws of a [`ProgressBar`], unless it is
+/// complete.
+const PROGRESS_REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Tracks the [`CopyProgress`] of the tendril that is being processed, and
+/// renders it after the `label`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct ProgressBar {
+    label: String,
+    total_files: u64,
+    total_bytes: u64,
+    files: u64,
+    copied: u64,
+    start: Option<Instant>,
+    last_drawn: Option<Instant>,
+}
+
+impl ProgressBar {
+    pub(crate) fn new(label: String) -> ProgressBar {
+        ProgressBar { label, ..Default::default() }
+    }
+
+    /// Applies the `progress` that was received at `now`. Returns the line
+    /// to display, or `None` if it was drawn too recently to be redrawn.
+    pub(crate) fn update(
+        &mut self,
+        progress: CopyProgress,
+        now: Instant,
+    ) -> Option<String> {
+        match progress {
+            CopyProgress::Start { files, bytes } => {
+                *self = ProgressBar {
+                    label: std::mem::take(&mut self.label),
+                    total_files: files,
+                    total_bytes: bytes,
+                    start: Some(now),
+                    ..Default::default()
+                };
+            }
+            CopyProgress::File { .. } => self.files += 1,
+            CopyProgress::Bytes { copied } => self.copied = copied,
+        }
+
+        let complete = self.files >= self.total_files
+            && self.copied >= self.total_bytes;
+        if let Some(last_drawn) = self.last_drawn {
+            if !complete
+                && now.duration_since(last_drawn) < PROGRESS_REDRAW_INTERVAL {
+                return None;
+            }
+        }
+        self.last_drawn = Some(now);
+        Some(self.line(now))
+    }
+
+    fn line(&self, now: Instant) -> String {
+        let ratio = if self.total_bytes == 0 {
+            1.0
+        }
+        else {
+            (self.copied as f64 / self.total_bytes as f64).min(1.0)
+        };
+        let filled = (ratio * PROGRESS_BAR_WIDTH as f64) as usize;
+        let elapsed = self
+            .start
+            .map_or(0.0, |start| now.duration_since(start).as_secs_f64());
+        let throughput = if elapsed > 0.0 {
+            let rate = (self.copied as f64 / elapsed) as u64;
+            format!(", {}/s", bytes_text(rate))
+        }
+        else {
+            String::new()
+        };
+
+        format!(
+            "{} [{}{}] {}% {}/{} ({}/{} files{throughput})",
+            self.label,
+            "#".repeat(filled),
+            "-".repeat(PROGRESS_BAR_WIDTH - filled),
+            (ratio * 100.0) as u64,
+            bytes_text(self.copied),
+            bytes_text(self.total_bytes),
+            self.files,
+            self.total_files,
+        )
+    }
+}
+
+/// Returns the `bytes` in the largest binary unit (KiB, MiB, etc.) in which
+/// they are at least 1.
+pub(crate) fn bytes_text(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
 // Note: For ansi styling to render properly with 'tabled' tables,
 // its 'ansi' feature must be enabled
 pub(crate) fn ansi_style(
@@ -170,7 +512,7 @@ fn ansi_styled_resolved_path(
             ansi_hyperlink(&raw_path_text, &raw_path_text)
         }
         Err(e) => ansi_style(
-            &format!("{:?}", e),
+            &e.to_string(),
             color_bright_red.to_owned(),
             color_reset,
         ),
@@ -179,10 +521,14 @@ fn ansi_styled_resolved_path(
 
 fn ansi_styled_result(
     result: &Result<TendrilActionSuccess, TendrilActionError>,
+    copy_method: Option<CopyMethod>,
 ) -> String {
     match result {
         Ok(r) => {
-            let text = r.to_string();
+            let text = match copy_method {
+                Some(m) => format!("{} ({})", r.to_string(), m.to_string()),
+                None => r.to_string(),
+            };
             ansi_style(&text, color_bright_green.to_owned(), color_reset)
         }
         Err(e) => {
@@ -211,7 +557,7 @@ pub(crate) fn print_action_reports(
         let (styled_path, styled_result) = match &report.log {
             Ok(log) => (
                 ansi_styled_resolved_path(Ok(&log.resolved_path())),
-                ansi_styled_result(&log.result),
+                ansi_styled_result(&log.result, log.copy_method()),
             ),
             Err(e) => (
                 // Print the resolving error in the result column
@@ -227,10 +573,70 @@ pub(crate) fn print_action_reports(
         ]);
     }
     writer.writeln(&tbl.draw());
+    print_outside_symlinks(reports, writer);
 
     print_totals(reports, writer);
 }
 
+/// Warns about any symlinks inside of the copied folders that point outside
+/// of them.
+fn print_outside_symlinks(
+    reports: &[TendrilReport<ActionLog>],
+    writer: &mut impl Writer,
+) {
+    for report in reports {
+        let Ok(log) = &report.log else {
+            continue;
+        };
+        for link in log.outside_symlinks() {
+            writer.writeln(&format!(
+                "{}: The symlink \"{}\" points outside of \"{}\"",
+                severity_prefix(&Severity::Warning),
+                link.to_string_lossy(),
+                report.raw_tendril.local,
+            ));
+        }
+    }
+}
+
+/// Prints the path to the Tendrils repo that the subsequent output belongs to,
+/// when operating on several repos at once. Any repo after the `first` is
+/// separated by a blank line.
+pub(crate) fn print_repo_header(
+    td_repo: &UniPath,
+    first: bool,
+    writer: &mut impl Writer,
+) {
+    if !first {
+        writer.writeln("");
+    }
+    let path_text = td_repo.inner().to_string_lossy();
+    writer.writeln(&format!(
+        "Tendrils repo: {}",
+        ansi_hyperlink(&path_text, &path_text),
+    ));
+}
+
+pub(crate) fn print_multi_action_reports(
+    repo_reports: &[RepoReports<ActionLog>],
+    writer: &mut impl Writer,
+) {
+    for (i, repo) in repo_reports.iter().enumerate() {
+        print_repo_header(&repo.td_repo, i == 0, writer);
+        print_action_reports(&repo.reports, writer);
+    }
+}
+
+pub(crate) fn print_multi_list_reports(
+    repo_reports: Vec<RepoReports<ListLog>>,
+    writer: &mut impl Writer,
+) {
+    for (i, repo) in repo_reports.into_iter().enumerate() {
+        print_repo_header(&repo.td_repo, i == 0, writer);
+        print_list_reports(repo.reports, writer);
+    }
+}
+
 pub(crate) fn print_list_reports(
     reports: Vec<TendrilReport<ListLog>>,
     writer: &mut impl Writer,
@@ -239,17 +645,34 @@ pub(crate) fn print_list_reports(
         return;
     }
 
-    let mut tbl = TdTable::new();
-    tbl.set_header(&[
+    // Only show the source column if any tendrils are defined outside of the
+    // tendrils.json file
+    let show_source = reports.iter().any(|r| r.raw_tendril.source.is_some());
+    let mut header = vec![
         String::from("Local"),
         String::from("Remote"),
         String::from("Mode"),
         String::from("Profiles"),
-    ]);
+    ];
+    if show_source {
+        header.push(String::from("Source"));
+    }
+
+    let mut tbl = TdTable::new();
+    tbl.set_header(&header);
 
     let total = reports.len();
     for report in reports {
         let styled_path = match &report.log {
+            Ok(log) if !report.raw_tendril.fallbacks.is_empty() => {
+                // Indicate which of the alternatives was chosen
+                let total_alts = report.raw_tendril.fallbacks.len() + 1;
+                format!(
+                    "{} (alternative {}/{total_alts})",
+                    ansi_styled_resolved_path(Ok(log.resolved_path())),
+                    log.remote_idx() + 1,
+                )
+            }
             Ok(log) => {
                 ansi_styled_resolved_path(Ok(log.resolved_path()))
             }
@@ -260,18 +683,212 @@ pub(crate) fn print_list_reports(
 
         let profiles_str = report.raw_tendril.profiles.join(", ");
 
-        tbl.push_row(&[
+        let mut row = vec![
             report.raw_tendril.local.clone(),
             styled_path,
             report.raw_tendril.mode.to_string(),
             profiles_str,
-        ]);
+        ];
+        if show_source {
+            let source = match &report.raw_tendril.source {
+                Some(v) => ConfigType::RepoInclude(v.clone()),
+                None => ConfigType::Repo(ConfigFormat::Json),
+            };
+            row.push(source.file_name());
+        }
+        tbl.push_row(&row);
     }
 
     writer.writeln(&tbl.draw());
     writer.writeln(&format!("Total: {total}"));
 }
 
+pub(crate) fn print_auto_profiles(
+    logs: &[AutoProfileLog],
+    writer: &mut impl Writer,
+) {
+    if logs.is_empty() {
+        return;
+    }
+
+    writer.writeln("Automatic profiles:");
+    for log in logs {
+        let status = if log.is_match() {
+            ansi_style("active", color_bright_green.to_owned(), color_reset)
+        }
+        else {
+            ansi_style("inactive", color_bright_red.to_owned(), color_reset)
+        };
+        writer.writeln(&format!(
+            "  {}: {status} ({})",
+            log.profile,
+            log.cfg_type.file_name(),
+        ));
+
+        if log.conditions.is_empty() {
+            writer.writeln("    (no conditions)");
+        }
+        for condition in log.conditions.iter() {
+            writer.writeln(&format!("    {}", condition_text(condition)));
+        }
+    }
+}
+
+/// Prints each config issue and then each finding prefixed by its severity,
+/// followed by a summary of the number of problems.
+pub(crate) fn print_check_findings(
+    issues: &[ConfigIssue],
+    findings: &[CheckFinding],
+    writer: &mut impl Writer,
+) {
+    if issues.is_empty() && findings.is_empty() {
+        writer.writeln("No conflicts found");
+        return;
+    }
+
+    for issue in issues {
+        let prefix = severity_prefix(&issue.severity);
+        writer.writeln(&format!("{prefix}: {}", issue.to_string()));
+    }
+    for finding in findings {
+        let prefix = severity_prefix(&finding.severity);
+        writer.writeln(&format!("{prefix}: {}", finding.to_string()));
+    }
+    let severities = issues
+        .iter()
+        .map(|i| &i.severity)
+        .chain(findings.iter().map(|f| &f.severity));
+    print_severity_totals(severities, writer);
+}
+
+/// Prints each unknown key in the `issues` to stderr. These are most likely
+/// typos that would otherwise be silently ignored.
+pub(crate) fn print_unknown_key_warnings(
+    issues: &[ConfigIssue],
+    writer: &mut impl Writer,
+) {
+    for issue in issues {
+        if let ConfigIssueKind::UnknownKey { .. } = issue.kind {
+            let prefix = severity_prefix(&issue.severity);
+            writer.ewrite(&format!("{prefix}: {}\n", issue.to_string()));
+        }
+    }
+}
+
+pub(crate) fn print_diagnoses(diagnoses: &[Diagnosis], writer: &mut impl Writer) {
+    if diagnoses.is_empty() {
+        writer.writeln("No problems found");
+        return;
+    }
+
+    for diagnosis in diagnoses {
+        let prefix = severity_prefix(&diagnosis.severity());
+        writer.writeln(&format!("{prefix}: {}", diagnosis.to_string()));
+        if let Diagnosis::GlobalConfig(GetConfigError::ParseError { loc: Some(loc), .. })
+            | Diagnosis::RepoConfig(GetConfigError::ParseError { loc: Some(loc), .. })
+            | Diagnosis::NoValidTendrilsRepo(GetTendrilsRepoError::ConfigError(
+                GetConfigError::ParseError { loc: Some(loc), .. },
+            )) = diagnosis
+        {
+            writer.writeln(&parse_err_snippet(loc));
+        }
+        writer.writeln(&format!("    Fix: {}", diagnosis.fix()));
+    }
+    let severities: Vec<Severity> =
+        diagnoses.iter().map(|d| d.severity()).collect();
+    print_severity_totals(severities.iter(), writer);
+}
+
+/// Returns the error text. Parse errors with a known location are followed
+/// by a snippet of the offending line with a caret under the error column.
+pub(crate) fn config_err_text(err: &GetConfigError) -> String {
+    match err {
+        GetConfigError::ParseError { loc: Some(loc), .. } => {
+            format!("{}\n{}", err.to_string(), parse_err_snippet(loc))
+        }
+        _ => err.to_string(),
+    }
+}
+
+/// Same as [`config_err_text`] for any config error wrapped in the
+/// [`SetupError`].
+pub(crate) fn setup_err_text(err: &SetupError) -> String {
+    match err {
+        SetupError::ConfigError(e)
+        | SetupError::NoValidTendrilsRepo(GetTendrilsRepoError::ConfigError(e)) => {
+            config_err_text(e)
+        }
+        _ => err.to_string(),
+    }
+}
+
+fn parse_err_snippet(loc: &ParseErrorLocation) -> String {
+    let line_num = loc.line.to_string();
+    let gutter = " ".repeat(line_num.len());
+
+    // Tabs are kept so that the caret lines up regardless of the tab width
+    let padding: String = loc
+        .line_text
+        .chars()
+        .chain(std::iter::repeat(' '))
+        .take(loc.column.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let caret = ansi_style("^", color_bright_red.to_owned(), color_reset);
+    format!(
+        "{gutter} |\n{line_num} | {}\n{gutter} | {padding}{caret}",
+        loc.line_text,
+    )
+}
+
+fn severity_prefix(severity: &Severity) -> String {
+    match severity {
+        Severity::Error => {
+            ansi_style("Error", color_bright_red.to_owned(), color_reset)
+        }
+        Severity::Warning => {
+            ansi_style("Warning", color_bright_yellow.to_owned(), color_reset)
+        }
+    }
+}
+
+fn print_severity_totals<'a>(
+    severities: impl Iterator<Item = &'a Severity>,
+    writer: &mut impl Writer,
+) {
+    let (mut err_count, mut warn_count) = (0, 0);
+    for severity in severities {
+        match severity {
+            Severity::Error => err_count += 1,
+            Severity::Warning => warn_count += 1,
+        }
+    }
+    writer.writeln(&format!(
+        "\n{err_count} {}, {warn_count} {}",
+        if err_count == 1 { "error" } else { "errors" },
+        if warn_count == 1 { "warning" } else { "warnings" },
+    ));
+}
+
+fn condition_text(log: &ConditionLog) -> String {
+    let condition = log.condition.to_string();
+    match (&log.condition, log.matched) {
+        (HostCondition::EnvVar(_), true) => format!("{condition} is set"),
+        (HostCondition::EnvVar(_), false) => format!("{condition} is not set"),
+        (HostCondition::PathExists(_), true) => format!("{condition} exists"),
+        (HostCondition::PathExists(_), false) => {
+            format!("{condition} does not exist")
+        }
+        (_, matched) => {
+            let verb = if matched { "matches" } else { "does not match" };
+            match &log.actual {
+                Some(v) => format!("{condition} {verb} \"{v}\""),
+                None => format!("{condition} {verb} (unknown)"),
+            }
+        }
+    }
+}
+
 fn print_empty_reports_warning<T>(
     reports: &[TendrilReport<T>],
     writer: &mut impl Writer,
@@ -313,3 +930,25 @@ fn print_totals(
         ),
     ));
 }
+
+/// Describes why a Tendrils repo was selected
+pub(crate) fn repo_source_text(source: &RepoSource) -> String {
+    match source {
+        RepoSource::Given => String::from("Given by the --path argument"),
+        RepoSource::CurrentDir => {
+            String::from("The current directory is a Tendrils repo")
+        }
+        RepoSource::ParentDir { cd } => format!(
+            "Found in a parent folder of the current directory \"{}\"",
+            cd.inner().to_string_lossy(),
+        ),
+        RepoSource::Default { searched_parents: true } => String::from(
+            "Using the default repo, as neither the current directory nor \
+            any of its parent folders are a Tendrils repo",
+        ),
+        RepoSource::Default { searched_parents: false } => String::from(
+            "Using the default repo, as the current directory is not a \
+            Tendrils repo (searching parent folders is disabled)",
+        ),
+    }
+}
@@ -6,25 +6,49 @@ use clap::Parser;
 mod cli;
 use cli::{
     ansi_hyperlink,
+    config_err_text,
     print_action_reports,
+    print_auto_profiles,
+    print_check_findings,
+    print_diagnoses,
     print_list_reports,
+    print_multi_action_reports,
+    print_multi_list_reports,
+    print_repo_header,
+    print_unknown_key_warnings,
+    repo_source_text,
+    setup_err_text,
     AboutSubcommands,
     ActionArgs,
+    ConfigSubcommands,
     FilterArgs,
     PathArgs,
+    ProgressBar,
+    RepoSource,
+    SchemaSubcommands,
     TendrilCliArgs,
     TendrilsSubcommands,
 };
 use std::path::Path;
 use tendrils_core::{
+    config_schema,
+    CONFIG_VERSION,
     ActionLog,
     ActionMode,
     CallbackUpdater,
+    CheckFinding,
+    ConfigIssue,
+    ConfigFormat,
+    ConfigType,
+    CopyProgress,
     FilterSpec,
     GetConfigError,
+    GetTendrilsRepoError,
     InitError,
     RawTendril,
     SetupError,
+    Severity,
+    TendrilReport,
     TendrilsActor,
     TendrilsApi,
     UniPath,
@@ -58,11 +82,35 @@ fn run(
             about(about_subcommand, writer);
             Ok(())
         }
+        TendrilsSubcommands::Check { path_args, filter_args, strict } => {
+            check(path_args, filter_args, strict, api, writer)
+        }
+        TendrilsSubcommands::Doctor { path_args } => {
+            doctor(path_args, api, writer)
+        }
+        TendrilsSubcommands::Fmt {
+            path_args,
+            check,
+            sort_keys,
+            drop_comments,
+        } => fmt(path_args, check, sort_keys, drop_comments, api, writer),
         TendrilsSubcommands::Init { path, force } => {
             init(path, force, api, writer)
         }
         TendrilsSubcommands::Path => path(api, writer),
-        TendrilsSubcommands::Profiles => profiles(api, writer),
+        TendrilsSubcommands::Profiles { path_args } => {
+            profiles(path_args, api, writer)
+        }
+        TendrilsSubcommands::Repo { path_args } => {
+            repo(path_args, api, writer)
+        }
+        TendrilsSubcommands::Config { config_subcommand } => {
+            config(config_subcommand, api, writer)
+        }
+        TendrilsSubcommands::Schema { schema_subcommand } => {
+            schema(schema_subcommand, writer);
+            Ok(())
+        }
         TendrilsSubcommands::Pull { action_args, filter_args } => {
             tendril_action_subcommand(
                 ActionMode::Pull,
@@ -102,6 +150,15 @@ fn run(
                 writer,
             )
         }
+        TendrilsSubcommands::Relink { action_args, filter_args } => {
+            tendril_action_subcommand(
+                ActionMode::Relink,
+                action_args,
+                filter_args,
+                api,
+                writer,
+            )
+        }
     }
 }
 
@@ -119,6 +176,306 @@ fn about(about_subcommand: AboutSubcommands, writer: &mut impl Writer) {
 
 /// Returns, but does not set, the suggested exit code in case of error.
 /// It is up to the calling function to handle exiting with this code.
+fn check(
+    path_args: PathArgs,
+    filter_args: FilterArgs,
+    strict: bool,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    let td_repos = repos_or_defaults(get_td_repos(path_args, api, writer)?, api);
+
+    let is_multi = td_repos.len() > 1;
+    let mut has_errors = false;
+    for (i, td_repo) in td_repos.iter().enumerate() {
+        if let (true, Some(p)) = (is_multi, td_repo) {
+            print_repo_header(p, i == 0, writer);
+        }
+
+        let filter = filter_args.clone().to_spec(None);
+        match check_repo(td_repo.as_ref(), filter, api) {
+            Ok((issues, findings)) => {
+                has_errors |= issues
+                    .iter()
+                    .map(|i| &i.severity)
+                    .chain(findings.iter().map(|f| &f.severity))
+                    .any(|s| strict || *s == Severity::Error);
+                print_check_findings(&issues, &findings, writer);
+            }
+            Err(e) => {
+                writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
+                return Err(setup_err_to_exit_code(e));
+            }
+        }
+    }
+
+    if has_errors {
+        return Err(exitcode::DATAERR);
+    }
+    Ok(())
+}
+
+/// Returns the config issues and the conflicts between the tendrils that
+/// match the `filter`.
+fn check_repo(
+    td_repo: Option<&UniPath>,
+    filter: FilterSpec,
+    api: &impl TendrilsApi,
+) -> Result<(Vec<ConfigIssue>, Vec<CheckFinding>), SetupError> {
+    let findings = api.check_tendrils(td_repo, filter)?;
+    let issues = api.validate_config(td_repo)?;
+    Ok((issues, findings))
+}
+
+/// Prints any problems the check command finds in the `td_repos`, and returns
+/// an exit code if there are any, including warnings.
+fn strict_check(
+    td_repos: &[UniPath],
+    filter: &FilterSpec,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    let td_repos = repos_or_defaults(td_repos.to_vec(), api);
+    let is_multi = td_repos.len() > 1;
+    let mut has_problems = false;
+    for td_repo in td_repos.iter() {
+        let (issues, findings) =
+            match check_repo(td_repo.as_ref(), filter.clone(), api) {
+                Ok(v) => v,
+                Err(e) => {
+                    writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
+                    return Err(setup_err_to_exit_code(e));
+                }
+            };
+        if issues.is_empty() && findings.is_empty() {
+            continue;
+        }
+
+        if let (true, Some(p)) = (is_multi, td_repo) {
+            print_repo_header(p, !has_problems, writer);
+        }
+        has_problems = true;
+        print_check_findings(&issues, &findings, writer);
+    }
+
+    if has_problems {
+        writer.writeln(&format!(
+            "{ERR_PREFIX}: No actions were performed because of the problems \
+            above (strict mode)"
+        ));
+        return Err(exitcode::DATAERR);
+    }
+    Ok(())
+}
+
+/// Prints any unknown keys in the config files of the `td_repos` to stderr.
+/// Any errors are left to be reported by the command itself.
+fn warn_unknown_keys(
+    td_repos: &[UniPath],
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) {
+    for td_repo in repos_or_defaults(td_repos.to_vec(), api).iter() {
+        if let Ok(issues) = api.validate_config(td_repo.as_ref()) {
+            print_unknown_key_warnings(&issues, writer);
+        }
+    }
+}
+
+/// Returns, but does not set, the suggested exit code in case of error.
+/// It is up to the calling function to handle exiting with this code.
+fn config(
+    config_subcommand: ConfigSubcommands,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    match config_subcommand {
+        ConfigSubcommands::Convert { format, path_args, drop_comments } => {
+            convert_config(format, path_args, drop_comments, api, writer)
+        }
+        ConfigSubcommands::Migrate { global: true, .. } => {
+            migrate_global_config(api, writer)
+        }
+        ConfigSubcommands::Migrate { global: false, path_args, drop_comments } => {
+            migrate_config(path_args, drop_comments, api, writer)
+        }
+    }
+}
+
+fn convert_config(
+    format: ConfigFormat,
+    path_args: PathArgs,
+    drop_comments: bool,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    let td_repos = repos_or_defaults(get_td_repos(path_args, api, writer)?, api);
+
+    let is_multi = td_repos.len() > 1;
+    for (i, td_repo) in td_repos.iter().enumerate() {
+        if let (true, Some(p)) = (is_multi, td_repo) {
+            print_repo_header(p, i == 0, writer);
+        }
+
+        let to_file = ConfigType::Repo(format).file_name();
+        match api.convert_config(td_repo.as_ref(), format, drop_comments) {
+            Ok(from) if from == format => {
+                writer.writeln(&format!("Already using {to_file}"));
+            }
+            Ok(from) => writer.writeln(&format!(
+                "Converted {} to {to_file}",
+                ConfigType::Repo(from).file_name(),
+            )),
+            Err(e) => {
+                writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
+                if let SetupError::ConfigError(
+                    GetConfigError::CommentsWouldBeLost { .. },
+                ) = e
+                {
+                    writer.writeln(
+                        "Use --drop-comments to convert it without its comments",
+                    );
+                }
+                return Err(setup_err_to_exit_code(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn migrate_config(
+    path_args: PathArgs,
+    drop_comments: bool,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    let td_repos = repos_or_defaults(get_td_repos(path_args, api, writer)?, api);
+
+    let is_multi = td_repos.len() > 1;
+    for (i, td_repo) in td_repos.iter().enumerate() {
+        if let (true, Some(p)) = (is_multi, td_repo) {
+            print_repo_header(p, i == 0, writer);
+        }
+
+        match api.migrate_config(td_repo.as_ref(), drop_comments) {
+            Ok(from) => writer.writeln(&migrate_text("config file", from)),
+            Err(e) => {
+                writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
+                if let SetupError::ConfigError(
+                    GetConfigError::CommentsWouldBeLost { .. },
+                ) = e
+                {
+                    writer.writeln(
+                        "Use --drop-comments to migrate it without its comments",
+                    );
+                }
+                return Err(setup_err_to_exit_code(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn migrate_global_config(
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    match api.migrate_global_config() {
+        Ok(from) => {
+            writer.writeln(&migrate_text("global config file", from));
+            Ok(())
+        }
+        Err(e) => {
+            writer.writeln(&format!("{ERR_PREFIX}: {}", config_err_text(&e)));
+            Err(setup_err_to_exit_code(SetupError::ConfigError(e)))
+        }
+    }
+}
+
+/// Describes the outcome of migrating the `file` from the given version.
+fn migrate_text(file: &str, from: u32) -> String {
+    if from == CONFIG_VERSION {
+        format!("The {file} is already at version {CONFIG_VERSION}")
+    }
+    else {
+        format!("Migrated the {file} from version {from} to {CONFIG_VERSION}")
+    }
+}
+
+fn doctor(
+    path_args: PathArgs,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    let td_repos = repos_or_defaults(get_td_repos(path_args, api, writer)?, api);
+
+    let is_multi = td_repos.len() > 1;
+    let mut has_errors = false;
+    for (i, td_repo) in td_repos.iter().enumerate() {
+        if let (true, Some(p)) = (is_multi, td_repo) {
+            print_repo_header(p, i == 0, writer);
+        }
+
+        let diagnoses = api.diagnose(td_repo.as_ref());
+        has_errors |= diagnoses.iter().any(|d| d.severity() == Severity::Error);
+        print_diagnoses(&diagnoses, writer);
+    }
+
+    if has_errors {
+        return Err(exitcode::DATAERR);
+    }
+    Ok(())
+}
+
+/// Returns, but does not set, the suggested exit code in case of error.
+/// It is up to the calling function to handle exiting with this code.
+fn fmt(
+    path_args: PathArgs,
+    check: bool,
+    sort_keys: bool,
+    drop_comments: bool,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    let td_repos = repos_or_defaults(get_td_repos(path_args, api, writer)?, api);
+
+    let is_multi = td_repos.len() > 1;
+    let mut has_unformatted = false;
+    for (i, td_repo) in td_repos.iter().enumerate() {
+        if let (true, Some(p)) = (is_multi, td_repo) {
+            print_repo_header(p, i == 0, writer);
+        }
+
+        match api.fmt_config(td_repo.as_ref(), sort_keys, check, drop_comments) {
+            Ok(false) => writer.writeln("The config file is already formatted"),
+            Ok(true) if check => {
+                has_unformatted = true;
+                writer.writeln("The config file is not formatted");
+            }
+            Ok(true) => writer.writeln("Formatted the config file"),
+            Err(e) => {
+                writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
+                if let SetupError::ConfigError(
+                    GetConfigError::CommentsWouldBeLost { .. },
+                ) = e
+                {
+                    writer.writeln(
+                        "Use --drop-comments to format it without its comments",
+                    );
+                }
+                return Err(setup_err_to_exit_code(e));
+            }
+        }
+    }
+
+    if has_unformatted {
+        return Err(exitcode::DATAERR);
+    }
+    Ok(())
+}
+
 fn init(
     path: Option<String>,
     force: bool,
@@ -175,14 +532,28 @@ fn list_tendrils_subcommand(
     api: &impl TendrilsApi,
     writer: &mut impl Writer,
 ) -> Result<(), i32> {
-    let td_repo = get_td_repo(path_args, api, writer)?;
+    let td_repos = get_td_repos(path_args, api, writer)?;
     let filter = filter_args.to_spec(None);
-    let list_result = api.list_tendrils(td_repo.as_ref(), filter);
+    warn_unknown_keys(&td_repos, api, writer);
+    if is_multi_repo(&td_repos, api) {
+        return match api.list_tendrils_multi(&td_repos, filter) {
+            Ok(repo_reports) => {
+                print_multi_list_reports(repo_reports, writer);
+                Ok(())
+            }
+            Err(e) => {
+                writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
+                Err(setup_err_to_exit_code(e))
+            }
+        };
+    }
+
+    let list_result = api.list_tendrils(td_repos.first(), filter);
 
     let list_reports = match list_result {
         Ok(reports) => reports,
         Err(e) => {
-            writer.writeln(&format!("{ERR_PREFIX}: {}", e.to_string()));
+            writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
             return Err(setup_err_to_exit_code(e));
         }
     };
@@ -204,7 +575,7 @@ fn path(api: &impl TendrilsApi, writer: &mut impl Writer) -> Result<(), i32> {
         }
         Ok(None) => Ok(()),
         Err(e) => {
-            writer.writeln(&format!("{ERR_PREFIX}: {}", e.to_string()));
+            writer.writeln(&format!("{ERR_PREFIX}: {}", config_err_text(&e)));
             Err(exitcode::DATAERR)
         }
     }
@@ -212,19 +583,108 @@ fn path(api: &impl TendrilsApi, writer: &mut impl Writer) -> Result<(), i32> {
 
 /// Returns, but does not set, the suggested exit code in case of error.
 /// It is up to the calling function to handle exiting with this code.
-fn profiles(api: &impl TendrilsApi, writer: &mut impl Writer) -> Result<(), i32> {
+fn profiles(
+    path_args: PathArgs,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
     match api.get_default_profiles() {
         Ok(Some(v)) => {
             let display = v.join("\n");
             writer.writeln(&display);
-            Ok(())
         }
-        Ok(None) => Ok(()),
+        Ok(None) => {},
         Err(e) => {
-            writer.writeln(&format!("{ERR_PREFIX}: {}", e.to_string()));
-            Err(exitcode::DATAERR)
+            writer.writeln(&format!("{ERR_PREFIX}: {}", config_err_text(&e)));
+            return Err(exitcode::DATAERR);
+        }
+    }
+
+    let td_repos = repos_or_defaults(get_td_repos(path_args, api, writer)?, api);
+
+    let is_multi = td_repos.len() > 1;
+    for (i, td_repo) in td_repos.iter().enumerate() {
+        if let (true, Some(p)) = (is_multi, td_repo) {
+            print_repo_header(p, i == 0, writer);
+        }
+
+        match api.get_auto_profiles(td_repo.as_ref()) {
+            Ok(logs) => print_auto_profiles(&logs, writer),
+            Err(e) => {
+                writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
+                return Err(setup_err_to_exit_code(e));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns, but does not set, the suggested exit code in case of error.
+/// It is up to the calling function to handle exiting with this code.
+fn repo(
+    path_args: PathArgs,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer,
+) -> Result<(), i32> {
+    let (td_repos, source) = select_td_repos(path_args, api, writer)?;
+    if td_repos.is_empty() {
+        let default_repos = match api.get_default_repo_paths() {
+            Ok(v) => v,
+            Err(e) => {
+                writer.writeln(&format!("{ERR_PREFIX}: {}", config_err_text(&e)));
+                return Err(exitcode::DATAERR);
+            }
+        };
+        if default_repos.is_empty() {
+            let err = GetTendrilsRepoError::DefaultNotSet;
+            writer.writeln(&format!("{ERR_PREFIX}: {}", err.to_string()));
+            return Err(exitcode::NOINPUT);
+        }
+
+        let default_repos: Vec<UniPath> =
+            default_repos.into_iter().map(UniPath::from).collect();
+        if let Some(v) = default_repos.iter().find(|v| !api.is_tendrils_repo(v)) {
+            let err = GetTendrilsRepoError::DefaultInvalid {
+                path: v.inner().to_path_buf(),
+            };
+            writer.writeln(&format!("{ERR_PREFIX}: {}", err.to_string()));
+            return Err(exitcode::NOINPUT);
+        }
+        print_repo_selection(&default_repos, &source, writer);
+    }
+    else {
+        if let Some(v) = td_repos.iter().find(|v| !api.is_tendrils_repo(v)) {
+            let err = GetTendrilsRepoError::GivenInvalid {
+                path: v.inner().to_path_buf(),
+            };
+            writer.writeln(&format!("{ERR_PREFIX}: {}", err.to_string()));
+            return Err(exitcode::NOINPUT);
         }
+        print_repo_selection(&td_repos, &source, writer);
+    }
+
+    Ok(())
+}
+
+fn schema(schema_subcommand: SchemaSubcommands, writer: &mut impl Writer) {
+    let cfg_type = match schema_subcommand {
+        SchemaSubcommands::Repo => ConfigType::Repo(ConfigFormat::Json),
+        SchemaSubcommands::Global => ConfigType::Global,
+    };
+    writer.writeln(&config_schema(&cfg_type));
+}
+
+fn print_repo_selection(
+    td_repos: &[UniPath],
+    source: &RepoSource,
+    writer: &mut impl Writer,
+) {
+    for td_repo in td_repos {
+        let path_text = td_repo.inner().to_string_lossy();
+        writer.writeln(&ansi_hyperlink(&path_text, &path_text));
     }
+    writer.writeln(&repo_source_text(source));
 }
 
 /// Returns, but does not set, the suggested exit code in case of error.
@@ -236,14 +696,22 @@ fn tendril_action_subcommand(
     api: &impl TendrilsApi,
     writer: &mut impl Writer,
 ) -> Result<(), i32> {
-    let td_repo = get_td_repo(action_args.path_args, api, writer)?;
+    let td_repos = get_td_repos(action_args.path_args, api, writer)?;
     let filter = filter_args.to_spec(Some(mode.clone()));
+    if action_args.strict {
+        strict_check(&td_repos, &filter, api, writer)?;
+    }
+    else {
+        warn_unknown_keys(&td_repos, api, writer);
+    }
+    let is_multi = is_multi_repo(&td_repos, api);
     let mut reports = vec![];
 
     // Create locks on shared resources between the callback functions
     let total_lock = std::sync::RwLock::new(0);
     let completed_lock = std::sync::RwLock::new(0);
     let writer_lock = std::sync::RwLock::new(writer);
+    let progress_lock = std::sync::RwLock::new(ProgressBar::default());
 
     let count_fn = |c: i32| {
         // Unwrap should never panic as long as back-end is single threaded
@@ -255,7 +723,9 @@ fn tendril_action_subcommand(
         let completed = *completed_lock.read().unwrap();
         let total = *total_lock.read().unwrap();
         let mut writer = writer_lock.write().unwrap();
-        (*writer).ewrite(&format!("Processing [{}/{}]: {}", completed + 1, total, t.remote));
+        let label = format!("Processing [{}/{}]: {}", completed + 1, total, t.remote);
+        (*writer).ewrite(&label);
+        *progress_lock.write().unwrap() = ProgressBar::new(label);
 
         // Flush to ensure immediate output
         use std::io::Write;
@@ -268,66 +738,144 @@ fn tendril_action_subcommand(
         reports.push(r);
         *completed += 1;
     };
-    let updater = CallbackUpdater::<_, _, _, ActionLog>::new(count_fn, before_fn, after_fn);
+    let progress_fn = |p: CopyProgress| {
+        let mut progress_bar = progress_lock.write().unwrap();
+        if let Some(line) = progress_bar.update(p, std::time::Instant::now()) {
+            let mut writer = writer_lock.write().unwrap();
+            (*writer).ewrite(&format!("{}{line}", cli::CLEAR_LINE));
+        }
+    };
+    let updater = CallbackUpdater::<_, _, _, ActionLog>::new(count_fn, before_fn, after_fn)
+        .with_progress(progress_fn);
 
-    let batch_result = api.tendril_action_updating(
-        updater,
-        mode,
-        td_repo.as_ref().map(|p| p),
-        filter,
-        action_args.dry_run,
-        action_args.force,
-    );
+    // The multi repo reports are grouped by repo, otherwise the reports
+    // are only collected by the updater
+    let batch_result = if is_multi {
+        api.tendril_action_multi_updating(
+            updater,
+            mode,
+            &td_repos,
+            filter,
+            action_args.dry_run,
+            action_args.force,
+        ).map(Some)
+    }
+    else {
+        api.tendril_action_updating(
+            updater,
+            mode,
+            td_repos.first(),
+            filter,
+            action_args.dry_run,
+            action_args.force,
+        ).map(|_| None)
+    };
 
     // Remove locking wrapper
     let writer= writer_lock.into_inner().unwrap();
-    let action_reports = match batch_result {
+    match batch_result {
         Err(e) => {
-            writer.writeln(&format!("{ERR_PREFIX}: {}", e.to_string()));
+            writer.writeln(&format!("{ERR_PREFIX}: {}", setup_err_text(&e)));
             return Err(setup_err_to_exit_code(e));
         }
-        Ok(()) => reports,
+        Ok(Some(repo_reports)) => {
+            print_multi_action_reports(&repo_reports, writer)
+        }
+        Ok(None) => print_action_reports(&reports, writer),
     };
 
-    print_action_reports(&action_reports, writer);
+    if has_action_failures(&reports) {
+        return Err(exitcode::SOFTWARE);
+    }
 
-    if action_reports.iter().any(|r| match &r.log {
+    Ok(())
+}
+
+fn has_action_failures(reports: &[TendrilReport<ActionLog>]) -> bool {
+    reports.iter().any(|r| match &r.log {
         Err(_) => true,
         Ok(log) => log.result.is_err(),
-    }) {
-        return Err(exitcode::SOFTWARE);
+    })
+}
+
+/// Returns `true` if the command should operate on several Tendrils repos at
+/// once, either because several were given or because there are several
+/// default repos.
+fn is_multi_repo(td_repos: &[UniPath], api: &impl TendrilsApi) -> bool {
+    match td_repos.len() {
+        0 => matches!(api.get_default_repo_paths(), Ok(v) if v.len() > 1),
+        1 => false,
+        _ => true,
     }
+}
 
-    Ok(())
+/// Returns each of the `td_repos`, or if none are given, each of the default
+/// repos if there are several of them, otherwise `None` so that the default
+/// repo is used.
+fn repos_or_defaults(
+    td_repos: Vec<UniPath>,
+    api: &impl TendrilsApi,
+) -> Vec<Option<UniPath>> {
+    if !td_repos.is_empty() {
+        return td_repos.into_iter().map(Some).collect();
+    }
+
+    match api.get_default_repo_paths() {
+        Ok(v) if v.len() > 1 => {
+            v.into_iter().map(|p| Some(UniPath::from(p))).collect()
+        }
+        _ => vec![None],
+    }
 }
 
-fn get_td_repo(
+fn get_td_repos(
     path_args: PathArgs,
     api: &impl TendrilsApi,
     writer: &mut impl Writer
-) -> Result<Option<UniPath>, i32> {
-    match path_args.path {
-        Some(v) => Ok(Some(UniPath::new_with_root(
-            Path::new(&v),
-            &std::env::current_dir().unwrap_or_default(),
-        ))),
-        None => match std::env::current_dir() {
-            Ok(cd) => {
-                let u_cd = UniPath::from(cd);
-                if api.is_tendrils_repo(&u_cd) {
-                    Ok(Some(u_cd))
-                }
-                else {
-                    Ok(None)
-                }
-            },
-            Err(_err) => {
-                writer.writeln(&format!(
-                    "{ERR_PREFIX}: Could not get the current directory"
-                ));
-                return Err(exitcode::OSERR);
-            }
-        },
+) -> Result<Vec<UniPath>, i32> {
+    Ok(select_td_repos(path_args, api, writer)?.0)
+}
+
+/// Returns the Tendrils repos to pass to the [`TendrilsApi`] (where an empty
+/// list indicates the default repos), along with the reason they were
+/// selected. Unless any paths are given explicitly, the current directory and
+/// then each of its parent folders are searched for a repo.
+fn select_td_repos(
+    path_args: PathArgs,
+    api: &impl TendrilsApi,
+    writer: &mut impl Writer
+) -> Result<(Vec<UniPath>, RepoSource), i32> {
+    if !path_args.path.is_empty() {
+        let cd = std::env::current_dir().unwrap_or_default();
+        let given = path_args
+            .path
+            .iter()
+            .map(|v| UniPath::new_with_root(Path::new(v), &cd))
+            .collect();
+        return Ok((given, RepoSource::Given));
+    }
+
+    let u_cd = match std::env::current_dir() {
+        Ok(cd) => UniPath::from(cd),
+        Err(_err) => {
+            writer.writeln(&format!(
+                "{ERR_PREFIX}: Could not get the current directory"
+            ));
+            return Err(exitcode::OSERR);
+        }
+    };
+
+    if path_args.no_discovery {
+        if api.is_tendrils_repo(&u_cd) {
+            return Ok((vec![u_cd], RepoSource::CurrentDir));
+        }
+        return Ok((vec![], RepoSource::Default { searched_parents: false }));
+    }
+
+    match api.find_tendrils_repo(&u_cd) {
+        Some(v) if v == u_cd => Ok((vec![v], RepoSource::CurrentDir)),
+        Some(v) => Ok((vec![v], RepoSource::ParentDir { cd: u_cd })),
+        None => Ok((vec![], RepoSource::Default { searched_parents: true })),
     }
 }
 
@@ -337,10 +885,17 @@ fn setup_err_to_exit_code(err: SetupError) -> i32 {
         SetupError::ConfigError(GetConfigError::IoError { .. }) => {
             exitcode::NOINPUT
         }
-        SetupError::ConfigError(GetConfigError::ParseError { .. }) => {
+        SetupError::ConfigError(GetConfigError::ParseError { .. })
+        | SetupError::ConfigError(GetConfigError::DuplicateKey { .. })
+        | SetupError::ConfigError(GetConfigError::MultipleRepoConfigs { .. })
+        | SetupError::ConfigError(GetConfigError::UnsupportedVersion { .. })
+        | SetupError::ConfigError(GetConfigError::CommentsWouldBeLost { .. }) => {
             exitcode::DATAERR
         }
         SetupError::NoValidTendrilsRepo { .. } => exitcode::NOINPUT,
+        SetupError::InvalidProfileFilter { .. } => exitcode::USAGE,
+        SetupError::RemoteConflict { .. }
+        | SetupError::TendrilConflict(_) => exitcode::DATAERR,
     }
 }
 
@@ -351,6 +906,11 @@ impl FilterArgs {
             locals: self.locals,
             remotes: self.remotes,
             profiles: self.profiles,
+            exclude_locals: self.exclude_locals,
+            exclude_remotes: self.exclude_remotes,
+            exclude_profiles: self.exclude_profiles,
+            tendril_modes: self.modes,
+            fso_types: self.types,
         }
     }
 }
@@ -1 +1,2 @@
+mod progress_bar_tests;
 mod run_tests;
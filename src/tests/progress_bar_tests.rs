@@ -0,0 +1,106 @@
+use crate::cli::{bytes_text, ProgressBar};
+use rstest::rstest;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tendrils_core::CopyProgress;
+
+fn file_progress() -> CopyProgress {
+    CopyProgress::File { path: PathBuf::from("misc.txt") }
+}
+
+#[rstest]
+#[case(0, "0 B")]
+#[case(1023, "1023 B")]
+#[case(1024, "1.0 KiB")]
+#[case(1536, "1.5 KiB")]
+#[case(5 * 1024 * 1024 * 1024, "5.0 GiB")]
+#[case(2048 * 1024 * 1024 * 1024 * 1024, "2048.0 TiB")]
+fn bytes_text_uses_largest_unit_of_at_least_one(
+    #[case] bytes: u64,
+    #[case] expected: &str,
+) {
+    assert_eq!(bytes_text(bytes), expected);
+}
+
+#[test]
+fn start_is_drawn_without_throughput() {
+    let mut bar = ProgressBar::new(String::from("Label"));
+
+    let actual = bar.update(
+        CopyProgress::Start { files: 3, bytes: 4096 },
+        Instant::now(),
+    );
+
+    assert_eq!(
+        actual,
+        Some(String::from("Label [--------------------] 0% 0 B/4.0 KiB (0/3 files)")),
+    );
+}
+
+#[test]
+fn bytes_are_drawn_with_average_throughput_since_start() {
+    let mut bar = ProgressBar::new(String::from("Label"));
+    let start = Instant::now();
+    bar.update(CopyProgress::Start { files: 1, bytes: 4 * 1024 * 1024 }, start);
+    bar.update(file_progress(), start);
+
+    let actual = bar.update(
+        CopyProgress::Bytes { copied: 1024 * 1024 },
+        start + Duration::from_secs(2),
+    );
+
+    assert_eq!(
+        actual,
+        Some(String::from(
+            "Label [#####---------------] 25% 1.0 MiB/4.0 MiB (1/1 files, 512.0 KiB/s)"
+        )),
+    );
+}
+
+#[test]
+fn updates_soon_after_last_draw_are_not_drawn_unless_complete() {
+    let mut bar = ProgressBar::new(String::from("Label"));
+    let start = Instant::now();
+    bar.update(CopyProgress::Start { files: 2, bytes: 200 }, start);
+    let soon = start + Duration::from_millis(50);
+    let later = start + Duration::from_millis(150);
+
+    assert_eq!(bar.update(file_progress(), soon), None);
+    assert_eq!(bar.update(CopyProgress::Bytes { copied: 100 }, soon), None);
+    assert!(bar.update(file_progress(), later).is_some());
+    assert!(bar.update(CopyProgress::Bytes { copied: 200 }, later).is_some());
+}
+
+#[test]
+fn empty_copy_is_shown_as_complete() {
+    let mut bar = ProgressBar::new(String::from("Label"));
+
+    let actual = bar.update(
+        CopyProgress::Start { files: 0, bytes: 0 },
+        Instant::now(),
+    );
+
+    assert_eq!(
+        actual,
+        Some(String::from("Label [####################] 100% 0 B/0 B (0/0 files)")),
+    );
+}
+
+#[test]
+fn start_resets_previous_progress_but_keeps_label() {
+    let mut bar = ProgressBar::new(String::from("Label"));
+    let start = Instant::now();
+    bar.update(CopyProgress::Start { files: 1, bytes: 10 }, start);
+    bar.update(file_progress(), start);
+    bar.update(CopyProgress::Bytes { copied: 10 }, start);
+
+    let actual = bar.update(
+        CopyProgress::Start { files: 1, bytes: 20 },
+        start + Duration::from_millis(10),
+    );
+
+    assert_eq!(
+        actual,
+        Some(String::from("Label [--------------------] 0% 0 B/20 B (0/1 files)")),
+    );
+}
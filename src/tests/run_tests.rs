@@ -3,15 +3,19 @@ use crate::cli::{
     AboutSubcommands,
     ActionArgs,
     CLEAR_LINE,
+    ConfigSubcommands,
     FilterArgs,
     PathArgs,
+    SchemaSubcommands,
     TendrilCliArgs,
     TendrilsSubcommands,
 };
 use crate::{run, Writer, ERR_PREFIX};
+use clap::Parser;
 use inline_colorization::{
     color_bright_green,
     color_bright_red,
+    color_bright_yellow,
     color_reset,
     style_reset,
     style_underline,
@@ -19,28 +23,44 @@ use inline_colorization::{
 use rstest::rstest;
 use serial_test::serial;
 use std::fs::create_dir_all;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::path::MAIN_SEPARATOR_STR as SEP;
 use std::vec;
 use tendrils_core::test_utils::{get_disposable_dir, MockTendrilsApi};
 use tendrils_core::{
+    config_schema,
+    CONFIG_VERSION,
     ActionLog,
     ActionMode,
+    AutoProfileLog,
+    CheckFinding,
+    CheckKind,
+    ConditionLog,
+    ConfigIssue,
+    ConfigIssueKind,
+    ConfigFormat,
     ConfigType,
+    CopyProgress,
+    Diagnosis,
     FilterSpec,
     FsoType,
     GetConfigError,
     GetTendrilsRepoError,
+    ParseErrorLocation,
+    HostCondition,
     InitError,
     ListLog,
     Location,
     RawTendril,
+    RepoReports,
     SetupError,
+    Severity,
     TendrilActionError,
     TendrilActionSuccess,
     TendrilMode,
     TendrilReport,
     TendrilsActor,
+    UniPath,
 };
 
 #[derive(Clone)]
@@ -95,9 +115,18 @@ fn build_action_subcommand(
     remotes: Vec<String>,
     profiles: Option<Vec<String>>,
 ) -> TendrilsSubcommands {
-    let path_args = PathArgs { path };
-    let action_args = ActionArgs { path_args, dry_run, force };
-    let filter_args = FilterArgs { locals, remotes, profiles };
+    let path_args = PathArgs { path: path.into_iter().collect(), no_discovery: false };
+    let action_args = ActionArgs { path_args, dry_run, force, strict: false };
+    let filter_args = FilterArgs {
+        locals,
+        remotes,
+        profiles,
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        modes: vec![],
+        types: vec![],
+    };
 
     match mode {
         ActionMode::Pull => {
@@ -112,6 +141,9 @@ fn build_action_subcommand(
         ActionMode::Out => {
             TendrilsSubcommands::Out { action_args, filter_args }
         }
+        ActionMode::Relink => {
+            TendrilsSubcommands::Relink { action_args, filter_args }
+        }
     }
 }
 
@@ -121,8 +153,17 @@ fn build_list_subcommand(
     remotes: Vec<String>,
     profiles: Option<Vec<String>>,
 ) -> TendrilsSubcommands {
-    let path_args = PathArgs { path };
-    let filter_args = FilterArgs { locals, remotes, profiles };
+    let path_args = PathArgs { path: path.into_iter().collect(), no_discovery: false };
+    let filter_args = FilterArgs {
+        locals,
+        remotes,
+        profiles,
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        modes: vec![],
+        types: vec![],
+    };
     TendrilsSubcommands::List { path_args, filter_args }
 }
 
@@ -179,6 +220,27 @@ fn about_acknowledgements_returns_message_and_hyperlink_to_repo_third_party_file
     assert_eq!(actual_exit_code, Ok(()));
 }
 
+#[rstest]
+#[case(SchemaSubcommands::Repo, ConfigType::Repo(ConfigFormat::Json))]
+#[case(SchemaSubcommands::Global, ConfigType::Global)]
+fn schema_prints_schema_for_config_type(
+    #[case] schema_subcommand: SchemaSubcommands,
+    #[case] cfg_type: ConfigType,
+) {
+    let api = TendrilsActor {};
+    let mut writer = MockWriter::new();
+    let expected = format!("{}\n", config_schema(&cfg_type));
+
+    let args = TendrilCliArgs {
+        tendrils_command: TendrilsSubcommands::Schema { schema_subcommand },
+    };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(writer.all_output, expected);
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
 #[rstest]
 #[case(true)]
 #[case(false)]
@@ -444,7 +506,8 @@ fn init_given_path_is_relative_prepends_with_cd(#[case] force: bool) {
     create_dir_all(&cd).unwrap();
     std::env::set_current_dir(&cd).unwrap();
 
-    api.init_exp_dir_arg = cd.join(&user_given_dir);
+    // The `..` is normalized
+    api.init_exp_dir_arg = temp_dir.path().join("Relative/Path");
     api.init_exp_force_arg = force;
     api.init_const_rt =
         Err(InitError::IoError { kind: std::io::ErrorKind::NotFound });
@@ -480,7 +543,7 @@ fn init_given_path_is_relative_and_cd_doesnt_exist_prepends_with_dir_sep(#[case]
     std::env::set_current_dir(&cd).unwrap();
     std::fs::remove_dir(&cd).unwrap();
 
-    api.init_exp_dir_arg = PathBuf::from("/../Relative/Path");
+    api.init_exp_dir_arg = PathBuf::from("/Relative/Path");
     api.init_exp_force_arg = force;
     api.init_const_rt =
         Err(InitError::IoError { kind: std::io::ErrorKind::NotFound });
@@ -611,219 +674,186 @@ fn path_io_error_accessing_global_config_file_prints_message() {
     assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-#[cfg_attr(windows, ignore)]
-fn tendril_action_no_path_given_and_no_cd_prints_message(
-    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
-    mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
-) {
+#[test]
+fn path_parse_error_with_location_prints_snippet_with_caret() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let cd = temp_dir.path().join("CurrentDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-    std::fs::remove_dir(&cd).unwrap();
-
-    api.ta_fn = Some(Box::new(|_, _, _, _, _| panic!()));
+    api.get_default_repo_const_rt = Err(GetConfigError::ParseError {
+        cfg_type: ConfigType::Global,
+        msg: "invalid type: integer `5`, expected a string".to_string(),
+        loc: Some(ParseErrorLocation {
+            line: 12,
+            column: 27,
+            key_path: "default-repo-path".to_string(),
+            line_text: "    \"default-repo-path\": 5".to_string(),
+        }),
+    });
+    let args = TendrilCliArgs { tendrils_command: TendrilsSubcommands::Path };
 
-    let tendrils_command = build_action_subcommand(
-        None,
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
+    let expected = format!(
+        "{ERR_PREFIX}: Could not parse the global-config.json file:\n\
+        invalid type: integer `5`, expected a string at line 12 column 27 in \
+        default-repo-path\n   \
+        |\n\
+        12 |     \"default-repo-path\": 5\n   \
+        |                           {color_bright_red}^{color_reset}\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
-
-    let expected =
-        format!("{ERR_PREFIX}: Could not get the current directory\n");
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    assert_eq!(actual_exit_code, Err(exitcode::OSERR));
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
     assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-#[cfg_attr(windows, ignore)]
-fn list_tendrils_no_path_given_and_no_cd_prints_message() {
+#[test]
+fn parse_error_snippet_keeps_tabs_before_caret() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let cd = temp_dir.path().join("CurrentDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-    std::fs::remove_dir(&cd).unwrap();
-
-    api.list_fn = Some(Box::new(|_, _| panic!()));
+    api.get_default_repo_const_rt = Err(GetConfigError::ParseError {
+        cfg_type: ConfigType::Global,
+        msg: "expected value".to_string(),
+        loc: Some(ParseErrorLocation {
+            line: 2,
+            column: 5,
+            key_path: String::new(),
+            line_text: "\t\tx:".to_string(),
+        }),
+    });
+    let args = TendrilCliArgs { tendrils_command: TendrilsSubcommands::Path };
 
-    let tendrils_command = build_list_subcommand(
-        None,
-        vec![],
-        vec![],
-        None
+    let expected = format!(
+        "{ERR_PREFIX}: Could not parse the global-config.json file:\n\
+        expected value at line 2 column 5\n  \
+        |\n\
+        2 | \t\tx:\n  \
+        | \t\t  {color_bright_red}^{color_reset}\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
-
-    let expected =
-        format!("{ERR_PREFIX}: Could not get the current directory\n");
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    assert_eq!(actual_exit_code, Err(exitcode::OSERR));
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
     assert_eq!(writer.all_output, expected);
 }
 
-// TODO: Test no path given and cd is not Tendrils repo
-// TODO: Test no path given and cd is Tendrils repo
+fn build_profiles_subcommand(path: Option<String>) -> TendrilsSubcommands {
+    let path_args = PathArgs { path: path.into_iter().collect(), no_discovery: false };
+    TendrilsSubcommands::Profiles { path_args }
+}
 
-#[rstest]
-#[serial(SERIAL_CD)]
-fn tendril_action_given_path_is_not_tendrils_repo_but_cd_is_should_print_message(
-    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
-    mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
-) {
+#[test]
+fn profiles_with_no_defaults_or_auto_profiles_prints_nothing() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let cd = temp_dir.path().join("CurrentDir");
-    let cd_for_closure = cd.clone();
     let given_dir = PathBuf::from("/SomeGivenDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-
-    api.is_tendrils_repo_fn = Some(Box::new(move |dir| {
-        dir.inner() == cd_for_closure
-    }));
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path= Some(&given_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_rt = Err(SetupError::NoValidTendrilsRepo(
-        GetTendrilsRepoError::GivenInvalid { path: given_dir.clone() },
-    ));
-
+    api.get_auto_profiles_exp_path = Some(&given_dir);
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_action_subcommand(
-        path,
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
-    );
-    let args = TendrilCliArgs { tendrils_command };
-
-    let expected =
-        format!("{ERR_PREFIX}: /SomeGivenDir is not a Tendrils repo\n");
+    let args = TendrilCliArgs { tendrils_command: build_profiles_subcommand(path) };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    // To free the TempDir from use
-    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
-
-    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
-    assert_eq!(writer.all_output, expected);
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "");
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-fn list_tendril_given_path_is_not_tendrils_repo_but_cd_is_should_print_message()
-{
+#[test]
+fn profiles_with_defaults_set_prints_each_profile() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let cd = temp_dir.path().join("CurrentDir");
-    let cd_for_closure = cd.clone();
     let given_dir = PathBuf::from("/SomeGivenDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
+    api.get_auto_profiles_exp_path = Some(&given_dir);
+    api.get_default_profiles_const_rt =
+        Ok(Some(vec!["p1".to_string(), "p2".to_string()]));
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs { tendrils_command: build_profiles_subcommand(path) };
 
-    api.is_tendrils_repo_fn = Some(Box::new(move |dir| {
-        dir.inner() == cd_for_closure
-    }));
-    api.list_exp_path = Some(&given_dir);
-    api.list_const_rt = Err(SetupError::NoValidTendrilsRepo(
-        GetTendrilsRepoError::GivenInvalid { path: given_dir.clone() },
-    ));
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "p1\np2\n");
+}
 
+#[test]
+fn profiles_with_auto_profiles_prints_which_rules_matched() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.get_default_profiles_const_rt = Ok(Some(vec!["p1".to_string()]));
+    api.get_auto_profiles_exp_path = Some(&given_dir);
+    api.get_auto_profiles_const_rt = Ok(vec![
+        AutoProfileLog {
+            profile: "work".to_string(),
+            cfg_type: ConfigType::Global,
+            conditions: vec![
+                ConditionLog {
+                    condition: HostCondition::Hostname("WORK-*".to_string()),
+                    actual: Some("work-pc".to_string()),
+                    matched: true,
+                },
+                ConditionLog {
+                    condition: HostCondition::EnvVar("VAR".to_string()),
+                    actual: Some("value".to_string()),
+                    matched: true,
+                },
+            ],
+        },
+        AutoProfileLog {
+            profile: "mac".to_string(),
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            conditions: vec![
+                ConditionLog {
+                    condition: HostCondition::Os("macos".to_string()),
+                    actual: Some("linux".to_string()),
+                    matched: false,
+                },
+                ConditionLog {
+                    condition: HostCondition::PathExists("~/Mac".to_string()),
+                    actual: None,
+                    matched: false,
+                },
+            ],
+        },
+        AutoProfileLog {
+            profile: "all".to_string(),
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            conditions: vec![],
+        },
+    ]);
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_list_subcommand(
-        path,
-        vec![],
-        vec![],
-        None,
-    );
-    let args = TendrilCliArgs { tendrils_command };
+    let args = TendrilCliArgs { tendrils_command: build_profiles_subcommand(path) };
 
-    let expected =
-        format!("{ERR_PREFIX}: /SomeGivenDir is not a Tendrils repo\n");
+    let expected = format!(
+        "p1\n\
+        Automatic profiles:\n  \
+        work: {color_bright_green}active{color_reset} (global-config.json)\n    \
+        hostname \"WORK-*\" matches \"work-pc\"\n    \
+        env \"VAR\" is set\n  \
+        mac: {color_bright_red}inactive{color_reset} (tendrils.json)\n    \
+        os \"macos\" does not match \"linux\"\n    \
+        path \"~/Mac\" does not exist\n  \
+        all: {color_bright_green}active{color_reset} (tendrils.json)\n    \
+        (no conditions)\n"
+    );
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    // To free the TempDir from use
-    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
-
-    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(actual_exit_code, Ok(()));
     assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-fn tendril_action_given_path_and_cd_are_both_tendrils_repos_uses_given_path(
-    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
-    mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
-) {
+#[test]
+fn profiles_auto_profiles_error_prints_message() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let cd = temp_dir.path().join("CurrentDir");
     let given_dir = PathBuf::from("/SomeGivenDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-
-    api.is_tendrils_repo_const_rt = true;
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&given_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
-
+    api.get_auto_profiles_exp_path = Some(&given_dir);
+    api.get_auto_profiles_const_rt =
+        Err(SetupError::ConfigError(GetConfigError::ParseError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: "Some parse error msg".to_string(),
+            loc: None,
+        }));
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_action_subcommand(
-        path,
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
-    );
-    let args = TendrilCliArgs { tendrils_command };
+    let args = TendrilCliArgs { tendrils_command: build_profiles_subcommand(path) };
 
     let expected = format!(
         "{ERR_PREFIX}: Could not parse the tendrils.json file:\nSome parse \
@@ -832,713 +862,2253 @@ fn tendril_action_given_path_and_cd_are_both_tendrils_repos_uses_given_path(
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    // To free the TempDir from use
-    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
-
     assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
     assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-fn list_tendrils_given_path_and_cd_are_both_tendrils_repos_uses_given_path() {
+#[test]
+fn profiles_auto_profiles_parse_error_in_no_valid_repo_err_prints_snippet() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let cd = temp_dir.path().join("CurrentDir");
     let given_dir = PathBuf::from("/SomeGivenDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-
-    api.is_tendrils_repo_const_rt = true;
-    api.list_exp_path = Some(&given_dir);
-    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
-
+    api.get_auto_profiles_exp_path = Some(&given_dir);
+    api.get_auto_profiles_const_rt = Err(SetupError::NoValidTendrilsRepo(
+        GetTendrilsRepoError::ConfigError(GetConfigError::ParseError {
+            cfg_type: ConfigType::Global,
+            msg: "expected `,` or `}`".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 3,
+                column: 1,
+                key_path: String::new(),
+                line_text: "]".to_string(),
+            }),
+        }),
+    ));
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_list_subcommand(
-        path,
-        vec![],
-        vec![],
-        None,
-    );
-    let args = TendrilCliArgs { tendrils_command };
+    let args = TendrilCliArgs { tendrils_command: build_profiles_subcommand(path) };
 
     let expected = format!(
-        "{ERR_PREFIX}: Could not parse the tendrils.json file:\nSome parse \
-         error msg\n"
+        "{ERR_PREFIX}: Could not parse the global-config.json file:\n\
+        expected `,` or `}}` at line 3 column 1\n  \
+        |\n\
+        3 | ]\n  \
+        | {color_bright_red}^{color_reset}\n"
     );
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    // To free the TempDir from use
-    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
-
-    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
     assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-fn tendril_action_given_path_is_relative_prepends_with_cd(
-    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
-    mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
-) {
+fn build_check_subcommand(
+    path: Option<String>,
+    locals: Vec<String>,
+    remotes: Vec<String>,
+    profiles: Option<Vec<String>>,
+) -> TendrilsSubcommands {
+    match build_list_subcommand(path, locals, remotes, profiles) {
+        TendrilsSubcommands::List { path_args, filter_args } => {
+            TendrilsSubcommands::Check { path_args, filter_args, strict: false }
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn check_finding(kind: CheckKind, severity: Severity) -> CheckFinding {
+    let mut outer = RawTendril::new("SomeLocal");
+    outer.remote = "/SomeRemote".to_string();
+    let mut inner = RawTendril::new("OtherLocal");
+    inner.remote = "/SomeRemote/Nested".to_string();
+    CheckFinding {
+        kind,
+        severity,
+        outer,
+        inner,
+        outer_path: PathBuf::from("/SomeRemote"),
+        inner_path: PathBuf::from("/SomeRemote/Nested"),
+    }
+}
+
+#[test]
+fn check_no_findings_prints_message() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let user_given_dir = "../Relative/Path";
-    let cd = temp_dir.path().join("CurrentDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-    let exp_passed_dir = cd.join(&user_given_dir);
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.check_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_check_subcommand(path, vec![], vec![], None),
+    };
 
-    api.is_tendrils_repo_const_rt = true;
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&exp_passed_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
+    let actual_exit_code = run(args, &api, &mut writer);
 
-    let tendrils_command = build_action_subcommand(
-        Some(user_given_dir.to_string()),
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "No conflicts found\n");
+}
+
+#[test]
+fn check_only_warnings_prints_each_and_returns_ok() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.check_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.check_const_rt = Ok(vec![
+        check_finding(CheckKind::NestedRemote, Severity::Warning),
+        check_finding(CheckKind::NestedRemote, Severity::Warning),
+    ]);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_check_subcommand(path, vec![], vec![], None),
+    };
+
+    let line = format!(
+        "{color_bright_yellow}Warning{color_reset}: The remote \
+        \"/SomeRemote/Nested\" of \"OtherLocal\" is inside the remote \
+        \"/SomeRemote\" of \"SomeLocal\"\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
+    let expected = format!("{line}{line}\n0 errors, 2 warnings\n");
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    // To free the TempDir from use
-    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
-
-    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-fn list_tendrils_given_path_is_relative_prepends_with_cd() {
+#[test]
+fn check_any_errors_prints_each_and_returns_exit_code() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let user_given_dir = "../Relative/Path";
-    let cd = temp_dir.path().join("CurrentDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-    let exp_passed_dir = cd.join(&user_given_dir);
-
-    api.is_tendrils_repo_const_rt = true;
-    api.list_exp_path = Some(&exp_passed_dir);
-    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.check_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.check_const_rt = Ok(vec![
+        check_finding(CheckKind::LinkInDirRemote, Severity::Error),
+        check_finding(CheckKind::NestedLocal, Severity::Warning),
+    ]);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_check_subcommand(path, vec![], vec![], None),
+    };
 
-    let tendrils_command = build_list_subcommand(
-        Some(user_given_dir.to_string()),
-        vec![],
-        vec![],
-        None,
+    let expected = format!(
+        "{color_bright_red}Error{color_reset}: The remote \
+        \"/SomeRemote/Nested\" of the link tendril \"OtherLocal\" is inside \
+        the remote \"/SomeRemote\" of \"SomeLocal\"\n\
+        {color_bright_yellow}Warning{color_reset}: The local \
+        \"/SomeRemote/Nested\" is inside the local \"/SomeRemote\"\n\
+        \n\
+        1 error, 1 warning\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    // To free the TempDir from use
-    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
-
     assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-#[cfg_attr(windows, ignore)]
-fn tendril_action_given_path_is_relative_and_cd_doesnt_exist_prepends_with_dir_sep(
-    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
-    mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
-) {
+fn config_issue(kind: ConfigIssueKind, severity: Severity) -> ConfigIssue {
+    ConfigIssue {
+        kind,
+        severity,
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        key_path: "tendrils.SomeLocal.dir_merge".to_string(),
+    }
+}
+
+#[test]
+fn check_config_issues_are_printed_before_findings() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let user_given_dir = "../Relative/Path";
-    let cd = temp_dir.path().join("CurrentDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-    std::fs::remove_dir(&cd).unwrap();
-    let exp_passed_dir = PathBuf::from("/../Relative/Path");
-
-    api.is_tendrils_repo_const_rt = true;
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&exp_passed_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.check_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.check_const_rt = Ok(vec![
+        check_finding(CheckKind::NestedRemote, Severity::Warning),
+    ]);
+    api.validate_const_rt = Ok(vec![config_issue(
+        ConfigIssueKind::UnknownKey { suggestion: Some("dir-merge".to_string()) },
+        Severity::Warning,
+    )]);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_check_subcommand(path, vec![], vec![], None),
+    };
 
-    let tendrils_command = build_action_subcommand(
-        Some(user_given_dir.to_string()),
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
+    let expected = format!(
+        "{color_bright_yellow}Warning{color_reset}: Unknown key at \
+        tendrils.SomeLocal.dir_merge in the tendrils.json file, did you mean \
+        \"dir-merge\"?\n\
+        {color_bright_yellow}Warning{color_reset}: The remote \
+        \"/SomeRemote/Nested\" of \"OtherLocal\" is inside the remote \
+        \"/SomeRemote\" of \"SomeLocal\"\n\
+        \n\
+        0 errors, 2 warnings\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD)]
-#[cfg_attr(windows, ignore)]
-fn list_tendrils_given_path_is_relative_and_cd_doesnt_exist_prepends_with_dir_sep(
-) {
+#[test]
+fn check_config_issue_error_returns_exit_code() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let user_given_dir = "../Relative/Path";
-    let cd = temp_dir.path().join("CurrentDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-    std::fs::remove_dir(&cd).unwrap();
-    let exp_passed_dir = PathBuf::from("/../Relative/Path");
-
-    api.is_tendrils_repo_const_rt = true;
-    api.list_exp_path = Some(&exp_passed_dir);
-    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
-
-    let tendrils_command = build_list_subcommand(
-        Some(user_given_dir.to_string()),
-        vec![],
-        vec![],
-        None,
-    );
-    let args = TendrilCliArgs { tendrils_command };
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.check_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.validate_const_rt =
+        Ok(vec![config_issue(ConfigIssueKind::InvalidLocal, Severity::Error)]);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_check_subcommand(path, vec![], vec![], None),
+    };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
     assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert!(writer.all_output.ends_with("1 error, 0 warnings\n"));
 }
 
 #[rstest]
-#[serial(SERIAL_MUT_ENV_VARS)]
-fn tendril_action_given_path_is_relative_but_resolves_to_abs_should_not_prepend_cd(
-    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
-    mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
+#[case(false, Ok(()))]
+#[case(true, Err(exitcode::DATAERR))]
+fn check_strict_returns_exit_code_for_only_warnings(
+    #[case] strict: bool,
+    #[case] expected: Result<(), i32>,
 ) {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    std::env::set_var("HOME", "/Some/Abs/Path");
-    let user_given_dir = "~/Relative/Path";
-    let exp_passed_dir = PathBuf::from("/Some/Abs/Path/Relative/Path");
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.check_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.validate_const_rt =
+        Ok(vec![config_issue(ConfigIssueKind::EmptyProfile, Severity::Warning)]);
+    let mut cli_args = vec!["td", "check", "--path", "/SomeGivenDir"];
+    if strict {
+        cli_args.push("--strict");
+    }
+    let args = TendrilCliArgs::try_parse_from(cli_args).unwrap();
 
-    api.is_tendrils_repo_const_rt = true;
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&exp_passed_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
+    let actual_exit_code = run(args, &api, &mut writer);
 
-    let tendrils_command = build_action_subcommand(
-        Some(user_given_dir.to_string()),
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
-    );
-    let args = TendrilCliArgs { tendrils_command };
+    assert_eq!(actual_exit_code, expected);
+    assert!(writer.all_output.ends_with("0 errors, 1 warning\n"));
+}
+
+#[test]
+fn check_filters_are_passed_properly() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.check_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.check_exp_filter.locals = vec!["SomeLocal".to_string()];
+    api.check_exp_filter.remotes = vec!["SomeRemote".to_string()];
+    api.check_exp_filter.profiles = Some(vec!["SomeProfile".to_string()]);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_check_subcommand(
+            path,
+            vec!["SomeLocal".to_string()],
+            vec!["SomeRemote".to_string()],
+            Some(vec!["SomeProfile".to_string()]),
+        ),
+    };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(actual_exit_code, Ok(()));
 }
 
-#[rstest]
-#[serial(SERIAL_MUT_ENV_VARS)]
-fn list_tendrils_given_path_is_relative_but_resolves_to_abs_should_not_prepend_cd(
-) {
+#[test]
+fn check_setup_error_prints_message() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    std::env::set_var("HOME", "/Some/Abs/Path");
-    let user_given_dir = "~/Relative/Path";
-    let exp_passed_dir = PathBuf::from("/Some/Abs/Path/Relative/Path");
-
-    api.is_tendrils_repo_const_rt = true;
-    api.list_exp_path = Some(&exp_passed_dir);
-    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.check_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.check_const_rt =
+        Err(SetupError::ConfigError(GetConfigError::ParseError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: "Some parse error msg".to_string(),
+            loc: None,
+        }));
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_check_subcommand(path, vec![], vec![], None),
+    };
 
-    let tendrils_command = build_list_subcommand(
-        Some(user_given_dir.to_string()),
-        vec![],
-        vec![],
-        None,
+    let expected = format!(
+        "{ERR_PREFIX}: Could not parse the tendrils.json file:\nSome parse \
+         error msg\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
     assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD, SERIAL_MUT_ENV_VARS)]
-#[cfg_attr(windows, ignore)]
-fn tendril_action_given_path_is_relative_but_resolves_to_abs_and_cd_doesnt_exist_should_not_prepend_cd(
-    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
-    mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
-) {
-    let mut api = MockTendrilsApi::new();
-    let mut writer = MockWriter::new();
-    std::env::set_var("HOME", "/Some/Abs/Path");
-    let user_given_dir = "~/Relative/Path";
-    let exp_passed_dir = PathBuf::from("/Some/Abs/Path/Relative/Path");
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let cd = temp_dir.path().join("CurrentDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-    std::fs::remove_dir(&cd).unwrap();
+fn build_config_convert_subcommand(
+    format: ConfigFormat,
+    path: Option<String>,
+    drop_comments: bool,
+) -> TendrilsSubcommands {
+    let path_args = PathArgs { path: path.into_iter().collect(), no_discovery: false };
+    TendrilsSubcommands::Config {
+        config_subcommand: ConfigSubcommands::Convert {
+            format,
+            path_args,
+            drop_comments,
+        },
+    }
+}
 
-    api.is_tendrils_repo_const_rt = true;
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&exp_passed_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
+#[test]
+fn config_convert_prints_converted_files() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.convert_exp_path = Some(&given_dir);
+    api.convert_exp_format = ConfigFormat::Toml;
+    api.convert_const_rt = Ok(ConfigFormat::Json);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_config_convert_subcommand(ConfigFormat::Toml, path, false),
+    };
 
-    let tendrils_command = build_action_subcommand(
-        Some(user_given_dir.to_string()),
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "Converted tendrils.json to tendrils.toml\n");
+}
+
+#[test]
+fn config_convert_same_format_prints_already_using() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.convert_exp_path = Some(&given_dir);
+    api.convert_exp_format = ConfigFormat::Jsonc;
+    api.convert_const_rt = Ok(ConfigFormat::Jsonc);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_config_convert_subcommand(ConfigFormat::Jsonc, path, false),
+    };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "Already using tendrils.jsonc\n");
+}
+
+#[test]
+fn config_convert_multiple_configs_prints_error_and_returns_data_err() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.convert_exp_path = Some(&given_dir);
+    api.convert_const_rt = Err(SetupError::ConfigError(
+        GetConfigError::MultipleRepoConfigs {
+            formats: vec![ConfigFormat::Json, ConfigFormat::Toml],
+        },
+    ));
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_config_convert_subcommand(ConfigFormat::Json, path, false),
+    };
+
+    let expected = format!(
+        "{ERR_PREFIX}: Found more than one config file in the Tendrils repo \
+        (tendrils.json, tendrils.toml). Only one of these may exist\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
     assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[serial(SERIAL_CD, SERIAL_MUT_ENV_VARS)]
-#[cfg_attr(windows, ignore)]
-fn list_tendrils_given_path_is_relative_but_resolves_to_abs_and_cd_doesnt_exist_should_not_prepend_cd(
-) {
+#[test]
+fn config_convert_passes_drop_comments_flag() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    std::env::set_var("HOME", "/Some/Abs/Path");
-    let user_given_dir = "~/Relative/Path";
-    let exp_passed_dir = PathBuf::from("/Some/Abs/Path/Relative/Path");
-    let temp_dir =
-        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
-    let cd = temp_dir.path().join("CurrentDir");
-    create_dir_all(&cd).unwrap();
-    std::env::set_current_dir(&cd).unwrap();
-    std::fs::remove_dir(&cd).unwrap();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.convert_exp_path = Some(&given_dir);
+    api.convert_exp_format = ConfigFormat::Json;
+    api.convert_exp_drop_comments = true;
+    api.convert_const_rt = Ok(ConfigFormat::Toml);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_config_convert_subcommand(ConfigFormat::Json, path, true),
+    };
 
-    api.is_tendrils_repo_const_rt = true;
-    api.list_exp_path = Some(&exp_passed_dir);
-    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
-        cfg_type: ConfigType::Repo,
-        msg: "Some parse error msg".to_string(),
-    }));
+    let actual_exit_code = run(args, &api, &mut writer);
 
-    let tendrils_command = build_list_subcommand(
-        Some(user_given_dir.to_string()),
-        vec![],
-        vec![],
-        None,
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "Converted tendrils.toml to tendrils.json\n");
+}
+
+#[test]
+fn config_convert_comments_would_be_lost_err_prints_error_and_drop_comments_hint() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.convert_exp_path = Some(&given_dir);
+    api.convert_const_rt = Err(SetupError::ConfigError(
+        GetConfigError::CommentsWouldBeLost {
+            cfg_type: ConfigType::Repo(ConfigFormat::Toml),
+        },
+    ));
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_config_convert_subcommand(ConfigFormat::Json, path, false),
+    };
+
+    let expected = format!(
+        "{ERR_PREFIX}: The tendrils.toml file contains comments, which would \
+         be removed by rewriting it\nUse --drop-comments to convert it \
+         without its comments\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
     assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[test]
+fn config_convert_parses_drop_comments_flag() {
+    let args = TendrilCliArgs::try_parse_from([
+        "td", "config", "convert", "json", "--drop-comments",
+    ]).unwrap();
+
+    match args.tendrils_command {
+        TendrilsSubcommands::Config {
+            config_subcommand: ConfigSubcommands::Convert { drop_comments, .. },
+        } => assert!(drop_comments),
+        _ => panic!(),
+    }
 }
 
 #[rstest]
-fn tendril_action_prints_returned_resolved_path_when_invalid_td_repo(
-    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
-    mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
+#[case("json", ConfigFormat::Json)]
+#[case("jsonc", ConfigFormat::Jsonc)]
+#[case("toml", ConfigFormat::Toml)]
+fn config_convert_parses_format_arg(
+    #[case] arg: &str,
+    #[case] expected: ConfigFormat,
+) {
+    let args = TendrilCliArgs::try_parse_from(["td", "config", "convert", arg])
+        .unwrap();
+
+    match args.tendrils_command {
+        TendrilsSubcommands::Config {
+            config_subcommand: ConfigSubcommands::Convert { format, .. },
+        } => assert_eq!(format, expected),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn config_convert_unknown_format_arg_returns_err() {
+    let actual = TendrilCliArgs::try_parse_from(["td", "config", "convert", "yaml"]);
+
+    assert!(actual.is_err());
+}
+
+fn build_config_migrate_subcommand(
+    path: Option<String>,
+    drop_comments: bool,
+) -> TendrilsSubcommands {
+    let path_args = PathArgs { path: path.into_iter().collect(), no_discovery: false };
+    TendrilsSubcommands::Config {
+        config_subcommand: ConfigSubcommands::Migrate {
+            global: false,
+            path_args,
+            drop_comments,
+        },
+    }
+}
+
+#[rstest]
+#[case(0, format!("Migrated the config file from version 0 to {CONFIG_VERSION}\n"))]
+#[case(
+    CONFIG_VERSION,
+    format!("The config file is already at version {CONFIG_VERSION}\n"),
+)]
+fn config_migrate_prints_result(
+    #[case] from: u32,
+    #[case] expected: String,
 ) {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let given_dir = PathBuf::from("/Given/Path");
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.migrate_exp_path = Some(&given_dir);
+    api.migrate_const_rt = Ok(from);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_config_migrate_subcommand(path, false),
+    };
 
-    api.is_tendrils_repo_const_rt = true;
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&given_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_rt = Err(SetupError::NoValidTendrilsRepo(
-        GetTendrilsRepoError::GivenInvalid {
-            path: PathBuf::from("/Resolved/Returned/Path"),
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[test]
+fn config_migrate_unsupported_version_prints_error_and_returns_data_err() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.migrate_exp_path = Some(&given_dir);
+    api.migrate_const_rt = Err(SetupError::ConfigError(
+        GetConfigError::UnsupportedVersion {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            version: 5,
+            supported: 4,
         },
     ));
-
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_action_subcommand(
-        path,
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
+    let args = TendrilCliArgs {
+        tendrils_command: build_config_migrate_subcommand(path, false),
+    };
+
+    let expected = format!(
+        "{ERR_PREFIX}: The tendrils.json file is version 5, but this version \
+        of Tendrils only supports up to version 4. Update Tendrils to use this \
+        file\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    let expected =
-        format!("{ERR_PREFIX}: /Resolved/Returned/Path is not a Tendrils repo\n");
-
-    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
     assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-fn list_tendrils_prints_returned_resolved_path_when_invalid_td_repo() {
+#[test]
+fn config_migrate_comments_would_be_lost_err_prints_error_and_drop_comments_hint() {
     let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
-    let given_dir = PathBuf::from("/Given/Path");
-
-    api.is_tendrils_repo_const_rt = true;
-    api.list_exp_path = Some(&given_dir);
-    api.list_const_rt = Err(SetupError::NoValidTendrilsRepo(
-        GetTendrilsRepoError::GivenInvalid {
-            path: PathBuf::from("/Resolved/Returned/Path"),
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.migrate_exp_path = Some(&given_dir);
+    api.migrate_const_rt = Err(SetupError::ConfigError(
+        GetConfigError::CommentsWouldBeLost {
+            cfg_type: ConfigType::Repo(ConfigFormat::Jsonc),
         },
     ));
-
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_list_subcommand(
-        path,
-        vec![],
-        vec![],
-        None,
+    let args = TendrilCliArgs {
+        tendrils_command: build_config_migrate_subcommand(path, false),
+    };
+
+    let expected = format!(
+        "{ERR_PREFIX}: The tendrils.jsonc file contains comments, which would \
+         be removed by rewriting it\nUse --drop-comments to migrate it \
+         without its comments\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    let expected =
-        format!("{ERR_PREFIX}: /Resolved/Returned/Path is not a Tendrils repo\n");
-
-    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
     assert_eq!(writer.all_output, expected);
 }
 
-#[rstest]
-#[case(ActionMode::Pull)]
-#[case(ActionMode::Push)]
-#[case(ActionMode::Link)]
-#[case(ActionMode::Out)]
-fn tendril_action_prints_progress_to_stderr_and_table_to_stdout(
-    #[case] mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
-) {
+#[test]
+fn config_migrate_passes_drop_comments_flag() {
     let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
     let given_dir = PathBuf::from("/SomeGivenDir");
-    let mut t1 = RawTendril::new("SomeApp/misc.txt");
-    let mut t2 = RawTendril::new("SomeApp/misc.txt");
-    t1.remote = "r1/misc.txt".to_string();
-    t2.remote = "r2/misc.txt".to_string();
-    if mode == ActionMode::Link {
-        t1.mode = TendrilMode::Link;
-        t2.mode = TendrilMode::Link;
-    }
-    let ok_result = Ok(TendrilActionSuccess::New);
-    let err_result = Err(TendrilActionError::IoError {
+    api.migrate_exp_path = Some(&given_dir);
+    api.migrate_exp_drop_comments = true;
+    api.migrate_const_rt = Ok(CONFIG_VERSION);
+    let args = TendrilCliArgs::try_parse_from([
+        "td", "config", "migrate", "--drop-comments", "--path", "/SomeGivenDir",
+    ]).unwrap();
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
+#[test]
+fn config_migrate_global_prints_result() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    api.migrate_global_const_rt = Ok(CONFIG_VERSION);
+    let args = TendrilCliArgs::try_parse_from(["td", "config", "migrate", "--global"])
+        .unwrap();
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(
+        writer.all_output,
+        format!("The global config file is already at version {CONFIG_VERSION}\n"),
+    );
+}
+
+#[test]
+fn config_migrate_global_missing_file_prints_error_and_returns_no_input() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    api.migrate_global_const_rt = Err(GetConfigError::IoError {
+        cfg_type: ConfigType::Global,
         kind: std::io::ErrorKind::NotFound,
-        loc: Location::Source,
     });
+    let args = TendrilCliArgs::try_parse_from(["td", "config", "migrate", "--global"])
+        .unwrap();
 
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&given_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_count_updater_rt = 2;
-    api.tau_const_before_updater_rts = vec![t1.clone(), t2.clone()];
-    api.tau_const_after_updater_rts = vec![
-        TendrilReport {
-            raw_tendril: t1.clone(),
-            log: Ok(ActionLog::new(
-                Some(FsoType::File),
-                None,
-                PathBuf::from("r1/misc.txt"),
-                ok_result,
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t2.clone(),
-            log: Ok(ActionLog::new(
-                Some(FsoType::File),
-                None,
-                PathBuf::from("r2/misc.txt"),
-                err_result,
-            )),
-        },
-    ];
-
-    let mut writer = MockWriter::new();
-    let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_action_subcommand(
-        path,
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
-    );
-    let args = TendrilCliArgs { tendrils_command };
-
-    let _ = run(args, &api, &mut writer);
-
-    // Update this example progress updater as format changes in the future
-    // Processing [1/2]: r1/misc.txt (then line is cleared and overwritten)
-    // Processing [2/2]: r1/misc.txt (then line is cleared and overwritten)
-    let mut exp_std_err_lines = vec![format!(
-        "Processing [1/2]: r1/misc.txt{CLEAR_LINE}\
-        Processing [2/2]: r2/misc.txt{CLEAR_LINE}"
-    )];
+    let actual_exit_code = run(args, &api, &mut writer);
 
-    // Update this example table as format changes in the future
-    // ╭──────────────────┬─────────────┬──────────────────╮
-    // │ Local            │ Remote      │ Report           │
-    // ├──────────────────┼─────────────┼──────────────────┤
-    // │ SomeApp/misc.txt │ r1/misc.txt │ Created          │
-    // ├──────────────────┼─────────────┼──────────────────┤
-    // │ SomeApp/misc.txt │ r2/misc.txt │ Source not found │
-    // ╰──────────────────┴─────────────┴──────────────────╯
-    let exp_link_n1 = ansi_hyperlink("r1/misc.txt", "r1/misc.txt");
-    let exp_link_n2 = ansi_hyperlink("r2/misc.txt", "r2/misc.txt");
-    let exp_std_out_lines = vec![
-        "╭──────────────────┬─────────────┬──────────────────╮".to_string(),
-        format!(
-            "│ {color_bright_green}{style_underline}Local{color_reset}{style_reset}            \
-            │ {color_bright_green}{style_underline}Remote{color_reset}{style_reset}      \
-            │ {color_bright_green}{style_underline}Report{color_reset}{style_reset}           │"
-        ),
-        "├──────────────────┼─────────────┼──────────────────┤".to_string(),
-        format!(
-            "│ SomeApp/misc.txt │ {exp_link_n1} │ {color_bright_green}Created{color_reset}          │"
-        ),
-        "├──────────────────┼─────────────┼──────────────────┤".to_string(),
+    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(
+        writer.all_output,
         format!(
-            "│ SomeApp/misc.txt │ {exp_link_n2} │ {color_bright_red}Source not found{color_reset} │"
+            "{ERR_PREFIX}: IO error while reading the global-config.json \
+            file:\nentity not found\n"
         ),
-        "╰──────────────────┴─────────────┴──────────────────╯".to_string(),
-        format!("Total: 2, Successful: {color_bright_green}1{color_reset}, Failed: {color_bright_red}1{color_reset}"),
-    ];
+    );
+}
 
-    for (i, exp_line) in exp_std_err_lines.iter().enumerate() {
-        assert_eq!(
-            &writer.std_err_lines()[i],
-            exp_line,
-            "Failed on line: {}",
-            i + 1,
-        );
-    }
-    for (i, exp_line) in exp_std_out_lines.iter().enumerate() {
-        assert_eq!(
-            &writer.std_out_lines()[i],
-            exp_line,
-            "Failed on line: {}",
-            i + 1,
-        );
+#[rstest]
+#[case("--path")]
+#[case("--no-discovery")]
+#[case("--drop-comments")]
+fn config_migrate_global_with_repo_args_returns_err(#[case] flag: &str) {
+    let mut cli_args = vec!["td", "config", "migrate", "--global", flag];
+    if flag == "--path" {
+        cli_args.push("/SomeGivenDir");
     }
 
-    // Expect first line of stdout to be on the same line as the stderr that
-    // was cleared.
-    exp_std_err_lines[0].push_str(&exp_std_out_lines[0]);
-    exp_std_err_lines.append(&mut exp_std_out_lines[1..].to_vec());
+    let actual = TendrilCliArgs::try_parse_from(cli_args);
 
+    assert!(actual.is_err());
+}
 
-    let exp_all_output_lines = exp_std_err_lines;
-    assert_eq!(writer.all_output_lines(), exp_all_output_lines);
+fn build_doctor_subcommand(path: Option<String>) -> TendrilsSubcommands {
+    let path_args = PathArgs { path: path.into_iter().collect(), no_discovery: false };
+    TendrilsSubcommands::Doctor { path_args }
 }
 
-#[rstest]
-#[case(ActionMode::Pull)]
-#[case(ActionMode::Push)]
-#[case(ActionMode::Link)]
-#[case(ActionMode::Out)]
-fn tendril_action_if_all_pass_they_are_totalled_and_returns_ok(
-    #[case] mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
-) {
+#[test]
+fn doctor_no_diagnoses_prints_message() {
     let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
     let given_dir = PathBuf::from("/SomeGivenDir");
-    let mut t1 = RawTendril::new("SomeApp/misc.txt");
-    let mut t2 = RawTendril::new("SomeApp/misc.txt");
-    let mut t3 = RawTendril::new("SomeApp/misc.txt");
-    t1.remote = "r1".to_string();
-    t2.remote = "r2".to_string();
-    t3.remote = "r3".to_string();
-    if mode == ActionMode::Link {
-        t1.mode = TendrilMode::Link;
-        t2.mode = TendrilMode::Link;
-        t3.mode = TendrilMode::Link;
-    }
-    let ok_result = Ok(TendrilActionSuccess::New);
+    api.diagnose_exp_path = Some(&given_dir);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs { tendrils_command: build_doctor_subcommand(path) };
 
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&given_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.tau_const_count_updater_rt = 3;
-    api.tau_const_before_updater_rts = vec![t1.clone(), t2.clone(), t3.clone()];
-    api.tau_const_after_updater_rts = vec![
-        TendrilReport {
-            raw_tendril: t1.clone(),
-            log: Ok(ActionLog::new(
-                Some(FsoType::File),
-                None,
-                PathBuf::from("r1"),
-                ok_result.clone(),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t2.clone(),
-            log: Ok(ActionLog::new(
-                None,
-                None,
-                PathBuf::from("r2"),
-                ok_result.clone(),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t3.clone(),
-            log: Ok(ActionLog::new(
-                Some(FsoType::Dir),
-                None,
-                PathBuf::from("r3"),
-                ok_result,
-            )),
-        },
-    ];
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "No problems found\n");
+}
 
+#[test]
+fn doctor_only_warnings_prints_each_with_fix_and_returns_ok() {
+    let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.diagnose_exp_path = Some(&given_dir);
+    api.diagnose_const_rt = vec![Diagnosis::LocalCaseConflict {
+        first: "SomeApp/misc.txt".to_string(),
+        second: "SomeApp/MISC.txt".to_string(),
+    }];
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_action_subcommand(
-        path,
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
+    let args = TendrilCliArgs { tendrils_command: build_doctor_subcommand(path) };
+
+    let expected = format!(
+        "{color_bright_yellow}Warning{color_reset}: The locals \
+        \"SomeApp/misc.txt\" and \"SomeApp/MISC.txt\" only differ by case\n    \
+        Fix: Rename one of the locals so that they differ by more than case\n\
+        \n\
+        0 errors, 1 warning\n"
     );
-    let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
     assert_eq!(actual_exit_code, Ok(()));
-    assert_eq!(
-        writer.all_output_lines().last().unwrap().to_string(),
-        format!(
-            "Total: 3, Successful: {color_bright_green}3{color_reset}, \
-             Failed: {color_bright_red}0{color_reset}"
-        )
+    assert_eq!(writer.all_output, expected);
+}
+
+#[test]
+fn doctor_repo_config_parse_error_prints_snippet_before_fix() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.diagnose_exp_path = Some(&given_dir);
+    api.diagnose_const_rt = vec![Diagnosis::RepoConfig(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "missing field `remotes`".to_string(),
+        loc: Some(ParseErrorLocation {
+            line: 3,
+            column: 21,
+            key_path: "tendrils.SomeApp".to_string(),
+            line_text: "    \"SomeApp\": {}".to_string(),
+        }),
+    })];
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs { tendrils_command: build_doctor_subcommand(path) };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert!(writer.all_output.contains(&format!(
+        "missing field `remotes` at line 3 column 21 in tendrils.SomeApp\n  \
+        |\n\
+        3 |     \"SomeApp\": {{}}\n  \
+        |                     {color_bright_red}^{color_reset}\n    \
+        Fix: "
+    )));
+}
+
+#[test]
+fn doctor_any_errors_prints_each_with_fix_and_returns_exit_code() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.diagnose_exp_path = Some(&given_dir);
+    api.diagnose_const_rt = vec![
+        Diagnosis::UnresolvedVariable {
+            name: "SOME_VAR".to_string(),
+            locals: vec!["SomeApp/misc.txt".to_string(), "OtherApp".to_string()],
+            in_fallbacks_only: false,
+        },
+        Diagnosis::BrokenSymlink {
+            local: "SomeApp/misc.txt".to_string(),
+            remote: PathBuf::from("/SomeRemote"),
+        },
+    ];
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs { tendrils_command: build_doctor_subcommand(path) };
+
+    let expected = format!(
+        "{color_bright_red}Error{color_reset}: The variable \"SOME_VAR\" is \
+        not set, but is used in the remotes of \"SomeApp/misc.txt\", \
+        \"OtherApp\"\n    \
+        Fix: Set the SOME_VAR environment variable, give it a default (i.e. \
+        ${{SOME_VAR:-default}}), or add a fallback remote\n\
+        {color_bright_yellow}Warning{color_reset}: The remote \"/SomeRemote\" \
+        of \"SomeApp/misc.txt\" is a broken symlink\n    \
+        Fix: Restore \"SomeApp/misc.txt\" in the Tendrils repo, or link the \
+        tendril again with the force option\n\
+        \n\
+        1 error, 1 warning\n"
     );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+fn build_fmt_subcommand(
+    path: Option<String>,
+    check: bool,
+    sort_keys: bool,
+    drop_comments: bool,
+) -> TendrilsSubcommands {
+    let path_args = PathArgs { path: path.into_iter().collect(), no_discovery: false };
+    TendrilsSubcommands::Fmt { path_args, check, sort_keys, drop_comments }
 }
 
 #[rstest]
-#[case(ActionMode::Pull)]
-#[case(ActionMode::Push)]
-#[case(ActionMode::Link)]
-#[case(ActionMode::Out)]
-fn tendril_action_if_any_fail_they_are_totalled_and_returns_exit_code(
-    #[case] mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
+#[case(true, true, "The config file is not formatted\n", Err(exitcode::DATAERR))]
+#[case(true, false, "The config file is already formatted\n", Ok(()))]
+#[case(false, true, "Formatted the config file\n", Ok(()))]
+#[case(false, false, "The config file is already formatted\n", Ok(()))]
+fn fmt_prints_result_and_returns_exit_code(
+    #[case] check: bool,
+    #[case] changed: bool,
+    #[case] exp_output: &str,
+    #[case] exp_exit_code: Result<(), i32>,
 ) {
     let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
     let given_dir = PathBuf::from("/SomeGivenDir");
-    let mut t1 = RawTendril::new("SomeApp/misc.txt");
-    let mut t2 = RawTendril::new("SomeApp/misc.txt");
-    let mut t3 = RawTendril::new("SomeApp/misc.txt");
-    t1.remote = "r1".to_string();
-    t2.remote = "r2".to_string();
-    t3.remote = "r3".to_string();
-    if mode == ActionMode::Link {
-        t1.mode = TendrilMode::Link;
-        t2.mode = TendrilMode::Link;
-        t3.mode = TendrilMode::Link;
-    }
-    let ok_result = Ok(TendrilActionSuccess::New);
-    let err_result = Err(TendrilActionError::IoError {
-        kind: std::io::ErrorKind::NotFound,
-        loc: Location::Source,
-    });
+    api.fmt_exp_path = Some(&given_dir);
+    api.fmt_exp_check = check;
+    api.fmt_exp_sort_keys = true;
+    api.fmt_exp_drop_comments = true;
+    api.fmt_const_rt = Ok(changed);
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_fmt_subcommand(path, check, true, true),
+    };
 
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&given_dir);
-    api.ta_exp_filter = FilterSpec::new();
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, exp_exit_code);
+    assert_eq!(writer.all_output, exp_output);
+}
+
+#[test]
+fn fmt_error_prints_error_and_returns_exit_code() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.fmt_exp_path = Some(&given_dir);
+    api.fmt_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_fmt_subcommand(path, false, false, false),
+    };
+
+    let expected = format!(
+        "{ERR_PREFIX}: Could not parse the tendrils.json file:\nSome parse \
+         error msg\n"
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[test]
+fn fmt_comments_would_be_lost_err_prints_error_and_drop_comments_hint() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.fmt_exp_path = Some(&given_dir);
+    api.fmt_const_rt = Err(SetupError::ConfigError(
+        GetConfigError::CommentsWouldBeLost {
+            cfg_type: ConfigType::Repo(ConfigFormat::Jsonc),
+        },
+    ));
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_fmt_subcommand(path, false, false, false),
+    };
+
+    let expected = format!(
+        "{ERR_PREFIX}: The tendrils.jsonc file contains comments, which would \
+         be removed by rewriting it\nUse --drop-comments to format it \
+         without its comments\n"
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[test]
+fn fmt_parses_flags() {
+    let args = TendrilCliArgs::try_parse_from([
+        "td", "fmt", "--check", "--sort-keys", "--drop-comments", "--path",
+        "/SomeGivenDir",
+    ]).unwrap();
+
+    match args.tendrils_command {
+        TendrilsSubcommands::Fmt { path_args, check, sort_keys, drop_comments } => {
+            assert_eq!(path_args.path, vec!["/SomeGivenDir".to_string()]);
+            assert!(check);
+            assert!(sort_keys);
+            assert!(drop_comments);
+        }
+        _ => panic!(),
+    }
+}
+
+fn build_repo_subcommand(
+    path: Option<String>,
+    no_discovery: bool,
+) -> TendrilsSubcommands {
+    let path_args = PathArgs { path: path.into_iter().collect(), no_discovery };
+    TendrilsSubcommands::Repo { path_args }
+}
+
+#[test]
+fn repo_given_path_is_tendrils_repo_prints_path_and_reason() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    api.is_tendrils_repo_const_rt = true;
+    let path = Some("/SomeGivenDir".to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_repo_subcommand(path, false),
+    };
+
+    let expected = format!(
+        "{}\nGiven by the --path argument\n",
+        ansi_hyperlink("/SomeGivenDir", "/SomeGivenDir"),
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[test]
+fn repo_given_path_is_not_tendrils_repo_prints_message() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    api.is_tendrils_repo_const_rt = false;
+    let path = Some("/SomeGivenDir".to_string());
+    let args = TendrilCliArgs {
+        tendrils_command: build_repo_subcommand(path, false),
+    };
+
+    let expected =
+        format!("{ERR_PREFIX}: /SomeGivenDir is not a Tendrils repo\n");
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn repo_no_path_given_and_cd_is_tendrils_repo_prints_cd_and_reason(
+    #[values(true, false)] no_discovery: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    api.is_tendrils_repo_const_rt = true;
+    let args = TendrilCliArgs {
+        tendrils_command: build_repo_subcommand(None, no_discovery),
+    };
+
+    let cd_str = cd.to_string_lossy();
+    let expected = format!(
+        "{}\nThe current directory is a Tendrils repo\n",
+        ansi_hyperlink(&cd_str, &cd_str),
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn repo_no_path_given_and_parent_of_cd_is_tendrils_repo_prints_parent_and_reason(
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let repo = temp_dir.path().to_path_buf();
+    let repo_for_closure = repo.clone();
+    let cd = repo.join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    let repo_for_is_repo = repo.clone();
+    api.is_tendrils_repo_fn = Some(Box::new(move |dir| {
+        dir.inner() == repo_for_is_repo
+    }));
+    api.find_tendrils_repo_fn = Some(Box::new(move |_| {
+        Some(UniPath::from(&repo_for_closure))
+    }));
+    let args = TendrilCliArgs {
+        tendrils_command: build_repo_subcommand(None, false),
+    };
+
+    let repo_str = repo.to_string_lossy();
+    let expected = format!(
+        "{}\nFound in a parent folder of the current directory \"{}\"\n",
+        ansi_hyperlink(&repo_str, &repo_str),
+        cd.to_string_lossy(),
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[case(false, "Using the default repo, as neither the current directory nor \
+    any of its parent folders are a Tendrils repo")]
+#[case(true, "Using the default repo, as the current directory is not a \
+    Tendrils repo (searching parent folders is disabled)")]
+#[serial(SERIAL_CD)]
+fn repo_no_path_given_and_no_repo_found_prints_default_and_reason(
+    #[case] no_discovery: bool,
+    #[case] exp_reason: &str,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    api.is_tendrils_repo_fn = Some(Box::new(|dir| {
+        dir.inner() == Path::new("/SomeDefaultDir")
+    }));
+    api.get_default_repo_const_rt = Ok(Some(PathBuf::from("/SomeDefaultDir")));
+    let args = TendrilCliArgs {
+        tendrils_command: build_repo_subcommand(None, no_discovery),
+    };
+
+    let expected = format!(
+        "{}\n{exp_reason}\n",
+        ansi_hyperlink("/SomeDefaultDir", "/SomeDefaultDir"),
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn repo_no_repo_found_and_default_is_invalid_or_unset_prints_message(
+    #[values(true, false)] default_set: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    api.is_tendrils_repo_const_rt = false;
+    let expected;
+    if default_set {
+        api.get_default_repo_const_rt =
+            Ok(Some(PathBuf::from("/SomeDefaultDir")));
+        expected = format!(
+            "{ERR_PREFIX}: The default path \"/SomeDefaultDir\" is not a \
+            Tendrils repo\n"
+        );
+    }
+    else {
+        api.get_default_repo_const_rt = Ok(None);
+        expected = format!(
+            "{ERR_PREFIX}: The default Tendrils repo path is not set\n"
+        );
+    }
+    let args = TendrilCliArgs {
+        tendrils_command: build_repo_subcommand(None, false),
+    };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+#[cfg_attr(windows, ignore)]
+fn tendril_action_no_path_given_and_no_cd_prints_message(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    std::fs::remove_dir(&cd).unwrap();
+
+    api.ta_fn = Some(Box::new(|_, _, _, _, _| panic!()));
+
+    let tendrils_command = build_action_subcommand(
+        None,
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let expected =
+        format!("{ERR_PREFIX}: Could not get the current directory\n");
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::OSERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+#[cfg_attr(windows, ignore)]
+fn list_tendrils_no_path_given_and_no_cd_prints_message() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    std::fs::remove_dir(&cd).unwrap();
+
+    api.list_fn = Some(Box::new(|_, _| panic!()));
+
+    let tendrils_command = build_list_subcommand(
+        None,
+        vec![],
+        vec![],
+        None
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let expected =
+        format!("{ERR_PREFIX}: Could not get the current directory\n");
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::OSERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn list_tendrils_no_path_given_and_cd_is_tendrils_repo_uses_cd() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = true;
+    api.list_exp_path = Some(&cd);
+    api.validate_exp_path = Some(&cd);
+
+    let tendrils_command = build_list_subcommand(None, vec![], vec![], None);
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn list_tendrils_no_path_given_and_parent_of_cd_is_tendrils_repo_uses_parent(
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let repo = temp_dir.path().to_path_buf();
+    let repo_for_closure = repo.clone();
+    let cd = repo.join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = false;
+    api.find_tendrils_repo_fn = Some(Box::new(move |dir| {
+        if dir.inner().starts_with(&repo_for_closure) {
+            Some(UniPath::from(&repo_for_closure))
+        }
+        else {
+            None
+        }
+    }));
+    api.list_exp_path = Some(&repo);
+    api.validate_exp_path = Some(&repo);
+
+    let tendrils_command = build_list_subcommand(None, vec![], vec![], None);
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn list_tendrils_no_path_given_and_no_repo_found_uses_default_repo() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = false;
+    api.find_tendrils_repo_fn = Some(Box::new(|_| None));
+    api.list_exp_path = None;
+
+    let tendrils_command = build_list_subcommand(None, vec![], vec![], None);
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn list_tendrils_no_discovery_does_not_search_parents_of_cd(
+    #[values(true, false)] cd_is_repo: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = cd_is_repo;
+    api.find_tendrils_repo_fn = Some(Box::new(|_| panic!()));
+    if cd_is_repo {
+        api.list_exp_path = Some(&cd);
+        api.validate_exp_path = Some(&cd);
+    }
+    else {
+        api.list_exp_path = None;
+    }
+
+    let mut tendrils_command =
+        build_list_subcommand(None, vec![], vec![], None);
+    if let TendrilsSubcommands::List { path_args, .. } = &mut tendrils_command {
+        path_args.no_discovery = true;
+    }
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn tendril_action_given_path_is_not_tendrils_repo_but_cd_is_should_print_message(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    let cd_for_closure = cd.clone();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_fn = Some(Box::new(move |dir| {
+        dir.inner() == cd_for_closure
+    }));
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path= Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_rt = Err(SetupError::NoValidTendrilsRepo(
+        GetTendrilsRepoError::GivenInvalid { path: given_dir.clone() },
+    ));
+
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let expected =
+        format!("{ERR_PREFIX}: /SomeGivenDir is not a Tendrils repo\n");
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn list_tendril_given_path_is_not_tendrils_repo_but_cd_is_should_print_message()
+{
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    let cd_for_closure = cd.clone();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_fn = Some(Box::new(move |dir| {
+        dir.inner() == cd_for_closure
+    }));
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Err(SetupError::NoValidTendrilsRepo(
+        GetTendrilsRepoError::GivenInvalid { path: given_dir.clone() },
+    ));
+
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(
+        path,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let expected =
+        format!("{ERR_PREFIX}: /SomeGivenDir is not a Tendrils repo\n");
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn tendril_action_given_path_and_cd_are_both_tendrils_repos_uses_given_path(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = true;
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let expected = format!(
+        "{ERR_PREFIX}: Could not parse the tendrils.json file:\nSome parse \
+         error msg\n"
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn list_tendrils_given_path_and_cd_are_both_tendrils_repos_uses_given_path() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = true;
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(
+        path,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let expected = format!(
+        "{ERR_PREFIX}: Could not parse the tendrils.json file:\nSome parse \
+         error msg\n"
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[test]
+fn list_tendrils_invalid_profile_filter_prints_message() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_exp_filter.profiles = Some(vec!["p1 &".to_string()]);
+    api.list_const_rt = Err(SetupError::InvalidProfileFilter {
+        filter: "p1 &".to_string(),
+        msg: "Some parse error msg".to_string(),
+    });
+
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(
+        path,
+        vec![],
+        vec![],
+        Some(vec!["p1 &".to_string()]),
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let expected = format!(
+        "{ERR_PREFIX}: Invalid profile filter \"p1 &\":\nSome parse error msg\n"
+    );
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::USAGE));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn tendril_action_given_path_is_relative_prepends_with_cd(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let user_given_dir = "../Relative/Path";
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    // The `..` is normalized
+    let exp_passed_dir = temp_dir.path().join("Relative/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&exp_passed_dir);
+    api.validate_exp_path = Some(&exp_passed_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let tendrils_command = build_action_subcommand(
+        Some(user_given_dir.to_string()),
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn list_tendrils_given_path_is_relative_prepends_with_cd() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let user_given_dir = "../Relative/Path";
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    // The `..` is normalized
+    let exp_passed_dir = temp_dir.path().join("Relative/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.list_exp_path = Some(&exp_passed_dir);
+    api.validate_exp_path = Some(&exp_passed_dir);
+    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let tendrils_command = build_list_subcommand(
+        Some(user_given_dir.to_string()),
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+#[cfg_attr(windows, ignore)]
+fn tendril_action_given_path_is_relative_and_cd_doesnt_exist_prepends_with_dir_sep(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let user_given_dir = "../Relative/Path";
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    std::fs::remove_dir(&cd).unwrap();
+    let exp_passed_dir = PathBuf::from("/Relative/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&exp_passed_dir);
+    api.validate_exp_path = Some(&exp_passed_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let tendrils_command = build_action_subcommand(
+        Some(user_given_dir.to_string()),
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+#[cfg_attr(windows, ignore)]
+fn list_tendrils_given_path_is_relative_and_cd_doesnt_exist_prepends_with_dir_sep(
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let user_given_dir = "../Relative/Path";
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    std::fs::remove_dir(&cd).unwrap();
+    let exp_passed_dir = PathBuf::from("/Relative/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.list_exp_path = Some(&exp_passed_dir);
+    api.validate_exp_path = Some(&exp_passed_dir);
+    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let tendrils_command = build_list_subcommand(
+        Some(user_given_dir.to_string()),
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+}
+
+#[rstest]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn tendril_action_given_path_is_relative_but_resolves_to_abs_should_not_prepend_cd(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    std::env::set_var("HOME", "/Some/Abs/Path");
+    let user_given_dir = "~/Relative/Path";
+    let exp_passed_dir = PathBuf::from("/Some/Abs/Path/Relative/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&exp_passed_dir);
+    api.validate_exp_path = Some(&exp_passed_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let tendrils_command = build_action_subcommand(
+        Some(user_given_dir.to_string()),
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+}
+
+#[rstest]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn list_tendrils_given_path_is_relative_but_resolves_to_abs_should_not_prepend_cd(
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    std::env::set_var("HOME", "/Some/Abs/Path");
+    let user_given_dir = "~/Relative/Path";
+    let exp_passed_dir = PathBuf::from("/Some/Abs/Path/Relative/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.list_exp_path = Some(&exp_passed_dir);
+    api.validate_exp_path = Some(&exp_passed_dir);
+    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let tendrils_command = build_list_subcommand(
+        Some(user_given_dir.to_string()),
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+}
+
+#[rstest]
+#[serial(SERIAL_CD, SERIAL_MUT_ENV_VARS)]
+#[cfg_attr(windows, ignore)]
+fn tendril_action_given_path_is_relative_but_resolves_to_abs_and_cd_doesnt_exist_should_not_prepend_cd(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    std::env::set_var("HOME", "/Some/Abs/Path");
+    let user_given_dir = "~/Relative/Path";
+    let exp_passed_dir = PathBuf::from("/Some/Abs/Path/Relative/Path");
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    std::fs::remove_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = true;
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&exp_passed_dir);
+    api.validate_exp_path = Some(&exp_passed_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let tendrils_command = build_action_subcommand(
+        Some(user_given_dir.to_string()),
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+}
+
+#[rstest]
+#[serial(SERIAL_CD, SERIAL_MUT_ENV_VARS)]
+#[cfg_attr(windows, ignore)]
+fn list_tendrils_given_path_is_relative_but_resolves_to_abs_and_cd_doesnt_exist_should_not_prepend_cd(
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    std::env::set_var("HOME", "/Some/Abs/Path");
+    let user_given_dir = "~/Relative/Path";
+    let exp_passed_dir = PathBuf::from("/Some/Abs/Path/Relative/Path");
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    std::fs::remove_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = true;
+    api.list_exp_path = Some(&exp_passed_dir);
+    api.validate_exp_path = Some(&exp_passed_dir);
+    api.list_const_rt = Err(SetupError::ConfigError(GetConfigError::ParseError {
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        msg: "Some parse error msg".to_string(),
+        loc: None,
+    }));
+
+    let tendrils_command = build_list_subcommand(
+        Some(user_given_dir.to_string()),
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+}
+
+#[rstest]
+fn tendril_action_prints_returned_resolved_path_when_invalid_td_repo(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/Given/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_rt = Err(SetupError::NoValidTendrilsRepo(
+        GetTendrilsRepoError::GivenInvalid {
+            path: PathBuf::from("/Resolved/Returned/Path"),
+        },
+    ));
+
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    let expected =
+        format!("{ERR_PREFIX}: /Resolved/Returned/Path is not a Tendrils repo\n");
+
+    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+fn list_tendrils_prints_returned_resolved_path_when_invalid_td_repo() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/Given/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Err(SetupError::NoValidTendrilsRepo(
+        GetTendrilsRepoError::GivenInvalid {
+            path: PathBuf::from("/Resolved/Returned/Path"),
+        },
+    ));
+
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(
+        path,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    let expected =
+        format!("{ERR_PREFIX}: /Resolved/Returned/Path is not a Tendrils repo\n");
+
+    assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+#[case(ActionMode::Pull)]
+#[case(ActionMode::Push)]
+#[case(ActionMode::Link)]
+#[case(ActionMode::Out)]
+#[case(ActionMode::Relink)]
+fn tendril_action_prints_progress_to_stderr_and_table_to_stdout(
+    #[case] mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut t1 = RawTendril::new("SomeApp/misc.txt");
+    let mut t2 = RawTendril::new("SomeApp/misc.txt");
+    t1.remote = "r1/misc.txt".to_string();
+    t2.remote = "r2/misc.txt".to_string();
+    if mode == ActionMode::Link {
+        t1.mode = TendrilMode::Link;
+        t2.mode = TendrilMode::Link;
+    }
+    let ok_result = Ok(TendrilActionSuccess::New);
+    let err_result = Err(TendrilActionError::IoError {
+        kind: std::io::ErrorKind::NotFound,
+        loc: Location::Source,
+    });
+
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_count_updater_rt = 2;
+    api.tau_const_before_updater_rts = vec![t1.clone(), t2.clone()];
+    api.tau_const_after_updater_rts = vec![
+        TendrilReport {
+            raw_tendril: t1.clone(),
+            log: Ok(ActionLog::new(
+                Some(FsoType::File),
+                None,
+                PathBuf::from("r1/misc.txt"),
+                ok_result,
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t2.clone(),
+            log: Ok(ActionLog::new(
+                Some(FsoType::File),
+                None,
+                PathBuf::from("r2/misc.txt"),
+                err_result,
+            )),
+        },
+    ];
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let _ = run(args, &api, &mut writer);
+
+    // Update this example progress updater as format changes in the future
+    // Processing [1/2]: r1/misc.txt (then line is cleared and overwritten)
+    // Processing [2/2]: r1/misc.txt (then line is cleared and overwritten)
+    let mut exp_std_err_lines = vec![format!(
+        "Processing [1/2]: r1/misc.txt{CLEAR_LINE}\
+        Processing [2/2]: r2/misc.txt{CLEAR_LINE}"
+    )];
+
+    // Update this example table as format changes in the future
+    // ╭──────────────────┬─────────────┬──────────────────╮
+    // │ Local            │ Remote      │ Report           │
+    // ├──────────────────┼─────────────┼──────────────────┤
+    // │ SomeApp/misc.txt │ r1/misc.txt │ Created          │
+    // ├──────────────────┼─────────────┼──────────────────┤
+    // │ SomeApp/misc.txt │ r2/misc.txt │ Source not found │
+    // ╰──────────────────┴─────────────┴──────────────────╯
+    let exp_link_n1 = ansi_hyperlink("r1/misc.txt", "r1/misc.txt");
+    let exp_link_n2 = ansi_hyperlink("r2/misc.txt", "r2/misc.txt");
+    let exp_std_out_lines = vec![
+        "╭──────────────────┬─────────────┬──────────────────╮".to_string(),
+        format!(
+            "│ {color_bright_green}{style_underline}Local{color_reset}{style_reset}            \
+            │ {color_bright_green}{style_underline}Remote{color_reset}{style_reset}      \
+            │ {color_bright_green}{style_underline}Report{color_reset}{style_reset}           │"
+        ),
+        "├──────────────────┼─────────────┼──────────────────┤".to_string(),
+        format!(
+            "│ SomeApp/misc.txt │ {exp_link_n1} │ {color_bright_green}Created{color_reset}          │"
+        ),
+        "├──────────────────┼─────────────┼──────────────────┤".to_string(),
+        format!(
+            "│ SomeApp/misc.txt │ {exp_link_n2} │ {color_bright_red}Source not found{color_reset} │"
+        ),
+        "╰──────────────────┴─────────────┴──────────────────╯".to_string(),
+        format!("Total: 2, Successful: {color_bright_green}1{color_reset}, Failed: {color_bright_red}1{color_reset}"),
+    ];
+
+    for (i, exp_line) in exp_std_err_lines.iter().enumerate() {
+        assert_eq!(
+            &writer.std_err_lines()[i],
+            exp_line,
+            "Failed on line: {}",
+            i + 1,
+        );
+    }
+    for (i, exp_line) in exp_std_out_lines.iter().enumerate() {
+        assert_eq!(
+            &writer.std_out_lines()[i],
+            exp_line,
+            "Failed on line: {}",
+            i + 1,
+        );
+    }
+
+    // Expect first line of stdout to be on the same line as the stderr that
+    // was cleared.
+    exp_std_err_lines[0].push_str(&exp_std_out_lines[0]);
+    exp_std_err_lines.append(&mut exp_std_out_lines[1..].to_vec());
+
+
+    let exp_all_output_lines = exp_std_err_lines;
+    assert_eq!(writer.all_output_lines(), exp_all_output_lines);
+}
+
+#[rstest]
+#[case(ActionMode::Pull)]
+#[case(ActionMode::Push)]
+#[case(ActionMode::Out)]
+fn tendril_action_prints_copy_progress_bar_after_processing_text(
+    #[case] mode: ActionMode,
+) {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut t1 = RawTendril::new("SomeApp/misc");
+    t1.remote = "r1/misc".to_string();
+
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.tau_const_count_updater_rt = 1;
+    api.tau_const_before_updater_rts = vec![t1.clone()];
+    api.tau_const_after_updater_rts = vec![TendrilReport {
+        raw_tendril: t1.clone(),
+        log: Ok(ActionLog::new(
+            Some(FsoType::Dir),
+            None,
+            PathBuf::from("r1/misc"),
+            Ok(TendrilActionSuccess::New),
+        )),
+    }];
+    api.tau_const_progress_updater_rts = vec![vec![
+        CopyProgress::Start { files: 2, bytes: 2048 },
+        CopyProgress::File { path: PathBuf::from("SomeApp/misc/a.txt") },
+        CopyProgress::Bytes { copied: 1024 },
+        CopyProgress::File { path: PathBuf::from("SomeApp/misc/b.txt") },
+        CopyProgress::Bytes { copied: 2048 },
+    ]];
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode,
+        false,
+        false,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let _ = run(args, &api, &mut writer);
+
+    // Intermediate updates may or may not be drawn depending on timing,
+    // but the first and the complete ones always are
+    // Processing [1/1]: r1/misc [--------------------] 0% 0 B/2.0 KiB (0/2 files)
+    // Processing [1/1]: r1/misc [####################] 100% 2.0 KiB/2.0 KiB (2/2 files, 1.2 MiB/s)
+    let exp_start = format!(
+        "Processing [1/1]: r1/misc{CLEAR_LINE}\
+        Processing [1/1]: r1/misc [--------------------] 0% 0 B/2.0 KiB (0/2 files)"
+    );
+    let exp_complete = format!(
+        "{CLEAR_LINE}Processing [1/1]: r1/misc [####################] 100% \
+        2.0 KiB/2.0 KiB (2/2 files"
+    );
+    assert!(writer.std_err.starts_with(&exp_start), "{}", writer.std_err);
+    let last_draw = writer.std_err.rfind(CLEAR_LINE).unwrap();
+    let complete_draw = writer.std_err[..last_draw].rfind(CLEAR_LINE).unwrap();
+    assert!(writer.std_err[complete_draw..].starts_with(&exp_complete));
+    assert!(writer.std_err.ends_with(CLEAR_LINE));
+}
+
+#[rstest]
+#[case(ActionMode::Pull)]
+#[case(ActionMode::Push)]
+#[case(ActionMode::Link)]
+#[case(ActionMode::Out)]
+#[case(ActionMode::Relink)]
+fn tendril_action_if_all_pass_they_are_totalled_and_returns_ok(
+    #[case] mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut t1 = RawTendril::new("SomeApp/misc.txt");
+    let mut t2 = RawTendril::new("SomeApp/misc.txt");
+    let mut t3 = RawTendril::new("SomeApp/misc.txt");
+    t1.remote = "r1".to_string();
+    t2.remote = "r2".to_string();
+    t3.remote = "r3".to_string();
+    if mode == ActionMode::Link {
+        t1.mode = TendrilMode::Link;
+        t2.mode = TendrilMode::Link;
+        t3.mode = TendrilMode::Link;
+    }
+    let ok_result = Ok(TendrilActionSuccess::New);
+
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.tau_const_count_updater_rt = 3;
+    api.tau_const_before_updater_rts = vec![t1.clone(), t2.clone(), t3.clone()];
+    api.tau_const_after_updater_rts = vec![
+        TendrilReport {
+            raw_tendril: t1.clone(),
+            log: Ok(ActionLog::new(
+                Some(FsoType::File),
+                None,
+                PathBuf::from("r1"),
+                ok_result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t2.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r2"),
+                ok_result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t3.clone(),
+            log: Ok(ActionLog::new(
+                Some(FsoType::Dir),
+                None,
+                PathBuf::from("r3"),
+                ok_result,
+            )),
+        },
+    ];
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(
+        writer.all_output_lines().last().unwrap().to_string(),
+        format!(
+            "Total: 3, Successful: {color_bright_green}3{color_reset}, \
+             Failed: {color_bright_red}0{color_reset}"
+        )
+    );
+}
+
+#[rstest]
+#[case(ActionMode::Pull)]
+#[case(ActionMode::Push)]
+#[case(ActionMode::Link)]
+#[case(ActionMode::Out)]
+#[case(ActionMode::Relink)]
+fn tendril_action_if_any_fail_they_are_totalled_and_returns_exit_code(
+    #[case] mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut t1 = RawTendril::new("SomeApp/misc.txt");
+    let mut t2 = RawTendril::new("SomeApp/misc.txt");
+    let mut t3 = RawTendril::new("SomeApp/misc.txt");
+    t1.remote = "r1".to_string();
+    t2.remote = "r2".to_string();
+    t3.remote = "r3".to_string();
+    if mode == ActionMode::Link {
+        t1.mode = TendrilMode::Link;
+        t2.mode = TendrilMode::Link;
+        t3.mode = TendrilMode::Link;
+    }
+    let ok_result = Ok(TendrilActionSuccess::New);
+    let err_result = Err(TendrilActionError::IoError {
+        kind: std::io::ErrorKind::NotFound,
+        loc: Location::Source,
+    });
+
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
     api.ta_exp_filter.mode = Some(mode.clone());
     api.ta_exp_dry_run = dry_run;
     api.ta_exp_force = force;
@@ -1608,6 +3178,7 @@ fn tendril_action_if_any_fail_they_are_totalled_and_returns_exit_code(
 #[case(ActionMode::Push)]
 #[case(ActionMode::Link)]
 #[case(ActionMode::Out)]
+#[case(ActionMode::Relink)]
 fn tendril_action_order_of_reports_is_unchanged(
     #[case] mode: ActionMode,
     #[values(true, false)] dry_run: bool,
@@ -1652,6 +3223,7 @@ fn tendril_action_order_of_reports_is_unchanged(
 
     api.ta_exp_mode = mode.clone();
     api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
     api.ta_exp_filter = FilterSpec::new();
     api.ta_exp_filter.mode = Some(mode.clone());
     api.ta_exp_dry_run = dry_run;
@@ -1671,94 +3243,672 @@ fn tendril_action_order_of_reports_is_unchanged(
     api.tau_const_after_updater_rts = vec![
         TendrilReport {
             raw_tendril: t2_3.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r2_3"),
+                result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t2_2.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r2_2"),
+                result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t2_1.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r2_1"),
+                result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t1_2.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r1_2"),
+                result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t1_1.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r1_1"),
+                result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t1_3.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r1_3"),
+                result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t3_3.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r3_3"),
+                result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t3_3.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r3_1"),
+                result.clone(),
+            )),
+        },
+        TendrilReport {
+            raw_tendril: t3_2.clone(),
+            log: Ok(ActionLog::new(
+                None,
+                None,
+                PathBuf::from("r3_2"),
+                result.clone(),
+            )),
+        },
+    ];
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    println!("{}", writer.all_output);
+    assert_eq!(actual_exit_code, Err(exitcode::SOFTWARE));
+    assert_eq!(
+        writer.all_output_lines().last().unwrap().to_string(),
+        format!(
+            "Total: 9, Successful: {color_bright_green}0{color_reset}, \
+             Failed: {color_bright_red}9{color_reset}"
+        )
+    );
+    assert!(writer.all_output_lines()[3] .contains("r2_3"));
+    assert!(writer.all_output_lines()[5] .contains("r2_2"));
+    assert!(writer.all_output_lines()[7] .contains("r2_1"));
+    assert!(writer.all_output_lines()[9] .contains("r1_2"));
+    assert!(writer.all_output_lines()[11].contains("r1_1"));
+    assert!(writer.all_output_lines()[13].contains("r1_3"));
+    assert!(writer.all_output_lines()[15].contains("r3_3"));
+    assert!(writer.all_output_lines()[17].contains("r3_1"));
+    assert!(writer.all_output_lines()[19].contains("r3_2"));
+}
+
+#[rstest]
+fn list_tendrils_order_of_reports_is_unchanged() {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut t1_1 = RawTendril::new("l1");
+    let mut t1_2 = RawTendril::new("l1");
+    let mut t1_3 = RawTendril::new("l1");
+    let mut t2_1 = RawTendril::new("l2");
+    let mut t2_2 = RawTendril::new("l2");
+    let mut t2_3 = RawTendril::new("l2");
+    let mut t3_1 = RawTendril::new("l3");
+    let mut t3_2 = RawTendril::new("l3");
+    let mut t3_3 = RawTendril::new("l3");
+    t1_1.remote = "r1_1".to_string();
+    t1_2.remote = "r1_2".to_string();
+    t1_3.remote = "r1_3".to_string();
+    t2_1.remote = "r2_1".to_string();
+    t2_2.remote = "r2_2".to_string();
+    t2_3.remote = "r2_3".to_string();
+    t3_1.remote = "r3_1".to_string();
+    t3_2.remote = "r3_2".to_string();
+    t3_3.remote = "r3_3".to_string();
+
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Ok(vec![
+        TendrilReport {
+            raw_tendril: t2_3.clone(),
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r2_3"),
-                result.clone(),
             )),
         },
         TendrilReport {
             raw_tendril: t2_2.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r2_2"),
-                result.clone(),
             )),
         },
         TendrilReport {
             raw_tendril: t2_1.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r2_1"),
-                result.clone(),
             )),
         },
         TendrilReport {
             raw_tendril: t1_2.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r1_2"),
-                result.clone(),
             )),
         },
         TendrilReport {
             raw_tendril: t1_1.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r1_1"),
-                result.clone(),
             )),
         },
         TendrilReport {
             raw_tendril: t1_3.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r1_3"),
-                result.clone(),
             )),
         },
         TendrilReport {
             raw_tendril: t3_3.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r3_3"),
-                result.clone(),
             )),
         },
         TendrilReport {
             raw_tendril: t3_3.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r3_1"),
-                result.clone(),
             )),
         },
         TendrilReport {
             raw_tendril: t3_2.clone(),
-            log: Ok(ActionLog::new(
+            log: Ok(ListLog::new(
                 None,
                 None,
                 PathBuf::from("r3_2"),
-                result.clone(),
             )),
         },
-    ];
+    ]);
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(
+        path,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    println!("{}", writer.all_output);
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(
+        writer.all_output_lines().last().unwrap().to_string(),
+        "Total: 9"
+    );
+    assert!(writer.all_output_lines()[3] .contains("r2_3"));
+    assert!(writer.all_output_lines()[5] .contains("r2_2"));
+    assert!(writer.all_output_lines()[7] .contains("r2_1"));
+    assert!(writer.all_output_lines()[9] .contains("r1_2"));
+    assert!(writer.all_output_lines()[11].contains("r1_1"));
+    assert!(writer.all_output_lines()[13].contains("r1_3"));
+    assert!(writer.all_output_lines()[15].contains("r3_3"));
+    assert!(writer.all_output_lines()[17].contains("r3_1"));
+    assert!(writer.all_output_lines()[19].contains("r3_2"));
+}
+
+#[rstest]
+#[case(ActionMode::Pull)]
+#[case(ActionMode::Push)]
+#[case(ActionMode::Link)]
+#[case(ActionMode::Out)]
+#[case(ActionMode::Relink)]
+fn tendril_action_filters_are_passed_properly(
+    #[case] mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let locals_filter = vec!["l1".to_string(), "l2".to_string()];
+    let remotes_filter = vec!["r1".to_string(), "r2".to_string()];
+    let profiles_filter = Some(vec!["p1".to_string(), "p2".to_string()]);
+    let filter = FilterSpec {
+        mode: Some(mode.clone()),
+        locals: locals_filter.clone(),
+        remotes: remotes_filter.clone(),
+        profiles: profiles_filter.clone(),
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        tendril_modes: vec![],
+        fso_types: vec![],
+    };
+
+    // These assertions occur in the mock run call
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = filter;
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode.clone(),
+        dry_run,
+        force,
+        locals_filter,
+        remotes_filter,
+        profiles_filter,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
+#[rstest]
+fn list_tendrils_filters_are_passed_properly() {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let locals_filter = vec!["l1".to_string(), "l2".to_string()];
+    let remotes_filter = vec!["r1".to_string(), "r2".to_string()];
+    let profiles_filter = Some(vec!["p1".to_string(), "p2".to_string()]);
+    let filter = FilterSpec {
+        mode: None,
+        locals: locals_filter.clone(),
+        remotes: remotes_filter.clone(),
+        profiles: profiles_filter.clone(),
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        tendril_modes: vec![],
+        fso_types: vec![],
+    };
+
+    // These assertions occur in the mock run call
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_exp_filter = filter;
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(
+        path,
+        locals_filter,
+        remotes_filter,
+        profiles_filter,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
+#[test]
+fn list_tendrils_exclude_mode_and_type_filters_are_passed_properly() {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut filter = FilterSpec::new();
+    filter.locals = vec!["!l1".to_string()];
+    filter.exclude_locals = vec!["l2".to_string()];
+    filter.exclude_remotes = vec!["r1".to_string(), "r2".to_string()];
+    filter.exclude_profiles = vec!["p1".to_string()];
+    filter.tendril_modes = vec![TendrilMode::Link, TendrilMode::DirMerge];
+    filter.fso_types = vec![FsoType::Dir, FsoType::SymFile];
+
+    // These assertions occur in the mock run call
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_exp_filter = filter;
+
+    let mut writer = MockWriter::new();
+    let args = TendrilCliArgs::try_parse_from([
+        "td", "list", "--path", "/SomeGivenDir",
+        "-l", "!l1",
+        "--exclude-locals", "l2",
+        "--exclude-remotes", "r1", "r2",
+        "--exclude-profiles", "p1",
+        "--modes", "link", "dir-merge",
+        "--types", "dir", "symfile",
+    ]).unwrap();
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+}
+
+#[rstest]
+#[case("--modes", "copy")]
+#[case("--modes", "Link")]
+#[case("--types", "symlink")]
+#[case("--types", "File")]
+fn invalid_mode_or_type_filter_is_rejected(
+    #[case] flag: &str,
+    #[case] value: &str,
+) {
+    let actual = TendrilCliArgs::try_parse_from(["td", "list", flag, value]);
+
+    assert!(actual.is_err());
+}
+
+#[rstest]
+#[case(ActionMode::Pull)]
+#[case(ActionMode::Push)]
+#[case(ActionMode::Link)]
+#[case(ActionMode::Out)]
+#[case(ActionMode::Relink)]
+fn tendril_action_empty_reports_list_prints_message(
+    #[case] mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_exp_dry_run = dry_run;
+    api.ta_exp_force = force;
+    api.ta_const_rt = Ok(vec![]);
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_action_subcommand(
+        path,
+        mode,
+        dry_run,
+        force,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "No tendrils matched the given filter(s)\n");
+}
+
+#[rstest]
+fn list_tendrils_empty_reports_list_prints_message() {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Ok(vec![]);
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(
+        path,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, "No tendrils matched the given filter(s)\n");
+}
+
+#[rstest]
+fn list_tendrils_with_fallbacks_prints_chosen_alternative() {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut t1 = RawTendril::new("l1");
+    t1.remote = "r1".to_string();
+    t1.fallbacks = vec!["r1_alt1".to_string(), "r1_alt2".to_string()];
+    let mut t2 = RawTendril::new("l2");
+    t2.remote = "r2".to_string();
+
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Ok(vec![
+        TendrilReport {
+            raw_tendril: t1,
+            log: Ok(ListLog::new(
+                None,
+                None,
+                PathBuf::from("r1_alt1"),
+            ).with_remote_idx(1)),
+        },
+        TendrilReport {
+            raw_tendril: t2,
+            log: Ok(ListLog::new(
+                None,
+                None,
+                PathBuf::from("r2"),
+            )),
+        },
+    ]);
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(
+        path,
+        vec![],
+        vec![],
+        None,
+    );
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert!(writer.all_output_lines()[3].contains("r1_alt1"));
+    assert!(writer.all_output_lines()[3].contains("(alternative 2/3)"));
+    assert!(writer.all_output_lines()[5].contains("r2"));
+    assert!(!writer.all_output_lines()[5].contains("alternative"));
+}
+
+#[test]
+fn path_arg_accepts_several_paths() {
+    let args = TendrilCliArgs::try_parse_from(
+        ["td", "list", "--path", "/Repo1", "/Repo2"]
+    )
+    .unwrap();
+
+    if let TendrilsSubcommands::List { path_args, .. } = args.tendrils_command {
+        assert_eq!(path_args.path, vec!["/Repo1", "/Repo2"]);
+    }
+    else {
+        panic!("Expected the list subcommand");
+    }
+}
+
+#[test]
+fn list_tendrils_several_paths_given_prints_reports_grouped_by_repo() {
+    let mut api = MockTendrilsApi::new();
+    let repo1 = PathBuf::from("/Repo1");
+    let repo2 = PathBuf::from("/Repo2");
+    let mut t1 = RawTendril::new("l1");
+    t1.remote = "r1".to_string();
+
+    api.list_multi_exp_paths = vec![&repo1, &repo2];
+    api.validate_fn = Some(Box::new(|_| Ok(vec![])));
+    api.list_multi_const_rt = Ok(vec![
+        RepoReports {
+            td_repo: UniPath::from(&repo1),
+            reports: vec![TendrilReport {
+                raw_tendril: t1,
+                log: Ok(ListLog::new(None, None, PathBuf::from("r1"))),
+            }],
+        },
+        RepoReports { td_repo: UniPath::from(&repo2), reports: vec![] },
+    ]);
+
+    let mut writer = MockWriter::new();
+    let mut tendrils_command = build_list_subcommand(None, vec![], vec![], None);
+    if let TendrilsSubcommands::List { path_args, .. } = &mut tendrils_command {
+        path_args.path = vec!["/Repo1".to_string(), "/Repo2".to_string()];
+    }
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    let lines = writer.all_output_lines();
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(
+        lines[0],
+        format!("Tendrils repo: {}", ansi_hyperlink("/Repo1", "/Repo1")),
+    );
+    assert!(lines[4].contains("l1"));
+    assert!(lines[4].contains("r1"));
+    assert_eq!(
+        lines[lines.len() - 3..],
+        [
+            "".to_string(),
+            format!("Tendrils repo: {}", ansi_hyperlink("/Repo2", "/Repo2")),
+            "No tendrils matched the given filter(s)".to_string(),
+        ],
+    );
+}
+
+#[rstest]
+#[serial(SERIAL_CD)]
+fn list_tendrils_no_repo_found_and_several_defaults_lists_each_default() {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+
+    api.is_tendrils_repo_const_rt = false;
+    api.find_tendrils_repo_fn = Some(Box::new(|_| None));
+    api.get_default_repos_fn = Some(Box::new(|| Ok(vec![
+        PathBuf::from("/Default1"),
+        PathBuf::from("/Default2"),
+    ])));
+    api.list_multi_exp_paths = vec![];
+    api.validate_fn = Some(Box::new(|_| Ok(vec![])));
+    api.list_multi_const_rt = Ok(vec![
+        RepoReports { td_repo: UniPath::from(Path::new("/Default1")), reports: vec![] },
+        RepoReports { td_repo: UniPath::from(Path::new("/Default2")), reports: vec![] },
+    ]);
+
+    let tendrils_command = build_list_subcommand(None, vec![], vec![], None);
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(
+        writer.all_output,
+        format!(
+            "Tendrils repo: {}\nNo tendrils matched the given filter(s)\n\n\
+            Tendrils repo: {}\nNo tendrils matched the given filter(s)\n",
+            ansi_hyperlink("/Default1", "/Default1"),
+            ansi_hyperlink("/Default2", "/Default2"),
+        ),
+    );
+}
+
+#[test]
+fn list_tendrils_remote_conflict_prints_message() {
+    let mut api = MockTendrilsApi::new();
+    let repo1 = PathBuf::from("/Repo1");
+    let repo2 = PathBuf::from("/Repo2");
+    api.list_multi_exp_paths = vec![&repo1, &repo2];
+    api.validate_fn = Some(Box::new(|_| Ok(vec![])));
+    api.list_multi_const_rt = Err(SetupError::RemoteConflict {
+        remote: PathBuf::from("/SomeRemote"),
+        first_repo: repo1.clone(),
+        second_repo: repo2.clone(),
+    });
+
+    let mut writer = MockWriter::new();
+    let mut tendrils_command = build_list_subcommand(None, vec![], vec![], None);
+    if let TendrilsSubcommands::List { path_args, .. } = &mut tendrils_command {
+        path_args.path = vec!["/Repo1".to_string(), "/Repo2".to_string()];
+    }
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(
+        writer.all_output,
+        format!(
+            "{ERR_PREFIX}: The remote \"/SomeRemote\" is used by tendrils in \
+            more than one Tendrils repo:\n    /Repo1\n    /Repo2\n"
+        ),
+    );
+}
 
+#[rstest]
+fn tendril_action_tendril_conflict_prints_message(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+) {
+    let mut api = MockTendrilsApi::new();
     let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/Given/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.tau_const_rt = Err(SetupError::TendrilConflict(Box::new(
+        check_finding(CheckKind::LinkInDirRemote, Severity::Error),
+    )));
+
     let path = Some(given_dir.to_str().unwrap().to_string());
     let tendrils_command = build_action_subcommand(
         path,
         mode,
-        dry_run,
-        force,
+        false,
+        false,
         vec![],
         vec![],
         None,
@@ -1767,152 +3917,175 @@ fn tendril_action_order_of_reports_is_unchanged(
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    println!("{}", writer.all_output);
-    assert_eq!(actual_exit_code, Err(exitcode::SOFTWARE));
-    assert_eq!(
-        writer.all_output_lines().last().unwrap().to_string(),
-        format!(
-            "Total: 9, Successful: {color_bright_green}0{color_reset}, \
-             Failed: {color_bright_red}9{color_reset}"
-        )
+    let expected = format!(
+        "{ERR_PREFIX}: Conflicting tendrils:\n    The remote \
+        \"/SomeRemote/Nested\" of the link tendril \"OtherLocal\" is inside \
+        the remote \"/SomeRemote\" of \"SomeLocal\"\n\
+        Run the check command for a list of all conflicts\n"
     );
-    assert!(writer.all_output_lines()[3] .contains("r2_3"));
-    assert!(writer.all_output_lines()[5] .contains("r2_2"));
-    assert!(writer.all_output_lines()[7] .contains("r2_1"));
-    assert!(writer.all_output_lines()[9] .contains("r1_2"));
-    assert!(writer.all_output_lines()[11].contains("r1_1"));
-    assert!(writer.all_output_lines()[13].contains("r1_3"));
-    assert!(writer.all_output_lines()[15].contains("r3_3"));
-    assert!(writer.all_output_lines()[17].contains("r3_1"));
-    assert!(writer.all_output_lines()[19].contains("r3_2"));
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
 }
 
 #[rstest]
-fn list_tendrils_order_of_reports_is_unchanged() {
+fn tendril_action_strict_with_warnings_prints_them_and_skips_action(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link, ActionMode::Out)]
+    mode: ActionMode,
+) {
     let mut api = MockTendrilsApi::new();
-    let given_dir = PathBuf::from("/SomeGivenDir");
-    let mut t1_1 = RawTendril::new("l1");
-    let mut t1_2 = RawTendril::new("l1");
-    let mut t1_3 = RawTendril::new("l1");
-    let mut t2_1 = RawTendril::new("l2");
-    let mut t2_2 = RawTendril::new("l2");
-    let mut t2_3 = RawTendril::new("l2");
-    let mut t3_1 = RawTendril::new("l3");
-    let mut t3_2 = RawTendril::new("l3");
-    let mut t3_3 = RawTendril::new("l3");
-    t1_1.remote = "r1_1".to_string();
-    t1_2.remote = "r1_2".to_string();
-    t1_3.remote = "r1_3".to_string();
-    t2_1.remote = "r2_1".to_string();
-    t2_2.remote = "r2_2".to_string();
-    t2_3.remote = "r2_3".to_string();
-    t3_1.remote = "r3_1".to_string();
-    t3_2.remote = "r3_2".to_string();
-    t3_3.remote = "r3_3".to_string();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/Given/Path");
 
-    api.list_exp_path = Some(&given_dir);
-    api.list_const_rt = Ok(vec![
-        TendrilReport {
-            raw_tendril: t2_3.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r2_3"),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t2_2.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r2_2"),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t2_1.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r2_1"),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t1_2.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r1_2"),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t1_1.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r1_1"),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t1_3.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r1_3"),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t3_3.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r3_3"),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t3_3.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r3_1"),
-            )),
-        },
-        TendrilReport {
-            raw_tendril: t3_2.clone(),
-            log: Ok(ListLog::new(
-                None,
-                None,
-                PathBuf::from("r3_2"),
-            )),
-        },
+    api.is_tendrils_repo_const_rt = true;
+    api.check_exp_path = Some(&given_dir);
+    api.check_exp_filter.mode = Some(mode.clone());
+    api.validate_exp_path = Some(&given_dir);
+    api.validate_const_rt = Ok(vec![config_issue(
+        ConfigIssueKind::UnknownKey { suggestion: None },
+        Severity::Warning,
+    )]);
+    // Fails the test if the action is performed
+    api.ta_exp_mode = ActionMode::Out;
+    api.ta_exp_path = None;
+
+    let subcommand = match mode {
+        ActionMode::Pull => "pull",
+        ActionMode::Push => "push",
+        ActionMode::Link => "link",
+        ActionMode::Out => "out",
+        ActionMode::Relink => "relink",
+    };
+    let args = TendrilCliArgs::try_parse_from(
+        ["td", subcommand, "--path", "/Given/Path", "--strict"]
+    )
+    .unwrap();
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    let expected = format!(
+        "{color_bright_yellow}Warning{color_reset}: Unknown key at \
+        tendrils.SomeLocal.dir_merge in the tendrils.json file\n\
+        \n\
+        0 errors, 1 warning\n\
+        {ERR_PREFIX}: No actions were performed because of the problems \
+        above (strict mode)\n"
+    );
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(writer.all_output, expected);
+}
+
+#[rstest]
+fn tendril_action_strict_without_problems_performs_action(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link)]
+    mode: ActionMode,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/Given/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.check_exp_path = Some(&given_dir);
+    api.check_exp_filter.mode = Some(mode.clone());
+    api.validate_exp_path = Some(&given_dir);
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.ta_exp_filter.mode = Some(mode.clone());
+
+    let subcommand = match mode {
+        ActionMode::Pull => "pull",
+        ActionMode::Push => "push",
+        _ => "link",
+    };
+    let args = TendrilCliArgs::try_parse_from(
+        ["td", subcommand, "--path", "/Given/Path", "--strict"]
+    )
+    .unwrap();
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Ok(()));
+    assert!(!writer.all_output.contains("strict mode"));
+}
+
+#[rstest]
+fn tendril_action_without_strict_prints_unknown_keys_to_stderr_and_performs_action(
+    #[values(ActionMode::Pull, ActionMode::Push, ActionMode::Link, ActionMode::Out)]
+    mode: ActionMode,
+) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let given_dir = PathBuf::from("/Given/Path");
+
+    api.is_tendrils_repo_const_rt = true;
+    api.validate_exp_path = Some(&given_dir);
+    api.validate_const_rt = Ok(vec![
+        config_issue(
+            ConfigIssueKind::UnknownKey { suggestion: Some("dir-merge".to_string()) },
+            Severity::Warning,
+        ),
+        config_issue(ConfigIssueKind::EmptyRemotes, Severity::Warning),
     ]);
+    api.ta_exp_mode = mode.clone();
+    api.ta_exp_path = Some(&given_dir);
+    api.ta_exp_filter.mode = Some(mode.clone());
 
-    let mut writer = MockWriter::new();
-    let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_list_subcommand(
-        path,
-        vec![],
-        vec![],
-        None,
+    let subcommand = match mode {
+        ActionMode::Pull => "pull",
+        ActionMode::Push => "push",
+        ActionMode::Link => "link",
+        _ => "out",
+    };
+    let args = TendrilCliArgs::try_parse_from(
+        ["td", subcommand, "--path", "/Given/Path"]
+    )
+    .unwrap();
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    let expected = format!(
+        "{color_bright_yellow}Warning{color_reset}: Unknown key at \
+        tendrils.SomeLocal.dir_merge in the tendrils.json file, did you mean \
+        \"dir-merge\"?\n"
     );
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.std_err, expected);
+    assert_eq!(writer.std_out, "No tendrils matched the given filter(s)\n");
+}
+
+#[test]
+fn list_tendrils_prints_unknown_keys_to_stderr_for_each_repo() {
+    let mut api = MockTendrilsApi::new();
+    let repo1 = PathBuf::from("/Repo1");
+    let repo2 = PathBuf::from("/Repo2");
+    api.list_multi_exp_paths = vec![&repo1, &repo2];
+    api.list_multi_const_rt = Ok(vec![]);
+    api.validate_fn = Some(Box::new(|td_repo| {
+        let mut issue = config_issue(
+            ConfigIssueKind::UnknownKey { suggestion: None },
+            Severity::Warning,
+        );
+        issue.key_path = td_repo.unwrap().inner().to_string_lossy().to_string();
+        Ok(vec![issue])
+    }));
+
+    let mut writer = MockWriter::new();
+    let mut tendrils_command = build_list_subcommand(None, vec![], vec![], None);
+    if let TendrilsSubcommands::List { path_args, .. } = &mut tendrils_command {
+        path_args.path = vec!["/Repo1".to_string(), "/Repo2".to_string()];
+    }
     let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    println!("{}", writer.all_output);
-    assert_eq!(actual_exit_code, Ok(()));
-    assert_eq!(
-        writer.all_output_lines().last().unwrap().to_string(),
-        "Total: 9"
+    let expected = format!(
+        "{color_bright_yellow}Warning{color_reset}: Unknown key at /Repo1 in \
+        the tendrils.json file\n\
+        {color_bright_yellow}Warning{color_reset}: Unknown key at /Repo2 in \
+        the tendrils.json file\n"
     );
-    assert!(writer.all_output_lines()[3] .contains("r2_3"));
-    assert!(writer.all_output_lines()[5] .contains("r2_2"));
-    assert!(writer.all_output_lines()[7] .contains("r2_1"));
-    assert!(writer.all_output_lines()[9] .contains("r1_2"));
-    assert!(writer.all_output_lines()[11].contains("r1_1"));
-    assert!(writer.all_output_lines()[13].contains("r1_3"));
-    assert!(writer.all_output_lines()[15].contains("r3_3"));
-    assert!(writer.all_output_lines()[17].contains("r3_1"));
-    assert!(writer.all_output_lines()[19].contains("r3_2"));
+    assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.std_err, expected);
 }
 
 #[rstest]
@@ -1920,141 +4093,362 @@ fn list_tendrils_order_of_reports_is_unchanged() {
 #[case(ActionMode::Push)]
 #[case(ActionMode::Link)]
 #[case(ActionMode::Out)]
-fn tendril_action_filters_are_passed_properly(
+#[case(ActionMode::Relink)]
+fn tendril_action_several_paths_given_if_any_fail_returns_exit_code(
     #[case] mode: ActionMode,
     #[values(true, false)] dry_run: bool,
     #[values(true, false)] force: bool,
 ) {
     let mut api = MockTendrilsApi::new();
-    let given_dir = PathBuf::from("/SomeGivenDir");
-    let locals_filter = vec!["l1".to_string(), "l2".to_string()];
-    let remotes_filter = vec!["r1".to_string(), "r2".to_string()];
-    let profiles_filter = Some(vec!["p1".to_string(), "p2".to_string()]);
-    let filter = FilterSpec {
-        mode: Some(mode.clone()),
-        locals: locals_filter.clone(),
-        remotes: remotes_filter.clone(),
-        profiles: profiles_filter.clone(),
-    };
+    let repo1 = PathBuf::from("/Repo1");
+    let repo2 = PathBuf::from("/Repo2");
+    let mut t1 = RawTendril::new("SomeApp/misc.txt");
+    let mut t2 = RawTendril::new("SomeApp/misc.txt");
+    t1.remote = "r1".to_string();
+    t2.remote = "r2".to_string();
+    let err_result = Err(TendrilActionError::IoError {
+        kind: std::io::ErrorKind::NotFound,
+        loc: Location::Source,
+    });
 
-    // These assertions occur in the mock run call
     api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&given_dir);
-    api.ta_exp_filter = filter;
+    api.ta_multi_exp_paths = vec![&repo1, &repo2];
+    api.validate_fn = Some(Box::new(|_| Ok(vec![])));
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
     api.ta_exp_dry_run = dry_run;
     api.ta_exp_force = force;
+    api.ta_multi_const_rt = Ok(vec![
+        RepoReports {
+            td_repo: UniPath::from(&repo1),
+            reports: vec![TendrilReport {
+                raw_tendril: t1,
+                log: Ok(ActionLog::new(
+                    Some(FsoType::File),
+                    None,
+                    PathBuf::from("r1"),
+                    Ok(TendrilActionSuccess::New),
+                )),
+            }],
+        },
+        RepoReports {
+            td_repo: UniPath::from(&repo2),
+            reports: vec![TendrilReport {
+                raw_tendril: t2,
+                log: Ok(ActionLog::new(
+                    None,
+                    None,
+                    PathBuf::from("r2"),
+                    err_result,
+                )),
+            }],
+        },
+    ]);
 
     let mut writer = MockWriter::new();
-    let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_action_subcommand(
-        path,
-        mode.clone(),
+    let mut tendrils_command = build_action_subcommand(
+        None,
+        mode,
         dry_run,
         force,
-        locals_filter,
-        remotes_filter,
-        profiles_filter,
+        vec![],
+        vec![],
+        None,
     );
+    match &mut tendrils_command {
+        TendrilsSubcommands::Pull { action_args, .. }
+        | TendrilsSubcommands::Push { action_args, .. }
+        | TendrilsSubcommands::Link { action_args, .. }
+        | TendrilsSubcommands::Out { action_args, .. }
+        | TendrilsSubcommands::Relink { action_args, .. } => {
+            action_args.path_args.path =
+                vec!["/Repo1".to_string(), "/Repo2".to_string()];
+        }
+        _ => unreachable!(),
+    }
     let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
-    assert_eq!(actual_exit_code, Ok(()));
+    let lines = writer.std_out_lines();
+    let repo2_header =
+        format!("Tendrils repo: {}", ansi_hyperlink("/Repo2", "/Repo2"));
+    assert_eq!(actual_exit_code, Err(exitcode::SOFTWARE));
+    assert_eq!(
+        lines[0],
+        format!("Tendrils repo: {}", ansi_hyperlink("/Repo1", "/Repo1")),
+    );
+    let repo2_idx = lines.iter().position(|l| l == &repo2_header).unwrap();
+    assert_eq!(lines[repo2_idx - 1], "");
+    assert_eq!(
+        lines[repo2_idx - 2],
+        format!(
+            "Total: 1, Successful: {color_bright_green}1{color_reset}, \
+             Failed: {color_bright_red}0{color_reset}"
+        ),
+    );
+    assert_eq!(
+        lines.last().unwrap().to_string(),
+        format!(
+            "Total: 1, Successful: {color_bright_green}0{color_reset}, \
+             Failed: {color_bright_red}1{color_reset}"
+        ),
+    );
 }
 
 #[rstest]
-fn list_tendrils_filters_are_passed_properly() {
+#[case(ActionMode::Pull)]
+#[case(ActionMode::Push)]
+#[case(ActionMode::Out)]
+fn tendril_action_several_paths_given_prints_progress_across_all_repos(
+    #[case] mode: ActionMode,
+) {
     let mut api = MockTendrilsApi::new();
-    let given_dir = PathBuf::from("/SomeGivenDir");
-    let locals_filter = vec!["l1".to_string(), "l2".to_string()];
-    let remotes_filter = vec!["r1".to_string(), "r2".to_string()];
-    let profiles_filter = Some(vec!["p1".to_string(), "p2".to_string()]);
-    let filter = FilterSpec {
-        mode: None,
-        locals: locals_filter.clone(),
-        remotes: remotes_filter.clone(),
-        profiles: profiles_filter.clone(),
+    let repo1 = PathBuf::from("/Repo1");
+    let repo2 = PathBuf::from("/Repo2");
+    let mut t1 = RawTendril::new("SomeApp/misc.txt");
+    let mut t2 = RawTendril::new("SomeApp/misc.txt");
+    t1.remote = "r1".to_string();
+    t2.remote = "r2".to_string();
+    let report = |t: RawTendril| TendrilReport {
+        log: Ok(ActionLog::new(
+            Some(FsoType::File),
+            None,
+            PathBuf::from(&t.remote),
+            Ok(TendrilActionSuccess::New),
+        )),
+        raw_tendril: t,
     };
 
-    // These assertions occur in the mock run call
-    api.list_exp_path = Some(&given_dir);
-    api.list_exp_filter = filter;
+    api.ta_exp_mode = mode.clone();
+    api.ta_multi_exp_paths = vec![&repo1, &repo2];
+    api.validate_fn = Some(Box::new(|_| Ok(vec![])));
+    api.ta_exp_filter = FilterSpec::new();
+    api.ta_exp_filter.mode = Some(mode.clone());
+    api.ta_multi_const_rt = Ok(vec![
+        RepoReports {
+            td_repo: UniPath::from(&repo1),
+            reports: vec![report(t1)],
+        },
+        RepoReports {
+            td_repo: UniPath::from(&repo2),
+            reports: vec![report(t2)],
+        },
+    ]);
+    api.tau_const_progress_updater_rts = vec![
+        vec![],
+        vec![
+            CopyProgress::Start { files: 1, bytes: 1024 },
+            CopyProgress::Bytes { copied: 1024 },
+        ],
+    ];
 
     let mut writer = MockWriter::new();
-    let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_list_subcommand(
-        path,
-        locals_filter,
-        remotes_filter,
-        profiles_filter,
+    let mut tendrils_command = build_action_subcommand(
+        None,
+        mode,
+        false,
+        false,
+        vec![],
+        vec![],
+        None,
+    );
+    match &mut tendrils_command {
+        TendrilsSubcommands::Pull { action_args, .. }
+        | TendrilsSubcommands::Push { action_args, .. }
+        | TendrilsSubcommands::Out { action_args, .. } => {
+            action_args.path_args.path =
+                vec!["/Repo1".to_string(), "/Repo2".to_string()];
+        }
+        _ => unreachable!(),
+    }
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // Processing [1/2]: r1 (then line is cleared and overwritten)
+    // Processing [2/2]: r2 [--------------------] 0% 0 B/1.0 KiB (0/1 files)
+    let exp_start = format!(
+        "Processing [1/2]: r1{CLEAR_LINE}\
+        Processing [2/2]: r2{CLEAR_LINE}\
+        Processing [2/2]: r2 [--------------------] 0% 0 B/1.0 KiB (0/1 files)"
+    );
+    assert_eq!(actual_exit_code, Ok(()));
+    assert!(writer.std_err.starts_with(&exp_start), "{}", writer.std_err);
+    assert!(writer.std_err.ends_with(CLEAR_LINE));
+    assert_eq!(
+        writer.std_out_lines()[0],
+        format!("Tendrils repo: {}", ansi_hyperlink("/Repo1", "/Repo1")),
     );
+}
+
+#[test]
+fn repo_several_paths_given_prints_each_path_and_reason() {
+    let api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let mut tendrils_command = build_repo_subcommand(None, false);
+    if let TendrilsSubcommands::Repo { path_args } = &mut tendrils_command {
+        path_args.path = vec!["/Repo1".to_string(), "/Repo2".to_string()];
+    }
     let args = TendrilCliArgs { tendrils_command };
 
+    let expected = format!(
+        "{}\n{}\nGiven by the --path argument\n",
+        ansi_hyperlink("/Repo1", "/Repo1"),
+        ansi_hyperlink("/Repo2", "/Repo2"),
+    );
+
     let actual_exit_code = run(args, &api, &mut writer);
 
     assert_eq!(actual_exit_code, Ok(()));
+    assert_eq!(writer.all_output, expected);
 }
 
 #[rstest]
-#[case(ActionMode::Pull)]
-#[case(ActionMode::Push)]
-#[case(ActionMode::Link)]
-#[case(ActionMode::Out)]
-fn tendril_action_empty_reports_list_prints_message(
-    #[case] mode: ActionMode,
-    #[values(true, false)] dry_run: bool,
-    #[values(true, false)] force: bool,
+#[serial(SERIAL_CD)]
+fn repo_no_repo_found_and_several_defaults_prints_each_default(
+    #[values(true, false)] second_is_valid: bool,
 ) {
+    let mut api = MockTendrilsApi::new();
+    let mut writer = MockWriter::new();
+    let temp_dir =
+        tempdir::TempDir::new_in(get_disposable_dir(), "TempDir").unwrap();
+    let cd = temp_dir.path().join("CurrentDir");
+    create_dir_all(&cd).unwrap();
+    std::env::set_current_dir(&cd).unwrap();
+    api.find_tendrils_repo_fn = Some(Box::new(|_| None));
+    api.is_tendrils_repo_fn = Some(Box::new(move |dir| {
+        dir.inner() == Path::new("/Default1")
+            || (second_is_valid && dir.inner() == Path::new("/Default2"))
+    }));
+    api.get_default_repos_fn = Some(Box::new(|| Ok(vec![
+        PathBuf::from("/Default1"),
+        PathBuf::from("/Default2"),
+    ])));
+    let args = TendrilCliArgs {
+        tendrils_command: build_repo_subcommand(None, false),
+    };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    // To free the TempDir from use
+    std::env::set_current_dir(temp_dir.path().parent().unwrap()).unwrap();
+
+    if second_is_valid {
+        assert_eq!(actual_exit_code, Ok(()));
+        assert_eq!(
+            writer.all_output,
+            format!(
+                "{}\n{}\nUsing the default repo, as neither the current \
+                directory nor any of its parent folders are a Tendrils repo\n",
+                ansi_hyperlink("/Default1", "/Default1"),
+                ansi_hyperlink("/Default2", "/Default2"),
+            ),
+        );
+    }
+    else {
+        let err = GetTendrilsRepoError::DefaultInvalid {
+            path: PathBuf::from("/Default2"),
+        };
+        assert_eq!(actual_exit_code, Err(exitcode::NOINPUT));
+        assert_eq!(
+            writer.all_output,
+            format!("{ERR_PREFIX}: {}\n", err.to_string()),
+        );
+    }
+}
+
+#[test]
+fn list_tendrils_with_included_sources_prints_source_column() {
     let mut api = MockTendrilsApi::new();
     let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut t1 = RawTendril::new("l1");
+    t1.remote = "r1".to_string();
+    let mut t2 = RawTendril::new("l2");
+    t2.remote = "r2".to_string();
+    t2.source = Some(PathBuf::from("tendrils.d/apps.json"));
 
-    api.ta_exp_mode = mode.clone();
-    api.ta_exp_path = Some(&given_dir);
-    api.ta_exp_filter = FilterSpec::new();
-    api.ta_exp_filter.mode = Some(mode.clone());
-    api.ta_exp_dry_run = dry_run;
-    api.ta_exp_force = force;
-    api.ta_const_rt = Ok(vec![]);
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Ok(vec![
+        TendrilReport {
+            raw_tendril: t1,
+            log: Ok(ListLog::new(None, None, PathBuf::from("r1"))),
+        },
+        TendrilReport {
+            raw_tendril: t2,
+            log: Ok(ListLog::new(None, None, PathBuf::from("r2"))),
+        },
+    ]);
 
     let mut writer = MockWriter::new();
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_action_subcommand(
-        path,
-        mode,
-        dry_run,
-        force,
-        vec![],
-        vec![],
-        None,
-    );
+    let tendrils_command = build_list_subcommand(path, vec![], vec![], None);
     let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
+    let lines = writer.all_output_lines();
     assert_eq!(actual_exit_code, Ok(()));
-    assert_eq!(writer.all_output, "No tendrils matched the given filter(s)\n");
+    assert!(lines[1].contains("Source"));
+    assert!(lines[3].contains("tendrils.json"));
+    assert!(lines[5].contains("tendrils.d/apps.json"));
 }
 
-#[rstest]
-fn list_tendrils_empty_reports_list_prints_message() {
+#[test]
+fn list_tendrils_without_included_sources_does_not_print_source_column() {
     let mut api = MockTendrilsApi::new();
     let given_dir = PathBuf::from("/SomeGivenDir");
+    let mut t1 = RawTendril::new("l1");
+    t1.remote = "r1".to_string();
 
     api.list_exp_path = Some(&given_dir);
-    api.list_const_rt = Ok(vec![]);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Ok(vec![TendrilReport {
+        raw_tendril: t1,
+        log: Ok(ListLog::new(None, None, PathBuf::from("r1"))),
+    }]);
 
     let mut writer = MockWriter::new();
     let path = Some(given_dir.to_str().unwrap().to_string());
-    let tendrils_command = build_list_subcommand(
-        path,
-        vec![],
-        vec![],
-        None,
-    );
+    let tendrils_command = build_list_subcommand(path, vec![], vec![], None);
     let args = TendrilCliArgs { tendrils_command };
 
     let actual_exit_code = run(args, &api, &mut writer);
 
     assert_eq!(actual_exit_code, Ok(()));
-    assert_eq!(writer.all_output, "No tendrils matched the given filter(s)\n");
+    assert!(!writer.all_output.contains("Source"));
+    assert!(!writer.all_output.contains("tendrils.json"));
+}
+
+#[test]
+fn list_tendrils_duplicate_key_in_included_file_prints_message() {
+    let mut api = MockTendrilsApi::new();
+    let given_dir = PathBuf::from("/SomeGivenDir");
+    api.list_exp_path = Some(&given_dir);
+    api.validate_exp_path = Some(&given_dir);
+    api.list_const_rt = Err(SetupError::ConfigError(
+        GetConfigError::DuplicateKey {
+            section: "tendrils".to_string(),
+            key: "SomeApp/misc.txt".to_string(),
+            first: ConfigType::Repo(ConfigFormat::Json),
+            second: ConfigType::RepoInclude(PathBuf::from("tendrils.d/a.json")),
+        }
+    ));
+
+    let mut writer = MockWriter::new();
+    let path = Some(given_dir.to_str().unwrap().to_string());
+    let tendrils_command = build_list_subcommand(path, vec![], vec![], None);
+    let args = TendrilCliArgs { tendrils_command };
+
+    let actual_exit_code = run(args, &api, &mut writer);
+
+    assert_eq!(actual_exit_code, Err(exitcode::DATAERR));
+    assert_eq!(
+        writer.all_output,
+        format!(
+            "{ERR_PREFIX}: \"SomeApp/misc.txt\" is defined in the tendrils of \
+            both the tendrils.json file and the tendrils.d/a.json file\n"
+        ),
+    );
 }
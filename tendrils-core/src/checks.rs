@@ -0,0 +1,261 @@
+use crate::{ActionMode, RawTendril, TendrilMode, UniPath};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// How serious a [`CheckFinding`] or a [`ConfigIssue`](crate::ConfigIssue)
+/// is.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Severity {
+    /// The outcome of the actions may be unexpected, but does not depend on
+    /// the order in which they are performed. For a config issue, part of
+    /// the config is ignored or has no effect.
+    Warning,
+
+    /// The outcome of the actions depends on the order in which they are
+    /// performed. Tendril actions are not performed while there are any of
+    /// these findings. For a config issue, the affected tendrils always
+    /// fail.
+    Error,
+}
+
+/// The kind of problem found between two tendrils.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CheckKind {
+    /// Both tendrils have the same remote path. This is a [`Severity::Error`]
+    /// for the actions that write to the remotes (see [`writes_remotes`]),
+    /// unless both tendrils also have the same local path.
+    DuplicateRemote,
+
+    /// The local path of one tendril is inside the local path of the other.
+    /// This is a [`Severity::Error`] for the actions that push to the
+    /// remotes (see [`pushes_remotes`]).
+    NestedLocal,
+
+    /// The remote path of one tendril is inside the remote path of the other.
+    NestedRemote,
+
+    /// The remote path of a link tendril is inside the remote path of the
+    /// other (folder) tendril.
+    LinkInDirRemote,
+}
+
+/// A problem found between two tendrils that may cause the tendril actions to
+/// behave differently depending on the order they are performed in.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CheckFinding {
+    pub kind: CheckKind,
+    pub severity: Severity,
+
+    /// The tendril whose path contains the path of the [`Self::inner`]
+    /// tendril. For a [`CheckKind::DuplicateRemote`], this is the tendril
+    /// that appears first.
+    pub outer: RawTendril,
+
+    /// The tendril whose path is inside (or for a
+    /// [`CheckKind::DuplicateRemote`], the same as) the path of the
+    /// [`Self::outer`] tendril.
+    pub inner: RawTendril,
+
+    /// The conflicting path of the [`Self::outer`] tendril. This is the
+    /// resolved remote path, or the local path relative to the Tendrils repo
+    /// for a [`CheckKind::NestedLocal`].
+    pub outer_path: PathBuf,
+
+    /// The conflicting path of the [`Self::inner`] tendril, in the same form
+    /// as the [`Self::outer_path`].
+    pub inner_path: PathBuf,
+}
+
+impl ToString for CheckFinding {
+    fn to_string(&self) -> String {
+        let outer_path = self.outer_path.to_string_lossy();
+        let inner_path = self.inner_path.to_string_lossy();
+        match self.kind {
+            CheckKind::DuplicateRemote => format!(
+                "\"{}\" and \"{}\" both have the remote \"{outer_path}\"",
+                self.outer.local,
+                self.inner.local,
+            ),
+            CheckKind::NestedLocal => format!(
+                "The local \"{inner_path}\" is inside the local \"{outer_path}\"",
+            ),
+            CheckKind::NestedRemote => format!(
+                "The remote \"{inner_path}\" of \"{}\" is inside the remote \
+                \"{outer_path}\" of \"{}\"",
+                self.inner.local,
+                self.outer.local,
+            ),
+            CheckKind::LinkInDirRemote => format!(
+                "The remote \"{inner_path}\" of the link tendril \"{}\" is \
+                inside the remote \"{outer_path}\" of \"{}\"",
+                self.inner.local,
+                self.outer.local,
+            ),
+        }
+    }
+}
+
+/// Checks each pair of the given tendrils for duplicate remotes, nested
+/// locals, and nested remotes. Remotes are compared after
+/// [resolving](RawTendril::resolve) each tendril, and any tendrils that
+/// cannot be resolved are skipped. The findings are in the order of the given
+/// tendrils.
+///
+/// The severity of some findings depends on the action `mode` that the
+/// tendrils are checked for. If given `None`, each finding has its highest
+/// severity for any action.
+pub(crate) fn check_tendrils(
+    td_repo: &UniPath,
+    raw_tendrils: &[RawTendril],
+    mode: Option<&ActionMode>,
+) -> Vec<CheckFinding> {
+    let resolved: Vec<(&RawTendril, PathBuf)> = raw_tendrils
+        .iter()
+        .filter_map(|raw| match raw.resolve(td_repo, mode) {
+            Ok(t) => Some((raw, t.remote().inner().to_path_buf())),
+            Err(_) => None,
+        })
+        .collect();
+
+    let mut findings = vec![];
+    let mut nested_locals: Vec<(&str, &str)> = vec![];
+    for (i, (first, first_remote)) in resolved.iter().enumerate() {
+        for (second, second_remote) in resolved.iter().skip(i + 1) {
+            if let Some(f) = check_remotes(
+                first,
+                first_remote,
+                second,
+                second_remote,
+                mode,
+            ) {
+                findings.push(f);
+            }
+
+            let Some((outer, inner)) = order_nested(
+                first,
+                Path::new(&first.local),
+                second,
+                Path::new(&second.local),
+            )
+            else {
+                continue;
+            };
+            // Each local may have several remotes, but each pair of locals
+            // is only reported once
+            let local_pair = (outer.local.as_str(), inner.local.as_str());
+            if !nested_locals.contains(&local_pair) {
+                nested_locals.push(local_pair);
+                let severity = if pushes_remotes(mode) {
+                    Severity::Error
+                }
+                else {
+                    Severity::Warning
+                };
+                findings.push(CheckFinding {
+                    kind: CheckKind::NestedLocal,
+                    severity,
+                    outer: (*outer).clone(),
+                    inner: (*inner).clone(),
+                    outer_path: PathBuf::from(&outer.local),
+                    inner_path: PathBuf::from(&inner.local),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+fn check_remotes(
+    first: &RawTendril,
+    first_remote: &Path,
+    second: &RawTendril,
+    second_remote: &Path,
+    mode: Option<&ActionMode>,
+) -> Option<CheckFinding> {
+    if first_remote == second_remote {
+        let severity = if first.local != second.local && writes_remotes(mode) {
+            Severity::Error
+        }
+        else {
+            Severity::Warning
+        };
+        return Some(CheckFinding {
+            kind: CheckKind::DuplicateRemote,
+            severity,
+            outer: first.clone(),
+            inner: second.clone(),
+            outer_path: first_remote.to_path_buf(),
+            inner_path: second_remote.to_path_buf(),
+        });
+    }
+
+    let (outer, inner) = order_nested(
+        (first, first_remote),
+        first_remote,
+        (second, second_remote),
+        second_remote,
+    )?;
+    let (kind, severity) = match inner.0.mode {
+        TendrilMode::Link | TendrilMode::Hardlink => {
+            (CheckKind::LinkInDirRemote, Severity::Error)
+        }
+        _ => (CheckKind::NestedRemote, Severity::Warning),
+    };
+
+    Some(CheckFinding {
+        kind,
+        severity,
+        outer: outer.0.clone(),
+        inner: inner.0.clone(),
+        outer_path: outer.1.to_path_buf(),
+        inner_path: inner.1.to_path_buf(),
+    })
+}
+
+/// Returns `true` if the action `mode` writes to the remotes, in which case
+/// two tendrils sharing a remote overwrite each other. This is the case for
+/// all actions except pulling, where a remote is only read from. `None`
+/// stands for any action.
+fn writes_remotes(mode: Option<&ActionMode>) -> bool {
+    matches!(
+        mode,
+        None | Some(
+            ActionMode::Push
+            | ActionMode::Link
+            | ActionMode::Out
+            | ActionMode::Relink
+        ),
+    )
+}
+
+/// Returns `true` if the action `mode` copies the locals to the remotes, in
+/// which case a local inside another local is copied twice. `None` stands for
+/// any action.
+fn pushes_remotes(mode: Option<&ActionMode>) -> bool {
+    matches!(mode, None | Some(ActionMode::Push | ActionMode::Out))
+}
+
+/// Returns the given items as `(outer, inner)` if one of the paths is
+/// strictly inside the other, otherwise returns `None`.
+fn order_nested<T>(
+    first: T,
+    first_path: &Path,
+    second: T,
+    second_path: &Path,
+) -> Option<(T, T)> {
+    if first_path == second_path {
+        None
+    }
+    else if second_path.starts_with(first_path) {
+        Some((first, second))
+    }
+    else if first_path.starts_with(second_path) {
+        Some((second, first))
+    }
+    else {
+        None
+    }
+}
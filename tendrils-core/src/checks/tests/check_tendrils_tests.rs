@@ -0,0 +1,223 @@
+use crate::checks::{check_tendrils, CheckKind, Severity};
+use crate::test_utils::Setup;
+use crate::{ActionMode, RawTendril, TendrilMode};
+use rstest::rstest;
+use std::path::PathBuf;
+
+fn raw(setup: &Setup, local: &str, remote: &str, mode: TendrilMode) -> RawTendril {
+    let mut raw = setup.file_tendril_raw();
+    raw.local = local.to_string();
+    raw.remote = setup.parent_dir.join(remote).to_string_lossy().to_string();
+    raw.mode = mode;
+    raw
+}
+
+#[test]
+fn empty_tendrils_list_returns_empty() {
+    let setup = Setup::new();
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[], None);
+
+    assert!(actual.is_empty());
+}
+
+#[test]
+fn unrelated_tendrils_returns_empty() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "SomeApp/misc", "misc", TendrilMode::Link);
+    let t3 = raw(&setup, "OtherApp/misc", "miscOther", TendrilMode::DirMerge);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2, t3], None);
+
+    assert!(actual.is_empty());
+}
+
+#[test]
+fn same_local_multiple_remotes_returns_empty() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc1.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "SomeApp/misc.txt", "misc2.txt", TendrilMode::Link);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2], None);
+
+    assert!(actual.is_empty());
+}
+
+#[test]
+fn duplicate_remote_with_different_locals_returns_error() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "OtherApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1.clone(), t2.clone()], None);
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, CheckKind::DuplicateRemote);
+    assert_eq!(actual[0].severity, Severity::Error);
+    assert_eq!(actual[0].outer, t1);
+    assert_eq!(actual[0].inner, t2);
+    assert_eq!(actual[0].outer_path, setup.parent_dir.join("misc.txt"));
+    assert_eq!(actual[0].inner_path, setup.parent_dir.join("misc.txt"));
+}
+
+#[rstest]
+#[case(ActionMode::Push, Severity::Error)]
+#[case(ActionMode::Link, Severity::Error)]
+#[case(ActionMode::Out, Severity::Error)]
+#[case(ActionMode::Relink, Severity::Error)]
+#[case(ActionMode::Pull, Severity::Warning)]
+fn duplicate_remote_with_different_locals_severity_depends_on_mode(
+    #[case] mode: ActionMode,
+    #[case] exp_severity: Severity,
+) {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "OtherApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2], Some(&mode));
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, CheckKind::DuplicateRemote);
+    assert_eq!(actual[0].severity, exp_severity);
+}
+
+#[test]
+fn duplicate_remote_with_same_local_returns_warning() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let mut t2 = t1.clone();
+    t2.profiles = vec!["p1".to_string()];
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2], None);
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, CheckKind::DuplicateRemote);
+    assert_eq!(actual[0].severity, Severity::Warning);
+}
+
+#[test]
+fn duplicate_remote_is_compared_after_resolving() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "OtherApp/misc.txt", "a/../misc.txt", TendrilMode::DirOverwrite);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2], None);
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, CheckKind::DuplicateRemote);
+}
+
+#[test]
+fn nested_remote_returns_warning_with_outer_first() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "OtherApp/nested.txt", "misc/nested.txt", TendrilMode::DirMerge);
+    let t2 = raw(&setup, "SomeApp/misc", "misc", TendrilMode::DirMerge);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1.clone(), t2.clone()], None);
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, CheckKind::NestedRemote);
+    assert_eq!(actual[0].severity, Severity::Warning);
+    assert_eq!(actual[0].outer, t2);
+    assert_eq!(actual[0].inner, t1);
+    assert_eq!(actual[0].outer_path, setup.parent_dir.join("misc"));
+    assert_eq!(actual[0].inner_path, setup.parent_dir.join("misc/nested.txt"));
+}
+
+#[test]
+fn link_inside_dir_remote_returns_error() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc", "misc", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "OtherApp/nested.txt", "misc/nested.txt", TendrilMode::Link);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1.clone(), t2.clone()], None);
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, CheckKind::LinkInDirRemote);
+    assert_eq!(actual[0].severity, Severity::Error);
+    assert_eq!(actual[0].outer, t1);
+    assert_eq!(actual[0].inner, t2);
+}
+
+#[test]
+fn remote_with_common_prefix_but_not_nested_returns_empty() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc", "misc", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "OtherApp/misc", "miscOther", TendrilMode::Link);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2], None);
+
+    assert!(actual.is_empty());
+}
+
+#[rstest]
+#[case(None, Severity::Error)]
+#[case(Some(ActionMode::Push), Severity::Error)]
+#[case(Some(ActionMode::Out), Severity::Error)]
+#[case(Some(ActionMode::Pull), Severity::Warning)]
+#[case(Some(ActionMode::Link), Severity::Warning)]
+#[case(Some(ActionMode::Relink), Severity::Warning)]
+fn nested_local_is_error_when_pushing_otherwise_warning(
+    #[case] mode: Option<ActionMode>,
+    #[case] exp_severity: Severity,
+) {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc/nested.txt", "nested.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "SomeApp/misc", "misc", TendrilMode::DirOverwrite);
+
+    let actual = check_tendrils(
+        &setup.uni_td_repo(),
+        &[t1.clone(), t2.clone()],
+        mode.as_ref(),
+    );
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, CheckKind::NestedLocal);
+    assert_eq!(actual[0].severity, exp_severity);
+    assert_eq!(actual[0].outer, t2);
+    assert_eq!(actual[0].inner, t1);
+    assert_eq!(actual[0].outer_path, PathBuf::from("SomeApp/misc"));
+    assert_eq!(actual[0].inner_path, PathBuf::from("SomeApp/misc/nested.txt"));
+}
+
+#[test]
+fn nested_local_with_multiple_remotes_is_only_reported_once() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc", "misc1", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "SomeApp/misc", "misc2", TendrilMode::DirOverwrite);
+    let t3 = raw(&setup, "SomeApp/misc/nested.txt", "nested1.txt", TendrilMode::DirOverwrite);
+    let t4 = raw(&setup, "SomeApp/misc/nested.txt", "nested2.txt", TendrilMode::DirOverwrite);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2, t3, t4], None);
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, CheckKind::NestedLocal);
+}
+
+#[test]
+fn nested_local_and_nested_remote_both_reported() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc", "misc", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "SomeApp/misc/nested.txt", "misc/nested.txt", TendrilMode::Link);
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2], None);
+
+    let actual_kinds: Vec<_> = actual.iter().map(|f| f.kind.clone()).collect();
+    assert_eq!(
+        actual_kinds,
+        vec![CheckKind::LinkInDirRemote, CheckKind::NestedLocal],
+    );
+}
+
+#[test]
+fn unresolvable_tendrils_are_skipped() {
+    let setup = Setup::new();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let mut t2 = raw(&setup, "OtherApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    t2.local = "../misc.txt".to_string();
+
+    let actual = check_tendrils(&setup.uni_td_repo(), &[t1, t2], None);
+
+    assert!(actual.is_empty());
+}
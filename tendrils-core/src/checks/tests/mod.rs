@@ -0,0 +1 @@
+mod check_tendrils_tests;
@@ -1,60 +1,384 @@
-use crate::ConfigType;
-use crate::enums::{GetConfigError, OneOrMany, TendrilMode};
-use crate::env_ext::get_home_dir;
-use crate::path_ext::UniPath;
+use crate::{ConfigFormat, ConfigType};
+use crate::enums::{
+    GetConfigError,
+    NestedSymlinks,
+    OneOrMany,
+    ParseErrorLocation,
+    ReflinkMode,
+    TendrilMode,
+};
+use crate::env_ext::{get_home_dir, get_xdg_dir};
+use crate::path_ext::{tokenize_vars, PathToken, UniPath, VarFallback};
+use crate::profiles::{
+    is_tendril_profile_expr,
+    AutoProfiles,
+    ProfileExpr,
+    ProfileGroups,
+};
 use crate::tendril::RawTendril;
-use serde::{de, Deserialize, Deserializer, Serialize};
-use std::path::PathBuf;
+use glob_match::glob_match;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::path::{Path, PathBuf};
+
+mod fmt;
+pub(crate) use fmt::fmt_repo_config;
+mod schema;
+pub use schema::config_schema;
 
 #[cfg(test)]
 mod tests;
 
-/// Intermediate serialization type for a Tendrils repo configuration.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// Intermediate serialization type for a Tendrils repo configuration. Its
+/// [`JsonSchema`] is used for the [`config_schema`].
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[schemars(
+    title = "tendrils.json",
+    description = "Defines the tendrils in a Tendrils repo.",
+    deny_unknown_fields
+)]
 struct SerdeConfig {
+    /// The JSON Schema that this file follows, for use by editors. This is
+    /// otherwise ignored.
+    #[serde(rename = "$schema")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+
+    /// The version of the layout that this file follows (see
+    /// [`CONFIG_VERSION`]). Files without a version follow version 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "The version of the layout that this file follows. \
+            Files without a version follow version 1.",
+        range(min = 1, max = "CONFIG_VERSION")
+    )]
+    pub version: Option<u32>,
+
     /// The tendrils that are defined in a Tendrils repo.
     /// Using [`IndexMap`](indexmap::IndexMap) to maintain the
     /// order of insertions when iterating over the map.
     #[serde(default)]
-    pub tendrils: indexmap::IndexMap<String, OneOrMany<TendrilSet>>
+    #[schemars(
+        description = "Maps each local path, relative to the Tendrils repo, \
+            to one or more sets of remotes."
+    )]
+    pub tendrils: indexmap::IndexMap<String, OneOrMany<TendrilSet>>,
+
+    /// Profiles that are activated automatically on hosts matching
+    /// their rules.
+    #[serde(rename = "auto-profiles")]
+    #[serde(default, skip_serializing_if = "indexmap::IndexMap::is_empty")]
+    pub auto_profiles: AutoProfiles,
+
+    /// Named groups of profiles.
+    #[serde(rename = "profile-groups")]
+    #[serde(default, skip_serializing_if = "indexmap::IndexMap::is_empty")]
+    pub profile_groups: ProfileGroups,
+
+    /// Other config files to include, relative to the `.tendrils` folder.
+    /// Entries may be glob patterns. Only the `tendrils.json` file may
+    /// include other files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+
+    /// Values that are applied to each [`TendrilSet`] in this file unless the
+    /// set overrides them. Included files inherit the defaults from the
+    /// `tendrils.json` file for any values they do not set themselves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Values that are applied to each tendril in this file \
+            unless the tendril sets them itself. Included files inherit the \
+            defaults from the tendrils.json file."
+    )]
+    pub defaults: Option<TendrilDefaults>,
+
+    /// Variables that can be used in the remotes using the `<NAME>` syntax.
+    /// These are available to the tendrils in all files in the repo.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub vars: RepoVars,
+
+    /// Whether the [`Self::vars`] take priority over environment variables
+    /// of the same name. Defaults to [`VarPriority::Repo`]. Included files
+    /// inherit the value from the `tendrils.json` file unless they set it
+    /// themselves.
+    #[serde(rename = "var-priority")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Whether the vars (repo) or environment variables \
+            (env) of the same name are used. Defaults to repo."
+    )]
+    pub var_priority: Option<VarPriority>,
+
+    /// Whether files are reflinked when they are copied. Defaults to
+    /// [`ReflinkMode::Never`]. This applies to the whole repo, so it is only
+    /// read from the `tendrils.json` file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Whether files are copied as reflinks (copy-on-write \
+            clones). Defaults to never. Only read from the tendrils.json \
+            file."
+    )]
+    pub reflink: Option<ReflinkMode>,
+}
+
+/// Variables defined in a Tendrils repo, keyed by name.
+pub(crate) type RepoVars = IndexMap<String, String>;
+
+/// Intermediate serialization type for the values that are applied to each
+/// [`TendrilSet`] unless the set overrides them.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[schemars(
+    rename = "tendrilDefaults",
+    description = "Values that are applied to each tendril in this file \
+        unless the tendril sets them itself.",
+    deny_unknown_fields
+)]
+struct TendrilDefaults {
+    #[serde(rename = "dir-merge")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true merges the folder contents with the destination, \
+            false overwrites the destination folder. Ignored for link and \
+            hard link tendrils."
+    )]
+    pub dir_merge: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true creates a symlink at each remote instead of \
+            copying, regardless of the dir-merge setting."
+    )]
+    pub link: Option<bool>,
+
+    #[serde(rename = "relative-link")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true creates the symlinks of link tendrils relative \
+            to the remote's folder instead of as absolute paths."
+    )]
+    pub relative_link: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true creates a hard link to the local file at each \
+            remote instead of copying, regardless of the dir-merge setting. \
+            Ignored if link is true."
+    )]
+    pub hardlink: Option<bool>,
+
+    #[serde(rename = "copy-fallback")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true copies hard link tendrils instead if the remote \
+            is on a different file system than the Tendrils repo."
+    )]
+    pub copy_fallback: Option<bool>,
+
+    #[serde(rename = "nested-symlinks")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "How symlinks inside of folder tendrils are copied. \
+            Defaults to follow."
+    )]
+    pub nested_symlinks: Option<NestedSymlinks>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "one_or_many_to_opt_vec")]
+    #[serde(serialize_with = "opt_vec_to_one_or_many")]
+    #[schemars(
+        with = "Option<OneOrMany<String>>",
+        description = "A list of profiles to which this tendril belongs. If \
+            empty, this tendril is included in all profiles. Entries may \
+            also be profile expressions such as \"!work\" or \
+            \"home & (linux | macos)\"."
+    )]
+    pub profiles: Option<Vec<String>>,
+}
+
+impl TendrilDefaults {
+    /// Fills any values that are not set with those from the `parent`.
+    fn inherit(self, parent: &TendrilDefaults) -> TendrilDefaults {
+        TendrilDefaults {
+            dir_merge: self.dir_merge.or(parent.dir_merge),
+            link: self.link.or(parent.link),
+            relative_link: self.relative_link.or(parent.relative_link),
+            hardlink: self.hardlink.or(parent.hardlink),
+            copy_fallback: self.copy_fallback.or(parent.copy_fallback),
+            nested_symlinks: self.nested_symlinks.or(parent.nested_symlinks),
+            profiles: self.profiles.or_else(|| parent.profiles.clone()),
+        }
+    }
+}
+
+/// Indicates which value to use when a repo variable and an environment
+/// variable have the same name.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub(crate) enum VarPriority {
+    /// The repo variable is used.
+    #[default]
+    #[serde(rename = "repo")]
+    Repo,
+
+    /// The environment variable is used if it is set, otherwise the repo
+    /// variable is used.
+    #[serde(rename = "env")]
+    Env,
 }
 
 /// Contains the configuration context for a Tendrils repo.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct Config {
     /// The tendrils that are defined in a Tendrils repo.
-    pub raw_tendrils: Vec<RawTendril>
+    pub raw_tendrils: Vec<RawTendril>,
+
+    /// Profiles that are activated automatically on hosts matching
+    /// their rules.
+    pub auto_profiles: AutoProfiles,
+
+    /// Named groups of profiles.
+    pub profile_groups: ProfileGroups,
+
+    /// The format of the repo config file.
+    pub format: ConfigFormat,
 }
 
 impl From<SerdeConfig> for Config {
     fn from(serde_cfg: SerdeConfig) -> Self {
-        let raw_tendrils = serde_cfg.tendrils.into_iter().map(|(k, v)| {
+        let defaults = serde_cfg.defaults.unwrap_or_default();
+        let vars = serde_cfg.vars;
+        let var_priority = serde_cfg.var_priority.unwrap_or_default();
+        let reflink = serde_cfg.reflink.unwrap_or_default();
+
+        let raw_tendrils = serde_cfg.tendrils.into_iter().flat_map(|(k, v)| {
             let remote_specs: Vec<TendrilSet> = v.into();
+            let defaults = &defaults;
+            let vars = &vars;
 
-            remote_specs.into_iter().map(move |spec| {
-                let mode = match (spec.dir_merge, spec.link) {
-                    (true, false) => TendrilMode::DirMerge,
-                    (false, false) => TendrilMode::DirOverwrite,
-                    (_, true) => TendrilMode::Link,
+            remote_specs.into_iter().flat_map(move |spec| {
+                let dir_merge =
+                    spec.dir_merge.or(defaults.dir_merge).unwrap_or(false);
+                let link = spec.link.or(defaults.link).unwrap_or(false);
+                let hardlink =
+                    spec.hardlink.or(defaults.hardlink).unwrap_or(false);
+                let mode = match (dir_merge, link, hardlink) {
+                    (true, false, false) => TendrilMode::DirMerge,
+                    (false, false, false) => TendrilMode::DirOverwrite,
+                    (_, true, _) => TendrilMode::Link,
+                    (_, false, true) => TendrilMode::Hardlink,
                 };
+                let relative_link = spec
+                    .relative_link
+                    .or(defaults.relative_link)
+                    .unwrap_or(false);
+                let copy_fallback = spec
+                    .copy_fallback
+                    .or(defaults.copy_fallback)
+                    .unwrap_or(false);
+                let nested_symlinks = spec
+                    .nested_symlinks
+                    .or(defaults.nested_symlinks)
+                    .unwrap_or_default();
 
                 let local = k.clone();
-                let profiles = spec.profiles.clone();
-                spec.remotes.into_iter().map(move |r| -> RawTendril {
-                    RawTendril {
-                        local: local.clone(),
-                        remote: r.clone(),
-                        mode: mode.clone(),
-                        profiles: profiles.clone(),
-                    }
-                })
-            }).flatten()
-        }).flatten().collect();
+                let profiles = spec
+                    .profiles
+                    .or_else(|| defaults.profiles.clone())
+                    .unwrap_or_default();
+                let remotes: Vec<String> = spec
+                    .remotes
+                    .iter()
+                    .map(|r| resolve_repo_vars(r, vars, var_priority))
+                    .collect();
+                let remote_groups: Vec<Vec<String>> = if spec.fallback {
+                    // All remotes are combined into a single tendril
+                    vec![remotes]
+                }
+                else {
+                    remotes.into_iter().map(|r| vec![r]).collect()
+                };
+
+                remote_groups
+                    .into_iter()
+                    .filter(|g| !g.is_empty())
+                    .map(move |mut g| -> RawTendril {
+                        RawTendril {
+                            local: local.clone(),
+                            remote: g.remove(0),
+                            fallbacks: g,
+                            mode: mode.clone(),
+                            relative_link,
+                            copy_fallback,
+                            reflink,
+                            nested_symlinks,
+                            profiles: profiles.clone(),
+                            source: None,
+                        }
+                    })
+            })
+        }).collect();
 
         Config {
-            raw_tendrils
+            raw_tendrils,
+            auto_profiles: serde_cfg.auto_profiles,
+            profile_groups: serde_cfg.profile_groups,
+            format: ConfigFormat::Json,
+        }
+    }
+}
+
+/// Replaces each variable in the `remote` (in any of the forms supported by
+/// [`resolve_env_variables`](crate::path_ext::PathExt::resolve_env_variables))
+/// with the value of the repo variable of the same name. If there is no such
+/// repo variable, or if the `priority` is [`VarPriority::Env`] and an
+/// environment variable of the same name is set, the variable is left as-is
+/// to be resolved as an environment variable later on. Variable values are
+/// not themselves searched for other repo variables.
+fn resolve_repo_vars(
+    remote: &str,
+    vars: &RepoVars,
+    priority: VarPriority,
+) -> String {
+    if vars.is_empty() {
+        return remote.to_string();
+    }
+
+    let mut resolved = String::with_capacity(remote.len());
+    for token in tokenize_vars(remote.as_bytes()) {
+        // The tokens are split on ASCII characters, so they are valid UTF-8
+        let raw = match &token {
+            PathToken::Text(t) | PathToken::Escaped(t) => t,
+            PathToken::Var(var) => var.raw,
+        };
+        let raw = std::str::from_utf8(raw).unwrap();
+
+        let PathToken::Var(var) = token else {
+            // Escapes are kept so that they are decoded along with the
+            // environment variables
+            resolved.push_str(raw);
+            continue;
+        };
+        let name = std::str::from_utf8(var.name).unwrap();
+        let use_env = priority == VarPriority::Env
+            && std::env::var_os(name).is_some();
+
+        match vars.get(name) {
+            // Empty values fall through to the default/required handling
+            Some(v) if v.is_empty() && var.fallback != VarFallback::None => {
+                resolved.push_str(raw)
+            }
+            Some(v) if !use_env => resolved.push_str(v),
+            _ => resolved.push_str(raw),
         }
     }
+
+    resolved
 }
 
 #[cfg(any(test, feature = "_test_utils"))]
@@ -80,27 +404,81 @@ impl From<Config> for SerdeConfig {
             tendril_map.insert(local, added_sets.into());
         }
 
-        SerdeConfig { tendrils: tendril_map }
+        SerdeConfig {
+            schema: None,
+            version: None,
+            tendrils: tendril_map,
+            auto_profiles: cfg.auto_profiles,
+            profile_groups: cfg.profile_groups,
+            include: vec![],
+            defaults: None,
+            vars: IndexMap::new(),
+            var_priority: None,
+            reflink: None,
+        }
     }
 }
 
-/// Contains the global configuration context for Tendrils.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// Contains the global configuration context for Tendrils. Its
+/// [`JsonSchema`] is used for the [`config_schema`].
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[schemars(
+    title = "global-config.json",
+    description = "The global configuration for Tendrils.",
+    deny_unknown_fields
+)]
 pub(crate) struct GlobalConfig {
-    /// The path to the default Tendrils repo.
+    /// The JSON Schema that this file follows, for use by editors. This is
+    /// otherwise ignored.
+    #[serde(rename = "$schema")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+
+    /// The version of the layout that this file follows (see
+    /// [`CONFIG_VERSION`]). Files without a version follow version 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "The version of the layout that this file follows. \
+            Files without a version follow version 1.",
+        range(min = 1, max = "CONFIG_VERSION")
+    )]
+    pub version: Option<u32>,
+
+    /// The path to the default Tendrils repo, or a list of paths if there
+    /// are several default repos.
     #[serde(rename = "default-repo-path")]
-    pub default_repo_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_repo_path: Option<OneOrMany<PathBuf>>,
 
     /// The default profiles to be used on this host.
     #[serde(rename = "default-profiles")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub default_profiles: Option<Vec<String>>,
+
+    /// Profiles that are activated automatically on hosts matching
+    /// their rules.
+    #[serde(rename = "auto-profiles")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_profiles: Option<AutoProfiles>,
 }
 
 impl GlobalConfig {
     fn new() -> GlobalConfig {
         GlobalConfig {
+            schema: None,
+            version: None,
             default_repo_path: None,
             default_profiles: None,
+            auto_profiles: None,
+        }
+    }
+
+    /// Returns each of the default Tendrils repo paths, in the order they
+    /// are listed.
+    pub fn default_repo_paths(&self) -> Vec<PathBuf> {
+        match &self.default_repo_path {
+            Some(v) => v.clone().into(),
+            None => vec![],
         }
     }
 }
@@ -127,6 +505,12 @@ impl LazyCachedGlobalConfig {
         }
     }
 
+    /// Allows mocking the return value during tests.
+    #[cfg(test)]
+    pub fn mock(cfg: GlobalConfig) -> LazyCachedGlobalConfig {
+        LazyCachedGlobalConfig { cached_cfg: Some(Ok(cfg)) }
+    }
+
     /// Allows mocking the return value during tests.
     #[cfg(test)]
     pub fn mock_w_parse_err() -> LazyCachedGlobalConfig {
@@ -134,6 +518,7 @@ impl LazyCachedGlobalConfig {
         cfg.cached_cfg = Some(Err(GetConfigError::ParseError {
             cfg_type: ConfigType::Global,
             msg: String::from("MOCK VALUE"),
+            loc: None,
         }));
 
         cfg
@@ -141,47 +526,154 @@ impl LazyCachedGlobalConfig {
 }
 
 /// Intermediate serialization type representing a one-to-many set of tendrils.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[schemars(
+    rename = "tendrilSet",
+    description = "The remotes of a local, and the settings of the tendrils \
+        that they form.",
+    deny_unknown_fields
+)]
 struct TendrilSet {
     #[serde(deserialize_with = "one_or_many_to_vec")]
+    #[serde(serialize_with = "vec_to_one_or_many")]
+    #[schemars(
+        with = "OneOrMany<String>",
+        description = "The paths at which to recreate the local. Each remote \
+            forms its own tendril unless fallback is true."
+    )]
     pub remotes: Vec<String>,
 
     /// `true` indicates that each tendril will have
     /// [`crate::TendrilMode::DirMerge`]. `false` indicates
     /// [`crate::TendrilMode::DirOverwrite`]. Note: this field
-    /// may be overriden depending on the value of `link`.
+    /// may be overriden depending on the value of `link`. If not set, the
+    /// [`TendrilDefaults`] value is used, otherwise `false`.
     #[serde(rename = "dir-merge")]
-    #[serde(default)]
-    pub dir_merge: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true merges the folder contents with the destination, \
+            false overwrites the destination folder. Ignored for link and \
+            hard link tendrils."
+    )]
+    pub dir_merge: Option<bool>,
 
     /// `true` indicates that each tendril will have
-    /// [`crate::TendrilMode::Link`], regardless of what the `dir_merge`
-    /// setting is. `false` indicates that the `dir_merge` setting will be
-    /// used.
-    #[serde(default)]
-    pub link: bool,
+    /// [`crate::TendrilMode::Link`], regardless of what the `dir_merge` and
+    /// `hardlink` settings are. `false` indicates that the other settings
+    /// will be used. If not set, the [`TendrilDefaults`] value is used,
+    /// otherwise `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true creates a symlink at each remote instead of \
+            copying, regardless of the dir-merge setting."
+    )]
+    pub link: Option<bool>,
+
+    /// `true` indicates that link tendrils are created as relative symlinks
+    /// (see [`RawTendril::relative_link`]). This has no effect on the other
+    /// modes. If not set, the [`TendrilDefaults`] value is used, otherwise
+    /// `false`.
+    #[serde(rename = "relative-link")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true creates the symlinks of link tendrils relative \
+            to the remote's folder instead of as absolute paths."
+    )]
+    pub relative_link: Option<bool>,
+
+    /// `true` indicates that each tendril will have
+    /// [`crate::TendrilMode::Hardlink`], regardless of what the `dir_merge`
+    /// setting is, unless `link` is also `true`. If not set, the
+    /// [`TendrilDefaults`] value is used, otherwise `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true creates a hard link to the local file at each \
+            remote instead of copying, regardless of the dir-merge setting. \
+            Ignored if link is true."
+    )]
+    pub hardlink: Option<bool>,
+
+    /// `true` indicates that hard link tendrils are copied instead if they
+    /// cannot be hard linked across file systems (see
+    /// [`RawTendril::copy_fallback`]). This has no effect on the other modes.
+    /// If not set, the [`TendrilDefaults`] value is used, otherwise `false`.
+    #[serde(rename = "copy-fallback")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "bool_to_opt")]
+    #[schemars(
+        description = "true copies hard link tendrils instead if the remote \
+            is on a different file system than the Tendrils repo."
+    )]
+    pub copy_fallback: Option<bool>,
+
+    /// How the symlinks inside of folder tendrils are copied (see
+    /// [`RawTendril::nested_symlinks`]). If not set, the [`TendrilDefaults`]
+    /// value is used, otherwise [`NestedSymlinks::Follow`].
+    #[serde(rename = "nested-symlinks")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "How symlinks inside of folder tendrils are copied. \
+            Defaults to follow."
+    )]
+    pub nested_symlinks: Option<NestedSymlinks>,
 
     /// A list of profiles to which this tendril belongs. If empty,
     /// this tendril is considered to be included in *all* profiles.
-    #[serde(default)]
-    #[serde(deserialize_with = "one_or_many_to_vec")]
-    pub profiles: Vec<String>,
+    /// Entries may also be profile expressions (see [`ProfileExpr`]). If not
+    /// set, the [`TendrilDefaults`] value is used, otherwise it is empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(deserialize_with = "one_or_many_to_opt_vec")]
+    #[serde(serialize_with = "opt_vec_to_one_or_many")]
+    #[schemars(
+        with = "Option<OneOrMany<String>>",
+        description = "A list of profiles to which this tendril belongs. If \
+            empty, this tendril is included in all profiles. Entries may \
+            also be profile expressions such as \"!work\" or \
+            \"home & (linux | macos)\"."
+    )]
+    pub profiles: Option<Vec<String>>,
+
+    /// `true` indicates that the `remotes` are an ordered list of
+    /// alternatives for a single tendril, rather than each remote defining
+    /// its own tendril. Only the first remote that exists is used (see
+    /// [`RawTendril::fallbacks`]).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    #[schemars(
+        description = "true treats the remotes as an ordered list of \
+            alternatives for a single tendril. Only the first remote that \
+            exists is used."
+    )]
+    pub fallback: bool,
 }
 
 #[cfg(any(test, feature = "_test_utils"))]
 impl From<RawTendril> for TendrilSet {
     fn from(raw: RawTendril) -> Self {
-        let (dir_merge, link) = match raw.mode {
-            TendrilMode::DirMerge => (true, false),
-            TendrilMode::DirOverwrite => (false, false),
-            TendrilMode::Link => (false, true),
+        let (dir_merge, link, hardlink) = match raw.mode {
+            TendrilMode::DirMerge => (true, false, false),
+            TendrilMode::DirOverwrite => (false, false, false),
+            TendrilMode::Link => (false, true, false),
+            TendrilMode::Hardlink => (false, false, true),
         };
 
+        let fallback = !raw.fallbacks.is_empty();
+        let mut remotes = vec![raw.remote];
+        remotes.extend(raw.fallbacks);
+
         TendrilSet {
-            remotes: vec![raw.remote],
-            dir_merge,
-            link,
-            profiles: raw.profiles,
+            remotes,
+            dir_merge: Some(dir_merge),
+            link: Some(link),
+            relative_link: Some(raw.relative_link),
+            hardlink: Some(hardlink),
+            copy_fallback: Some(raw.copy_fallback),
+            nested_symlinks: Some(raw.nested_symlinks),
+            profiles: Some(raw.profiles),
+            fallback,
         }
     }
 }
@@ -200,30 +692,976 @@ fn one_or_many_to_vec<'de, D: Deserializer<'de>>(
     Ok(one_or_many.into())
 }
 
-/// Parses the `tendrils.json` file in the given Tendrils repo and returns
-/// the configuration within.
-/// The tendril bundles are returned in the order they are defined in the file.
+/// Deserializes a value that may be absent (see `#[serde(default)]`) but
+/// cannot be `null`.
+fn bool_to_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<bool>, D::Error> {
+    Ok(Some(de::Deserialize::deserialize(deserializer)?))
+}
+
+fn one_or_many_to_opt_vec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<String>>, D::Error> {
+    Ok(Some(one_or_many_to_vec(deserializer)?))
+}
+
+/// Serializes a list with a single item as that item.
+fn vec_to_one_or_many<S: Serializer>(
+    value: &[String],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    OneOrMany::from(value.to_vec()).serialize(serializer)
+}
+
+fn opt_vec_to_one_or_many<S: Serializer>(
+    value: &Option<Vec<String>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(v) => vec_to_one_or_many(v, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// The folder (relative to the `.tendrils` folder) whose `.json`, `.jsonc`
+/// and `.toml` files are always included in the repo configuration.
+const INCLUDE_DIR: &str = "tendrils.d";
+
+/// Parses the repo config file (`tendrils.json`, `tendrils.jsonc` or
+/// `tendrils.toml`) in the given Tendrils repo, along with any files that it
+/// includes, and returns the combined configuration.
+/// The files are combined in this order:
+/// 1. The repo config file
+/// 2. The files listed in its `include` array, in the order they are listed.
+///    Files matching a glob pattern are sorted by path.
+/// 3. The config files in the `.tendrils/tendrils.d` folder, sorted by name
+///
+/// Each file is only included once, and is parsed according to its
+/// [file extension](ConfigFormat::from_path). The tendril bundles are
+/// returned in the order they are defined in the files. Returns
+/// [`GetConfigError::DuplicateKey`] if the same local, automatic profile, or
+/// profile group is defined in more than one file, or
+/// [`GetConfigError::MultipleRepoConfigs`] if there is more than one repo
+/// config file.
 ///
 /// # Arguments
 /// - `td_repo` - Path to the Tendrils folder.
 pub(crate) fn get_config(
     td_repo: &UniPath,
 ) -> Result<Config, GetConfigError> {
-    let config_file_path = td_repo.inner().join(".tendrils/tendrils.json");
-    let config_file_contents = std::fs::read_to_string(config_file_path)?;
-    let serde_config = parse_config(&config_file_contents)?;
-    Ok(serde_config.into())
+    let dot_td_dir = td_repo.inner().join(".tendrils");
+    let format = find_repo_config(&dot_td_dir)?;
+    let cfg_type = ConfigType::Repo(format);
+    let serde_config =
+        read_serde_config(&dot_td_dir.join(cfg_type.file_name()), format, &cfg_type)?;
+
+    let mut files = vec![];
+    for path in find_include_files(&dot_td_dir, &serde_config.include)? {
+        let cfg_type = ConfigType::RepoInclude(path.clone());
+        let serde_include = read_serde_config(
+            &dot_td_dir.join(&path),
+            include_format(&path),
+            &cfg_type,
+        )?;
+
+        if !serde_include.include.is_empty() {
+            return Err(GetConfigError::ParseError {
+                cfg_type,
+                msg: String::from(
+                    "Included files cannot include other files"
+                ),
+                loc: None,
+            });
+        }
+        files.push((cfg_type, serde_include));
+    }
+
+    files.insert(0, (cfg_type, serde_config));
+    merge_configs(files)
+}
+
+/// Returns the format of the repo config file in the `dot_td_dir`. Returns
+/// [`GetConfigError::MultipleRepoConfigs`] if there is more than one of them,
+/// or a [`std::io::ErrorKind::NotFound`] error if there are none.
+pub(crate) fn find_repo_config(
+    dot_td_dir: &Path,
+) -> Result<ConfigFormat, GetConfigError> {
+    let formats: Vec<ConfigFormat> = ConfigFormat::ALL
+        .iter()
+        .copied()
+        .filter(|f| dot_td_dir.join(ConfigType::Repo(*f).file_name()).is_file())
+        .collect();
+
+    match formats.as_slice() {
+        [] => Err(GetConfigError::IoError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            kind: std::io::ErrorKind::NotFound,
+        }),
+        [format] => Ok(*format),
+        _ => Err(GetConfigError::MultipleRepoConfigs { formats }),
+    }
+}
+
+/// Returns the format of an included file based on its extension. Files
+/// with any other extension are parsed as JSON.
+pub(crate) fn include_format(path: &Path) -> ConfigFormat {
+    ConfigFormat::from_path(path).unwrap_or(ConfigFormat::Json)
+}
+
+/// Reads the config file at the `path` and parses it according to its
+/// `format`. The `cfg_type` is only used to report any errors.
+fn read_serde_config(
+    path: &Path,
+    format: ConfigFormat,
+    cfg_type: &ConfigType,
+) -> Result<SerdeConfig, GetConfigError> {
+    let text = read_config_file(path, cfg_type)?;
+    deserialize_serde_config(&text, format, cfg_type)
+}
+
+/// Parses the `text` of a repo config file according to its `format` (see
+/// [`read_serde_config`]).
+fn deserialize_serde_config(
+    text: &str,
+    format: ConfigFormat,
+    cfg_type: &ConfigType,
+) -> Result<SerdeConfig, GetConfigError> {
+    deserialize_serde_config_with(text, format, cfg_type, REPO_MIGRATIONS)
+}
+
+/// Parses the `text` of a repo config file according to its `format` (see
+/// [`read_serde_config`]), without upgrading it from its own version. Any
+/// version that is not supported is still reported as an error.
+fn deserialize_serde_config_unmigrated(
+    text: &str,
+    format: ConfigFormat,
+    cfg_type: &ConfigType,
+) -> Result<SerdeConfig, GetConfigError> {
+    let version = read_version(text, format, cfg_type).unwrap_or(1);
+    let migrations = if (1..=CONFIG_VERSION).contains(&version) {
+        &REPO_MIGRATIONS[..version as usize - 1]
+    }
+    else {
+        REPO_MIGRATIONS
+    };
+    deserialize_serde_config_with(text, format, cfg_type, migrations)
+}
+
+fn deserialize_serde_config_with(
+    text: &str,
+    format: ConfigFormat,
+    cfg_type: &ConfigType,
+    migrations: &[Migration],
+) -> Result<SerdeConfig, GetConfigError> {
+    let serde_cfg: SerdeConfig =
+        deserialize_versioned(text, format, cfg_type, migrations)?;
+    check_profile_exprs(&serde_cfg)
+        .map_err(|e| parse_err(e, text, cfg_type.clone()))?;
+    Ok(serde_cfg)
+}
+
+/// Reads the text of the config file at the `path`. The `cfg_type` is only
+/// used to report any errors.
+pub(crate) fn read_config_file(
+    path: &Path,
+    cfg_type: &ConfigType,
+) -> Result<String, GetConfigError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| GetConfigError::from(e).with_cfg_type(cfg_type.clone()))
 }
 
-/// Parses the `~/.tendrils/global-config.json` file and returns the
-/// configuration within. If the file doesn't exist, an empty configuration is
-/// returned (i.e all fields set to `None`).
+/// Deserializes the `text` of a config file in the given `format`. The
+/// `cfg_type` is only used to report any errors.
+pub(crate) fn deserialize_config<T: DeserializeOwned>(
+    text: &str,
+    format: ConfigFormat,
+    cfg_type: &ConfigType,
+) -> Result<T, GetConfigError> {
+    match format {
+        ConfigFormat::Json => serde_json::from_str(text)
+            .map_err(|e| parse_err(e, text, cfg_type.clone())),
+        ConfigFormat::Jsonc => {
+            let json = strip_jsonc(text);
+            serde_json::from_str(&json).map_err(|e| {
+                let mut err = parse_err(e, &json, cfg_type.clone());
+                // Show the original line, including any comments
+                if let GetConfigError::ParseError { loc: Some(loc), .. } = &mut err {
+                    loc.line_text = text
+                        .split('\n')
+                        .nth(loc.line - 1)
+                        .unwrap_or_default()
+                        .trim_end_matches('\r')
+                        .to_string();
+                }
+                err
+            })
+        }
+        ConfigFormat::Toml => toml_edit::de::from_str(text)
+            .map_err(|e| toml_parse_err(e, text, cfg_type.clone())),
+    }
+}
+
+/// The latest version of the layout of the config files. Any files with an
+/// older version are [migrated](migrate_value) to this version when they are
+/// read.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Upgrades the layout of a config file by one version, before it is
+/// deserialized.
+pub(crate) type Migration = fn(&mut serde_json::Value);
+
+/// The migrations for the repo config files. The first entry upgrades
+/// version 1 to version 2, and so on, so there must be one less entry than
+/// the [`CONFIG_VERSION`].
+const REPO_MIGRATIONS: &[Migration] = &[escape_repo_dollars];
+
+/// The migrations for the `global-config.json` file (see
+/// [`REPO_MIGRATIONS`]).
+const GLOBAL_MIGRATIONS: &[Migration] = &[escape_global_dollars];
+
+/// Upgrades a repo config file from version 1 to version 2. Version 2 added
+/// the `$NAME` and `${NAME}` forms of variables, so each `$` in the remotes,
+/// the `vars` and the `path` of the `auto-profiles` is escaped to keep its
+/// meaning as a literal `$`.
+fn escape_repo_dollars(value: &mut serde_json::Value) {
+    if let Some(tendrils) = value.get_mut("tendrils").and_then(|v| v.as_object_mut()) {
+        for set in tendrils.values_mut().flat_map(one_or_many_mut) {
+            if let Some(remotes) = set.get_mut("remotes") {
+                one_or_many_mut(remotes).into_iter().for_each(escape_dollars);
+            }
+        }
+    }
+    if let Some(vars) = value.get_mut("vars").and_then(|v| v.as_object_mut()) {
+        vars.values_mut().for_each(escape_dollars);
+    }
+    escape_auto_profile_dollars(value);
+}
+
+/// Upgrades the `global-config.json` file from version 1 to version 2 (see
+/// [`escape_repo_dollars`]) by escaping each `$` in the `default-repo-path`
+/// and the `path` of the `auto-profiles`.
+fn escape_global_dollars(value: &mut serde_json::Value) {
+    if let Some(paths) = value.get_mut("default-repo-path") {
+        one_or_many_mut(paths).into_iter().for_each(escape_dollars);
+    }
+    escape_auto_profile_dollars(value);
+}
+
+fn escape_auto_profile_dollars(value: &mut serde_json::Value) {
+    let Some(auto_profiles) = value
+        .get_mut("auto-profiles")
+        .and_then(|v| v.as_object_mut())
+    else {
+        return;
+    };
+    for rule in auto_profiles.values_mut().flat_map(one_or_many_mut) {
+        if let Some(path) = rule.get_mut("path") {
+            escape_dollars(path);
+        }
+    }
+}
+
+/// Returns the items of the `value` if it is a list, otherwise the `value`
+/// itself.
+fn one_or_many_mut(value: &mut serde_json::Value) -> Vec<&mut serde_json::Value> {
+    match value {
+        serde_json::Value::Array(items) => items.iter_mut().collect(),
+        _ => vec![value],
+    }
+}
+
+/// Replaces each `$` in the `value` with its escaped form `$$`, if it is a
+/// string.
+fn escape_dollars(value: &mut serde_json::Value) {
+    if let serde_json::Value::String(s) = value {
+        *s = s.replace('$', "$$");
+    }
+}
+
+/// Only used to read the version of a config file before the rest of it.
+#[derive(Deserialize)]
+struct ConfigVersion {
+    #[serde(default)]
+    version: Option<u32>,
+}
+
+/// Returns the version of the config file with the given `text`. Returns
+/// `None` if it does not have one, or if it cannot be parsed.
+fn read_version(text: &str, format: ConfigFormat, cfg_type: &ConfigType) -> Option<u32> {
+    deserialize_config::<ConfigVersion>(text, format, cfg_type)
+        .ok()
+        .and_then(|v| v.version)
+}
+
+/// Deserializes the `text` of a config file in the given `format` (see
+/// [`deserialize_config`]). If the file has an older version than the latest
+/// version of the `migrations` (see [`latest_version`]), it is upgraded using
+/// the `migrations` first. Returns [`GetConfigError::UnsupportedVersion`] if
+/// the version is newer than the latest version, or is `0`.
+fn deserialize_versioned<T: DeserializeOwned>(
+    text: &str,
+    format: ConfigFormat,
+    cfg_type: &ConfigType,
+    migrations: &[Migration],
+) -> Result<T, GetConfigError> {
+    let version = read_version(text, format, cfg_type).unwrap_or(1);
+    deserialize_from_version(text, format, cfg_type, version, migrations)
+}
+
+/// Same as [`deserialize_versioned`], where the `version` of the `text` has
+/// already been read.
+fn deserialize_from_version<T: DeserializeOwned>(
+    text: &str,
+    format: ConfigFormat,
+    cfg_type: &ConfigType,
+    version: u32,
+    migrations: &[Migration],
+) -> Result<T, GetConfigError> {
+    let latest = latest_version(migrations);
+    if version == 0 || version > latest {
+        return Err(GetConfigError::UnsupportedVersion {
+            cfg_type: cfg_type.clone(),
+            version,
+            supported: latest,
+        });
+    }
+    if version == latest {
+        // Deserialize directly to keep the location of any errors
+        return deserialize_config(text, format, cfg_type);
+    }
+
+    // The version is replaced when migrating, so check that it is valid first
+    deserialize_config::<ConfigVersion>(text, format, cfg_type)?;
+    let mut value: serde_json::Value = deserialize_config(text, format, cfg_type)?;
+    migrate_value(&mut value, version, migrations);
+    T::deserialize(value).map_err(|e| {
+        // The migrated value has no location, so report the location from
+        // the original text instead if it is also invalid as-is
+        match deserialize_config::<T>(text, format, cfg_type) {
+            Err(err @ GetConfigError::ParseError { loc: Some(_), .. }) => err,
+            _ => GetConfigError::ParseError {
+                cfg_type: cfg_type.clone(),
+                msg: e.to_string(),
+                loc: None,
+            },
+        }
+    })
+}
+
+/// Returns the version that the config files are upgraded to by the
+/// `migrations`. For the [`REPO_MIGRATIONS`] and [`GLOBAL_MIGRATIONS`], this
+/// is the [`CONFIG_VERSION`].
+pub(crate) fn latest_version(migrations: &[Migration]) -> u32 {
+    migrations.len() as u32 + 1
+}
+
+/// Upgrades the `value` of a config file from the `version` to the latest
+/// version by applying each of the `migrations` after it in turn, then sets
+/// its `version` to the latest version. The first migration upgrades
+/// version 1 to version 2, and so on.
+pub(crate) fn migrate_value(
+    value: &mut serde_json::Value,
+    version: u32,
+    migrations: &[Migration],
+) {
+    let start = (version.max(1) - 1) as usize;
+    for migration in migrations.iter().skip(start) {
+        migration(value);
+    }
+
+    if let serde_json::Value::Object(map) = value {
+        map.insert(String::from("version"), latest_version(migrations).into());
+    }
+}
+
+/// Rewrites the repo config file in the `td_repo` in the layout of the
+/// [`CONFIG_VERSION`], keeping its format. Returns the version of the file
+/// before it was migrated, where files without a version are version 1.
+/// Nothing is changed if the file is already at the [`CONFIG_VERSION`],
+/// whether or not it has a version.
+///
+/// Only the repo config file is migrated, any files that it includes are
+/// migrated in memory whenever they are read. Any keys that are not
+/// recognized are not carried over when the file is rewritten. If the file
+/// contains comments, [`GetConfigError::CommentsWouldBeLost`] is returned
+/// instead, unless `drop_comments` is `true`.
+pub(crate) fn migrate_repo_config(
+    td_repo: &UniPath,
+    drop_comments: bool,
+) -> Result<u32, GetConfigError> {
+    migrate_repo_config_with(td_repo, drop_comments, REPO_MIGRATIONS)
+}
+
+/// Same as [`migrate_repo_config`], using the given `migrations` rather than
+/// the [`REPO_MIGRATIONS`].
+pub(crate) fn migrate_repo_config_with(
+    td_repo: &UniPath,
+    drop_comments: bool,
+    migrations: &[Migration],
+) -> Result<u32, GetConfigError> {
+    let dot_td_dir = td_repo.inner().join(".tendrils");
+    let format = find_repo_config(&dot_td_dir)?;
+    let cfg_type = ConfigType::Repo(format);
+    let path = dot_td_dir.join(cfg_type.file_name());
+    let text = read_config_file(&path, &cfg_type)?;
+    let version = read_version(&text, format, &cfg_type).unwrap_or(1);
+    let mut serde_cfg: SerdeConfig =
+        deserialize_from_version(&text, format, &cfg_type, version, migrations)?;
+    check_profile_exprs(&serde_cfg)
+        .map_err(|e| parse_err(e, &text, cfg_type.clone()))?;
+    let latest = latest_version(migrations);
+    if version == latest {
+        return Ok(version);
+    }
+    if !drop_comments && fmt::has_comments(&text, format) {
+        return Err(GetConfigError::CommentsWouldBeLost { cfg_type });
+    }
+
+    serde_cfg.version = Some(latest);
+    write_config(&path, &serde_cfg, format, cfg_type)?;
+    Ok(version)
+}
+
+/// Rewrites the `global-config.json` file in the layout of the
+/// [`CONFIG_VERSION`] (see [`migrate_repo_config`]). Returns a
+/// [`std::io::ErrorKind::NotFound`] error if the file does not exist.
+pub(crate) fn migrate_global_config() -> Result<u32, GetConfigError> {
+    migrate_global_config_with(GLOBAL_MIGRATIONS)
+}
+
+/// Same as [`migrate_global_config`], using the given `migrations` rather
+/// than the [`GLOBAL_MIGRATIONS`].
+pub(crate) fn migrate_global_config_with(
+    migrations: &[Migration],
+) -> Result<u32, GetConfigError> {
+    let cfg_type = ConfigType::Global;
+    let path = match global_config_paths().into_iter().find(|p| p.exists()) {
+        Some(v) => v,
+        None => return Err(GetConfigError::IoError {
+            cfg_type,
+            kind: std::io::ErrorKind::NotFound,
+        }),
+    };
+    let text = read_config_file(&path, &cfg_type)?;
+    let version = read_version(&text, ConfigFormat::Json, &cfg_type)
+        .unwrap_or(1);
+    let mut global_cfg: GlobalConfig = deserialize_from_version(
+        &text,
+        ConfigFormat::Json,
+        &cfg_type,
+        version,
+        migrations,
+    )?;
+    let latest = latest_version(migrations);
+    if version == latest {
+        return Ok(version);
+    }
+
+    global_cfg.version = Some(latest);
+    write_config(&path, &global_cfg, ConfigFormat::Json, cfg_type)?;
+    Ok(version)
+}
+
+/// Serializes the `cfg` in the given `format` (see
+/// [`serialize_serde_config`]) and writes it to the `path`. The `cfg_type` is
+/// only used to report any errors.
+fn write_config<T: Serialize>(
+    path: &Path,
+    cfg: &T,
+    format: ConfigFormat,
+    cfg_type: ConfigType,
+) -> Result<(), GetConfigError> {
+    let text = serialize_serde_config(cfg, format).map_err(|msg| {
+        GetConfigError::ParseError { cfg_type: cfg_type.clone(), msg, loc: None }
+    })?;
+    std::fs::write(path, text)
+        .map_err(|e| GetConfigError::from(e).with_cfg_type(cfg_type))
+}
+
+/// Converts the repo config file in the `td_repo` to the given `format`. The
+/// original file is only removed once the new file has been written. Returns
+/// the format that it was converted from. Nothing is changed if the file is
+/// already in the given `format`.
+///
+/// Only the repo config file is converted, any files that it includes are
+/// left as-is. Any keys that are not recognized are not carried over to the
+/// new file. If the file contains comments,
+/// [`GetConfigError::CommentsWouldBeLost`] is returned instead, unless
+/// `drop_comments` is `true`.
+pub(crate) fn convert_repo_config(
+    td_repo: &UniPath,
+    format: ConfigFormat,
+    drop_comments: bool,
+) -> Result<ConfigFormat, GetConfigError> {
+    let dot_td_dir = td_repo.inner().join(".tendrils");
+    let from = find_repo_config(&dot_td_dir)?;
+    if from == format {
+        return Ok(from);
+    }
+
+    let from_type = ConfigType::Repo(from);
+    let from_path = dot_td_dir.join(from_type.file_name());
+    let text = read_config_file(&from_path, &from_type)?;
+    let serde_cfg = deserialize_serde_config(&text, from, &from_type)?;
+    if !drop_comments && fmt::has_comments(&text, from) {
+        return Err(GetConfigError::CommentsWouldBeLost { cfg_type: from_type });
+    }
+
+    let to_type = ConfigType::Repo(format);
+    let to_path = dot_td_dir.join(to_type.file_name());
+    if let Err(e) = write_config(&to_path, &serde_cfg, format, to_type) {
+        // Only the original file is left, rather than a partial copy of it
+        let _ = std::fs::remove_file(&to_path);
+        return Err(e);
+    }
+    std::fs::remove_file(from_path)
+        .map_err(|e| GetConfigError::from(e).with_cfg_type(from_type))?;
+    Ok(from)
+}
+
+/// Serializes the `serde_cfg` in the given `format`. JSON is indented with
+/// four spaces, so that [formatting](crate::TendrilsApi::fmt_config) the file
+/// created by
+/// [`TendrilsApi::init_tendrils_repo`](crate::TendrilsApi::init_tendrils_repo)
+/// leaves it unchanged.
+fn serialize_serde_config<T: Serialize>(
+    serde_cfg: &T,
+    format: ConfigFormat,
+) -> Result<String, String> {
+    match format {
+        ConfigFormat::Json | ConfigFormat::Jsonc => {
+            let mut buf = vec![];
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+            let mut serializer =
+                serde_json::Serializer::with_formatter(&mut buf, formatter);
+            serde_cfg.serialize(&mut serializer).map_err(|e| e.to_string())?;
+            buf.push(b'\n');
+            String::from_utf8(buf).map_err(|e| e.to_string())
+        }
+        ConfigFormat::Toml => {
+            use toml_edit::visit_mut::VisitMut;
+
+            let toml = toml_edit::ser::to_string_pretty(serde_cfg)
+                .map_err(|e| e.to_string())?;
+            let mut doc = toml
+                .parse::<toml_edit::DocumentMut>()
+                .map_err(|e| e.to_string())?;
+            SpaceTables.visit_document_mut(&mut doc);
+            Ok(doc.to_string().trim_start().to_string())
+        }
+    }
+}
+
+/// Separates each table in a TOML document with a blank line.
+struct SpaceTables;
+
+impl toml_edit::visit_mut::VisitMut for SpaceTables {
+    fn visit_table_mut(&mut self, table: &mut toml_edit::Table) {
+        table.decor_mut().set_prefix("\n");
+        toml_edit::visit_mut::visit_table_mut(self, table);
+    }
+}
+
+/// Replaces the comments and trailing commas in the JSONC `text` with spaces
+/// so that it can be parsed as JSON. Each replaced character becomes a single
+/// space and line breaks are kept, so that the lines and columns in the
+/// result match those in the `text`.
+fn strip_jsonc(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out: Vec<char> = Vec::with_capacity(chars.len());
+
+    // The position in the `out` of the last comma, until another value is
+    // found. If it is followed by the end of an object or list it is removed.
+    let mut last_comma: Option<usize> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match (c, next) {
+            ('"', _) => {
+                last_comma = None;
+                out.push(c);
+                i += 1;
+                while let Some(&c) = chars.get(i) {
+                    out.push(c);
+                    i += 1;
+                    if c == '\\' {
+                        out.extend(chars.get(i));
+                        i += 1;
+                    }
+                    else if c == '"' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(' ');
+                    i += 1;
+                }
+                continue;
+            }
+            ('/', Some('*')) => {
+                out.extend([' ', ' ']);
+                i += 2;
+                while i < chars.len() {
+                    if chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        out.extend([' ', ' ']);
+                        i += 2;
+                        break;
+                    }
+                    out.push(match chars[i] {
+                        '\n' | '\r' => chars[i],
+                        _ => ' ',
+                    });
+                    i += 1;
+                }
+                continue;
+            }
+            (',', _) => {
+                last_comma = Some(out.len());
+                out.push(c);
+            }
+            ('}' | ']', _) => {
+                if let Some(comma) = last_comma.take() {
+                    out[comma] = ' ';
+                }
+                out.push(c);
+            }
+            _ if c.is_whitespace() => out.push(c),
+            _ => {
+                last_comma = None;
+                out.push(c);
+            }
+        }
+        i += 1;
+    }
+
+    out.into_iter().collect()
+}
+
+/// Converts the error from parsing the given `toml` into a
+/// [`GetConfigError::ParseError`], including its [location](ParseErrorLocation)
+/// if it is known.
+fn toml_parse_err(
+    err: toml_edit::de::Error,
+    toml: &str,
+    cfg_type: ConfigType,
+) -> GetConfigError {
+    let msg = err.message().trim_end().to_string();
+    let offset = match err.span() {
+        Some(span) => span.start,
+        None => return GetConfigError::ParseError { cfg_type, msg, loc: None },
+    };
+
+    let before = toml.get(..offset).unwrap_or(toml);
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line_text = toml[line_start..]
+        .split('\n')
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('\r');
+
+    GetConfigError::ParseError {
+        cfg_type,
+        msg,
+        loc: Some(ParseErrorLocation {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            key_path: toml_key_path_at(toml, offset),
+            line_text: line_text.to_string(),
+        }),
+    }
+}
+
+/// Returns the path to the innermost value in the `toml` that contains the
+/// given byte `offset`, i.e. `tendrils."SomeApp/file".remotes[2]`. This is
+/// empty if the `toml` cannot be parsed or the `offset` is not within any
+/// value.
+fn toml_key_path_at(toml: &str, offset: usize) -> String {
+    match toml_edit::ImDocument::parse(toml) {
+        Ok(doc) => toml_table_path(doc.as_table(), offset, "").unwrap_or_default(),
+        Err(_) => String::new(),
+    }
+}
+
+fn toml_table_path(
+    table: &toml_edit::Table,
+    offset: usize,
+    path: &str,
+) -> Option<String> {
+    use toml_edit::Item;
+
+    for (key, item) in table.iter() {
+        let key_path = join_key_path(path, key);
+        let found = match item {
+            Item::Value(v) => toml_value_path(v, offset, &key_path),
+            Item::Table(t) => toml_sub_table_path(t, offset, key_path),
+            Item::ArrayOfTables(tables) => {
+                tables.iter().enumerate().find_map(|(i, t)| {
+                    toml_sub_table_path(t, offset, format!("{key_path}[{i}]"))
+                })
+            }
+            Item::None => None,
+        };
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    None
+}
+
+/// Returns the path within the `table`, or the path to the `table` itself if
+/// the `offset` is within the table but not within any of its values.
+fn toml_sub_table_path(
+    table: &toml_edit::Table,
+    offset: usize,
+    path: String,
+) -> Option<String> {
+    toml_table_path(table, offset, &path)
+        .or_else(|| span_contains(table.span(), offset).then_some(path))
+}
+
+fn toml_value_path(
+    value: &toml_edit::Value,
+    offset: usize,
+    path: &str,
+) -> Option<String> {
+    use toml_edit::Value;
+
+    if !span_contains(value.span(), offset) {
+        return None;
+    }
+
+    let inner = match value {
+        Value::Array(values) => values.iter().enumerate().find_map(|(i, v)| {
+            toml_value_path(v, offset, &format!("{path}[{i}]"))
+        }),
+        Value::InlineTable(t) => t.iter().find_map(|(k, v)| {
+            toml_value_path(v, offset, &join_key_path(path, k))
+        }),
+        _ => None,
+    };
+    Some(inner.unwrap_or_else(|| path.to_string()))
+}
+
+fn span_contains(span: Option<std::ops::Range<usize>>, offset: usize) -> bool {
+    span.is_some_and(|s| s.contains(&offset))
+}
+
+/// Returns the paths (relative to the `dot_td_dir`) to each of the files
+/// that are included, in the order they should be combined. This includes
+/// the config files in the [`INCLUDE_DIR`], which are always included.
+/// Glob patterns only match files with a [config file
+/// extension](ConfigFormat::from_path), and the repo config file itself is
+/// never included.
+pub(crate) fn find_include_files(
+    dot_td_dir: &Path,
+    include: &[String],
+) -> Result<Vec<PathBuf>, GetConfigError> {
+    let mut include_files: Vec<PathBuf> = vec![];
+    let mut all_files: Option<Vec<String>> = None;
+
+    for entry in include {
+        if !is_glob(entry) {
+            include_files.push(PathBuf::from(entry));
+            continue;
+        }
+
+        if all_files.is_none() {
+            let mut files = vec![];
+            list_files_recursive(dot_td_dir, "", &mut files)?;
+            files.sort();
+            all_files = Some(files);
+        }
+        let matches = all_files
+            .iter()
+            .flatten()
+            .filter(|f| glob_match(entry, f))
+            .map(PathBuf::from)
+            .filter(|f| ConfigFormat::from_path(f).is_some());
+        include_files.extend(matches);
+    }
+
+    let include_dir = dot_td_dir.join(INCLUDE_DIR);
+    if include_dir.is_dir() {
+        let mut dir_files = vec![];
+        for entry in std::fs::read_dir(include_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_file() && ConfigFormat::from_path(&path).is_some() {
+                dir_files.push(Path::new(INCLUDE_DIR).join(entry.file_name()));
+            }
+        }
+        dir_files.sort();
+        include_files.extend(dir_files);
+    }
+
+    let mut unique_files = Vec::with_capacity(include_files.len());
+    for file in include_files {
+        if !is_repo_config_file(&file) && !unique_files.contains(&file) {
+            unique_files.push(file);
+        }
+    }
+    Ok(unique_files)
+}
+
+/// Whether the `path` (relative to the `.tendrils` folder) points to one of
+/// the repo config files.
+fn is_repo_config_file(path: &Path) -> bool {
+    let rel_path: PathBuf = path
+        .components()
+        .filter(|c| c != &std::path::Component::CurDir)
+        .collect();
+
+    ConfigFormat::ALL
+        .iter()
+        .any(|f| rel_path == Path::new(&ConfigType::Repo(*f).file_name()))
+}
+
+fn is_glob(path: &str) -> bool {
+    path.contains(['*', '?', '[', '{'])
+}
+
+/// Appends the path of each file within the `dir` (and its subfolders) to
+/// the `files`. The paths are relative to the `dir`, prefixed with the
+/// `prefix`, and always use `/` as the directory separator so that they can
+/// be matched against the `include` glob patterns on all platforms.
+fn list_files_recursive(
+    dir: &Path,
+    prefix: &str,
+    files: &mut Vec<String>,
+) -> Result<(), GetConfigError> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+        let path = entry.path();
+        if path.is_dir() {
+            list_files_recursive(&path, &format!("{name}/"), files)?;
+        }
+        else if path.is_file() {
+            files.push(name);
+        }
+    }
+
+    Ok(())
+}
+
+/// Combines the configurations from each of the `files` in the order given,
+/// where the first file is the `tendrils.json` file. Returns
+/// [`GetConfigError::DuplicateKey`] if the same local, automatic profile,
+/// profile group, or variable is defined in more than one file.
+fn merge_configs(
+    files: Vec<(ConfigType, SerdeConfig)>,
+) -> Result<Config, GetConfigError> {
+    let mut locals: IndexMap<String, ConfigType> = IndexMap::new();
+    let mut auto_profile_srcs: IndexMap<String, ConfigType> = IndexMap::new();
+    let mut profile_group_srcs: IndexMap<String, ConfigType> = IndexMap::new();
+    let mut var_srcs: IndexMap<String, ConfigType> = IndexMap::new();
+    let format = match files.first() {
+        Some((ConfigType::Repo(format), _)) => *format,
+        _ => ConfigFormat::Json,
+    };
+    let mut merged = Config {
+        raw_tendrils: vec![],
+        auto_profiles: IndexMap::new(),
+        profile_groups: IndexMap::new(),
+        format,
+    };
+
+    // Vars are shared by all files so must be collected before any
+    // are converted
+    let mut all_vars = RepoVars::new();
+    for (cfg_type, serde_cfg) in files.iter() {
+        claim_keys(&mut var_srcs, serde_cfg.vars.keys(), cfg_type, "vars")?;
+        all_vars.extend(serde_cfg.vars.clone());
+    }
+    let (main_defaults, main_var_priority, main_reflink) = match files.first() {
+        Some((_, main)) => {
+            (main.defaults.clone(), main.var_priority, main.reflink)
+        }
+        None => (None, None, None),
+    };
+    let main_defaults = main_defaults.unwrap_or_default();
+
+    for (cfg_type, mut serde_cfg) in files {
+        serde_cfg.defaults = Some(
+            serde_cfg.defaults.unwrap_or_default().inherit(&main_defaults)
+        );
+        serde_cfg.vars = all_vars.clone();
+        serde_cfg.var_priority = serde_cfg.var_priority.or(main_var_priority);
+        serde_cfg.reflink = main_reflink;
+
+        claim_keys(&mut locals, serde_cfg.tendrils.keys(), &cfg_type, "tendrils")?;
+        claim_keys(
+            &mut auto_profile_srcs,
+            serde_cfg.auto_profiles.keys(),
+            &cfg_type,
+            "auto-profiles",
+        )?;
+        claim_keys(
+            &mut profile_group_srcs,
+            serde_cfg.profile_groups.keys(),
+            &cfg_type,
+            "profile-groups",
+        )?;
+
+        let source = match &cfg_type {
+            ConfigType::RepoInclude(path) => Some(path.clone()),
+            ConfigType::Repo(_) | ConfigType::Global => None,
+        };
+        let cfg: Config = serde_cfg.into();
+        merged.raw_tendrils.extend(cfg.raw_tendrils.into_iter().map(|mut t| {
+            t.source = source.clone();
+            t
+        }));
+        merged.auto_profiles.extend(cfg.auto_profiles);
+        merged.profile_groups.extend(cfg.profile_groups);
+    }
+
+    Ok(merged)
+}
+
+/// Records each of the `keys` as being defined in the `cfg_type` file, or
+/// returns [`GetConfigError::DuplicateKey`] if any were already defined in
+/// a previous file.
+fn claim_keys<'a>(
+    claimed: &mut IndexMap<String, ConfigType>,
+    keys: impl Iterator<Item = &'a String>,
+    cfg_type: &ConfigType,
+    section: &str,
+) -> Result<(), GetConfigError> {
+    for key in keys {
+        if let Some(first) = claimed.get(key) {
+            return Err(GetConfigError::DuplicateKey {
+                section: section.to_string(),
+                key: key.clone(),
+                first: first.clone(),
+                second: cfg_type.clone(),
+            });
+        }
+        claimed.insert(key.clone(), cfg_type.clone());
+    }
+
+    Ok(())
+}
+
+/// Parses the global `global-config.json` file and returns the
+/// configuration within. The file is read from the `tendrils` folder in the
+/// [XDG config directory](get_xdg_dir) if it exists there, otherwise from
+/// the legacy `~/.tendrils` folder. If neither file exists, an empty
+/// configuration is returned (i.e all fields set to `None`).
 pub(crate) fn get_global_config() -> Result<GlobalConfig, GetConfigError> {
-    let home_dir = match get_home_dir() {
+    let config_file_path = match global_config_paths()
+        .into_iter()
+        .find(|p| p.exists())
+    {
         Some(v) => v,
         None => return Ok(GlobalConfig::new()),
     };
-    let config_file_path = PathBuf::from(home_dir).join(".tendrils/global-config.json");
     let config_file_contents = match std::fs::read_to_string(config_file_path) {
         Ok(v) => v,
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
@@ -234,23 +1672,87 @@ pub(crate) fn get_global_config() -> Result<GlobalConfig, GetConfigError> {
         )
     };
 
-    match parse_global_config(&config_file_contents) {
-        Ok(v) => Ok(v),
-        Err(e) => Err(
-            Into::<GetConfigError>::into(e).with_cfg_type(ConfigType::Global)
-        ),
+    deserialize_versioned(
+        &config_file_contents,
+        ConfigFormat::Json,
+        &ConfigType::Global,
+        GLOBAL_MIGRATIONS,
+    )
+}
+
+/// Returns the possible locations of the `global-config.json` file in
+/// order of priority:
+/// 1. `$XDG_CONFIG_HOME/tendrils/global-config.json`
+/// 2. `~/.tendrils/global-config.json`
+pub(crate) fn global_config_paths() -> Vec<PathBuf> {
+    let mut paths = vec![];
+    if let Some(xdg_cfg_dir) = get_xdg_dir("XDG_CONFIG_HOME") {
+        paths.push(xdg_cfg_dir.join("tendrils/global-config.json"));
     }
+    if let Some(home_dir) = get_home_dir() {
+        paths.push(PathBuf::from(home_dir).join(".tendrils/global-config.json"));
+    }
+    paths
 }
 
+/// Parses a single config file, ignoring any files that it includes.
+///
 /// # Arguments
 /// - `json` - JSON object following the tendrils.json schema
+#[cfg(test)]
 fn parse_config(
     json: &str
 ) -> Result<Config, serde_json::Error> {
-    match serde_json::from_str::<SerdeConfig>(json) {
-        Ok(raw) => Ok(raw.into()),
-        Err(e) => Err(e)
+    Ok(parse_serde_config(json)?.into())
+}
+
+/// Parses the given JSON into the intermediate serialization type, which also
+/// contains the list of included files.
+///
+/// # Arguments
+/// - `json` - JSON object following the tendrils.json schema
+#[cfg(test)]
+fn parse_serde_config(
+    json: &str
+) -> Result<SerdeConfig, serde_json::Error> {
+    let raw = serde_json::from_str::<SerdeConfig>(json)?;
+    check_profile_exprs(&raw)?;
+    Ok(raw)
+}
+
+/// Checks that any profile expressions in the tendrils' `profiles` are
+/// valid.
+fn check_profile_exprs(
+    serde_cfg: &SerdeConfig,
+) -> Result<(), serde_json::Error> {
+    let default_profiles = serde_cfg
+        .defaults
+        .iter()
+        .flat_map(|d| d.profiles.iter().flatten());
+    check_profile_exprs_in("defaults", default_profiles)?;
+
+    for (local, sets) in serde_cfg.tendrils.iter() {
+        let sets: Vec<TendrilSet> = sets.clone().into();
+        let profiles = sets.iter().flat_map(|s| s.profiles.iter().flatten());
+        check_profile_exprs_in(local, profiles)?;
     }
+
+    Ok(())
+}
+
+fn check_profile_exprs_in<'a>(
+    location: &str,
+    profiles: impl Iterator<Item = &'a String>,
+) -> Result<(), serde_json::Error> {
+    for p in profiles.filter(|p| is_tendril_profile_expr(p)) {
+        if let Err(msg) = ProfileExpr::parse(p) {
+            return Err(de::Error::custom(format!(
+                "Invalid profile expression \"{p}\" in \"{location}\": {msg}"
+            )));
+        }
+    }
+
+    Ok(())
 }
 
 // Exposes the otherwise private function
@@ -261,10 +1763,134 @@ pub fn parse_config_expose(
     parse_config(json)
 }
 
-/// # Arguments
-/// - `json` - JSON object following the global-config.json schema
-fn parse_global_config(
-    json: &str
-) -> Result<GlobalConfig, serde_json::Error> {
-    serde_json::from_str::<GlobalConfig>(json)
+/// Converts the error from parsing the given `json` into a
+/// [`GetConfigError::ParseError`], including its [location](ParseErrorLocation)
+/// if it is known.
+pub(crate) fn parse_err(
+    err: serde_json::Error,
+    json: &str,
+    cfg_type: ConfigType,
+) -> GetConfigError {
+    let full_msg = err.to_string();
+    if err.line() == 0 {
+        return GetConfigError::ParseError { cfg_type, msg: full_msg, loc: None };
+    }
+
+    let suffix = format!(" at line {} column {}", err.line(), err.column());
+    let msg = full_msg.strip_suffix(&suffix).unwrap_or(&full_msg).to_string();
+    let line_text = json
+        .split('\n')
+        .nth(err.line() - 1)
+        .unwrap_or_default()
+        .trim_end_matches('\r');
+
+    // The serde column is the number of bytes into the line, which is
+    // converted to a number of characters
+    let line_start: usize = json
+        .split_inclusive('\n')
+        .take(err.line() - 1)
+        .map(|l| l.len())
+        .sum();
+    let byte_column = err.column().min(line_text.len());
+    let column = line_text
+        .char_indices()
+        .take_while(|(i, _)| *i < byte_column)
+        .count()
+        .max(1);
+
+    // Some errors are reported just before the offending value, in which
+    // case the whitespace is skipped to point at the value itself
+    let skipped = line_text
+        .chars()
+        .skip(column - 1)
+        .take_while(|c| c.is_whitespace())
+        .count();
+    let column = match line_text.chars().nth(column - 1 + skipped) {
+        Some(_) => column + skipped,
+        None => column,
+    };
+
+    GetConfigError::ParseError {
+        cfg_type,
+        msg,
+        loc: Some(ParseErrorLocation {
+            line: err.line(),
+            column,
+            key_path: key_path_at(
+                json.get(..line_start + byte_column).unwrap_or(json),
+            ),
+            line_text: line_text.to_string(),
+        }),
+    }
+}
+
+/// Returns the path to the innermost value that is still open at the end of
+/// the given (partial) JSON, i.e. `tendrils."SomeApp/file".remotes[2]`.
+fn key_path_at(partial_json: &str) -> String {
+    enum Frame {
+        Object { key: Option<String>, expecting_key: bool },
+        List { index: usize },
+    }
+
+    let mut frames: Vec<Frame> = vec![];
+    let mut chars = partial_json.chars();
+    while let Some(c) = chars.next() {
+        match (c, frames.last_mut()) {
+            ('"', top) => {
+                let mut text = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => text.extend(chars.next()),
+                        _ => text.push(c),
+                    }
+                }
+                if let Some(Frame::Object { key, expecting_key: e @ true }) = top {
+                    *key = Some(text);
+                    *e = false;
+                }
+            }
+            ('{', _) => frames.push(Frame::Object { key: None, expecting_key: true }),
+            ('[', _) => frames.push(Frame::List { index: 0 }),
+            ('}' | ']', _) => {
+                frames.pop();
+            }
+            (',', Some(Frame::Object { key, expecting_key })) => {
+                *key = None;
+                *expecting_key = true;
+            }
+            (',', Some(Frame::List { index })) => *index += 1,
+            _ => {}
+        }
+    }
+
+    let mut path = String::new();
+    for frame in frames {
+        match frame {
+            Frame::Object { key: Some(key), .. } => {
+                path = join_key_path(&path, &key);
+            }
+            Frame::Object { key: None, .. } => {}
+            Frame::List { index } => path.push_str(&format!("[{index}]")),
+        }
+    }
+    path
 }
+
+/// Appends the `key` to the key `path`, i.e. `tendrils` and `SomeApp/file`
+/// becomes `tendrils."SomeApp/file"`. Keys that are not made up of only
+/// alphanumeric characters, `-` and `_` are quoted.
+pub(crate) fn join_key_path(path: &str, key: &str) -> String {
+    let is_plain = !key.is_empty() && key
+        .chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    let key = match is_plain {
+        true => key.to_string(),
+        false => format!("{key:?}"),
+    };
+    match path.is_empty() {
+        true => key,
+        false => format!("{path}.{key}"),
+    }
+}
+
@@ -0,0 +1,567 @@
+use super::{
+    find_repo_config,
+    deserialize_serde_config_unmigrated,
+    read_config_file,
+    serialize_serde_config,
+    strip_jsonc,
+    SerdeConfig,
+    TendrilDefaults,
+    TendrilSet,
+    VarPriority,
+};
+use crate::enums::{GetConfigError, NestedSymlinks, OneOrMany, ReflinkMode};
+use crate::path_ext::{tokenize_vars, PathToken, UniPath};
+use crate::{ConfigFormat, ConfigType};
+use indexmap::IndexMap;
+use toml_edit::{Decor, DocumentMut, Item, Key, RawString, TableLike, Value};
+
+/// Rewrites the repo config file in the `td_repo` in its canonical form (see
+/// [`fmt_serde_config`]). Returns `true` if the file was not already in this
+/// form. If `check` is `true`, the file is left as-is.
+///
+/// TOML files are formatted in place (see [`fmt_toml`]), so their comments
+/// and any keys that are not recognized are kept. Other files are rewritten
+/// from their parsed contents, so any keys that are not recognized are not
+/// carried over. If a JSONC file contains comments,
+/// [`GetConfigError::CommentsWouldBeLost`] is returned instead, unless
+/// `drop_comments` is `true`.
+///
+/// Only the repo config file is formatted, any files that it includes are
+/// left as-is.
+pub(crate) fn fmt_repo_config(
+    td_repo: &UniPath,
+    sort_keys: bool,
+    check: bool,
+    drop_comments: bool,
+) -> Result<bool, GetConfigError> {
+    let dot_td_dir = td_repo.inner().join(".tendrils");
+    let format = find_repo_config(&dot_td_dir)?;
+    let cfg_type = ConfigType::Repo(format);
+    let path = dot_td_dir.join(cfg_type.file_name());
+    let text = read_config_file(&path, &cfg_type)?;
+    let serde_cfg = deserialize_serde_config_unmigrated(&text, format, &cfg_type)?;
+    let fmt_cfg = fmt_serde_config(serde_cfg, sort_keys);
+
+    let formatted = match format {
+        ConfigFormat::Toml => fmt_toml(&text, &fmt_cfg, sort_keys),
+        ConfigFormat::Jsonc if !drop_comments && has_jsonc_comments(&text) => {
+            return Err(GetConfigError::CommentsWouldBeLost { cfg_type });
+        }
+        ConfigFormat::Json | ConfigFormat::Jsonc => {
+            serialize_serde_config(&fmt_cfg, format)
+        }
+    }.map_err(|msg| {
+        GetConfigError::ParseError { cfg_type: cfg_type.clone(), msg, loc: None }
+    })?;
+
+    if formatted == text {
+        return Ok(false);
+    }
+    if !check {
+        std::fs::write(&path, formatted)
+            .map_err(|e| GetConfigError::from(e).with_cfg_type(cfg_type))?;
+    }
+    Ok(true)
+}
+
+/// Returns `true` if the `text` of a config file in the given `format`
+/// contains any comments. JSON files never contain comments.
+pub(super) fn has_comments(text: &str, format: ConfigFormat) -> bool {
+    match format {
+        ConfigFormat::Json => false,
+        ConfigFormat::Jsonc => has_jsonc_comments(text),
+        ConfigFormat::Toml => has_toml_comments(text),
+    }
+}
+
+/// Returns `true` if the TOML `text` contains any comments, i.e. a `#`
+/// outside of any string.
+fn has_toml_comments(text: &str) -> bool {
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let (delim, has_escapes) = match c {
+            '#' => return true,
+            '"' if rest.starts_with("\"\"\"") => ("\"\"\"", true),
+            '"' => ("\"", true),
+            '\'' if rest.starts_with("'''") => ("'''", false),
+            '\'' => ("'", false),
+            _ => {
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+        };
+
+        // Skip to the end of the string
+        rest = &rest[delim.len()..];
+        loop {
+            match rest.chars().next() {
+                None => return false,
+                Some(_) if rest.starts_with(delim) => {
+                    rest = &rest[delim.len()..];
+                    break;
+                }
+                Some('\\') if has_escapes => {
+                    let mut escaped = rest[1..].chars();
+                    escaped.next();
+                    rest = escaped.as_str();
+                }
+                Some(c) => rest = &rest[c.len_utf8()..],
+            }
+        }
+    }
+
+    false
+}
+
+/// Returns `true` if the JSONC `text` contains any comments.
+fn has_jsonc_comments(text: &str) -> bool {
+    // Comments are replaced by spaces, and always start with a slash
+    text.chars()
+        .zip(strip_jsonc(text).chars())
+        .any(|(orig, stripped)| orig == '/' && stripped == ' ')
+}
+
+/// Returns the canonical form of the `serde_cfg`, without changing the
+/// tendrils that it defines:
+/// - Lists with a single item are written as that item, wherever a single
+///   value is also accepted
+/// - Backslashes in the locals and remotes are replaced by forward slashes
+///   (see [`to_forward_slashes`])
+/// - Values that are the same as their defaults are removed
+/// - If `sort_keys` is `true`, the tendrils, automatic profiles, profile
+///   groups and variables are sorted by name. Otherwise they keep their
+///   order.
+fn fmt_serde_config(mut serde_cfg: SerdeConfig, sort_keys: bool) -> SerdeConfig {
+    let defaults = serde_cfg.defaults.take().and_then(fmt_defaults);
+    let set_defaults = defaults.clone().unwrap_or_default();
+
+    let orig_tendrils = std::mem::take(&mut serde_cfg.tendrils);
+    let mut tendrils = IndexMap::new();
+    for (local, sets) in orig_tendrils.clone() {
+        let sets: Vec<TendrilSet> = sets.into();
+        let sets: Vec<TendrilSet> = sets
+            .into_iter()
+            .map(|s| fmt_tendril_set(s, &set_defaults))
+            .collect();
+
+        let local = fmt_local(&local, |l| orig_tendrils.contains_key(l));
+        let sets: OneOrMany<TendrilSet> = sets.into();
+        tendrils.insert(local, sets);
+    }
+
+    serde_cfg.tendrils = tendrils;
+    serde_cfg.defaults = defaults;
+    serde_cfg.auto_profiles = std::mem::take(&mut serde_cfg.auto_profiles)
+        .into_iter()
+        .map(|(name, rules)| (name, Vec::from(rules).into()))
+        .collect();
+    if serde_cfg.var_priority == Some(VarPriority::default()) {
+        serde_cfg.var_priority = None;
+    }
+    if serde_cfg.reflink == Some(ReflinkMode::default()) {
+        serde_cfg.reflink = None;
+    }
+
+    if sort_keys {
+        serde_cfg.tendrils.sort_keys();
+        serde_cfg.auto_profiles.sort_keys();
+        serde_cfg.profile_groups.sort_keys();
+        serde_cfg.vars.sort_keys();
+    }
+    serde_cfg
+}
+
+/// Removes any values that are the same as when they are not set.
+fn fmt_defaults(defaults: TendrilDefaults) -> Option<TendrilDefaults> {
+    let defaults = TendrilDefaults {
+        dir_merge: defaults.dir_merge.filter(|v| *v),
+        link: defaults.link.filter(|v| *v),
+        relative_link: defaults.relative_link.filter(|v| *v),
+        hardlink: defaults.hardlink.filter(|v| *v),
+        copy_fallback: defaults.copy_fallback.filter(|v| *v),
+        nested_symlinks: defaults
+            .nested_symlinks
+            .filter(|v| *v != NestedSymlinks::default()),
+        profiles: defaults.profiles.filter(|p| !p.is_empty()),
+    };
+
+    if defaults == TendrilDefaults::default() {
+        None
+    }
+    else {
+        Some(defaults)
+    }
+}
+
+/// Removes any values that are the same as the `defaults`, and replaces the
+/// backslashes in the remotes.
+fn fmt_tendril_set(set: TendrilSet, defaults: &TendrilDefaults) -> TendrilSet {
+    let default_profiles = defaults.profiles.clone().unwrap_or_default();
+
+    TendrilSet {
+        remotes: set.remotes.iter().map(|r| to_forward_slashes(r)).collect(),
+        dir_merge: set
+            .dir_merge
+            .filter(|v| *v != defaults.dir_merge.unwrap_or(false)),
+        link: set.link.filter(|v| *v != defaults.link.unwrap_or(false)),
+        relative_link: set
+            .relative_link
+            .filter(|v| *v != defaults.relative_link.unwrap_or(false)),
+        hardlink: set
+            .hardlink
+            .filter(|v| *v != defaults.hardlink.unwrap_or(false)),
+        copy_fallback: set
+            .copy_fallback
+            .filter(|v| *v != defaults.copy_fallback.unwrap_or(false)),
+        nested_symlinks: set.nested_symlinks.filter(|v| {
+            *v != defaults.nested_symlinks.unwrap_or_default()
+        }),
+        profiles: set.profiles.filter(|p| *p != default_profiles),
+        fallback: set.fallback,
+    }
+}
+
+/// Returns the `local` with forward slashes (see [`to_forward_slashes`]).
+/// The original `local` is kept if another local that `is_defined` is
+/// already written with forward slashes, so that they are not combined.
+fn fmt_local(local: &str, is_defined: impl Fn(&str) -> bool) -> String {
+    let fmt_local = to_forward_slashes(local);
+    if fmt_local != local && is_defined(&fmt_local) {
+        local.to_string()
+    }
+    else {
+        fmt_local
+    }
+}
+
+/// Replaces the backslashes in the `path` by forward slashes, which are used
+/// as the directory separator on all platforms. Backslashes within variables
+/// (i.e. in their default values) are left as-is, as are paths starting with
+/// two backslashes (UNC and verbatim paths on Windows).
+fn to_forward_slashes(path: &str) -> String {
+    if path.starts_with("\\\\") {
+        return path.to_string();
+    }
+
+    let bytes: Vec<u8> = tokenize_vars(path.as_bytes())
+        .into_iter()
+        .flat_map(|token| match token {
+            PathToken::Text(text) => text
+                .iter()
+                .map(|b| if *b == b'\\' { b'/' } else { *b })
+                .collect(),
+            PathToken::Escaped(raw) => raw.to_vec(),
+            PathToken::Var(var) => var.raw.to_vec(),
+        })
+        .collect();
+
+    // Only ASCII backslashes were replaced, so this is still valid UTF-8
+    String::from_utf8(bytes).unwrap_or_else(|_| path.to_string())
+}
+
+/// Formats the TOML `text` of a repo config file in place so that its
+/// comments, the formatting of its values and any keys that are not
+/// recognized are kept. The `fmt_cfg` is the canonical form of the file (see
+/// [`fmt_serde_config`]), which decides which values are removed. Lists with
+/// a single item are only written as that item if the list does not contain
+/// any comments.
+fn fmt_toml(
+    text: &str,
+    fmt_cfg: &SerdeConfig,
+    sort_keys: bool,
+) -> Result<String, String> {
+    let mut doc = text.parse::<DocumentMut>().map_err(|e| e.to_string())?;
+    let root = doc.as_table_mut();
+
+    if let Some(tendrils) = root.get_mut("tendrils") {
+        fmt_toml_tendrils(tendrils, &fmt_cfg.tendrils);
+    }
+    if let Some(auto_profiles) = root
+        .get_mut("auto-profiles")
+        .and_then(Item::as_table_like_mut)
+    {
+        for (_, rules) in auto_profiles.iter_mut() {
+            unwrap_toml_item(rules);
+        }
+    }
+
+    let defaults = fmt_cfg.defaults.clone().unwrap_or_default();
+    let is_defaults_empty = match root
+        .get_mut("defaults")
+        .and_then(Item::as_table_like_mut)
+    {
+        Some(table) => {
+            fmt_toml_defaults(table, &defaults);
+            table.is_empty()
+        }
+        None => false,
+    };
+    if is_defaults_empty {
+        root.remove("defaults");
+    }
+    if fmt_cfg.var_priority.is_none() {
+        root.remove("var-priority");
+    }
+    if fmt_cfg.reflink.is_none() {
+        root.remove("reflink");
+    }
+
+    if sort_keys {
+        for section in ["tendrils", "auto-profiles", "profile-groups", "vars"] {
+            if let Some(item) = root.get_mut(section) {
+                sort_toml_table(item);
+            }
+        }
+    }
+    Ok(doc.to_string())
+}
+
+/// Formats each of the tendril sets in the `tendrils` table, and replaces
+/// the backslashes in their locals (see [`fmt_local`]).
+fn fmt_toml_tendrils(
+    tendrils: &mut Item,
+    fmt_tendrils: &IndexMap<String, OneOrMany<TendrilSet>>,
+) {
+    let locals: Vec<String> = match tendrils.as_table_like() {
+        Some(table) => table.iter().map(|(k, _)| k.to_string()).collect(),
+        None => return,
+    };
+    rename_toml_keys(tendrils, |local| {
+        fmt_local(local, |l| locals.iter().any(|orig| orig == l))
+    });
+
+    let table = match tendrils.as_table_like_mut() {
+        Some(v) => v,
+        None => return,
+    };
+    for (local, sets) in table.iter_mut() {
+        let fmt_sets: Vec<TendrilSet> = match fmt_tendrils.get(local.get()) {
+            Some(v) => v.clone().into(),
+            None => continue,
+        };
+        unwrap_toml_item(sets);
+        for (set, fmt_set) in toml_tables_mut(sets).into_iter().zip(&fmt_sets) {
+            fmt_toml_set(set, fmt_set);
+        }
+    }
+}
+
+/// Returns the `item` if it is a table, otherwise each of the tables in it
+/// if it is a list.
+fn toml_tables_mut(item: &mut Item) -> Vec<&mut dyn TableLike> {
+    match item {
+        Item::ArrayOfTables(tables) => tables
+            .iter_mut()
+            .map(|t| t as &mut dyn TableLike)
+            .collect(),
+        Item::Value(Value::Array(values)) => values
+            .iter_mut()
+            .filter_map(Value::as_inline_table_mut)
+            .map(|t| t as &mut dyn TableLike)
+            .collect(),
+        _ => item.as_table_like_mut().into_iter().collect(),
+    }
+}
+
+/// Removes the values of the `set` that are not in the `fmt_set`, and
+/// formats its remotes and profiles (see [`fmt_toml_strings`]).
+fn fmt_toml_set(set: &mut dyn TableLike, fmt_set: &TendrilSet) {
+    let unset = [
+        ("dir-merge", fmt_set.dir_merge.is_none()),
+        ("link", fmt_set.link.is_none()),
+        ("relative-link", fmt_set.relative_link.is_none()),
+        ("hardlink", fmt_set.hardlink.is_none()),
+        ("copy-fallback", fmt_set.copy_fallback.is_none()),
+        ("nested-symlinks", fmt_set.nested_symlinks.is_none()),
+        ("fallback", !fmt_set.fallback),
+    ];
+    for (key, _) in unset.iter().filter(|(_, is_unset)| *is_unset) {
+        set.remove(key);
+    }
+
+    fmt_toml_strings(set, "remotes", Some(&fmt_set.remotes));
+    fmt_toml_strings(set, "profiles", fmt_set.profiles.as_deref());
+}
+
+/// Removes the values of the `defaults` table that are not in the
+/// `fmt_defaults`, and formats its profiles (see [`fmt_toml_strings`]).
+fn fmt_toml_defaults(
+    defaults: &mut dyn TableLike,
+    fmt_defaults: &TendrilDefaults,
+) {
+    let unset = [
+        ("dir-merge", fmt_defaults.dir_merge.is_none()),
+        ("link", fmt_defaults.link.is_none()),
+        ("relative-link", fmt_defaults.relative_link.is_none()),
+        ("hardlink", fmt_defaults.hardlink.is_none()),
+        ("copy-fallback", fmt_defaults.copy_fallback.is_none()),
+        ("nested-symlinks", fmt_defaults.nested_symlinks.is_none()),
+    ];
+    for (key, _) in unset.iter().filter(|(_, is_unset)| *is_unset) {
+        defaults.remove(key);
+    }
+
+    fmt_toml_strings(defaults, "profiles", fmt_defaults.profiles.as_deref());
+}
+
+/// Updates the string or list of strings at the `key` of the `table` to the
+/// `fmt` values, keeping their formatting. The `key` is removed if `fmt` is
+/// `None`.
+fn fmt_toml_strings(
+    table: &mut dyn TableLike,
+    key: &str,
+    fmt: Option<&[String]>,
+) {
+    let fmt = match fmt {
+        Some(v) => v,
+        None => {
+            table.remove(key);
+            return;
+        }
+    };
+    let value = match table.get_mut(key).and_then(Item::as_value_mut) {
+        Some(v) => v,
+        None => return,
+    };
+
+    if fmt.len() == 1 {
+        unwrap_toml_value(value);
+    }
+    if let Value::Array(values) = value {
+        for (value, fmt) in values.iter_mut().zip(fmt) {
+            set_toml_str(value, fmt);
+        }
+    }
+    else if let Some(fmt) = fmt.first() {
+        set_toml_str(value, fmt);
+    }
+}
+
+/// Replaces the `value` by the string `s` if it is a different string,
+/// keeping its decor.
+fn set_toml_str(value: &mut Value, s: &str) {
+    if value.as_str().is_some_and(|v| v != s) {
+        let decor = value.decor().clone();
+        *value = Value::from(s);
+        *value.decor_mut() = decor;
+    }
+}
+
+/// Replaces the `item` by its only table if it is a list of tables with a
+/// single table (see [`unwrap_toml_value`]).
+fn unwrap_toml_item(item: &mut Item) {
+    match item {
+        Item::ArrayOfTables(tables) if tables.len() == 1 => {
+            if let Some(table) = tables.get(0).cloned() {
+                *item = Item::Table(table);
+            }
+        }
+        Item::Value(value) => unwrap_toml_value(value),
+        _ => {}
+    }
+}
+
+/// Replaces the `value` by its only item if it is a list with a single item,
+/// keeping the decor of the list. Lists containing any comments are left
+/// as-is, so that the comments are kept.
+fn unwrap_toml_value(value: &mut Value) {
+    let item = match value {
+        Value::Array(values) if values.len() == 1 => {
+            let has_comments = is_toml_comment(values.trailing())
+                || values.iter().any(|v| decor_has_comments(v.decor()));
+            match has_comments {
+                true => None,
+                false => values.get(0).cloned(),
+            }
+        }
+        _ => None,
+    };
+
+    if let Some(mut item) = item {
+        *item.decor_mut() = value.decor().clone();
+        *value = item;
+    }
+}
+
+/// Returns `true` if the `decor` contains any comments.
+fn decor_has_comments(decor: &Decor) -> bool {
+    [decor.prefix(), decor.suffix()]
+        .iter()
+        .flatten()
+        .any(|raw| is_toml_comment(raw))
+}
+
+fn is_toml_comment(raw: &RawString) -> bool {
+    raw.as_str().is_some_and(|s| s.contains('#'))
+}
+
+/// Renames each of the keys in the `table` to the result of `rename`,
+/// keeping their order and decor.
+fn rename_toml_keys(table: &mut Item, rename: impl Fn(&str) -> String) {
+    let keys: Vec<String> = match table.as_table_like() {
+        Some(t) => t.iter().map(|(k, _)| k.to_string()).collect(),
+        None => return,
+    };
+    if keys.iter().all(|k| rename(k) == *k) {
+        return;
+    }
+
+    let renamed = |key: Key| {
+        let name = rename(key.get());
+        if name == key.get() {
+            return key;
+        }
+        Key::new(name)
+            .with_leaf_decor(key.leaf_decor().clone())
+            .with_dotted_decor(key.dotted_decor().clone())
+    };
+
+    // Each key is removed and inserted again to keep their order
+    match table {
+        Item::Table(table) => {
+            for k in keys {
+                if let Some((key, item)) = table.remove_entry(&k) {
+                    table.insert_formatted(&renamed(key), item);
+                }
+            }
+        }
+        Item::Value(Value::InlineTable(table)) => {
+            for k in keys {
+                if let Some((key, value)) = table.remove_entry(&k) {
+                    table.insert_formatted(&renamed(key), value);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Sorts the keys of the table `item` by name. Tables that are written under
+/// their own header are moved along with their keys.
+fn sort_toml_table(item: &mut Item) {
+    if let Some(table) = item.as_table_like_mut() {
+        table.sort_values();
+    }
+    let table = match item.as_table_mut() {
+        Some(v) => v,
+        None => return,
+    };
+
+    // Tables with their own header are written in the order of their
+    // positions in the document, rather than in the order of their keys
+    let mut sub_tables: Vec<&mut toml_edit::Table> = table
+        .iter_mut()
+        .flat_map(|(_, item)| match item {
+            Item::Table(t) => vec![t],
+            Item::ArrayOfTables(tables) => tables.iter_mut().collect(),
+            _ => vec![],
+        })
+        .filter(|t| t.position().is_some())
+        .collect();
+    let mut positions: Vec<usize> =
+        sub_tables.iter().filter_map(|t| t.position()).collect();
+    positions.sort_unstable();
+    for (sub_table, position) in sub_tables.iter_mut().zip(positions) {
+        sub_table.set_position(position);
+    }
+}
@@ -0,0 +1,38 @@
+use crate::config::{GlobalConfig, SerdeConfig};
+use crate::ConfigType;
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::Value;
+
+/// Returns the [JSON Schema](https://json-schema.org) for the given type of
+/// config file, for use with editors that support autocompletion and
+/// validation. Included files use the same schema as the `tendrils.json`
+/// file.
+pub fn config_schema(cfg_type: &ConfigType) -> String {
+    let schema = match cfg_type {
+        ConfigType::Repo(_) | ConfigType::RepoInclude(_) => repo_schema(),
+        ConfigType::Global => global_schema(),
+    };
+
+    // Serializing a `Value` cannot fail
+    serde_json::to_string_pretty(&schema).unwrap_or_default()
+}
+
+/// Derives the draft-07 schema of `T`. Absent values are left out of the
+/// config files rather than set to `null`, so `null` is not allowed.
+fn schema_for<T: JsonSchema>() -> Value {
+    let schema = SchemaSettings::draft07()
+        .with(|s| s.option_add_null_type = false)
+        .into_generator()
+        .into_root_schema_for::<T>();
+
+    serde_json::to_value(schema).unwrap_or_default()
+}
+
+pub(super) fn repo_schema() -> Value {
+    schema_for::<SerdeConfig>()
+}
+
+pub(super) fn global_schema() -> Value {
+    schema_for::<GlobalConfig>()
+}
@@ -0,0 +1,231 @@
+use crate::config::schema::{global_schema, repo_schema};
+use crate::config::{
+    config_schema,
+    CONFIG_VERSION,
+    GlobalConfig,
+    SerdeConfig,
+    TendrilDefaults,
+    TendrilSet,
+    VarPriority,
+};
+use crate::enums::{NestedSymlinks, OneOrMany, ReflinkMode};
+use crate::profiles::ProfileRule;
+use crate::validation::{
+    CONFIG_KEYS,
+    DEFAULTS_KEYS,
+    PROFILE_RULE_KEYS,
+    TENDRIL_SET_KEYS,
+};
+use crate::{ConfigFormat, ConfigType};
+use indexmap::IndexMap;
+use rstest::rstest;
+use serde_json::Value;
+use std::path::PathBuf;
+
+fn full_tendril_set() -> TendrilSet {
+    TendrilSet {
+        remotes: vec!["r1".to_string()],
+        dir_merge: Some(true),
+        link: Some(false),
+        relative_link: Some(true),
+        hardlink: Some(true),
+        copy_fallback: Some(true),
+        nested_symlinks: Some(NestedSymlinks::Preserve),
+        profiles: Some(vec!["p1".to_string()]),
+        fallback: true,
+    }
+}
+
+fn full_defaults() -> TendrilDefaults {
+    TendrilDefaults {
+        dir_merge: Some(true),
+        link: Some(false),
+        relative_link: Some(true),
+        hardlink: Some(true),
+        copy_fallback: Some(true),
+        nested_symlinks: Some(NestedSymlinks::Preserve),
+        profiles: Some(vec!["p1".to_string()]),
+    }
+}
+
+fn full_profile_rule() -> ProfileRule {
+    ProfileRule {
+        hostname: Some("h".to_string()),
+        os: Some("linux".to_string()),
+        arch: Some("x86_64".to_string()),
+        username: Some("u".to_string()),
+        env: Some("VAR".to_string()),
+        path: Some("/p".to_string()),
+    }
+}
+
+/// The keys of the serialized `value`.
+fn serialized_keys(value: impl serde::Serialize) -> Vec<String> {
+    match serde_json::to_value(value).unwrap() {
+        Value::Object(map) => map.keys().cloned().collect(),
+        v => panic!("Expected an object, got {}", v),
+    }
+}
+
+/// The keys of the `properties` in the `schema`.
+fn schema_keys(schema: &Value) -> Vec<String> {
+    schema["properties"].as_object().unwrap().keys().cloned().collect()
+}
+
+fn sorted(mut keys: Vec<String>) -> Vec<String> {
+    keys.sort();
+    keys
+}
+
+fn sorted_strs(keys: &[&str]) -> Vec<String> {
+    sorted(keys.iter().map(|k| k.to_string()).collect())
+}
+
+#[test]
+fn repo_schema_matches_serde_config_fields() {
+    let mut tendrils = IndexMap::new();
+    tendrils.insert("SomeApp".to_string(), OneOrMany::One(full_tendril_set()));
+    let mut auto_profiles = IndexMap::new();
+    auto_profiles.insert("p1".to_string(), OneOrMany::One(full_profile_rule()));
+    let mut profile_groups = IndexMap::new();
+    profile_groups.insert("g1".to_string(), vec!["p1".to_string()]);
+    let mut vars = IndexMap::new();
+    vars.insert("VAR".to_string(), "value".to_string());
+    let serde_cfg = SerdeConfig {
+        schema: Some("./tendrils.schema.json".to_string()),
+        version: Some(1),
+        tendrils,
+        auto_profiles,
+        profile_groups,
+        include: vec!["extra.json".to_string()],
+        defaults: Some(full_defaults()),
+        vars,
+        var_priority: Some(VarPriority::Env),
+        reflink: Some(ReflinkMode::Auto),
+    };
+    let schema = repo_schema();
+    let definitions = &schema["definitions"];
+
+    assert_eq!(sorted(schema_keys(&schema)), sorted(serialized_keys(serde_cfg)));
+    assert_eq!(
+        sorted(schema_keys(&definitions["tendrilSet"])),
+        sorted(serialized_keys(full_tendril_set())),
+    );
+    assert_eq!(
+        sorted(schema_keys(&definitions["tendrilDefaults"])),
+        sorted(serialized_keys(full_defaults())),
+    );
+    assert_eq!(
+        sorted(schema_keys(&definitions["profileRule"])),
+        sorted(serialized_keys(full_profile_rule())),
+    );
+}
+
+#[test]
+fn repo_schema_matches_validation_keys() {
+    let schema = repo_schema();
+    let definitions = &schema["definitions"];
+
+    assert_eq!(sorted(schema_keys(&schema)), sorted_strs(CONFIG_KEYS));
+    assert_eq!(
+        sorted(schema_keys(&definitions["tendrilSet"])),
+        sorted_strs(TENDRIL_SET_KEYS),
+    );
+    assert_eq!(
+        sorted(schema_keys(&definitions["tendrilDefaults"])),
+        sorted_strs(DEFAULTS_KEYS),
+    );
+    assert_eq!(
+        sorted(schema_keys(&definitions["profileRule"])),
+        sorted_strs(PROFILE_RULE_KEYS),
+    );
+}
+
+#[test]
+fn global_schema_matches_serde_global_config_fields() {
+    let mut auto_profiles = IndexMap::new();
+    auto_profiles.insert("p1".to_string(), OneOrMany::One(full_profile_rule()));
+    let global_cfg = GlobalConfig {
+        schema: Some("./global-config.schema.json".to_string()),
+        version: Some(1),
+        default_repo_path: Some(OneOrMany::One(PathBuf::from("/repo"))),
+        default_profiles: Some(vec!["p1".to_string()]),
+        auto_profiles: Some(auto_profiles),
+    };
+    let schema = global_schema();
+
+    assert_eq!(sorted(schema_keys(&schema)), sorted(serialized_keys(global_cfg)));
+    assert_eq!(
+        sorted(schema_keys(&schema["definitions"]["profileRule"])),
+        sorted(serialized_keys(full_profile_rule())),
+    );
+}
+
+#[rstest]
+#[case(ConfigType::Repo(ConfigFormat::Json), "tendrils.json")]
+#[case(ConfigType::RepoInclude(PathBuf::from("extra.json")), "tendrils.json")]
+#[case(ConfigType::Global, "global-config.json")]
+fn config_schema_returns_valid_json_with_matching_title(
+    #[case] cfg_type: ConfigType,
+    #[case] expected_title: &str,
+) {
+    let actual: Value = serde_json::from_str(&config_schema(&cfg_type)).unwrap();
+
+    assert_eq!(actual["title"], expected_title);
+    assert_eq!(actual["$schema"], "http://json-schema.org/draft-07/schema#");
+}
+
+#[rstest]
+#[case(repo_schema())]
+#[case(global_schema())]
+fn version_is_limited_to_supported_versions(#[case] schema: Value) {
+    let version = &schema["properties"]["version"];
+
+    assert_eq!(version["type"], "integer");
+    assert_eq!(version["minimum"].as_f64(), Some(1.0));
+    assert_eq!(version["maximum"].as_f64(), Some(CONFIG_VERSION as f64));
+}
+
+#[test]
+fn unknown_keys_are_not_allowed_in_any_object() {
+    let repo_schema = repo_schema();
+    let global_schema = global_schema();
+    let objects = [
+        &repo_schema,
+        &repo_schema["definitions"]["tendrilSet"],
+        &repo_schema["definitions"]["tendrilDefaults"],
+        &repo_schema["definitions"]["profileRule"],
+        &global_schema,
+    ];
+
+    for object in objects {
+        assert_eq!(object["additionalProperties"], false, "{}", object);
+    }
+}
+
+#[test]
+fn each_ref_points_to_a_definition() {
+    fn find_refs(value: &Value, refs: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(r)) = map.get("$ref") {
+                    refs.push(r.clone());
+                }
+                map.values().for_each(|v| find_refs(v, refs));
+            }
+            Value::Array(items) => items.iter().for_each(|v| find_refs(v, refs)),
+            _ => {}
+        }
+    }
+
+    for schema in [repo_schema(), global_schema()] {
+        let mut refs = vec![];
+        find_refs(&schema, &mut refs);
+
+        assert!(!refs.is_empty());
+        for r in refs {
+            let name = r.strip_prefix("#/definitions/").unwrap();
+            assert!(schema["definitions"].get(name).is_some(), "{}", r);
+        }
+    }
+}
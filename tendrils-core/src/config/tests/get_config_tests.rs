@@ -1,8 +1,19 @@
-use crate::{ConfigType, GetConfigError, RawTendril};
+use crate::{
+    ConfigFormat,
+    ConfigType,
+    GetConfigError,
+    ParseErrorLocation,
+    RawTendril,
+    ReflinkMode,
+    TendrilMode,
+};
 use crate::config::{Config, get_config};
 use crate::test_utils::{get_disposable_dir, Setup};
 use crate::tests::sample_tendrils::SampleTendrils;
-use std::fs::write;
+use indexmap::IndexMap;
+use rstest::rstest;
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
 use tempdir::TempDir;
 
 #[test]
@@ -14,7 +25,7 @@ fn no_tendrils_json_file_returns_io_not_found_error() {
     assert_eq!(
         actual,
         Err(GetConfigError::IoError {
-            cfg_type: ConfigType::Repo,
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
             kind: std::io::ErrorKind::NotFound,
         })
     );
@@ -31,8 +42,14 @@ fn invalid_json_returns_parse_error() {
     assert_eq!(
         actual,
         Err(GetConfigError::ParseError {
-            cfg_type: ConfigType::Repo,
-            msg: "expected value at line 1 column 1".to_string(),
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: "expected value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: "I'm not JSON".to_string(),
+            }),
         }),
     );
 }
@@ -48,8 +65,102 @@ fn empty_config_file_returns_parse_error() {
     assert_eq!(
         actual,
         Err(GetConfigError::ParseError {
-            cfg_type: ConfigType::Repo,
-            msg: "EOF while parsing a value at line 1 column 0".to_string(),
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: "EOF while parsing a value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: String::new(),
+            }),
+        }),
+    );
+}
+
+#[rstest]
+#[case(
+    "{\n    \"tendrils\": {\n        \"SomeApp/file\": {\"remotes\": [\"r1\", \"r2\", 5]}\n    }\n}",
+    "invalid type: integer `5`, expected a string",
+    3,
+    50,
+    "tendrils.\"SomeApp/file\".remotes[2]",
+)]
+#[case(
+    "{\n    \"tendrils\": {\n        \"SomeApp/file\": [{\"remotes\": \"r1\"}, {\"link\": true}]\n    }\n}",
+    "missing field `remotes`",
+    3,
+    58,
+    "tendrils.\"SomeApp/file\"[1]",
+)]
+#[case(
+    "{\n    \"tendrils\": {\n        \"SomeApp/file\": {\"remotes\": \"r1\", \"link\": \"yes\"}\n    }\n}",
+    "invalid type: string \"yes\", expected a boolean",
+    3,
+    55,
+    "tendrils.\"SomeApp/file\".link",
+)]
+#[case(
+    "{\"profile-groups\": {\"g\": [\"p1\", {}]}}",
+    "invalid type: map, expected a string",
+    1,
+    33,
+    "profile-groups.g[1]",
+)]
+#[case(
+    "{\"tendrils\": {}, \"include\": 5}",
+    "invalid type: integer `5`, expected a sequence",
+    1,
+    29,
+    "include",
+)]
+fn parse_error_includes_line_column_and_key_path(
+    #[case] json: &str,
+    #[case] exp_msg: &str,
+    #[case] exp_line: usize,
+    #[case] exp_column: usize,
+    #[case] exp_key_path: &str,
+) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::ParseError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: exp_msg.to_string(),
+            loc: Some(ParseErrorLocation {
+                line: exp_line,
+                column: exp_column,
+                key_path: exp_key_path.to_string(),
+                line_text: json.lines().nth(exp_line - 1).unwrap().to_string(),
+            }),
+        }),
+    );
+}
+
+#[test]
+fn parse_error_column_counts_characters_and_ignores_carriage_returns() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = "{\r\n\"tendrils\": {\"Ünïcödé\": {\"remotes\": 5}}\r\n}";
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::ParseError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: "invalid type: integer `5`, expected a string".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 2,
+                column: 37,
+                key_path: "tendrils.Ünïcödé.remotes".to_string(),
+                line_text: "\"tendrils\": {\"Ünïcödé\": {\"remotes\": 5}}".to_string(),
+            }),
         }),
     );
 }
@@ -62,7 +173,12 @@ fn empty_json_object_returns_empty_tendrils_list() {
 
     let actual = get_config(&setup.uni_td_repo());
 
-    assert_eq!(actual, Ok(Config { raw_tendrils: vec![] }));
+    assert_eq!(actual, Ok(Config {
+        raw_tendrils: vec![],
+        auto_profiles: IndexMap::new(),
+        profile_groups: IndexMap::new(),
+        format: ConfigFormat::Json,
+    }));
 }
 
 #[test]
@@ -96,3 +212,596 @@ fn config_file_is_unchanged() {
 
     assert_eq!(setup.td_json_file_contents(), r#"{"tendrils": {}}"#);
 }
+
+fn simple_tendril_json(local: &str, remote: &str) -> String {
+    format!(r#"{{"tendrils": {{"{local}": {{"remotes": "{remote}"}}}}}}"#)
+}
+
+fn simple_raw_tendril(local: &str, remote: &str, source: Option<&str>) -> RawTendril {
+    let mut raw = RawTendril::new(local);
+    raw.remote = remote.to_string();
+    raw.source = source.map(PathBuf::from);
+    raw
+}
+
+#[test]
+fn include_array_files_are_merged_after_main_file_in_listed_order() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(
+        &setup.td_json_file,
+        r#"{"include": ["b.json", "sub/a.json"], "tendrils": {"l0": {"remotes": "r0"}}}"#,
+    ).unwrap();
+    create_dir_all(setup.dot_td_dir.join("sub")).unwrap();
+    write(setup.dot_td_dir.join("b.json"), simple_tendril_json("l1", "r1")).unwrap();
+    write(setup.dot_td_dir.join("sub/a.json"), simple_tendril_json("l2", "r2")).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual, vec![
+        simple_raw_tendril("l0", "r0", None),
+        simple_raw_tendril("l1", "r1", Some("b.json")),
+        simple_raw_tendril("l2", "r2", Some("sub/a.json")),
+    ]);
+}
+
+#[test]
+fn include_glob_pattern_files_are_merged_sorted_by_path() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{"include": ["apps/**/*.json"]}"#).unwrap();
+    create_dir_all(setup.dot_td_dir.join("apps/nested")).unwrap();
+    write(setup.dot_td_dir.join("apps/b.json"), simple_tendril_json("l1", "r1")).unwrap();
+    write(setup.dot_td_dir.join("apps/a.json"), simple_tendril_json("l2", "r2")).unwrap();
+    write(setup.dot_td_dir.join("apps/nested/a.json"), simple_tendril_json("l3", "r3")).unwrap();
+    write(setup.dot_td_dir.join("apps/c.txt"), "Not a config file").unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual, vec![
+        simple_raw_tendril("l2", "r2", Some("apps/a.json")),
+        simple_raw_tendril("l1", "r1", Some("apps/b.json")),
+        simple_raw_tendril("l3", "r3", Some("apps/nested/a.json")),
+    ]);
+}
+
+#[test]
+fn include_glob_pattern_with_no_matches_returns_main_config() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(
+        &setup.td_json_file,
+        r#"{"include": ["apps/*.json"], "tendrils": {"l0": {"remotes": "r0"}}}"#,
+    ).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual, vec![simple_raw_tendril("l0", "r0", None)]);
+}
+
+#[test]
+fn include_glob_pattern_skips_repo_config_and_non_config_files() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(
+        &setup.td_json_file,
+        r#"{"include": ["*"], "tendrils": {"l0": {"remotes": "r0"}}}"#,
+    ).unwrap();
+    write(setup.dot_td_dir.join("a.json"), simple_tendril_json("l1", "r1")).unwrap();
+    write(setup.dot_td_dir.join("b.txt"), "Not a config file").unwrap();
+    write(setup.dot_td_dir.join("tendrils.schema"), "Not a config file").unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual, vec![
+        simple_raw_tendril("l0", "r0", None),
+        simple_raw_tendril("l1", "r1", Some("a.json")),
+    ]);
+}
+
+#[rstest]
+#[case("tendrils.json")]
+#[case("./tendrils.json")]
+fn include_entry_for_repo_config_is_skipped(#[case] entry: &str) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(
+        &setup.td_json_file,
+        format!(r#"{{"include": ["{entry}"], "tendrils": {{"l0": {{"remotes": "r0"}}}}}}"#),
+    ).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual, vec![simple_raw_tendril("l0", "r0", None)]);
+}
+
+#[test]
+fn include_dir_json_files_are_merged_sorted_by_name_after_include_array() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{"include": ["other.json"]}"#).unwrap();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d/nested")).unwrap();
+    write(setup.dot_td_dir.join("other.json"), simple_tendril_json("l1", "r1")).unwrap();
+    write(setup.dot_td_dir.join("tendrils.d/b.json"), simple_tendril_json("l2", "r2")).unwrap();
+    write(setup.dot_td_dir.join("tendrils.d/a.json"), simple_tendril_json("l3", "r3")).unwrap();
+    write(setup.dot_td_dir.join("tendrils.d/c.txt"), "Not a config file").unwrap();
+    write(setup.dot_td_dir.join("tendrils.d/nested/d.json"), "Not included").unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual, vec![
+        simple_raw_tendril("l1", "r1", Some("other.json")),
+        simple_raw_tendril("l3", "r3", Some("tendrils.d/a.json")),
+        simple_raw_tendril("l2", "r2", Some("tendrils.d/b.json")),
+    ]);
+}
+
+#[test]
+fn file_included_more_than_once_is_only_merged_once() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(
+        &setup.td_json_file,
+        r#"{"include": ["tendrils.d/a.json", "tendrils.d/*.json"]}"#,
+    ).unwrap();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    write(setup.dot_td_dir.join("tendrils.d/a.json"), simple_tendril_json("l1", "r1")).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual, vec![simple_raw_tendril("l1", "r1", Some("tendrils.d/a.json"))]);
+}
+
+#[test]
+fn included_profiles_are_merged() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(
+        &setup.td_json_file,
+        r#"{"include": ["a.json"], "profile-groups": {"g1": ["p1"]}}"#,
+    ).unwrap();
+    write(
+        setup.dot_td_dir.join("a.json"),
+        r#"{"profile-groups": {"g2": ["p2"]}, "auto-profiles": {"work": {"hostname": "WORK"}}}"#,
+    ).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap();
+
+    assert_eq!(
+        actual.profile_groups.keys().collect::<Vec<_>>(),
+        vec!["g1", "g2"],
+    );
+    assert_eq!(actual.auto_profiles.keys().collect::<Vec<_>>(), vec!["work"]);
+}
+
+#[rstest]
+#[case("tendrils", r#"{"tendrils": {"l1": {"remotes": "r1"}}}"#, r#"{"tendrils": {"l1": {"remotes": "r2"}}}"#, "l1")]
+#[case("auto-profiles", r#"{"auto-profiles": {"work": {"os": "linux"}}}"#, r#"{"auto-profiles": {"work": {"os": "windows"}}}"#, "work")]
+#[case("profile-groups", r#"{"profile-groups": {"g1": ["p1"]}}"#, r#"{"profile-groups": {"g1": ["p2"]}}"#, "g1")]
+#[case("vars", r#"{"vars": {"v1": "a"}}"#, r#"{"vars": {"v1": "b"}}"#, "v1")]
+fn key_defined_in_main_and_included_file_returns_duplicate_key_err(
+    #[case] section: &str,
+    #[case] first_json: &str,
+    #[case] second_json: &str,
+    #[case] key: &str,
+    #[values(true, false)] main_is_first: bool,
+) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    let second_file = "tendrils.d/b.json";
+    let first_type = if main_is_first {
+        write(&setup.td_json_file, first_json).unwrap();
+        ConfigType::Repo(ConfigFormat::Json)
+    }
+    else {
+        let first_file = "tendrils.d/a.json";
+        write(&setup.td_json_file, "{}").unwrap();
+        write(setup.dot_td_dir.join(first_file), first_json).unwrap();
+        ConfigType::RepoInclude(PathBuf::from(first_file))
+    };
+    write(setup.dot_td_dir.join(second_file), second_json).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::DuplicateKey {
+            section: section.to_string(),
+            key: key.to_string(),
+            first: first_type,
+            second: ConfigType::RepoInclude(PathBuf::from(second_file)),
+        }),
+    );
+}
+
+#[test]
+fn included_file_does_not_exist_returns_io_not_found_error() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{"include": ["missing.json"]}"#).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::IoError {
+            cfg_type: ConfigType::RepoInclude(PathBuf::from("missing.json")),
+            kind: std::io::ErrorKind::NotFound,
+        })
+    );
+}
+
+#[test]
+fn included_file_invalid_json_returns_parse_error() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    write(&setup.td_json_file, "{}").unwrap();
+    write(setup.dot_td_dir.join("tendrils.d/a.json"), "I'm not JSON").unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::ParseError {
+            cfg_type: ConfigType::RepoInclude(PathBuf::from("tendrils.d/a.json")),
+            msg: "expected value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: "I'm not JSON".to_string(),
+            }),
+        }),
+    );
+}
+
+#[test]
+fn included_file_with_its_own_includes_returns_parse_error() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{"include": ["a.json"]}"#).unwrap();
+    write(setup.dot_td_dir.join("a.json"), r#"{"include": ["b.json"]}"#).unwrap();
+    write(setup.dot_td_dir.join("b.json"), "{}").unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::ParseError {
+            cfg_type: ConfigType::RepoInclude(PathBuf::from("a.json")),
+            msg: "Included files cannot include other files".to_string(),
+            loc: None,
+        }),
+    );
+}
+
+#[test]
+fn included_file_inherits_main_defaults_unless_it_sets_them() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    write(
+        &setup.td_json_file,
+        r#"{"defaults": {"link": true, "profiles": "p1"}}"#,
+    ).unwrap();
+    write(
+        setup.dot_td_dir.join("tendrils.d/a.json"),
+        r#"{"tendrils": {"l1": {"remotes": "r1"}}}"#,
+    ).unwrap();
+    write(
+        setup.dot_td_dir.join("tendrils.d/b.json"),
+        r#"{"defaults": {"profiles": "p2"}, "tendrils": {"l2": {"remotes": "r2"}}}"#,
+    ).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].mode, TendrilMode::Link);
+    assert_eq!(actual[0].profiles, vec!["p1"]);
+    assert_eq!(actual[1].mode, TendrilMode::Link);
+    assert_eq!(actual[1].profiles, vec!["p2"]);
+}
+
+#[test]
+fn included_file_uses_main_reflink_even_if_it_sets_its_own() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    write(&setup.td_json_file, r#"{"reflink": "auto"}"#).unwrap();
+    write(
+        setup.dot_td_dir.join("tendrils.d/a.json"),
+        r#"{"tendrils": {"l1": {"remotes": "r1"}}}"#,
+    ).unwrap();
+    write(
+        setup.dot_td_dir.join("tendrils.d/b.json"),
+        r#"{"reflink": "never", "tendrils": {"l2": {"remotes": "r2"}}}"#,
+    ).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].reflink, ReflinkMode::Auto);
+    assert_eq!(actual[1].reflink, ReflinkMode::Auto);
+}
+
+#[test]
+fn included_file_defaults_do_not_apply_to_other_files() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    write(&setup.td_json_file, r#"{"tendrils": {"l0": {"remotes": "r0"}}}"#).unwrap();
+    write(
+        setup.dot_td_dir.join("tendrils.d/a.json"),
+        r#"{"defaults": {"link": true}}"#,
+    ).unwrap();
+    write(
+        setup.dot_td_dir.join("tendrils.d/b.json"),
+        r#"{"tendrils": {"l2": {"remotes": "r2"}}}"#,
+    ).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].mode, TendrilMode::DirOverwrite);
+    assert_eq!(actual[1].mode, TendrilMode::DirOverwrite);
+}
+
+#[test]
+fn vars_from_any_file_are_available_in_all_files() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    write(
+        &setup.td_json_file,
+        r#"{"vars": {"Main": "/main"}, "tendrils": {"l0": {"remotes": "<Incl>/r0"}}}"#,
+    ).unwrap();
+    write(
+        setup.dot_td_dir.join("tendrils.d/a.json"),
+        r#"{"vars": {"Incl": "/incl"}, "tendrils": {"l1": {"remotes": "<Main>/r1"}}}"#,
+    ).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].remote, "/incl/r0");
+    assert_eq!(actual[1].remote, "/main/r1");
+}
+
+#[rstest]
+#[case(ConfigFormat::Jsonc, r#"{
+    // Comment with "quotes" and {braces}
+    "tendrils": {
+        /* The app's settings */
+        "SomeApp/file": {"remotes": ["//server/share/file", "r2",],},
+    },
+}"#)]
+#[case(ConfigFormat::Toml, r#"
+# Comment with "quotes" and {braces}
+[tendrils."SomeApp/file"] # The app's settings
+remotes = ["//server/share/file", "r2"]
+"#)]
+fn other_formats_are_parsed_same_as_json(
+    #[case] format: ConfigFormat,
+    #[case] text: &str,
+) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let file_name = ConfigType::Repo(format).file_name();
+    write(setup.dot_td_dir.join(&file_name), text).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap();
+
+    assert_eq!(actual.format, format);
+    assert_eq!(actual.raw_tendrils, vec![
+        simple_raw_tendril("SomeApp/file", "//server/share/file", None),
+        simple_raw_tendril("SomeApp/file", "r2", None),
+    ]);
+}
+
+#[test]
+fn multiple_repo_config_files_returns_multiple_repo_configs_err() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "{}").unwrap();
+    write(setup.dot_td_dir.join("tendrils.toml"), "").unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::MultipleRepoConfigs {
+            formats: vec![ConfigFormat::Json, ConfigFormat::Toml],
+        }),
+    );
+    assert_eq!(
+        actual.unwrap_err().to_string(),
+        "Found more than one config file in the Tendrils repo (tendrils.json, \
+        tendrils.toml). Only one of these may exist",
+    );
+}
+
+#[test]
+fn invalid_toml_returns_parse_error_with_location() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml = "[tendrils.\"SomeApp/file\"]\nremotes = [\"r1\", 5]\n";
+    write(setup.dot_td_dir.join("tendrils.toml"), toml).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::ParseError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Toml),
+            msg: "invalid type: integer `5`, expected a string".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 2,
+                column: 18,
+                key_path: "tendrils.\"SomeApp/file\".remotes[1]".to_string(),
+                line_text: "remotes = [\"r1\", 5]".to_string(),
+            }),
+        }),
+    );
+}
+
+#[test]
+fn invalid_jsonc_returns_parse_error_with_original_line() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let jsonc = "{\"tendrils\": /* Ünïcödé */ []}";
+    write(setup.dot_td_dir.join("tendrils.jsonc"), jsonc).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::ParseError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Jsonc),
+            msg: "invalid type: sequence, expected a map".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 28,
+                key_path: "tendrils".to_string(),
+                line_text: jsonc.to_string(),
+            }),
+        }),
+    );
+}
+
+#[test]
+fn included_files_are_parsed_according_to_their_extension() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{"include": ["a.toml", "b.jsonc"]}"#).unwrap();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    write(setup.dot_td_dir.join("a.toml"), "[tendrils.l1]\nremotes = \"r1\"").unwrap();
+    write(
+        setup.dot_td_dir.join("b.jsonc"),
+        r#"{"tendrils": {"l2": {"remotes": "r2"}}} // Comment"#,
+    ).unwrap();
+    write(
+        setup.dot_td_dir.join("tendrils.d/c.toml"),
+        "tendrils.l3.remotes = \"r3\"",
+    ).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual, vec![
+        simple_raw_tendril("l1", "r1", Some("a.toml")),
+        simple_raw_tendril("l2", "r2", Some("b.jsonc")),
+        simple_raw_tendril("l3", "r3", Some("tendrils.d/c.toml")),
+    ]);
+}
+
+#[rstest]
+#[case(ConfigFormat::Json, r#"{"version": 1, "tendrils": {"l1": {"remotes": "r1"}}}"#)]
+#[case(ConfigFormat::Toml, "version = 1\n[tendrils.l1]\nremotes = \"r1\"\n")]
+fn supported_version_returns_config(
+    #[case] format: ConfigFormat,
+    #[case] contents: &str,
+) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let file_name = ConfigType::Repo(format).file_name();
+    write(setup.dot_td_dir.join(file_name), contents).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].local, "l1");
+}
+
+#[rstest]
+#[case("", r"C:\\$Recycle.Bin", "$$Var/x")]
+#[case(r#""version": 1,"#, r"C:\\$Recycle.Bin", "$$Var/x")]
+#[case(r#""version": 2,"#, r"C:\\$$Recycle.Bin", "/value/x")]
+fn dollar_var_forms_are_only_used_from_version_2(
+    #[case] version: &str,
+    #[case] literal_remote: &str,
+    #[case] exp_var_remote: &str,
+) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, format!(r#"{{
+        {version}
+        "vars": {{"Var": "/value"}},
+        "tendrils": {{
+            "l1": {{"remotes": "{literal_remote}"}},
+            "l2": {{"remotes": "$Var/x"}}
+        }}
+    }}"#)).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap().raw_tendrils;
+
+    // Either way, the escaped $$ resolves to a literal $
+    assert_eq!(actual[0].remote, r"C:\$$Recycle.Bin");
+    assert_eq!(actual[1].remote, exp_var_remote);
+}
+
+#[rstest]
+#[case(0)]
+#[case(crate::CONFIG_VERSION + 1)]
+fn unsupported_version_returns_unsupported_version_err(#[case] version: u32) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    // The rest of the file may follow a layout that cannot be parsed
+    let json = format!(r#"{{"version": {version}, "tendrils": []}}"#);
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::UnsupportedVersion {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            version,
+            supported: crate::CONFIG_VERSION,
+        }),
+    );
+}
+
+#[test]
+fn newer_version_err_message_suggests_updating() {
+    let err = GetConfigError::UnsupportedVersion {
+        cfg_type: ConfigType::Repo(ConfigFormat::Toml),
+        version: 3,
+        supported: 2,
+    };
+
+    assert_eq!(
+        err.to_string(),
+        "The tendrils.toml file is version 3, but this version of Tendrils \
+        only supports up to version 2. Update Tendrils to use this file",
+    );
+}
+
+#[test]
+fn included_file_with_unsupported_version_returns_unsupported_version_err() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{"include": ["extra.json"]}"#).unwrap();
+    write(setup.dot_td_dir.join("extra.json"), r#"{"version": 99}"#).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::UnsupportedVersion {
+            cfg_type: ConfigType::RepoInclude(PathBuf::from("extra.json")),
+            version: 99,
+            supported: crate::CONFIG_VERSION,
+        }),
+    );
+}
+
+#[test]
+fn invalid_version_returns_parse_error_with_location() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{"version": "1"}"#).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo());
+
+    assert!(matches!(
+        actual,
+        Err(GetConfigError::ParseError { loc: Some(_), .. }),
+    ));
+}
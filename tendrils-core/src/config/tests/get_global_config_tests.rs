@@ -1,16 +1,25 @@
-use crate::{ConfigType, GetConfigError};
+use crate::{ConfigType, GetConfigError, ParseErrorLocation, CONFIG_VERSION};
 use crate::config::{get_global_config, GlobalConfig};
+use crate::enums::OneOrMany;
+use crate::profiles::ProfileRule;
 use crate::test_utils::{
+    default_repo_path_as_json,
     global_cfg_file,
     set_ra,
+    xdg_global_cfg_file,
     Setup,
 };
+use indexmap::IndexMap;
 use serial_test::serial;
+use std::fs::{create_dir_all, write};
 use std::path::PathBuf;
 
 const EMPTY_CONFIG: GlobalConfig = GlobalConfig {
+    schema: None,
+    version: None,
     default_repo_path: None,
     default_profiles: None,
+    auto_profiles: None,
 };
 
 #[test]
@@ -40,7 +49,13 @@ fn invalid_json_returns_parse_error() {
         actual,
         Err(GetConfigError::ParseError {
             cfg_type: ConfigType::Global,
-            msg: "expected value at line 1 column 1".to_string(),
+            msg: "expected value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: "I'm not JSON".to_string(),
+            }),
         }),
     );
 }
@@ -57,7 +72,13 @@ fn empty_config_file_returns_parse_error() {
         actual,
         Err(GetConfigError::ParseError {
             cfg_type: ConfigType::Global,
-            msg: "EOF while parsing a value at line 1 column 0".to_string(),
+            msg: "EOF while parsing a value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: String::new(),
+            }),
         }),
     );
 }
@@ -72,7 +93,7 @@ fn empty_json_object_returns_empty_config() {
 
     assert_eq!(
         actual,
-        Ok(EMPTY_CONFIG),
+        Ok(GlobalConfig { version: Some(CONFIG_VERSION), ..EMPTY_CONFIG }),
     );
 }
 
@@ -110,8 +131,63 @@ fn valid_json_returns_config_values() {
     assert_eq!(
         actual,
         Ok(GlobalConfig {
-            default_repo_path: Some(PathBuf::from("Some/Path")),
+            schema: None,
+            version: Some(CONFIG_VERSION),
+            default_repo_path: Some(OneOrMany::One(PathBuf::from("Some/Path"))),
             default_profiles: Some(vec!["p1".to_string()]),
+            auto_profiles: None,
+        }),
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn valid_json_returns_list_of_default_repo_paths() {
+    let setup = Setup::new();
+    setup.make_global_cfg_file(
+        r#"{"default-repo-path": ["Some/Path", "Other/Path"]}"#.to_string()
+    );
+
+    let actual = get_global_config();
+
+    assert_eq!(
+        actual,
+        Ok(GlobalConfig {
+            schema: None,
+            version: Some(CONFIG_VERSION),
+            default_repo_path: Some(OneOrMany::Vec(vec![
+                PathBuf::from("Some/Path"),
+                PathBuf::from("Other/Path"),
+            ])),
+            default_profiles: None,
+            auto_profiles: None,
+        }),
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn valid_json_returns_auto_profiles() {
+    let setup = Setup::new();
+    setup.make_global_cfg_file(
+        r#"{"auto-profiles": {"work": {"hostname": "WORK-*"}}}"#.to_string()
+    );
+    let mut exp_auto_profiles = IndexMap::new();
+    exp_auto_profiles.insert("work".to_string(), OneOrMany::One(ProfileRule {
+        hostname: Some("WORK-*".to_string()),
+        ..Default::default()
+    }));
+
+    let actual = get_global_config();
+
+    assert_eq!(
+        actual,
+        Ok(GlobalConfig {
+            schema: None,
+            version: Some(CONFIG_VERSION),
+            default_repo_path: None,
+            default_profiles: None,
+            auto_profiles: Some(exp_auto_profiles),
         }),
     );
 }
@@ -130,3 +206,93 @@ fn config_file_is_unchanged() {
     assert_eq!(global_cfg_file_contents, r#"{"default-repo-path": "Orig text"}"#);
 }
 
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn xdg_config_file_is_used_if_legacy_file_doesnt_exist() {
+    let setup = Setup::new();
+    setup.set_home_dir();
+    create_dir_all(xdg_global_cfg_file().parent().unwrap()).unwrap();
+    write(xdg_global_cfg_file(), default_repo_path_as_json("XDG")).unwrap();
+    assert!(!global_cfg_file().exists());
+
+    let actual = get_global_config().unwrap();
+
+    assert_eq!(actual.default_repo_path, Some(OneOrMany::One(PathBuf::from("XDG"))));
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn xdg_config_file_is_preferred_over_legacy_file() {
+    let setup = Setup::new();
+    setup.make_global_cfg_file(default_repo_path_as_json("Legacy"));
+    create_dir_all(xdg_global_cfg_file().parent().unwrap()).unwrap();
+    write(xdg_global_cfg_file(), default_repo_path_as_json("XDG")).unwrap();
+
+    let actual = get_global_config().unwrap();
+
+    assert_eq!(actual.default_repo_path, Some(OneOrMany::One(PathBuf::from("XDG"))));
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn xdg_config_home_env_var_is_used_if_absolute() {
+    let setup = Setup::new();
+    setup.make_global_cfg_file(default_repo_path_as_json("Legacy"));
+    let xdg_cfg_home = setup.temp_dir.path().join("CustomConfig");
+    create_dir_all(xdg_cfg_home.join("tendrils")).unwrap();
+    write(
+        xdg_cfg_home.join("tendrils/global-config.json"),
+        default_repo_path_as_json("CustomXDG"),
+    ).unwrap();
+    std::env::set_var("XDG_CONFIG_HOME", &xdg_cfg_home);
+
+    let actual = get_global_config().unwrap();
+    std::env::remove_var("XDG_CONFIG_HOME");
+
+    assert_eq!(
+        actual.default_repo_path,
+        Some(OneOrMany::One(PathBuf::from("CustomXDG"))),
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS, SERIAL_CD)]
+fn xdg_config_home_env_var_is_ignored_if_relative() {
+    let setup = Setup::new();
+    setup.make_global_cfg_file(default_repo_path_as_json("Legacy"));
+    create_dir_all(setup.temp_dir.path().join("Rel/tendrils")).unwrap();
+    write(
+        setup.temp_dir.path().join("Rel/tendrils/global-config.json"),
+        default_repo_path_as_json("Relative"),
+    ).unwrap();
+    std::env::set_current_dir(setup.temp_dir.path()).unwrap();
+    std::env::set_var("XDG_CONFIG_HOME", "Rel");
+
+    let actual = get_global_config().unwrap();
+    std::env::remove_var("XDG_CONFIG_HOME");
+    std::env::set_current_dir(setup.temp_dir.path().parent().unwrap()).unwrap();
+
+    assert_eq!(
+        actual.default_repo_path,
+        Some(OneOrMany::One(PathBuf::from("Legacy"))),
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn newer_version_returns_unsupported_version_err() {
+    let setup = Setup::new();
+    setup.make_global_cfg_file(r#"{"version": 99}"#.to_string());
+
+    let actual = get_global_config();
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::UnsupportedVersion {
+            cfg_type: ConfigType::Global,
+            version: 99,
+            supported: crate::CONFIG_VERSION,
+        }),
+    );
+}
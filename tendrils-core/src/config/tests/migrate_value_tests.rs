@@ -0,0 +1,116 @@
+use crate::config::{
+    deserialize_versioned,
+    latest_version,
+    migrate_value,
+    Migration,
+    SerdeConfig,
+    CONFIG_VERSION,
+    GLOBAL_MIGRATIONS,
+    REPO_MIGRATIONS,
+};
+use crate::{ConfigFormat, ConfigType, GetConfigError};
+use rstest::rstest;
+use serde_json::{json, Value};
+
+fn push_1(value: &mut Value) {
+    value["steps"].as_array_mut().unwrap().push(1.into());
+}
+
+fn push_2(value: &mut Value) {
+    value["steps"].as_array_mut().unwrap().push(2.into());
+}
+
+const MIGRATIONS: &[Migration] = &[push_1, push_2];
+
+#[rstest]
+#[case(1, json!([1, 2]))]
+#[case(2, json!([2]))]
+#[case(3, json!([]))]
+fn applies_each_migration_after_version_in_order(
+    #[case] version: u32,
+    #[case] expected_steps: Value,
+) {
+    let mut value = json!({"version": version, "steps": []});
+
+    migrate_value(&mut value, version, MIGRATIONS);
+
+    assert_eq!(value, json!({"version": 3, "steps": expected_steps}));
+}
+
+#[test]
+fn no_migrations_only_sets_version() {
+    let mut value = json!({"tendrils": {}});
+
+    migrate_value(&mut value, 1, &[]);
+
+    assert_eq!(value, json!({"version": 1, "tendrils": {}}));
+}
+
+#[test]
+fn value_that_is_not_an_object_is_unchanged() {
+    let mut value = json!([]);
+
+    migrate_value(&mut value, 1, &[]);
+
+    assert_eq!(value, json!([]));
+}
+
+#[test]
+fn there_is_one_migration_for_each_version_after_the_first() {
+    assert_eq!(REPO_MIGRATIONS.len() as u32, CONFIG_VERSION - 1);
+    assert_eq!(GLOBAL_MIGRATIONS.len() as u32, CONFIG_VERSION - 1);
+}
+
+#[test]
+fn latest_version_is_one_more_than_number_of_migrations() {
+    assert_eq!(latest_version(&[]), 1);
+    assert_eq!(latest_version(MIGRATIONS), 3);
+}
+
+/// Upgrades a test layout from version 1, where the tendrils were under
+/// `old-tendrils`, to version 2.
+fn rename_old_tendrils(value: &mut Value) {
+    if let Some(map) = value.as_object_mut() {
+        if let Some(tendrils) = map.remove("old-tendrils") {
+            map.insert(String::from("tendrils"), tendrils);
+        }
+    }
+}
+
+#[rstest]
+#[case(ConfigFormat::Json, r#"{"version": 1, "old-tendrils": {"l1": {"remotes": "r1"}}}"#)]
+#[case(ConfigFormat::Toml, "[old-tendrils.l1]\nremotes = \"r1\"\n")]
+fn older_version_is_migrated_in_memory_when_read(
+    #[case] format: ConfigFormat,
+    #[case] text: &str,
+) {
+    let cfg_type = ConfigType::Repo(format);
+
+    let actual: SerdeConfig =
+        deserialize_versioned(text, format, &cfg_type, &[rename_old_tendrils])
+            .unwrap();
+
+    assert_eq!(actual.version, Some(2));
+    assert_eq!(actual.tendrils.keys().collect::<Vec<_>>(), vec!["l1"]);
+}
+
+#[test]
+fn newer_version_than_migrations_returns_unsupported_version_err() {
+    let cfg_type = ConfigType::Repo(ConfigFormat::Json);
+
+    let actual: Result<SerdeConfig, _> = deserialize_versioned(
+        r#"{"version": 3}"#,
+        ConfigFormat::Json,
+        &cfg_type,
+        &[rename_old_tendrils],
+    );
+
+    assert_eq!(
+        actual.map(|_| ()),
+        Err(GetConfigError::UnsupportedVersion {
+            cfg_type,
+            version: 3,
+            supported: 2,
+        }),
+    );
+}
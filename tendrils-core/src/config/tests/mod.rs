@@ -1,3 +1,5 @@
+mod config_schema_tests;
 mod get_config_tests;
 mod get_global_config_tests;
+mod migrate_value_tests;
 mod parse_config_tests;
@@ -1,6 +1,9 @@
 use crate::config::parse_config;
+use crate::profiles::ProfileRule;
 use crate::tests::sample_tendrils::SampleTendrils;
-use crate::TendrilMode;
+use crate::{NestedSymlinks, ReflinkMode, TendrilMode};
+use rstest::rstest;
+use serial_test::serial;
 
 #[test]
 fn empty_string_returns_error() {
@@ -69,6 +72,20 @@ fn ignores_extra_top_level_fields() {
     assert_eq!(actual, expected);
 }
 
+#[test]
+fn schema_key_is_ignored() {
+    let original_json = SampleTendrils::build_tendrils_json(&[
+        SampleTendrils::tendril_1_json()
+    ]);
+    let given = original_json
+        .replacen("{", r#"{"$schema": "./tendrils.schema.json", "#, 1);
+    let expected = SampleTendrils::raw_tendrils_1();
+
+    let actual = parse_config(&given).unwrap().raw_tendrils;
+
+    assert_eq!(actual, expected);
+}
+
 #[test]
 fn json_missing_remotes_returns_error() {
     let original_tendril_json = SampleTendrils::tendril_2_json();
@@ -82,7 +99,7 @@ fn json_missing_remotes_returns_error() {
     let actual = parse_config(&given);
 
     assert!(actual.is_err());
-    assert!(format!("{:?}", actual).contains("data did not match any variant of untagged enum OneOrMany"));
+    assert!(actual.unwrap_err().to_string().starts_with("missing field `remotes`"));
 }
 
 #[test]
@@ -167,7 +184,7 @@ fn json_remotes_is_null_returns_error() {
     let actual = parse_config(&given);
 
     assert!(actual.is_err());
-    assert!(format!("{:?}", actual).contains("data did not match any variant of untagged enum OneOrMany"));
+    assert!(actual.unwrap_err().to_string().starts_with("invalid type: null, expected a string"));
 }
 
 #[test]
@@ -185,7 +202,7 @@ fn json_individual_remote_is_null_returns_error() {
     let actual = parse_config(&given);
 
     assert!(actual.is_err());
-    assert!(format!("{:?}", actual).contains("data did not match any variant of untagged enum OneOrMany"));
+    assert!(actual.unwrap_err().to_string().starts_with("invalid type: null, expected a string"));
 }
 
 #[test]
@@ -201,7 +218,7 @@ fn json_dir_merge_is_null_returns_error() {
     let actual = parse_config(&given);
 
     assert!(actual.is_err());
-    assert!(format!("{:?}", actual).contains("data did not match any variant of untagged enum OneOrMany"));
+    assert!(actual.unwrap_err().to_string().starts_with("invalid type: null, expected a boolean"));
 }
 
 #[test]
@@ -217,7 +234,7 @@ fn json_link_is_null_returns_error() {
     let actual = parse_config(&given);
 
     assert!(actual.is_err());
-    assert!(format!("{:?}", actual).contains("data did not match any variant of untagged enum OneOrMany"));
+    assert!(actual.unwrap_err().to_string().starts_with("invalid type: null, expected a boolean"));
 }
 
 #[test]
@@ -233,7 +250,7 @@ fn json_profiles_is_null_returns_error() {
     let actual = parse_config(&given);
 
     assert!(actual.is_err());
-    assert!(format!("{:?}", actual).contains("data did not match any variant of untagged enum OneOrMany"));
+    assert!(actual.unwrap_err().to_string().starts_with("invalid type: null, expected a string"));
 }
 
 #[test]
@@ -249,7 +266,7 @@ fn json_individual_profile_is_null_returns_error() {
     let actual = parse_config(&given);
 
     assert!(actual.is_err());
-    assert!(format!("{:?}", actual).contains("data did not match any variant of untagged enum OneOrMany"));
+    assert!(actual.unwrap_err().to_string().starts_with("invalid type: null, expected a string"));
 }
 
 #[test]
@@ -332,3 +349,516 @@ fn non_list_single_profile_returns_list_of_len_1() {
     assert_eq!(actual, expected);
     assert_eq!(actual[0].profiles, vec!["win"]);
 }
+
+#[test]
+fn json_missing_fallback_defaults_to_false() {
+    let given = SampleTendrils::build_tendrils_json(
+        &[SampleTendrils::tendril_6_json()],
+    );
+    let expected = SampleTendrils::raw_tendrils_6();
+    assert!(expected.len() > 1);
+    assert!(expected.iter().all(|t| t.fallbacks.is_empty()));
+
+    let actual = parse_config(&given).unwrap().raw_tendrils;
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn fallback_true_combines_remotes_into_single_tendril() {
+    let given = SampleTendrils::build_tendrils_json(&[
+        r#""SomeApp/misc.txt": {
+            "remotes": ["r1", "r2", "r3"],
+            "fallback": true,
+            "profiles": "p1"
+        }"#.to_string()
+    ]);
+
+    let actual = parse_config(&given).unwrap().raw_tendrils;
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].local, "SomeApp/misc.txt");
+    assert_eq!(actual[0].remote, "r1");
+    assert_eq!(actual[0].fallbacks, vec!["r2", "r3"]);
+    assert_eq!(actual[0].profiles, vec!["p1"]);
+}
+
+#[test]
+fn fallback_true_with_single_remote_has_no_fallbacks() {
+    let given = SampleTendrils::build_tendrils_json(&[
+        r#""SomeApp/misc.txt": {
+            "remotes": "r1",
+            "fallback": true
+        }"#.to_string()
+    ]);
+
+    let actual = parse_config(&given).unwrap().raw_tendrils;
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].remote, "r1");
+    assert!(actual[0].fallbacks.is_empty());
+}
+
+#[test]
+fn fallback_true_with_empty_remotes_returns_empty() {
+    let given = SampleTendrils::build_tendrils_json(&[
+        r#""SomeApp/misc.txt": {
+            "remotes": [],
+            "fallback": true
+        }"#.to_string()
+    ]);
+
+    assert!(parse_config(&given).unwrap().raw_tendrils.is_empty());
+}
+
+#[test]
+fn json_missing_auto_profiles_defaults_to_empty() {
+    let given = SampleTendrils::build_tendrils_json(&[]);
+
+    let actual = parse_config(&given).unwrap();
+
+    assert!(actual.auto_profiles.is_empty());
+}
+
+#[test]
+fn auto_profiles_accept_single_rule_or_list_of_rules() {
+    let given = r#"{
+        "tendrils": {},
+        "auto-profiles": {
+            "work": { "hostname": "WORK-*", "os": "windows" },
+            "laptop": [
+                { "hostname": "*-laptop" },
+                { "env": "IS_LAPTOP" },
+                { "arch": "aarch64", "username": "me", "path": "~/.laptop" },
+                {}
+            ]
+        }
+    }"#;
+
+    let actual = parse_config(given).unwrap().auto_profiles;
+
+    let work: Vec<ProfileRule> = actual["work"].clone().into();
+    let laptop: Vec<ProfileRule> = actual["laptop"].clone().into();
+    assert_eq!(actual.keys().collect::<Vec<_>>(), vec!["work", "laptop"]);
+    assert_eq!(work, vec![ProfileRule {
+        hostname: Some("WORK-*".to_string()),
+        os: Some("windows".to_string()),
+        ..Default::default()
+    }]);
+    assert_eq!(laptop, vec![
+        ProfileRule {
+            hostname: Some("*-laptop".to_string()),
+            ..Default::default()
+        },
+        ProfileRule {
+            env: Some("IS_LAPTOP".to_string()),
+            ..Default::default()
+        },
+        ProfileRule {
+            arch: Some("aarch64".to_string()),
+            username: Some("me".to_string()),
+            path: Some("~/.laptop".to_string()),
+            ..Default::default()
+        },
+        ProfileRule::default(),
+    ]);
+}
+
+#[test]
+fn auto_profiles_with_invalid_rule_returns_error() {
+    let given = r#"{
+        "tendrils": {},
+        "auto-profiles": { "work": "WORK-*" }
+    }"#;
+
+    let actual = parse_config(given);
+
+    assert!(actual.is_err());
+}
+
+#[test]
+fn json_missing_profile_groups_defaults_to_empty() {
+    let given = SampleTendrils::build_tendrils_json(&[]);
+
+    let actual = parse_config(&given).unwrap();
+
+    assert!(actual.profile_groups.is_empty());
+}
+
+#[test]
+fn profile_groups_are_parsed_in_order() {
+    let given = r#"{
+        "tendrils": {},
+        "profile-groups": {
+            "desktop": ["home-pc", "work-pc"],
+            "mobile": []
+        }
+    }"#;
+
+    let actual = parse_config(given).unwrap().profile_groups;
+
+    assert_eq!(actual.keys().collect::<Vec<_>>(), vec!["desktop", "mobile"]);
+    assert_eq!(actual["desktop"], vec!["home-pc", "work-pc"]);
+    assert!(actual["mobile"].is_empty());
+}
+
+#[test]
+fn profile_expressions_are_kept_as_given() {
+    let given = SampleTendrils::build_tendrils_json(&[
+        r#""SomeApp/misc.txt": {
+            "remotes": "r1",
+            "profiles": ["p1", "work & !laptop"]
+        }"#.to_string()
+    ]);
+
+    let actual = parse_config(&given).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].profiles, vec!["p1", "work & !laptop"]);
+}
+
+#[test]
+fn invalid_profile_expression_returns_error() {
+    let given = SampleTendrils::build_tendrils_json(&[
+        r#""SomeApp/misc.txt": {
+            "remotes": "r1",
+            "profiles": "work & (laptop"
+        }"#.to_string()
+    ]);
+
+    let actual = parse_config(&given);
+
+    assert!(actual.is_err());
+    assert_eq!(
+        actual.unwrap_err().to_string(),
+        "Invalid profile expression \"work & (laptop\" in \"SomeApp/misc.txt\": \
+        Missing \")\" to close the \"(\" at position 8",
+    );
+}
+
+#[test]
+fn defaults_apply_to_sets_that_do_not_set_them() {
+    let given = r#"{
+        "defaults": {"link": true, "profiles": ["p1", "p2"]},
+        "tendrils": {
+            "SomeApp/misc.txt": {"remotes": "r1"},
+            "SomeApp/misc2.txt": {"remotes": "r2", "dir-merge": true}
+        }
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].mode, TendrilMode::Link);
+    assert_eq!(actual[0].profiles, vec!["p1", "p2"]);
+    assert_eq!(actual[1].mode, TendrilMode::Link);
+    assert_eq!(actual[1].profiles, vec!["p1", "p2"]);
+}
+
+#[test]
+fn set_values_override_defaults() {
+    let given = r#"{
+        "defaults": {"link": true, "dir-merge": true, "profiles": "p1"},
+        "tendrils": {
+            "SomeApp/misc.txt": {"remotes": "r1", "link": false, "profiles": []},
+            "SomeApp/misc2.txt": {
+                "remotes": "r2",
+                "link": false,
+                "dir-merge": false,
+                "profiles": "p2"
+            }
+        }
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].mode, TendrilMode::DirMerge);
+    assert!(actual[0].profiles.is_empty());
+    assert_eq!(actual[1].mode, TendrilMode::DirOverwrite);
+    assert_eq!(actual[1].profiles, vec!["p2"]);
+}
+
+#[test]
+fn relative_link_is_set_by_set_or_defaults_otherwise_false() {
+    let given = r#"{
+        "defaults": {"relative-link": true},
+        "tendrils": {
+            "SomeApp/misc.txt": {"remotes": "r1", "link": true},
+            "SomeApp/misc2.txt": {"remotes": "r2", "relative-link": false}
+        }
+    }"#;
+    let given_wo_defaults = r#"{
+        "tendrils": {"SomeApp/misc.txt": {"remotes": "r1", "link": true}}
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+    let actual_wo_defaults =
+        parse_config(given_wo_defaults).unwrap().raw_tendrils;
+
+    assert!(actual[0].relative_link);
+    assert!(!actual[1].relative_link);
+    assert!(!actual_wo_defaults[0].relative_link);
+}
+
+#[test]
+fn hardlink_sets_hardlink_mode_unless_link_is_set() {
+    let given = r#"{
+        "defaults": {"hardlink": true},
+        "tendrils": {
+            "SomeApp/misc.txt": {"remotes": "r1", "dir-merge": true},
+            "SomeApp/misc2.txt": {"remotes": "r2", "link": true},
+            "SomeApp/misc3.txt": {"remotes": "r3", "hardlink": false}
+        }
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].mode, TendrilMode::Hardlink);
+    assert_eq!(actual[1].mode, TendrilMode::Link);
+    assert_eq!(actual[2].mode, TendrilMode::DirOverwrite);
+}
+
+#[test]
+fn copy_fallback_is_set_by_set_or_defaults_otherwise_false() {
+    let given = r#"{
+        "defaults": {"copy-fallback": true},
+        "tendrils": {
+            "SomeApp/misc.txt": {"remotes": "r1", "hardlink": true},
+            "SomeApp/misc2.txt": {"remotes": "r2", "copy-fallback": false}
+        }
+    }"#;
+    let given_wo_defaults = r#"{
+        "tendrils": {"SomeApp/misc.txt": {"remotes": "r1", "hardlink": true}}
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+    let actual_wo_defaults =
+        parse_config(given_wo_defaults).unwrap().raw_tendrils;
+
+    assert!(actual[0].copy_fallback);
+    assert!(!actual[1].copy_fallback);
+    assert!(!actual_wo_defaults[0].copy_fallback);
+}
+
+#[test]
+fn nested_symlinks_is_set_by_set_or_defaults_otherwise_follow() {
+    let given = r#"{
+        "defaults": {"nested-symlinks": "preserve"},
+        "tendrils": {
+            "SomeApp/misc": {"remotes": "r1"},
+            "SomeApp/misc2": {"remotes": "r2", "nested-symlinks": "skip"}
+        }
+    }"#;
+    let given_wo_defaults = r#"{
+        "tendrils": {"SomeApp/misc": {"remotes": "r1"}}
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+    let actual_wo_defaults =
+        parse_config(given_wo_defaults).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].nested_symlinks, NestedSymlinks::Preserve);
+    assert_eq!(actual[1].nested_symlinks, NestedSymlinks::Skip);
+    assert_eq!(actual_wo_defaults[0].nested_symlinks, NestedSymlinks::Follow);
+}
+
+#[test]
+fn invalid_nested_symlinks_returns_error() {
+    let given = r#"{
+        "tendrils": {
+            "SomeApp/misc": {"remotes": "r1", "nested-symlinks": "copy"}
+        }
+    }"#;
+
+    let actual = parse_config(given);
+
+    assert!(actual.is_err());
+    assert!(format!("{:?}", actual).contains("unknown variant `copy`"));
+}
+
+#[test]
+fn empty_defaults_have_no_effect() {
+    let original_json = SampleTendrils::build_tendrils_json(&[
+        SampleTendrils::tendril_2_json()
+    ]);
+    let given = original_json.replacen("{", r#"{"defaults": {}, "#, 1);
+    let expected = SampleTendrils::raw_tendrils_2();
+
+    let actual = parse_config(&given).unwrap().raw_tendrils;
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn defaults_link_is_null_returns_error() {
+    let given = r#"{"defaults": {"link": null}}"#;
+
+    let actual = parse_config(given);
+
+    assert!(actual.is_err());
+    assert!(format!("{:?}", actual).contains("invalid type: null"));
+}
+
+#[test]
+fn invalid_profile_expression_in_defaults_returns_error() {
+    let given = r#"{"defaults": {"profiles": "work &"}}"#;
+
+    let actual = parse_config(given);
+
+    assert!(actual.is_err());
+    assert!(
+        actual.unwrap_err().to_string().starts_with(
+            "Invalid profile expression \"work &\" in \"defaults\""
+        )
+    );
+}
+
+#[test]
+fn vars_are_resolved_in_remotes_and_fallbacks() {
+    let given = r#"{
+        "vars": {"Vault": "<OneDrive>/work-vault/.obsidian", "Alt": "/alt"},
+        "tendrils": {
+            "SomeApp/misc.txt": {
+                "remotes": ["<Vault>/misc.txt", "<Alt>/<Vault>", "<Unknown>/x"],
+                "fallback": true
+            }
+        }
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].remote, "<OneDrive>/work-vault/.obsidian/misc.txt");
+    assert_eq!(
+        actual[0].fallbacks,
+        vec!["/alt/<OneDrive>/work-vault/.obsidian", "<Unknown>/x"],
+    );
+}
+
+#[rstest]
+#[case("$Var/misc.txt", "/value/misc.txt")]
+#[case("${Var}/misc.txt", "/value/misc.txt")]
+#[case("${Var:-/default}/misc.txt", "/value/misc.txt")]
+#[case("${Var:?Required}/misc.txt", "/value/misc.txt")]
+#[case("${Empty:-/default}", "${Empty:-/default}")]
+#[case("<<Var>/$$Var", "<<Var>/$$Var")]
+#[case("${Unknown:-/default}", "${Unknown:-/default}")]
+fn vars_are_resolved_in_all_var_forms(
+    #[case] remote: &str,
+    #[case] exp_remote: &str,
+) {
+    let given = format!(r#"{{
+        "vars": {{"Var": "/value", "Empty": ""}},
+        "tendrils": {{"SomeApp/misc.txt": {{"remotes": "{remote}"}}}}
+    }}"#);
+
+    let actual = parse_config(&given).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].remote, exp_remote);
+}
+
+#[test]
+fn vars_are_not_resolved_in_locals() {
+    let given = r#"{
+        "vars": {"Var": "Value"},
+        "tendrils": {"SomeApp/<Var>": {"remotes": "r1"}}
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].local, "SomeApp/<Var>");
+}
+
+#[test]
+fn var_values_are_not_searched_for_other_vars() {
+    let given = r#"{
+        "vars": {"Outer": "<Inner>/path", "Inner": "/inner"},
+        "tendrils": {"SomeApp/misc.txt": {"remotes": "<Outer>"}}
+    }"#;
+
+    let actual = parse_config(given).unwrap().raw_tendrils;
+
+    assert_eq!(actual[0].remote, "<Inner>/path");
+}
+
+#[rstest]
+#[case(None, true, "/from/repo")]
+#[case(None, false, "/from/repo")]
+#[case(Some("repo"), true, "/from/repo")]
+#[case(Some("repo"), false, "/from/repo")]
+#[case(Some("env"), true, "<TD_PARSE_CONFIG_TEST_VAR>")]
+#[case(Some("env"), false, "/from/repo")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn var_priority_determines_whether_repo_or_env_var_is_used(
+    #[case] priority: Option<&str>,
+    #[case] env_var_set: bool,
+    #[case] exp_remote: &str,
+) {
+    let priority_json = match priority {
+        Some(v) => format!(r#""var-priority": "{v}","#),
+        None => String::new(),
+    };
+    let given = format!(r#"{{
+        {priority_json}
+        "vars": {{"TD_PARSE_CONFIG_TEST_VAR": "/from/repo"}},
+        "tendrils": {{"SomeApp/misc.txt": {{"remotes": "<TD_PARSE_CONFIG_TEST_VAR>"}}}}
+    }}"#);
+    if env_var_set {
+        std::env::set_var("TD_PARSE_CONFIG_TEST_VAR", "/from/env");
+    }
+    else {
+        std::env::remove_var("TD_PARSE_CONFIG_TEST_VAR");
+    }
+
+    let actual = parse_config(&given);
+    std::env::remove_var("TD_PARSE_CONFIG_TEST_VAR");
+
+    assert_eq!(actual.unwrap().raw_tendrils[0].remote, exp_remote);
+}
+
+#[test]
+fn invalid_var_priority_returns_error() {
+    let given = r#"{"var-priority": "global"}"#;
+
+    let actual = parse_config(given);
+
+    assert!(actual.is_err());
+    assert!(format!("{:?}", actual).contains("unknown variant `global`"));
+}
+
+#[rstest]
+#[case(None, ReflinkMode::Never)]
+#[case(Some("auto"), ReflinkMode::Auto)]
+#[case(Some("always"), ReflinkMode::Always)]
+#[case(Some("never"), ReflinkMode::Never)]
+fn reflink_is_applied_to_all_tendrils(
+    #[case] reflink: Option<&str>,
+    #[case] exp: ReflinkMode,
+) {
+    let reflink_json = match reflink {
+        Some(v) => format!(r#""reflink": "{v}","#),
+        None => String::new(),
+    };
+    let given = format!(r#"{{
+        {reflink_json}
+        "tendrils": {{
+            "SomeApp/misc.txt": {{"remotes": "r1"}},
+            "SomeApp/folder": [
+                {{"remotes": "r2", "link": true}},
+                {{"remotes": "r3", "dir-merge": true}}
+            ]
+        }}
+    }}"#);
+
+    let actual = parse_config(&given).unwrap().raw_tendrils;
+
+    assert_eq!(actual.len(), 3);
+    assert!(actual.iter().all(|t| t.reflink == exp));
+}
+
+#[test]
+fn invalid_reflink_returns_error() {
+    let given = r#"{"reflink": "sometimes"}"#;
+
+    let actual = parse_config(given);
+
+    assert!(actual.is_err());
+    assert!(format!("{:?}", actual).contains("unknown variant `sometimes`"));
+}
@@ -0,0 +1,590 @@
+use crate::path_ext::{tokenize_vars, PathToken, PathExt};
+use crate::{
+    is_on_other_device,
+    link_status,
+    FsoType,
+    GetConfigError,
+    GetTendrilsRepoError,
+    LinkStatus,
+    RawTendril,
+    Severity,
+    TendrilMode,
+    UniPath,
+};
+use same_file::is_same_file;
+use std::fs::read_dir;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// A problem with the environment or the Tendrils repo that may cause the
+/// tendril actions to fail.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Diagnosis {
+    /// The `global-config.json` file could not be read.
+    GlobalConfig(GetConfigError),
+
+    /// The Tendrils repo could not be found.
+    NoValidTendrilsRepo(GetTendrilsRepoError),
+
+    /// The `tendrils.json` file (or one of the files it includes) could not
+    /// be read.
+    RepoConfig(GetConfigError),
+
+    /// The Tendrils repo folder cannot be written to, so the tendrils cannot
+    /// be pulled.
+    RepoNotWritable { path: PathBuf, kind: ErrorKind },
+
+    /// There are link tendrils, but the current process is missing the
+    /// permissions required to create symlinks.
+    CannotSymlink,
+
+    /// An environment variable used in the remotes is not set.
+    UnresolvedVariable {
+        name: String,
+
+        /// The locals of each tendril that uses the variable.
+        locals: Vec<String>,
+
+        /// Whether each of these tendrils still has another remote that can
+        /// be resolved.
+        in_fallbacks_only: bool,
+    },
+
+    /// The remote of a link tendril is a symlink whose target does not
+    /// exist.
+    BrokenSymlink { local: String, remote: PathBuf },
+
+    /// The remote of a link tendril is a symlink that points somewhere other
+    /// than the local. The `status` is either [`LinkStatus::OldRepo`] or
+    /// [`LinkStatus::WrongTarget`].
+    MisdirectedSymlink {
+        local: String,
+        remote: PathBuf,
+        target: PathBuf,
+        status: LinkStatus,
+    },
+
+    /// The remote of a hard link tendril is a file, but no longer the same
+    /// file as the local, i.e. after an app replaced it when saving.
+    BrokenHardlink { local: String, remote: PathBuf },
+
+    /// The nearest existing folder to the remote cannot be written to, so
+    /// the tendril cannot be pushed or linked.
+    RemoteNotWritable {
+        local: String,
+        remote: PathBuf,
+        dir: PathBuf,
+        kind: ErrorKind,
+    },
+
+    /// Write access to the Tendrils repo and the remotes is not checked,
+    /// because the check is not supported on the current platform.
+    WriteAccessNotChecked,
+
+    /// Two locals only differ by case, so they are the same file on
+    /// case-insensitive file systems.
+    LocalCaseConflict { first: String, second: String },
+
+    /// Two remotes only differ by case, so they are the same file on
+    /// case-insensitive file systems.
+    RemoteCaseConflict {
+        first_local: String,
+        second_local: String,
+        first_remote: PathBuf,
+        second_remote: PathBuf,
+    },
+
+    /// The local only exists with a different case in the Tendrils repo, so
+    /// it will not be found on case-sensitive file systems.
+    LocalCaseMismatch { local: String, actual: PathBuf },
+}
+
+impl Diagnosis {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Diagnosis::GlobalConfig(_)
+            | Diagnosis::NoValidTendrilsRepo(_)
+            | Diagnosis::RepoConfig(_)
+            | Diagnosis::RepoNotWritable { .. }
+            | Diagnosis::CannotSymlink => Severity::Error,
+            Diagnosis::UnresolvedVariable { in_fallbacks_only, .. } => {
+                if *in_fallbacks_only {
+                    Severity::Warning
+                }
+                else {
+                    Severity::Error
+                }
+            }
+            Diagnosis::BrokenSymlink { .. }
+            | Diagnosis::MisdirectedSymlink { .. }
+            | Diagnosis::BrokenHardlink { .. }
+            | Diagnosis::RemoteNotWritable { .. }
+            | Diagnosis::WriteAccessNotChecked
+            | Diagnosis::LocalCaseConflict { .. }
+            | Diagnosis::RemoteCaseConflict { .. }
+            | Diagnosis::LocalCaseMismatch { .. } => Severity::Warning,
+        }
+    }
+
+    /// Returns a suggestion for how to resolve the problem.
+    pub fn fix(&self) -> String {
+        match self {
+            Diagnosis::GlobalConfig(_)
+            | Diagnosis::NoValidTendrilsRepo(GetTendrilsRepoError::ConfigError(_)) => {
+                String::from(
+                    "Correct the global-config.json file, or delete it to use \
+                    the defaults",
+                )
+            }
+            Diagnosis::NoValidTendrilsRepo(GetTendrilsRepoError::GivenInvalid { .. }) => {
+                String::from(
+                    "Give the path to an existing Tendrils repo, or run the \
+                    init command to create one",
+                )
+            }
+            Diagnosis::NoValidTendrilsRepo(GetTendrilsRepoError::DefaultInvalid { .. }) => {
+                String::from(
+                    "Update the default-repo-path in the global-config.json \
+                    file, or run the init command in that folder",
+                )
+            }
+            Diagnosis::NoValidTendrilsRepo(GetTendrilsRepoError::DefaultNotSet) => {
+                String::from(
+                    "Set the default-repo-path in the global-config.json \
+                    file, or give the path to a Tendrils repo",
+                )
+            }
+            Diagnosis::RepoConfig(_) => String::from(
+                "Correct the errors in the Tendrils repo's config files",
+            ),
+            Diagnosis::RepoNotWritable { path, kind } => {
+                not_writable_fix(path, kind)
+            }
+            Diagnosis::CannotSymlink => String::from(
+                "Run the command in an elevated terminal, enable developer \
+                mode, or change the link tendrils to a copy mode",
+            ),
+            Diagnosis::UnresolvedVariable { name, .. } => format!(
+                "Set the {name} environment variable, give it a default (i.e. \
+                ${{{name}:-default}}), or add a fallback remote",
+            ),
+            Diagnosis::BrokenSymlink { local, .. } => format!(
+                "Restore \"{local}\" in the Tendrils repo, or link the tendril \
+                again with the force option",
+            ),
+            Diagnosis::MisdirectedSymlink { .. } => String::from(
+                "Run the relink command to point it to the Tendrils repo",
+            ),
+            Diagnosis::BrokenHardlink { .. } => String::from(
+                "Copy any changes in the remote to the Tendrils repo, then run \
+                the link command with the force flag to replace it",
+            ),
+            Diagnosis::RemoteNotWritable { dir, kind, .. } => {
+                not_writable_fix(dir, kind)
+            }
+            Diagnosis::WriteAccessNotChecked => String::from(
+                "Check that the current user can write to the Tendrils repo \
+                and to the remotes' folders",
+            ),
+            Diagnosis::LocalCaseConflict { .. } => String::from(
+                "Rename one of the locals so that they differ by more than \
+                case",
+            ),
+            Diagnosis::RemoteCaseConflict { .. } => String::from(
+                "Use the same case for both remotes, or rename one of them",
+            ),
+            Diagnosis::LocalCaseMismatch { actual, .. } => format!(
+                "Change the local to \"{}\", or rename it in the Tendrils repo",
+                actual.to_string_lossy(),
+            ),
+        }
+    }
+}
+
+impl ToString for Diagnosis {
+    fn to_string(&self) -> String {
+        match self {
+            Diagnosis::GlobalConfig(e) | Diagnosis::RepoConfig(e) => {
+                e.to_string()
+            }
+            Diagnosis::NoValidTendrilsRepo(e) => e.to_string(),
+            Diagnosis::RepoNotWritable { path, kind } => format!(
+                "The Tendrils repo \"{}\" {}",
+                path.to_string_lossy(),
+                not_writable_text(kind),
+            ),
+            Diagnosis::CannotSymlink => String::from(
+                "Missing the permissions required to create symlinks for the \
+                link tendrils",
+            ),
+            Diagnosis::UnresolvedVariable { name, locals, in_fallbacks_only } => {
+                let locals = locals
+                    .iter()
+                    .map(|l| format!("\"{l}\""))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let used_in = match in_fallbacks_only {
+                    true => "only some of the remotes",
+                    false => "the remotes",
+                };
+                format!("The variable \"{name}\" is not set, but is used in {used_in} of {locals}")
+            }
+            Diagnosis::BrokenSymlink { local, remote } => format!(
+                "The remote \"{}\" of \"{local}\" is a broken symlink",
+                remote.to_string_lossy(),
+            ),
+            Diagnosis::MisdirectedSymlink { local, remote, target, status } => {
+                let points_to = match status {
+                    LinkStatus::OldRepo => "the old repo location ",
+                    _ => "",
+                };
+                format!(
+                    "The remote \"{}\" of \"{local}\" is a symlink to \
+                    {points_to}\"{}\" instead of the Tendrils repo",
+                    remote.to_string_lossy(),
+                    target.to_string_lossy(),
+                )
+            }
+            Diagnosis::BrokenHardlink { local, remote } => format!(
+                "The remote \"{}\" of \"{local}\" is no longer a hard link to \
+                the Tendrils repo",
+                remote.to_string_lossy(),
+            ),
+            Diagnosis::RemoteNotWritable { local, remote, dir, kind } => format!(
+                "The remote \"{}\" of \"{local}\" is in \"{}\", which {}",
+                remote.to_string_lossy(),
+                dir.to_string_lossy(),
+                not_writable_text(kind),
+            ),
+            Diagnosis::WriteAccessNotChecked => String::from(
+                "Write access to the Tendrils repo and the remotes is not \
+                checked on this platform",
+            ),
+            Diagnosis::LocalCaseConflict { first, second } => format!(
+                "The locals \"{first}\" and \"{second}\" only differ by case",
+            ),
+            Diagnosis::RemoteCaseConflict {
+                first_local,
+                second_local,
+                first_remote,
+                second_remote,
+            } => format!(
+                "The remotes \"{}\" of \"{first_local}\" and \"{}\" of \
+                \"{second_local}\" only differ by case",
+                first_remote.to_string_lossy(),
+                second_remote.to_string_lossy(),
+            ),
+            Diagnosis::LocalCaseMismatch { local, actual } => format!(
+                "The local \"{local}\" only exists as \"{}\"",
+                actual.to_string_lossy(),
+            ),
+        }
+    }
+}
+
+fn not_writable_text(kind: &ErrorKind) -> String {
+    match kind {
+        ErrorKind::ReadOnlyFilesystem => String::from("is on a read-only file system"),
+        ErrorKind::PermissionDenied => String::from("cannot be written to"),
+        _ => format!("cannot be written to:\n{kind}"),
+    }
+}
+
+fn not_writable_fix(dir: &Path, kind: &ErrorKind) -> String {
+    match kind {
+        ErrorKind::ReadOnlyFilesystem => String::from(
+            "Remount the file system with write access",
+        ),
+        _ => format!(
+            "Grant the current user write access to \"{}\"",
+            dir.to_string_lossy(),
+        ),
+    }
+}
+
+/// Checks the given Tendrils repo and its tendrils for problems that are not
+/// caught until the tendril actions are performed. Tendrils that cannot be
+/// [resolved](RawTendril::resolve) are only checked for unset variables and
+/// case conflicts between their locals.
+pub(crate) fn diagnose_repo(
+    td_repo: &UniPath,
+    raw_tendrils: &[RawTendril],
+    can_symlink: bool,
+) -> Vec<Diagnosis> {
+    let mut diagnoses = vec![];
+    if let Err(kind) = probe_writable(td_repo.inner()) {
+        diagnoses.push(Diagnosis::RepoNotWritable {
+            path: td_repo.inner().to_path_buf(),
+            kind,
+        });
+    }
+    if !can_symlink && raw_tendrils.iter().any(|t| t.mode == TendrilMode::Link) {
+        diagnoses.push(Diagnosis::CannotSymlink);
+    }
+
+    diagnoses.extend(diagnose_vars(td_repo, raw_tendrils));
+    diagnoses.extend(diagnose_remotes(td_repo, raw_tendrils));
+    diagnoses.extend(diagnose_case(td_repo, raw_tendrils));
+    diagnoses
+}
+
+/// Returns a [`Diagnosis::UnresolvedVariable`] for each variable used in the
+/// remotes (including fallbacks) that cannot be resolved, in the order they
+/// first appear.
+fn diagnose_vars(td_repo: &UniPath, raw_tendrils: &[RawTendril]) -> Vec<Diagnosis> {
+    let mut diagnoses: Vec<Diagnosis> = vec![];
+    for raw in raw_tendrils {
+        let is_resolvable = raw.resolve(td_repo, None).is_ok();
+        let remotes = std::iter::once(&raw.remote).chain(raw.fallbacks.iter());
+        for remote in remotes {
+            for token in tokenize_vars(remote.as_bytes()) {
+                let var = match token {
+                    PathToken::Var(v) if v.lookup().is_none() => v,
+                    _ => continue,
+                };
+                let name = var.name_os().to_string_lossy().to_string();
+                let existing = diagnoses.iter_mut().find(|d| {
+                    matches!(d, Diagnosis::UnresolvedVariable { name: n, .. } if n == &name)
+                });
+
+                match existing {
+                    Some(Diagnosis::UnresolvedVariable {
+                        locals,
+                        in_fallbacks_only,
+                        ..
+                    }) => {
+                        if !locals.contains(&raw.local) {
+                            locals.push(raw.local.clone());
+                        }
+                        *in_fallbacks_only &= is_resolvable;
+                    }
+                    _ => diagnoses.push(Diagnosis::UnresolvedVariable {
+                        name,
+                        locals: vec![raw.local.clone()],
+                        in_fallbacks_only: is_resolvable,
+                    }),
+                }
+            }
+        }
+    }
+
+    diagnoses
+}
+
+/// Checks the resolved remotes for broken or misdirected symlinks (link
+/// tendrils only), for broken hard links (hard link tendrils only) and for
+/// whether they can be written to. Each folder is only
+/// checked for write access once.
+fn diagnose_remotes(td_repo: &UniPath, raw_tendrils: &[RawTendril]) -> Vec<Diagnosis> {
+    let mut diagnoses = vec![];
+    let mut probed_dirs: Vec<(PathBuf, Result<(), ErrorKind>)> = vec![];
+    for raw in raw_tendrils {
+        let tendril = match raw.resolve(td_repo, None) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let remote = tendril.remote().inner();
+
+        if raw.mode == TendrilMode::Link {
+            match link_status(remote, tendril.local_abs(), tendril.local()) {
+                Some((
+                    status @ (LinkStatus::OldRepo | LinkStatus::WrongTarget),
+                    target,
+                )) => {
+                    diagnoses.push(Diagnosis::MisdirectedSymlink {
+                        local: raw.local.clone(),
+                        remote: remote.to_path_buf(),
+                        target,
+                        status,
+                    });
+                }
+                _ if remote.get_type() == Some(FsoType::BrokenSym) => {
+                    diagnoses.push(Diagnosis::BrokenSymlink {
+                        local: raw.local.clone(),
+                        remote: remote.to_path_buf(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        else if raw.mode == TendrilMode::Hardlink
+            && remote.get_type() == Some(FsoType::File)
+            && tendril.local_abs().get_type() == Some(FsoType::File)
+            && !is_same_file(tendril.local_abs(), remote).unwrap_or(true)
+            && !(raw.copy_fallback
+                && is_on_other_device(tendril.local_abs(), remote)) {
+            diagnoses.push(Diagnosis::BrokenHardlink {
+                local: raw.local.clone(),
+                remote: remote.to_path_buf(),
+            });
+        }
+
+        let dir = match nearest_existing_dir(remote) {
+            Some(v) => v,
+            None => continue,
+        };
+        let result = match probed_dirs.iter().find(|(d, _)| d == &dir) {
+            Some((_, r)) => *r,
+            None => {
+                let r = probe_writable(&dir);
+                probed_dirs.push((dir.clone(), r));
+                r
+            }
+        };
+        if let Err(kind) = result {
+            diagnoses.push(Diagnosis::RemoteNotWritable {
+                local: raw.local.clone(),
+                remote: remote.to_path_buf(),
+                dir,
+                kind,
+            });
+        }
+    }
+
+    diagnoses
+}
+
+/// Checks for locals and remotes that only differ by case, and for locals that
+/// only exist in the Tendrils repo with a different case. Each pair is only
+/// reported once.
+fn diagnose_case(td_repo: &UniPath, raw_tendrils: &[RawTendril]) -> Vec<Diagnosis> {
+    let mut diagnoses = vec![];
+    let mut locals: Vec<&str> = vec![];
+    for raw in raw_tendrils {
+        if !locals.contains(&raw.local.as_str()) {
+            locals.push(&raw.local);
+        }
+    }
+
+    for (i, first) in locals.iter().enumerate() {
+        for second in locals.iter().skip(i + 1) {
+            if first.to_lowercase() == second.to_lowercase() {
+                diagnoses.push(Diagnosis::LocalCaseConflict {
+                    first: first.to_string(),
+                    second: second.to_string(),
+                });
+            }
+        }
+    }
+    for local in locals {
+        if let Some(actual) = find_case_mismatch(td_repo.inner(), Path::new(local)) {
+            diagnoses.push(Diagnosis::LocalCaseMismatch {
+                local: local.to_string(),
+                actual,
+            });
+        }
+    }
+
+    let resolved: Vec<(&RawTendril, PathBuf)> = raw_tendrils
+        .iter()
+        .filter_map(|raw| match raw.resolve(td_repo, None) {
+            Ok(t) => Some((raw, t.remote().inner().to_path_buf())),
+            Err(_) => None,
+        })
+        .collect();
+    let mut remote_pairs: Vec<(&Path, &Path)> = vec![];
+    for (i, (first, first_remote)) in resolved.iter().enumerate() {
+        for (second, second_remote) in resolved.iter().skip(i + 1) {
+            let is_case_conflict = first_remote != second_remote
+                && first_remote.to_string_lossy().to_lowercase()
+                    == second_remote.to_string_lossy().to_lowercase();
+            let pair = (first_remote.as_path(), second_remote.as_path());
+            if is_case_conflict && !remote_pairs.contains(&pair) {
+                remote_pairs.push(pair);
+                diagnoses.push(Diagnosis::RemoteCaseConflict {
+                    first_local: first.local.clone(),
+                    second_local: second.local.clone(),
+                    first_remote: first_remote.clone(),
+                    second_remote: second_remote.clone(),
+                });
+            }
+        }
+    }
+
+    diagnoses
+}
+
+/// Returns the path of the given local (relative to the Tendrils repo) as it
+/// is actually named in the Tendrils repo, if any of its components only
+/// match with a different case. Returns `None` if the local exists with the
+/// given case, or if it does not exist with any case.
+fn find_case_mismatch(td_repo: &Path, local: &Path) -> Option<PathBuf> {
+    let mut dir = td_repo.to_path_buf();
+    let mut actual = PathBuf::new();
+    let mut is_mismatched = false;
+    for comp in local.components() {
+        let name = match comp {
+            Component::Normal(v) => v,
+            _ => return None,
+        };
+        let entries: Vec<_> = read_dir(&dir)
+            .ok()?
+            .filter_map(|e| e.ok().map(|e| e.file_name()))
+            .collect();
+
+        let found = match entries.iter().find(|e| *e == name) {
+            Some(e) => e,
+            None => {
+                let lower_name = name.to_string_lossy().to_lowercase();
+                is_mismatched = true;
+                entries
+                    .iter()
+                    .find(|e| e.to_string_lossy().to_lowercase() == lower_name)?
+            }
+        };
+        dir.push(found);
+        actual.push(found);
+    }
+
+    match is_mismatched {
+        true => Some(actual),
+        false => None,
+    }
+}
+
+/// Returns the nearest existing folder that would need to be written to in
+/// order to create or replace the given path.
+fn nearest_existing_dir(path: &Path) -> Option<PathBuf> {
+    let mut dir = path.parent()?;
+    while !dir.is_dir() {
+        dir = dir.parent()?;
+    }
+    Some(dir.to_path_buf())
+}
+
+/// Checks whether the given folder can be written to, without modifying it.
+/// The file system must not be mounted read-only and the current user must
+/// have write access to the folder.
+#[cfg(unix)]
+fn probe_writable(dir: &Path) -> Result<(), ErrorKind> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_dir = CString::new(dir.as_os_str().as_bytes())
+        .map_err(|_| ErrorKind::InvalidInput)?;
+
+    // SAFETY: `c_dir` is a valid nul-terminated string and `stats` is only
+    // read if the call succeeds, in which case it has been filled in.
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_dir.as_ptr(), &mut stats) } == 0
+        && stats.f_flag & libc::ST_RDONLY != 0 {
+        return Err(ErrorKind::ReadOnlyFilesystem);
+    }
+
+    // SAFETY: `c_dir` is a valid nul-terminated string.
+    match unsafe { libc::access(c_dir.as_ptr(), libc::W_OK) } {
+        0 => Ok(()),
+        _ => Err(std::io::Error::last_os_error().kind()),
+    }
+}
+
+/// Write access cannot be checked without modifying the folder on this
+/// platform, as the read-only attribute of a folder does not prevent writing
+/// to it. This is reported as a [`Diagnosis::WriteAccessNotChecked`] instead.
+#[cfg(not(unix))]
+fn probe_writable(_dir: &Path) -> Result<(), ErrorKind> {
+    Ok(())
+}
@@ -0,0 +1,402 @@
+use crate::doctor::{diagnose_repo, Diagnosis};
+use crate::test_utils::{default_repo_path_as_json, Setup, symlink_expose};
+use crate::{LinkStatus, RawTendril, Severity, TendrilMode};
+use rstest::rstest;
+use serial_test::serial;
+use std::fs::{
+    create_dir_all,
+    metadata,
+    read_dir,
+    remove_dir_all,
+    remove_file,
+    write,
+};
+use std::path::PathBuf;
+
+fn raw(setup: &Setup, local: &str, remote: &str, mode: TendrilMode) -> RawTendril {
+    let mut raw = setup.file_tendril_raw();
+    raw.local = local.to_string();
+    raw.remote = setup.parent_dir.join(remote).to_string_lossy().to_string();
+    raw.mode = mode;
+    raw
+}
+
+#[test]
+fn valid_repo_with_no_problems_returns_empty() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    setup.make_local_file();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "SomeApp/misc", "misc", TendrilMode::Link);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1, t2], true);
+
+    assert_eq!(actual, vec![]);
+}
+
+#[test]
+fn write_access_check_does_not_modify_folders() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let list_dir = |dir: &PathBuf| -> Vec<PathBuf> {
+        read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect()
+    };
+    let mtime = |dir: &PathBuf| metadata(dir).unwrap().modified().unwrap();
+    let repo_entries = list_dir(&setup.td_repo);
+    let parent_entries = list_dir(&setup.parent_dir);
+    let repo_mtime = mtime(&setup.td_repo);
+    let parent_mtime = mtime(&setup.parent_dir);
+
+    diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    assert_eq!(list_dir(&setup.td_repo), repo_entries);
+    assert_eq!(list_dir(&setup.parent_dir), parent_entries);
+    assert_eq!(mtime(&setup.td_repo), repo_mtime);
+    assert_eq!(mtime(&setup.parent_dir), parent_mtime);
+}
+
+#[rstest]
+#[case(TendrilMode::DirMerge, false)]
+#[case(TendrilMode::DirOverwrite, false)]
+#[case(TendrilMode::Link, true)]
+fn cannot_symlink_only_reported_if_any_link_tendrils(
+    #[case] mode: TendrilMode,
+    #[case] expected: bool,
+) {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", mode);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], false);
+
+    assert_eq!(actual.contains(&Diagnosis::CannotSymlink), expected);
+}
+
+#[test]
+fn unset_var_in_remote_returns_error_listing_each_local_once() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    let mut t1 = setup.file_tendril_raw();
+    t1.remote = "<TD_DOCTOR_UNSET>/misc.txt".to_string();
+    let mut t2 = t1.clone();
+    t2.remote = "${TD_DOCTOR_UNSET}/misc2.txt".to_string();
+    let mut t3 = t1.clone();
+    t3.local = "OtherApp/misc.txt".to_string();
+    t3.remote = "$TD_DOCTOR_UNSET/misc.txt".to_string();
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1, t2, t3], true);
+
+    let expected = Diagnosis::UnresolvedVariable {
+        name: "TD_DOCTOR_UNSET".to_string(),
+        locals: vec![
+            "SomeApp/misc.txt".to_string(),
+            "OtherApp/misc.txt".to_string(),
+        ],
+        in_fallbacks_only: false,
+    };
+    assert_eq!(actual, vec![expected.clone()]);
+    assert_eq!(expected.severity(), Severity::Error);
+}
+
+#[test]
+fn unset_var_only_in_unused_fallback_returns_warning() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    let mut t1 = setup.file_tendril_raw();
+    t1.fallbacks = vec!["<TD_DOCTOR_UNSET>/misc.txt".to_string()];
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    let expected = Diagnosis::UnresolvedVariable {
+        name: "TD_DOCTOR_UNSET".to_string(),
+        locals: vec!["SomeApp/misc.txt".to_string()],
+        in_fallbacks_only: true,
+    };
+    assert_eq!(actual, vec![expected.clone()]);
+    assert_eq!(expected.severity(), Severity::Warning);
+}
+
+#[rstest]
+#[case("${TD_DOCTOR_UNSET:-Default}/misc.txt")]
+#[case("$$TD_DOCTOR_UNSET/misc.txt")]
+#[case("<<TD_DOCTOR_UNSET>/misc.txt")]
+fn unset_var_with_default_or_escaped_is_not_reported(#[case] remote: &str) {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    let mut t1 = setup.file_tendril_raw();
+    t1.remote = setup.parent_dir.join(remote).to_string_lossy().to_string();
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    assert!(!actual.iter().any(|d| matches!(d, Diagnosis::UnresolvedVariable { .. })));
+}
+
+#[rstest]
+#[case(TendrilMode::Link, true)]
+#[case(TendrilMode::DirOverwrite, false)]
+fn broken_symlink_at_remote_only_reported_for_link_tendrils(
+    #[case] mode: TendrilMode,
+    #[case] expected: bool,
+) {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    setup.make_local_file();
+    symlink_expose(&setup.remote_file, &setup.local_file, false, true).unwrap();
+    remove_file(&setup.local_file).unwrap();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", mode);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    let expected_diagnosis = Diagnosis::BrokenSymlink {
+        local: "SomeApp/misc.txt".to_string(),
+        remote: setup.remote_file.clone(),
+    };
+    assert_eq!(actual.contains(&expected_diagnosis), expected);
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn symlink_at_remote_to_old_repo_returns_misdirected_symlink_warning() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    setup.make_local_file();
+    let old_repo = setup.parent_dir.join("OldRepo");
+    let old_target = old_repo.join("SomeApp/misc.txt");
+    create_dir_all(old_repo.join("SomeApp")).unwrap();
+    write(&old_target, "Old repo file contents").unwrap();
+    symlink_expose(&setup.remote_file, &old_target, false, true).unwrap();
+    remove_dir_all(&old_repo).unwrap();
+    setup.make_global_cfg_file(
+        default_repo_path_as_json(&old_repo.to_string_lossy()),
+    );
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::Link);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    let expected = Diagnosis::MisdirectedSymlink {
+        local: "SomeApp/misc.txt".to_string(),
+        remote: setup.remote_file.clone(),
+        target: old_target,
+        status: LinkStatus::OldRepo,
+    };
+    assert_eq!(actual, vec![expected.clone()]);
+    assert_eq!(expected.severity(), Severity::Warning);
+}
+
+#[test]
+fn symlink_at_remote_to_wrong_target_returns_misdirected_symlink_warning() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    setup.make_local_file();
+    setup.make_target_file();
+    symlink_expose(&setup.remote_file, &setup.target_file, false, true)
+        .unwrap();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::Link);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    assert_eq!(actual, vec![Diagnosis::MisdirectedSymlink {
+        local: "SomeApp/misc.txt".to_string(),
+        remote: setup.remote_file.clone(),
+        target: setup.target_file.clone(),
+        status: LinkStatus::WrongTarget,
+    }]);
+}
+
+#[rstest]
+#[case(TendrilMode::Hardlink, true)]
+#[case(TendrilMode::DirOverwrite, false)]
+fn separate_file_at_remote_is_broken_hardlink_only_for_hardlink_tendrils(
+    #[case] mode: TendrilMode,
+    #[case] expected: bool,
+) {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_local_file();
+    setup.make_remote_file();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", mode);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    let expected_diagnosis = Diagnosis::BrokenHardlink {
+        local: "SomeApp/misc.txt".to_string(),
+        remote: setup.remote_file.clone(),
+    };
+    assert_eq!(actual.contains(&expected_diagnosis), expected);
+    assert_eq!(expected_diagnosis.severity(), Severity::Warning);
+}
+
+#[test]
+fn hard_link_at_remote_is_not_reported() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_local_file();
+    setup.make_parent_dir();
+    std::fs::hard_link(&setup.local_file, &setup.remote_file).unwrap();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::Hardlink);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    assert_eq!(actual, vec![]);
+}
+
+/// `/dev/shm` is a separate in-memory file system on Linux
+#[cfg(target_os = "linux")]
+#[rstest]
+fn copy_on_other_file_system_is_broken_hardlink_unless_copy_fallback(
+    #[values(true, false)] copy_fallback: bool,
+) {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_local_file();
+    let other_fs_dir = tempdir::TempDir::new_in("/dev/shm", "Remote").unwrap();
+    let remote = other_fs_dir.path().join("misc.txt");
+    std::fs::copy(&setup.local_file, &remote).unwrap();
+    let mut t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::Hardlink);
+    t1.remote = remote.to_string_lossy().to_string();
+    t1.copy_fallback = copy_fallback;
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    let expected_diagnosis = Diagnosis::BrokenHardlink {
+        local: "SomeApp/misc.txt".to_string(),
+        remote,
+    };
+    assert_eq!(actual.contains(&expected_diagnosis), !copy_fallback);
+}
+
+#[test]
+fn valid_symlink_at_remote_is_not_reported() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    setup.make_local_file();
+    symlink_expose(&setup.remote_file, &setup.local_file, false, true).unwrap();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::Link);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    assert_eq!(actual, vec![]);
+}
+
+#[test]
+fn locals_differing_only_by_case_returns_warning() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc1.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "SomeApp/MISC.txt", "misc2.txt", TendrilMode::DirOverwrite);
+    let t3 = raw(&setup, "SomeApp/misc.txt", "misc3.txt", TendrilMode::DirOverwrite);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1, t2, t3], true);
+
+    let expected = Diagnosis::LocalCaseConflict {
+        first: "SomeApp/misc.txt".to_string(),
+        second: "SomeApp/MISC.txt".to_string(),
+    };
+    assert_eq!(actual, vec![expected.clone()]);
+    assert_eq!(expected.severity(), Severity::Warning);
+}
+
+#[test]
+fn remotes_differing_only_by_case_returns_warning() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let t2 = raw(&setup, "OtherApp/misc.txt", "Misc.txt", TendrilMode::DirOverwrite);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1, t2], true);
+
+    assert_eq!(
+        actual,
+        vec![Diagnosis::RemoteCaseConflict {
+            first_local: "SomeApp/misc.txt".to_string(),
+            second_local: "OtherApp/misc.txt".to_string(),
+            first_remote: setup.parent_dir.join("misc.txt"),
+            second_remote: setup.parent_dir.join("Misc.txt"),
+        }],
+    );
+}
+
+#[test]
+fn local_only_existing_with_different_case_returns_actual_name() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    create_dir_all(setup.td_repo.join("someapp")).unwrap();
+    write(setup.td_repo.join("someapp/Misc.txt"), "Local file contents").unwrap();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    assert_eq!(
+        actual,
+        vec![Diagnosis::LocalCaseMismatch {
+            local: "SomeApp/misc.txt".to_string(),
+            actual: PathBuf::from("someapp").join("Misc.txt"),
+        }],
+    );
+}
+
+#[test]
+fn local_missing_with_any_case_is_not_reported() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    assert_eq!(actual, vec![]);
+}
+
+#[test]
+#[cfg(unix)]
+#[cfg_attr(target_os = "linux", ignore)]
+fn remote_folder_without_write_access_returns_warning() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "misc.txt", TendrilMode::DirOverwrite);
+    let mut perms = std::fs::metadata(&setup.parent_dir).unwrap().permissions();
+    perms.set_mode(0o555);
+    std::fs::set_permissions(&setup.parent_dir, perms.clone()).unwrap();
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    perms.set_mode(0o755);
+    std::fs::set_permissions(&setup.parent_dir, perms).unwrap();
+    assert_eq!(
+        actual,
+        vec![Diagnosis::RemoteNotWritable {
+            local: "SomeApp/misc.txt".to_string(),
+            remote: setup.remote_file.clone(),
+            dir: setup.parent_dir.clone(),
+            kind: std::io::ErrorKind::PermissionDenied,
+        }],
+    );
+}
+
+#[test]
+fn missing_remote_parents_are_checked_at_nearest_existing_folder() {
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_parent_dir();
+    let t1 = raw(&setup, "SomeApp/misc.txt", "a/b/misc.txt", TendrilMode::DirOverwrite);
+
+    let actual = diagnose_repo(&setup.uni_td_repo(), &[t1], true);
+
+    assert_eq!(actual, vec![]);
+    assert!(!setup.parent_dir.join("a").exists());
+}
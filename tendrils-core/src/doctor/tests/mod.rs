@@ -0,0 +1 @@
+mod diagnose_repo_tests;
@@ -1,5 +1,10 @@
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use crate::checks::CheckFinding;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{self, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
 
 /// Indicates the tendril action to be performed.
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -18,6 +23,12 @@ pub enum ActionMode {
 
     /// Perform all outward bound actions (link & push)
     Out,
+
+    /// Replace the symlinks at the various locations on the computer that
+    /// do not point to the tendrils in the Tendrils repo (see
+    /// [`LinkStatus`]). Correct symlinks, and remotes that are not symlinks,
+    /// are left as-is.
+    Relink,
 }
 
 /// Indicates an error while initializing a new
@@ -108,7 +119,37 @@ pub enum GetConfigError {
     IoError { cfg_type: ConfigType, kind: std::io::ErrorKind },
 
     /// An error while parsing the json from the file.
-    ParseError { cfg_type: ConfigType, msg: String },
+    ParseError {
+        cfg_type: ConfigType,
+        msg: String,
+
+        /// Where in the file the error occurred, if known.
+        loc: Option<ParseErrorLocation>,
+    },
+
+    /// The same key is defined in the given `section` of two different
+    /// configuration files (i.e. in both `tendrils.json` and a file that it
+    /// includes).
+    DuplicateKey {
+        section: String,
+        key: String,
+        first: ConfigType,
+        second: ConfigType,
+    },
+
+    /// The Tendrils repo contains more than one repo-level configuration
+    /// file (i.e. both `tendrils.json` and `tendrils.toml`), so it is unclear
+    /// which one to use.
+    MultipleRepoConfigs { formats: Vec<ConfigFormat> },
+
+    /// The `version` of the configuration file is not supported, most
+    /// likely because it was written by a newer version of Tendrils.
+    /// `supported` is the latest version that is supported.
+    UnsupportedVersion { cfg_type: ConfigType, version: u32, supported: u32 },
+
+    /// The configuration file contains comments that would be removed by
+    /// rewriting it, which was not allowed.
+    CommentsWouldBeLost { cfg_type: ConfigType },
 }
 
 impl GetConfigError {
@@ -118,9 +159,17 @@ impl GetConfigError {
             GetConfigError::IoError { kind, .. } => {
                 GetConfigError::IoError { cfg_type, kind }
             }
-            GetConfigError::ParseError { msg, .. } => {
-                GetConfigError::ParseError { cfg_type, msg }
+            GetConfigError::ParseError { msg, loc, .. } => {
+                GetConfigError::ParseError { cfg_type, msg, loc }
+            }
+            GetConfigError::UnsupportedVersion { version, supported, .. } => {
+                GetConfigError::UnsupportedVersion { cfg_type, version, supported }
             }
+            GetConfigError::CommentsWouldBeLost { .. } => {
+                GetConfigError::CommentsWouldBeLost { cfg_type }
+            }
+            GetConfigError::DuplicateKey { .. }
+            | GetConfigError::MultipleRepoConfigs { .. } => self,
         }
     }
 }
@@ -132,48 +181,155 @@ impl ToString for GetConfigError {
                 "IO error while reading the {} file:\n{kind}",
                 cfg_type.file_name(),
             ),
-            GetConfigError::ParseError { cfg_type, msg } => format!(
+            GetConfigError::ParseError { cfg_type, msg, loc: None } => format!(
                 "Could not parse the {} file:\n{msg}",
                 cfg_type.file_name(),
             ),
+            GetConfigError::ParseError { cfg_type, msg, loc: Some(loc) } => {
+                let key_path = match loc.key_path.is_empty() {
+                    true => String::new(),
+                    false => format!(" in {}", loc.key_path),
+                };
+                format!(
+                    "Could not parse the {} file:\n{msg} at line {} column {}{key_path}",
+                    cfg_type.file_name(),
+                    loc.line,
+                    loc.column,
+                )
+            }
+            GetConfigError::DuplicateKey { section, key, first, second } => {
+                format!(
+                    "\"{key}\" is defined in the {section} of both the {} \
+                    file and the {} file",
+                    first.file_name(),
+                    second.file_name(),
+                )
+            }
+            GetConfigError::MultipleRepoConfigs { formats } => {
+                let files: Vec<String> = formats
+                    .iter()
+                    .map(|f| ConfigType::Repo(*f).file_name())
+                    .collect();
+                format!(
+                    "Found more than one config file in the Tendrils repo \
+                    ({}). Only one of these may exist",
+                    files.join(", "),
+                )
+            }
+            GetConfigError::UnsupportedVersion { cfg_type, version, supported }
+                if version > supported => format!(
+                "The {} file is version {version}, but this version of \
+                Tendrils only supports up to version {supported}. Update \
+                Tendrils to use this file",
+                cfg_type.file_name(),
+            ),
+            GetConfigError::UnsupportedVersion { cfg_type, version, supported } => {
+                format!(
+                    "The {} file is version {version}, which does not exist. \
+                    The supported versions are 1 to {supported}",
+                    cfg_type.file_name(),
+                )
+            }
+            GetConfigError::CommentsWouldBeLost { cfg_type } => format!(
+                "The {} file contains comments, which would be removed by \
+                rewriting it",
+                cfg_type.file_name(),
+            ),
         }
     }
 }
 
 impl From<std::io::Error> for GetConfigError {
     fn from(err: std::io::Error) -> Self {
-        GetConfigError::IoError { cfg_type: ConfigType::Repo, kind: err.kind() }
+        GetConfigError::IoError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            kind: err.kind(),
+        }
     }
 }
 
-impl From<serde_json::Error> for GetConfigError {
-    fn from(err: serde_json::Error) -> Self {
-        GetConfigError::ParseError {
-            cfg_type: ConfigType::Repo,
-            msg: err.to_string()
-        }
-    }
+/// The location of a [`GetConfigError::ParseError`] within the file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseErrorLocation {
+    /// The line number, starting from 1.
+    pub line: usize,
+
+    /// The character position within the [`Self::line`], starting from 1.
+    pub column: usize,
+
+    /// The path to the offending value, i.e.
+    /// `tendrils."SomeApp/file".remotes[2]`. This is empty if the error is
+    /// not within any object or list.
+    pub key_path: String,
+
+    /// The full text of the [`Self::line`].
+    pub line_text: String,
 }
 
 /// Indicates the type of configuration file.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum ConfigType {
-    /// The repo-level `tendrils.json` file
-    Repo,
+    /// The repo-level `tendrils.json`, `tendrils.jsonc` or `tendrils.toml`
+    /// file, depending on its format
+    Repo(ConfigFormat),
 
     /// The `global-config.json` file
     Global,
+
+    /// A file included by the repo-level `tendrils.json` file. The path is
+    /// relative to the `.tendrils` folder.
+    RepoInclude(PathBuf),
 }
 
 impl ConfigType {
-    fn file_name(&self) -> &str {
+    /// The name of the configuration file. For included files, this is the
+    /// path relative to the `.tendrils` folder.
+    pub fn file_name(&self) -> String {
         match self {
-            ConfigType::Repo => "tendrils.json",
-            ConfigType::Global => "global-config.json"
+            ConfigType::Repo(format) => format!("tendrils.{}", format.ext()),
+            ConfigType::Global => String::from("global-config.json"),
+            ConfigType::RepoInclude(path) => {
+                path.to_string_lossy().to_string()
+            }
         }
     }
 }
 
+/// The file format of a repo-level configuration file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConfigFormat {
+    /// Plain JSON (`.json`)
+    Json,
+
+    /// JSON that may also contain `//` and `/* */` comments, and trailing
+    /// commas (`.jsonc`)
+    Jsonc,
+
+    /// TOML (`.toml`)
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Each of the formats, in the order they are searched for.
+    pub const ALL: [ConfigFormat; 3] =
+        [ConfigFormat::Json, ConfigFormat::Jsonc, ConfigFormat::Toml];
+
+    /// The file extension used by this format, without the leading `.`.
+    pub fn ext(&self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "json",
+            ConfigFormat::Jsonc => "jsonc",
+            ConfigFormat::Toml => "toml",
+        }
+    }
+
+    /// The format matching the extension of the given `path`, if any.
+    pub fn from_path(path: &Path) -> Option<ConfigFormat> {
+        let ext = path.extension()?;
+        ConfigFormat::ALL.iter().copied().find(|f| ext == f.ext())
+    }
+}
+
 /// Indicates an error with the setup of a Tendrils repo.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub enum SetupError {
@@ -183,6 +339,14 @@ pub enum SetupError {
     ConfigError(GetConfigError),
     /// No valid Tendrils repo was found.
     NoValidTendrilsRepo(GetTendrilsRepoError),
+    /// An entry in the profiles filter is not a valid profile expression.
+    InvalidProfileFilter { filter: String, msg: String },
+    /// Tendrils from two different Tendrils repos share the same remote path.
+    RemoteConflict { remote: PathBuf, first_repo: PathBuf, second_repo: PathBuf },
+    /// Two tendrils in the same Tendrils repo conflict in a way that makes the
+    /// outcome of the actions depend on their order. Contains the first
+    /// [`Severity::Error`](crate::Severity::Error) finding.
+    TendrilConflict(Box<CheckFinding>),
 }
 
 impl ToString for SetupError {
@@ -198,6 +362,25 @@ impl ToString for SetupError {
             ),
             SetupError::ConfigError(err) => err.to_string(),
             SetupError::NoValidTendrilsRepo(err) => err.to_string(),
+            SetupError::InvalidProfileFilter { filter, msg } => {
+                format!("Invalid profile filter \"{filter}\":\n{msg}")
+            }
+            SetupError::RemoteConflict { remote, first_repo, second_repo } => {
+                format!(
+                    "The remote \"{}\" is used by tendrils in more than one \
+                    Tendrils repo:\n    {}\n    {}",
+                    remote.to_string_lossy(),
+                    first_repo.to_string_lossy(),
+                    second_repo.to_string_lossy(),
+                )
+            }
+            SetupError::TendrilConflict(finding) => {
+                format!(
+                    "Conflicting tendrils:\n    {}\nRun the check command \
+                    for a list of all conflicts",
+                    finding.to_string(),
+                )
+            }
         }
     }
 }
@@ -236,6 +419,34 @@ pub enum TendrilActionSuccess {
     /// An action that was expected to succeed in overwriting a file system
     /// object at the destination but was skipped due to a dry-run.
     OverwriteSkipped,
+
+    /// A link action where the destination was already a symlink to the
+    /// source, so it was left as-is.
+    AlreadyLinked,
+
+    /// A successful hardlink action where the source was copied to the
+    /// destination instead, as the two are on different file systems (see
+    /// [`RawTendril::copy_fallback`](crate::RawTendril::copy_fallback)).
+    CopiedInsteadOfLinked,
+
+    /// A successful link action that replaced a symlink at the destination
+    /// that did not point to the source.
+    Relinked {
+        /// Where the replaced symlink pointed.
+        old_target: PathBuf,
+        /// Why the replaced symlink was wrong.
+        status: LinkStatus,
+    },
+
+    /// A link action that was expected to succeed in replacing a symlink at
+    /// the destination that did not point to the source, but was skipped
+    /// due to a dry-run.
+    RelinkSkipped {
+        /// Where the symlink points.
+        old_target: PathBuf,
+        /// Why the symlink is wrong.
+        status: LinkStatus,
+    },
 }
 
 impl ToString for TendrilActionSuccess {
@@ -247,6 +458,62 @@ impl ToString for TendrilActionSuccess {
             TendrilActionSuccess::OverwriteSkipped => {
                 String::from("Skipped overwrite")
             }
+            TendrilActionSuccess::AlreadyLinked => String::from("Already linked"),
+            TendrilActionSuccess::CopiedInsteadOfLinked => {
+                String::from("Copied instead of linked")
+            }
+            TendrilActionSuccess::Relinked { old_target, status } => format!(
+                "Relinked from {}",
+                status.describe_target(old_target),
+            ),
+            TendrilActionSuccess::RelinkSkipped { old_target, status } => {
+                format!(
+                    "Skipped relink from {}",
+                    status.describe_target(old_target),
+                )
+            }
+        }
+    }
+}
+
+/// Indicates where an existing symlink at the remote of a link tendril
+/// points.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LinkStatus {
+    /// The symlink points to the local in the Tendrils repo, either with an
+    /// absolute or a relative path.
+    Linked,
+
+    /// The symlink points to the same local in a different Tendrils repo,
+    /// i.e. where the Tendrils repo used to be before it was moved. That
+    /// repo must either still exist or be one of the default repos in the
+    /// `global-config.json` file, otherwise the symlink is
+    /// [`Broken`](LinkStatus::Broken).
+    OldRepo,
+
+    /// The symlink points to an existing file system object other than the
+    /// local.
+    WrongTarget,
+
+    /// The symlink points to something other than the local that does not
+    /// exist.
+    Broken,
+}
+
+impl LinkStatus {
+    /// Describes the `target` of a symlink with this status, i.e.
+    /// `the old repo location "/old/repo/file"`.
+    fn describe_target(&self, target: &Path) -> String {
+        let target = target.to_string_lossy();
+        match self {
+            LinkStatus::Linked => format!("\"{target}\""),
+            LinkStatus::OldRepo => {
+                format!("the old repo location \"{target}\"")
+            }
+            LinkStatus::WrongTarget => format!("the wrong target \"{target}\""),
+            LinkStatus::Broken => {
+                format!("the missing target \"{target}\"")
+            }
         }
     }
 }
@@ -264,6 +531,18 @@ pub enum TendrilActionError {
         loc: Location,
     },
 
+    /// The tendril's reflink mode is [`ReflinkMode::Always`], but a file
+    /// could not be reflinked because the file system does not support it.
+    ReflinkUnsupported,
+
+    /// A symlink inside of a folder tendril leads back to one of the folders
+    /// that contain it, so following it would never end. Only applies if the
+    /// tendril's nested symlinks are [followed](NestedSymlinks::Follow).
+    SymlinkLoop {
+        /// The symlink that leads back to the folder that contains it
+        symlink: PathBuf,
+    },
+
     /// The tendril mode does not match the attempted action, such as:
     /// - Attempting to pull a link-type tendril
     /// - Attempting to link a copy-type tendril
@@ -296,7 +575,7 @@ impl From<std::io::ErrorKind> for TendrilActionError {
 
 impl ToString for TendrilActionError {
     fn to_string(&self) -> String {
-        use std::io::ErrorKind::NotFound;
+        use std::io::ErrorKind::{CrossesDevices, NotFound};
         use FsoType::{Dir, File, SymDir, SymFile, BrokenSym};
         use Location::{Dest, Source, Unknown};
         match self {
@@ -309,6 +588,9 @@ impl ToString for TendrilActionError {
             TendrilActionError::IoError { kind: NotFound, loc: Unknown } => {
                 String::from("Not found")
             }
+            TendrilActionError::IoError { kind: CrossesDevices, .. } => {
+                String::from("Cannot hard link across file systems")
+            }
             TendrilActionError::IoError { kind: e_kind, loc: Source } => {
                 format!("{:?} error at source", e_kind)
             }
@@ -318,6 +600,13 @@ impl ToString for TendrilActionError {
             TendrilActionError::IoError { kind: e_kind, loc: Unknown } => {
                 format!("{:?} error", e_kind)
             }
+            TendrilActionError::ReflinkUnsupported => {
+                String::from("Reflink copy not supported")
+            }
+            TendrilActionError::SymlinkLoop { symlink } => format!(
+                "Symlink loop at \"{}\"",
+                symlink.to_string_lossy(),
+            ),
             TendrilActionError::ModeMismatch => {
                 String::from("Wrong tendril type")
             }
@@ -405,6 +694,19 @@ pub enum TendrilMode {
     /// Create a symlink at the remote location that points to local
     /// file/folder.
     Link,
+
+    /// Create a hard link at the remote location to the local file. This
+    /// only applies to file tendrils, and requires the remote to be on the
+    /// same file system as the Tendrils repo.
+    Hardlink,
+}
+
+impl TendrilMode {
+    /// Returns `true` if tendrils with this mode are link-type, i.e. they
+    /// are linked rather than copied back and forth.
+    pub fn is_link_type(&self) -> bool {
+        matches!(self, TendrilMode::Link | TendrilMode::Hardlink)
+    }
 }
 
 impl ToString for TendrilMode {
@@ -413,6 +715,65 @@ impl ToString for TendrilMode {
             TendrilMode::DirMerge => String::from("Directory merge"),
             TendrilMode::DirOverwrite => String::from("Directory overwrite"),
             TendrilMode::Link => String::from("Link"),
+            TendrilMode::Hardlink => String::from("Hard link"),
+        }
+    }
+}
+
+/// Indicates how symlinks inside of a folder tendril are handled when it is
+/// copied. The folder itself is handled as usual.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum NestedSymlinks {
+    /// Copy the symlinks themselves, pointing to the same targets.
+    #[serde(rename = "preserve")]
+    Preserve,
+
+    /// Copy the contents of the symlinks' targets in their place.
+    #[default]
+    #[serde(rename = "follow")]
+    Follow,
+
+    /// Leave the symlinks out of the copy.
+    #[serde(rename = "skip")]
+    Skip,
+}
+
+/// Indicates whether files are copied as reflinks (copy-on-write clones),
+/// which share their data with the source until either is modified. This
+/// is only supported on some file systems (i.e. Btrfs, XFS, APFS and ReFS).
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub enum ReflinkMode {
+    /// Reflink each file where supported, otherwise fall back to a normal
+    /// copy.
+    #[serde(rename = "auto")]
+    Auto,
+
+    /// Reflink each file, and fail if this is not supported.
+    #[serde(rename = "always")]
+    Always,
+
+    /// Always use a normal copy.
+    #[default]
+    #[serde(rename = "never")]
+    Never,
+}
+
+/// Indicates how a file system object was copied.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CopyMethod {
+    /// The contents were copied in full.
+    Copy,
+
+    /// The contents were reflinked (see [`ReflinkMode`]). For folders, this
+    /// means that every file within was reflinked.
+    Reflink,
+}
+
+impl ToString for CopyMethod {
+    fn to_string(&self) -> String {
+        match self {
+            CopyMethod::Copy => String::from("copy"),
+            CopyMethod::Reflink => String::from("reflink"),
         }
     }
 }
@@ -427,9 +788,42 @@ pub enum InvalidTendrilError {
     /// - A folder tendril is an ancestor to the Tendrils repo
     /// - A tendril is inside the Tendrils repo
     Recursion,
+
+    /// The tendril remote contains an environment variable that could not
+    /// be resolved, either because it is unset or because it is required
+    /// (`${NAME:?message}`) and empty.
+    UnresolvedVariable {
+        name: String,
+
+        /// The message given in a required variable, if any.
+        msg: Option<String>,
+    },
+
+    /// The tendril remote starts with `~username`, but the user cannot be
+    /// found in the system user database (or is on Windows, where this is
+    /// not supported).
+    UnknownUser { username: String },
+}
+
+impl ToString for InvalidTendrilError {
+    fn to_string(&self) -> String {
+        match self {
+            InvalidTendrilError::InvalidLocal => String::from("InvalidLocal"),
+            InvalidTendrilError::Recursion => String::from("Recursion"),
+            InvalidTendrilError::UnresolvedVariable { name, msg: None } => {
+                format!("Unresolved variable \"{name}\"")
+            }
+            InvalidTendrilError::UnresolvedVariable { name, msg: Some(msg) } => {
+                format!("Unresolved variable \"{name}\" - {msg}")
+            }
+            InvalidTendrilError::UnknownUser { username } => {
+                format!("Unknown user \"{username}\"")
+            }
+        }
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Debug, Serialize, JsonSchema, Eq, PartialEq)]
 #[serde(untagged)]
 pub(crate) enum OneOrMany<T> {
     // https://github.com/Mingun/ksc-rs/blob/8532f701e660b07b6d2c74963fdc0490be4fae4b/src/parser.rs#L29pub
@@ -440,6 +834,67 @@ pub(crate) enum OneOrMany<T> {
     Vec(Vec<T>),
 }
 
+/// Deserializes a list as [`OneOrMany::Vec`], and anything else as
+/// [`OneOrMany::One`]. Unlike an untagged enum, any errors within the value
+/// are reported as-is, at their own location.
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for OneOrMany<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(OneOrManyVisitor(PhantomData))
+    }
+}
+
+struct OneOrManyVisitor<T>(PhantomData<T>);
+
+impl<T> OneOrManyVisitor<T> {
+    fn one<'de, D>(deserializer: D) -> Result<OneOrMany<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(OneOrMany::One)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for OneOrManyVisitor<T> {
+    type Value = OneOrMany<T>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a value or a list of values")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        Vec::<T>::deserialize(SeqAccessDeserializer::new(seq)).map(OneOrMany::Vec)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        Self::one(MapAccessDeserializer::new(map))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Self::one(v.into_deserializer())
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Self::one(v.into_deserializer())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Self::one(v.into_deserializer())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Self::one(v.into_deserializer())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Self::one(v.into_deserializer())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Self::one(().into_deserializer())
+    }
+}
+
 impl<T> From<OneOrMany<T>> for Vec<T> {
     fn from(from: OneOrMany<T>) -> Self {
         match from {
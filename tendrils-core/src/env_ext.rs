@@ -1,4 +1,8 @@
-pub(crate) fn get_home_dir() -> Option<std::ffi::OsString> {
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub(crate) fn get_home_dir() -> Option<OsString> {
     use std::env::var_os;
     if let Some(v) = var_os("HOME") {
         return Some(v);
@@ -12,6 +16,179 @@ pub(crate) fn get_home_dir() -> Option<std::ffi::OsString> {
     }
 }
 
+/// Returns the home directory of the given user as listed in the system user
+/// database (see `getpwnam_r`), or `None` if the user cannot be found or has
+/// no home directory. Always returns `None` on Windows.
+pub(crate) fn get_user_home_dir(username: &[u8]) -> Option<OsString> {
+    #[cfg(unix)]
+    {
+        use std::ffi::{CStr, CString};
+        use std::os::unix::ffi::OsStringExt;
+
+        /// The largest buffer given to `getpwnam_r` before giving up.
+        const MAX_BUF_LEN: usize = 1 << 20;
+
+        let c_username = CString::new(username).ok()?;
+        // SAFETY: `sysconf` has no preconditions.
+        let mut buf_len = match unsafe { libc::sysconf(libc::_SC_GETPW_R_SIZE_MAX) } {
+            n if n > 0 => n as usize,
+            _ => 1024,
+        };
+
+        loop {
+            let mut buf: Vec<libc::c_char> = vec![0; buf_len];
+            // SAFETY: `passwd` is plain data that is only read if the lookup
+            // succeeds, in which case it has been filled in.
+            let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+            let mut result: *mut libc::passwd = std::ptr::null_mut();
+
+            // SAFETY: `c_username` is a valid nul-terminated string, and
+            // `buf` is valid for `buf.len()` bytes and outlives `passwd`.
+            let code = unsafe {
+                libc::getpwnam_r(
+                    c_username.as_ptr(),
+                    &mut passwd,
+                    buf.as_mut_ptr(),
+                    buf.len(),
+                    &mut result,
+                )
+            };
+            if code == libc::ERANGE && buf_len < MAX_BUF_LEN {
+                buf_len *= 2;
+                continue;
+            }
+            if code != 0 || result.is_null() || passwd.pw_dir.is_null() {
+                return None;
+            }
+
+            // SAFETY: `pw_dir` is a nul-terminated string within `buf`.
+            let home = unsafe { CStr::from_ptr(passwd.pw_dir) }.to_bytes();
+            return match home.is_empty() {
+                true => None,
+                false => Some(OsString::from_vec(home.to_vec())),
+            };
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = username;
+        None
+    }
+}
+
+/// The XDG base directory environment variables that can be used in paths
+/// even if they are not set, along with their default paths relative to the
+/// home directory.
+const XDG_BASE_DIRS: [(&str, &str); 4] = [
+    ("XDG_CONFIG_HOME", ".config"),
+    ("XDG_DATA_HOME", ".local/share"),
+    ("XDG_STATE_HOME", ".local/state"),
+    ("XDG_CACHE_HOME", ".cache"),
+];
+
+/// Returns the default value of the given XDG base directory variable (such
+/// as `XDG_CONFIG_HOME`) as defined by the XDG Base Directory Specification.
+/// Returns `None` if the variable is not one of the XDG base directories or
+/// if the home directory cannot be determined.
+pub(crate) fn get_xdg_default(var: &OsStr) -> Option<OsString> {
+    let (_, default_subdir) = XDG_BASE_DIRS.iter().find(|(n, _)| *n == var)?;
+    let home_dir = get_home_dir()?;
+
+    Some(PathBuf::from(home_dir).join(default_subdir).into_os_string())
+}
+
+/// Returns the given XDG base directory (such as `XDG_CONFIG_HOME`). The
+/// environment variable is used if it is set to an absolute path, otherwise
+/// its [default](get_xdg_default) is used.
+pub(crate) fn get_xdg_dir(var: &str) -> Option<PathBuf> {
+    match std::env::var_os(var) {
+        Some(v) if Path::new(&v).is_absolute() => Some(PathBuf::from(v)),
+        _ => get_xdg_default(OsStr::new(var)).map(PathBuf::from),
+    }
+}
+
+/// Facts about the current host that automatic profile rules are evaluated
+/// against.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct HostFacts {
+    /// `None` if the host name cannot be determined.
+    pub hostname: Option<String>,
+
+    /// The operating system as given by [`std::env::consts::OS`].
+    pub os: String,
+
+    /// The OS family as given by [`std::env::consts::FAMILY`].
+    pub family: String,
+
+    /// The CPU architecture as given by [`std::env::consts::ARCH`].
+    pub arch: String,
+
+    /// `None` if the user name cannot be determined.
+    pub username: Option<String>,
+}
+
+impl HostFacts {
+    pub fn current() -> HostFacts {
+        HostFacts {
+            hostname: get_hostname(),
+            os: String::from(std::env::consts::OS),
+            family: String::from(std::env::consts::FAMILY),
+            arch: String::from(std::env::consts::ARCH),
+            username: get_username(),
+        }
+    }
+}
+
+/// Returns the name of the current host (see [`lookup_hostname`]). This may
+/// need to run the `hostname` command, so it is only looked up once per
+/// process.
+fn get_hostname() -> Option<String> {
+    static HOSTNAME: OnceLock<Option<String>> = OnceLock::new();
+    HOSTNAME.get_or_init(lookup_hostname).clone()
+}
+
+/// Returns the name of the current host, checking the `COMPUTERNAME` and
+/// `HOSTNAME` variables, then `/proc/sys/kernel/hostname`, and finally falling
+/// back to the output of the `hostname` command.
+fn lookup_hostname() -> Option<String> {
+    for var in ["COMPUTERNAME", "HOSTNAME"] {
+        if let Ok(v) = std::env::var(var) {
+            if !v.trim().is_empty() {
+                return Some(v.trim().to_string());
+            }
+        }
+    }
+
+    if let Ok(v) = std::fs::read_to_string("/proc/sys/kernel/hostname") {
+        if !v.trim().is_empty() {
+            return Some(v.trim().to_string());
+        }
+    }
+
+    match std::process::Command::new("hostname").output() {
+        Ok(o) if o.status.success() => {
+            let v = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            if v.is_empty() {
+                None
+            }
+            else {
+                Some(v)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name of the current user from the `USER`, `LOGNAME`, or
+/// `USERNAME` variables.
+fn get_username() -> Option<String> {
+    ["USER", "LOGNAME", "USERNAME"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.is_empty())
+}
+
 /// Returns `true` if the current Tendrils process is capable
 /// of creating symlinks.
 ///
@@ -1,8 +1,28 @@
 use std::vec;
 
-use crate::{ActionMode, RawTendril, TendrilMode};
+use crate::{
+    ActionMode,
+    ConfigFormat,
+    FsoType,
+    RawTendril,
+    SetupError,
+    TendrilMode,
+};
 use crate::config::LazyCachedGlobalConfig;
+use crate::env_ext::HostFacts;
+use crate::path_ext::{PathExt, UniPath};
+use crate::profiles::{
+    active_profiles,
+    eval_auto_profiles,
+    expand_groups,
+    is_filter_profile_expr,
+    is_tendril_profile_expr,
+    AutoProfiles,
+    ProfileExpr,
+    ProfileGroups,
+};
 use glob_match::glob_match;
+use std::path::Path;
 
 #[cfg(test)]
 mod tests;
@@ -17,20 +37,56 @@ pub struct FilterSpec {
     pub mode: Option<ActionMode>,
 
     /// Matches only those tendrils whose local matches any of the given
-    /// locals. Glob patterns are supported.
+    /// locals. Glob patterns are supported. Entries starting with `!` are
+    /// treated as exclusions (see [`FilterSpec::exclude_locals`]).
     pub locals: Vec<String>,
 
     /// Matches only those tendril remotes that match any of the given remotes.
     /// Any tendril remotes that do not match are omitted, and any tendrils
-    /// without any matching remotes are omitted entirely. Glob patterns
-    /// are supported.
+    /// without any matching remotes are omitted entirely. A tendril with
+    /// [fallbacks](RawTendril::fallbacks) matches if any of its alternatives
+    /// match. Glob patterns are supported. Entries starting with `!` are
+    /// treated as exclusions (see [`FilterSpec::exclude_remotes`]).
     pub remotes: Vec<String>,
 
     /// Matches only those tendrils that match any of the given profiles, and
     /// those that belong to all profiles (i.e. those that do not have any
     /// profiles defined). Glob patterns
-    /// are supported.
+    /// are supported. Each entry may also be a profile expression combining
+    /// globs with `&`, `|`, `!` and parentheses (e.g. `work & (linux | mac)`),
+    /// in which case it is evaluated against all of a tendril's profiles.
+    /// Entries that are a single glob starting with `!` are treated as
+    /// exclusions (see [`FilterSpec::exclude_profiles`]).
+    /// If `None`, the `default-profiles` from the global config
+    /// are used, along with any `auto-profiles` whose rules match the current
+    /// host.
     pub profiles: Option<Vec<String>>,
+
+    /// Omits any tendrils whose local matches any of the given locals. Glob
+    /// patterns are supported.
+    pub exclude_locals: Vec<String>,
+
+    /// Omits any tendrils whose remote, or any of its
+    /// [fallbacks](RawTendril::fallbacks), matches any of the given remotes.
+    /// Glob patterns are supported.
+    pub exclude_remotes: Vec<String>,
+
+    /// Omits any tendrils that match any of the given profiles. Tendrils that
+    /// do not have any profiles defined are never omitted. Glob patterns and
+    /// profile expressions are supported. This is applied regardless of
+    /// whether the [`FilterSpec::profiles`] are `None`.
+    pub exclude_profiles: Vec<String>,
+
+    /// Matches only those tendrils whose [`TendrilMode`] is any of the given
+    /// modes. If empty, all modes will match.
+    pub tendril_modes: Vec<TendrilMode>,
+
+    /// Matches only those tendrils whose local is any of the given
+    /// [`FsoType`]s. If the local does not exist, the type of the first
+    /// existing remote is used instead. Tendrils where neither exist are
+    /// omitted. If empty, all tendrils will match regardless of whether they
+    /// exist.
+    pub fso_types: Vec<FsoType>,
 }
 
 impl FilterSpec {
@@ -40,37 +96,91 @@ impl FilterSpec {
             locals: vec![],
             remotes: vec![],
             profiles: None,
+            exclude_locals: vec![],
+            exclude_remotes: vec![],
+            exclude_profiles: vec![],
+            tendril_modes: vec![],
+            fso_types: vec![],
         }
     }
 }
 
 /// Filters a list of given tendrils according to the given [`FilterSpec`].
 /// The filters are cumulative (i.e. the tendril must match all filters to
-/// be included in the final result).
+/// be included in the final result). The `repo_auto_profiles` are only
+/// evaluated if the filter does not explicitly set the profiles. The `td_repo`
+/// is only used to determine the type of each local when filtering by
+/// [`FsoType`]. Returns [`SetupError::InvalidProfileFilter`] if any of the
+/// profiles are an invalid expression.
 pub(crate) fn filter_tendrils(
     tendrils: Vec<RawTendril>,
     filter: FilterSpec,
+    td_repo: &UniPath,
     global_cfg: &mut LazyCachedGlobalConfig,
-) -> Vec<RawTendril> {
+    repo_auto_profiles: &AutoProfiles,
+    profile_groups: &ProfileGroups,
+) -> Result<Vec<RawTendril>, SetupError> {
     let mut filtered = match filter.mode {
         Some(v) => filter_by_mode(tendrils.to_vec(), v),
         None => tendrils.to_vec(),
     };
+    filtered = filter_by_tendril_modes(filtered, &filter.tendril_modes);
 
     let profiles;
     if let Some(f) = filter.profiles {
         profiles = f;
     }
     else {
-        profiles = match global_cfg.eval() {
-            Ok(cfg) => cfg.default_profiles.unwrap_or_default(),
-            _ => vec![],
-        };
+        profiles = default_profiles(global_cfg, repo_auto_profiles);
     }
 
-    filtered = filter_by_profiles(filtered, &profiles);
+    filtered = filter_by_profiles(filtered, &profiles, profile_groups)?;
+    filtered = exclude_by_profiles(
+        filtered,
+        &filter.exclude_profiles,
+        profile_groups,
+    )?;
     filtered = filter_by_locals(filtered, &filter.locals);
-    filter_by_remotes(filtered, &filter.remotes)
+    filtered = exclude_by_locals(filtered, &filter.exclude_locals);
+    filtered = filter_by_remotes(filtered, &filter.remotes);
+    filtered = exclude_by_remotes(filtered, &filter.exclude_remotes);
+    Ok(filter_by_fso_types(filtered, td_repo, &filter.fso_types))
+}
+
+/// Returns the `default-profiles` from the global config combined with any
+/// `auto-profiles` (from either the global or repo config) that are active on
+/// the current host.
+fn default_profiles(
+    global_cfg: &mut LazyCachedGlobalConfig,
+    repo_auto_profiles: &AutoProfiles,
+) -> Vec<String> {
+    let (mut profiles, global_auto_profiles) = match global_cfg.eval() {
+        Ok(cfg) => (
+            cfg.default_profiles.unwrap_or_default(),
+            cfg.auto_profiles.unwrap_or_default(),
+        ),
+        _ => (vec![], AutoProfiles::new()),
+    };
+
+    if global_auto_profiles.is_empty() && repo_auto_profiles.is_empty() {
+        return profiles;
+    }
+
+    // Only the names of the active profiles are needed, so the format of the
+    // repo config file that they came from is irrelevant
+    let logs = eval_auto_profiles(
+        &global_auto_profiles,
+        repo_auto_profiles,
+        ConfigFormat::Json,
+        &HostFacts::current(),
+    );
+    for p in active_profiles(&logs) {
+        if !profiles.contains(&p) {
+            profiles.push(p);
+        }
+    }
+
+    profiles
 }
 
 fn filter_by_mode(
@@ -84,33 +194,193 @@ fn filter_by_mode(
     tendrils
         .into_iter()
         .filter(|t| match (&t.mode, &mode) {
-            (TendrilMode::Link, ActionMode::Link) => true,
-            (TendrilMode::Link, _) => false,
-            (_, ActionMode::Link) => false,
+            (TendrilMode::Link, ActionMode::Link | ActionMode::Relink) => true,
+            (TendrilMode::Hardlink, ActionMode::Link) => true,
+            (TendrilMode::Link | TendrilMode::Hardlink, _) => false,
+            (_, ActionMode::Link | ActionMode::Relink) => false,
             (_, _) => true,
         })
         .collect()
 }
 
-fn filter_by_profiles(
+fn filter_by_tendril_modes(
     tendrils: Vec<RawTendril>,
-    profiles: &[String],
+    modes: &[TendrilMode],
 ) -> Vec<RawTendril> {
-    if profiles.is_empty() {
+    if modes.is_empty() {
         return tendrils;
     }
 
     tendrils
+        .into_iter()
+        .filter(|t| modes.contains(&t.mode))
+        .collect()
+}
+
+fn filter_by_fso_types(
+    tendrils: Vec<RawTendril>,
+    td_repo: &UniPath,
+    types: &[FsoType],
+) -> Vec<RawTendril> {
+    if types.is_empty() {
+        return tendrils;
+    }
+
+    tendrils
+        .into_iter()
+        .filter(|t| match tendril_fso_type(t, td_repo) {
+            Some(fso_type) => types.contains(&fso_type),
+            None => false,
+        })
+        .collect()
+}
+
+/// Returns the type of the tendril's local, or the type of its first
+/// existing remote if the local does not exist.
+fn tendril_fso_type(tendril: &RawTendril, td_repo: &UniPath) -> Option<FsoType> {
+    let local = td_repo.inner().join_raw(Path::new(&tendril.local));
+    if let Some(fso_type) = local.get_type() {
+        return Some(fso_type);
+    }
+
+    std::iter::once(&tendril.remote)
+        .chain(tendril.fallbacks.iter())
+        .find_map(|r| UniPath::from(Path::new(r)).inner().get_type())
+}
+
+/// Splits the given filters into those that include, and those that
+/// exclude (i.e. those with a leading `!`, which is removed).
+fn split_negated(filters: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut includes = vec![];
+    let mut excludes = vec![];
+    for f in filters {
+        match f.strip_prefix('!') {
+            Some(negated) => excludes.push(negated.to_string()),
+            None => includes.push(f.clone()),
+        }
+    }
+
+    (includes, excludes)
+}
+
+fn filter_by_profiles(
+    tendrils: Vec<RawTendril>,
+    profiles: &[String],
+    groups: &ProfileGroups,
+) -> Result<Vec<RawTendril>, SetupError> {
+    if profiles.is_empty() {
+        return Ok(tendrils);
+    }
+
+    let (exprs, globs): (Vec<String>, Vec<String>) = profiles
+        .iter()
+        .cloned()
+        .partition(|p| is_filter_profile_expr(p));
+    let (mut includes, excludes) = split_negated(&globs);
+    includes.extend(exprs);
+
+    let filtered = if includes.is_empty() {
+        tendrils
+    }
+    else {
+        let filters = parse_profile_filters(&includes)?;
+
+        tendrils
+            .into_iter()
+            .filter(|t| -> bool {
+                t.profiles.is_empty()
+                    || tendril_matches_profiles(&t.profiles, &filters, groups)
+            })
+            .collect()
+    };
+
+    exclude_by_profiles(filtered, &excludes, groups)
+}
+
+fn exclude_by_profiles(
+    tendrils: Vec<RawTendril>,
+    excludes: &[String],
+    groups: &ProfileGroups,
+) -> Result<Vec<RawTendril>, SetupError> {
+    if excludes.is_empty() {
+        return Ok(tendrils);
+    }
+
+    let filters = parse_profile_filters(excludes)?;
+
+    Ok(tendrils
         .into_iter()
         .filter(|t| -> bool {
             t.profiles.is_empty()
-                || t.profiles
-                    .iter()
-                    .any(|p| profiles.iter().any(|f| glob_match(f, p)))
+                || !tendril_matches_profiles(&t.profiles, &filters, groups)
+        })
+        .collect())
+}
+
+/// Parses each entry in the profiles filter. Entries that are not
+/// [expressions](is_filter_profile_expr) are treated as a single glob
+/// pattern.
+fn parse_profile_filters(
+    profiles: &[String],
+) -> Result<Vec<ProfileExpr>, SetupError> {
+    profiles
+        .iter()
+        .map(|f| {
+            if is_filter_profile_expr(f) {
+                ProfileExpr::parse(f).map_err(|msg| {
+                    SetupError::InvalidProfileFilter { filter: f.clone(), msg }
+                })
+            }
+            else {
+                Ok(ProfileExpr::Atom(f.clone()))
+            }
         })
         .collect()
 }
 
+/// Returns `true` if the tendril's profiles are selected by the `filters`.
+/// Its profile names are matched as a set against each filter, while each of
+/// its profile expressions is evaluated against the profiles that the filters
+/// select.
+fn tendril_matches_profiles(
+    tendril_profiles: &[String],
+    filters: &[ProfileExpr],
+    groups: &ProfileGroups,
+) -> bool {
+    let (exprs, names): (Vec<&String>, Vec<&String>) = tendril_profiles
+        .iter()
+        .partition(|p| is_tendril_profile_expr(p));
+    let names: Vec<String> = names.into_iter().cloned().collect();
+
+    if !names.is_empty() && filters_select(filters, &names, groups) {
+        return true;
+    }
+
+    exprs.into_iter().any(|e| match ProfileExpr::parse(e) {
+        Ok(expr) => expr.eval(&|atom| {
+            filters_select(filters, &[atom.to_string()], groups)
+        }),
+        // Invalid expressions are already rejected when parsing the config
+        Err(_) => false,
+    })
+}
+
+/// Returns `true` if any of the `filters` is true for the given set of
+/// `profiles`.
+fn filters_select(
+    filters: &[ProfileExpr],
+    profiles: &[String],
+    groups: &ProfileGroups,
+) -> bool {
+    let profiles = expand_groups(profiles, groups);
+
+    filters.iter().any(|f| f.eval(&|atom| {
+        expand_groups(&[atom.to_string()], groups)
+            .iter()
+            .any(|a| profiles.iter().any(|p| glob_match(a, p)))
+    }))
+}
+
 fn filter_by_locals(
     tendrils: Vec<RawTendril>,
     locals: &[String],
@@ -119,12 +389,34 @@ fn filter_by_locals(
         return tendrils;
     }
 
+    let (includes, excludes) = split_negated(locals);
+    let filtered = if includes.is_empty() {
+        tendrils
+    }
+    else {
+        tendrils
+            .into_iter()
+            .filter(|t| local_matches_any(t, &includes))
+            .collect()
+    };
+
+    exclude_by_locals(filtered, &excludes)
+}
+
+fn exclude_by_locals(
+    tendrils: Vec<RawTendril>,
+    excludes: &[String],
+) -> Vec<RawTendril> {
     tendrils
         .into_iter()
-        .filter(|t| locals.iter().any(|f| glob_match(f, &t.local)))
+        .filter(|t| !local_matches_any(t, excludes))
         .collect()
 }
 
+fn local_matches_any(tendril: &RawTendril, filters: &[String]) -> bool {
+    filters.iter().any(|f| glob_match(f, &tendril.local))
+}
+
 fn filter_by_remotes(
     tendrils: Vec<RawTendril>,
     remotes: &[String],
@@ -133,8 +425,35 @@ fn filter_by_remotes(
         return tendrils;
     }
 
+    let (includes, excludes) = split_negated(remotes);
+    let filtered = if includes.is_empty() {
+        tendrils
+    }
+    else {
+        tendrils
+            .into_iter()
+            .filter(|t| remote_matches_any(t, &includes))
+            .collect()
+    };
+
+    exclude_by_remotes(filtered, &excludes)
+}
+
+fn exclude_by_remotes(
+    tendrils: Vec<RawTendril>,
+    excludes: &[String],
+) -> Vec<RawTendril> {
     tendrils
         .into_iter()
-        .filter(|t| remotes.iter().any(|f| glob_match(f, &t.remote)))
+        .filter(|t| !remote_matches_any(t, excludes))
         .collect()
 }
+
+/// Returns `true` if the tendril's remote or any of its fallbacks match any
+/// of the `filters`.
+fn remote_matches_any(tendril: &RawTendril, filters: &[String]) -> bool {
+    filters.iter().any(|f| {
+        glob_match(f, &tendril.remote)
+            || tendril.fallbacks.iter().any(|r| glob_match(f, r))
+    })
+}
@@ -0,0 +1,123 @@
+use crate::filtering::filter_by_fso_types;
+use crate::path_ext::UniPath;
+use crate::test_utils::{symlink_expose, Setup};
+use crate::{FsoType, RawTendril};
+use rstest::rstest;
+
+fn file_and_dir_tendrils(setup: &Setup) -> Vec<RawTendril> {
+    let mut t1 = RawTendril::new("SomeApp/misc.txt");
+    t1.remote = setup.remote_file.to_string_lossy().to_string();
+    let mut t2 = RawTendril::new("SomeApp/misc");
+    t2.remote = setup.remote_dir.to_string_lossy().to_string();
+    vec![t1, t2]
+}
+
+#[test]
+fn empty_filters_list_returns_all_tendrils_even_if_missing() {
+    let setup = Setup::new();
+    let tendrils = file_and_dir_tendrils(&setup);
+    let td_repo = UniPath::from(&setup.td_repo);
+
+    let actual = filter_by_fso_types(tendrils.clone(), &td_repo, &[]);
+
+    assert_eq!(actual, tendrils);
+}
+
+#[rstest]
+#[case(FsoType::File, 0)]
+#[case(FsoType::Dir, 1)]
+fn matches_type_of_local(#[case] fso_type: FsoType, #[case] exp_idx: usize) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_local_dir();
+    let tendrils = file_and_dir_tendrils(&setup);
+    let td_repo = UniPath::from(&setup.td_repo);
+
+    let actual = filter_by_fso_types(tendrils.clone(), &td_repo, &[fso_type]);
+
+    assert_eq!(actual, vec![tendrils[exp_idx].clone()]);
+}
+
+#[test]
+fn local_type_takes_precedence_over_remote_type() {
+    let setup = Setup::new();
+    setup.make_local_file();
+    std::fs::create_dir_all(&setup.remote_file).unwrap();
+    let tendrils = vec![file_and_dir_tendrils(&setup)[0].clone()];
+    let td_repo = UniPath::from(&setup.td_repo);
+
+    let actual = filter_by_fso_types(tendrils.clone(), &td_repo, &[FsoType::Dir]);
+
+    assert!(actual.is_empty());
+}
+
+#[rstest]
+#[case(FsoType::File, 0)]
+#[case(FsoType::Dir, 1)]
+fn local_does_not_exist_matches_type_of_remote(
+    #[case] fso_type: FsoType,
+    #[case] exp_idx: usize,
+) {
+    let setup = Setup::new();
+    setup.make_remote_file();
+    setup.make_remote_dir();
+    let tendrils = file_and_dir_tendrils(&setup);
+    let td_repo = UniPath::from(&setup.td_repo);
+
+    let actual = filter_by_fso_types(tendrils.clone(), &td_repo, &[fso_type]);
+
+    assert_eq!(actual, vec![tendrils[exp_idx].clone()]);
+}
+
+#[test]
+fn local_and_remote_do_not_exist_matches_type_of_first_existing_fallback() {
+    let setup = Setup::new();
+    setup.make_target_dir();
+    let mut tendrils = file_and_dir_tendrils(&setup);
+    tendrils[0].fallbacks = vec![
+        setup.parent_dir.join("I_Do_Not_Exist").to_string_lossy().to_string(),
+        setup.target_dir.to_string_lossy().to_string(),
+    ];
+    let td_repo = UniPath::from(&setup.td_repo);
+
+    let actual = filter_by_fso_types(tendrils.clone(), &td_repo, &[FsoType::Dir]);
+
+    assert_eq!(actual, vec![tendrils[0].clone()]);
+}
+
+#[test]
+fn local_and_remotes_do_not_exist_excludes_tendril() {
+    let setup = Setup::new();
+    let tendrils = file_and_dir_tendrils(&setup);
+    let td_repo = UniPath::from(&setup.td_repo);
+    let all_types = [
+        FsoType::File,
+        FsoType::Dir,
+        FsoType::SymFile,
+        FsoType::SymDir,
+        FsoType::BrokenSym,
+    ];
+
+    let actual = filter_by_fso_types(tendrils, &td_repo, &all_types);
+
+    assert!(actual.is_empty());
+}
+
+#[test]
+fn symlinks_match_only_symlink_types() {
+    let setup = Setup::new();
+    setup.make_target_file();
+    setup.make_group_dir();
+    symlink_expose(&setup.local_file, &setup.target_file, false, false)
+        .unwrap();
+    let tendrils = vec![file_and_dir_tendrils(&setup)[0].clone()];
+    let td_repo = UniPath::from(&setup.td_repo);
+
+    let file_actual =
+        filter_by_fso_types(tendrils.clone(), &td_repo, &[FsoType::File]);
+    let symfile_actual =
+        filter_by_fso_types(tendrils.clone(), &td_repo, &[FsoType::SymFile]);
+
+    assert!(file_actual.is_empty());
+    assert_eq!(symfile_actual, tendrils);
+}
@@ -1,4 +1,4 @@
-use crate::filtering::filter_by_locals;
+use crate::filtering::{exclude_by_locals, filter_by_locals};
 use crate::filtering::tests::filter_tendrils_tests::{
     string_filter_empty_tests,
     string_filter_match_tests,
@@ -111,3 +111,56 @@ fn empty_filters_list_returns_all_tendrils() {
 
     assert_eq!(actual, tendrils);
 }
+
+#[rstest]
+#[case(&["!v1".to_string()], &["v2"])]
+#[case(&["!v2".to_string()], &["v1"])]
+#[case(&["!v3".to_string()], &["v1", "v2"])]
+#[case(&["v*".to_string(), "!v1".to_string()], &["v2"])]
+#[case(&["!v1".to_string(), "v*".to_string()], &["v2"])]
+#[case(&["v1".to_string(), "!v1".to_string()], &[])]
+#[case(&["!v1".to_string(), "!v2".to_string()], &[])]
+#[case(&["!*".to_string()], &[])]
+fn negated_local_excludes_matching_tendrils(
+    #[case] filters: &[String],
+    #[case] exp_matches: &[&str],
+) {
+    let t1 = RawTendril::new("v1");
+    let t2 = RawTendril::new("v2");
+    let tendrils = vec![t1.clone(), t2.clone()];
+
+    let actual = filter_by_locals(tendrils, filters);
+
+    let expected = match exp_matches {
+        [] => vec![],
+        ["v1"] => vec![t1],
+        ["v2"] => vec![t2],
+        ["v1", "v2"] => vec![t1, t2],
+        _ => panic!(),
+    };
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn exclude_by_locals_omits_tendrils_that_match_any() {
+    let t1 = RawTendril::new("v1");
+    let t2 = RawTendril::new("v2");
+    let t3 = RawTendril::new("v3");
+    let tendrils = vec![t1.clone(), t2.clone(), t3.clone()];
+    let excludes = ["v1".to_string(), "*3".to_string()];
+
+    let actual = exclude_by_locals(tendrils, &excludes);
+
+    assert_eq!(actual, vec![t2]);
+}
+
+#[test]
+fn exclude_by_locals_empty_excludes_returns_all_tendrils() {
+    let t1 = RawTendril::new("v1");
+    let t2 = RawTendril::new("v2");
+    let tendrils = vec![t1.clone(), t2.clone()];
+
+    let actual = exclude_by_locals(tendrils.clone(), &[]);
+
+    assert_eq!(actual, tendrils);
+}
@@ -4,6 +4,7 @@ use rstest::rstest;
 
 #[rstest]
 #[case(ActionMode::Link)]
+#[case(ActionMode::Relink)]
 #[case(ActionMode::Push)]
 #[case(ActionMode::Pull)]
 #[case(ActionMode::Out)]
@@ -15,8 +16,12 @@ fn empty_tendril_list_returns_empty(#[case] action_mode: ActionMode) {
     assert!(actual.is_empty())
 }
 
-#[test]
-fn link_action_only_includes_tendrils_with_link_true() {
+#[rstest]
+#[case(ActionMode::Link)]
+#[case(ActionMode::Relink)]
+fn link_action_only_includes_tendrils_with_link_true(
+    #[case] action_mode: ActionMode,
+) {
     let mut t1 = RawTendril::new("SomeLocal");
     t1.mode = TendrilMode::DirMerge;
     let mut t2 = RawTendril::new("SomeLocal");
@@ -25,7 +30,7 @@ fn link_action_only_includes_tendrils_with_link_true() {
     t3.mode = TendrilMode::DirOverwrite;
     let tendrils = vec![t1.clone(), t2.clone(), t3.clone()];
 
-    let actual = filter_by_mode(tendrils, ActionMode::Link);
+    let actual = filter_by_mode(tendrils, action_mode);
 
     assert_eq!(actual, vec![t2]);
 }
@@ -63,3 +68,21 @@ fn out_action_includes_all() {
 
     assert_eq!(actual, vec![t1, t2, t3]);
 }
+
+#[rstest]
+#[case(ActionMode::Link, true)]
+#[case(ActionMode::Relink, false)]
+#[case(ActionMode::Push, false)]
+#[case(ActionMode::Pull, false)]
+#[case(ActionMode::Out, true)]
+fn hardlink_tendrils_only_included_in_link_and_out_actions(
+    #[case] action_mode: ActionMode,
+    #[case] expected: bool,
+) {
+    let mut t1 = RawTendril::new("SomeLocal");
+    t1.mode = TendrilMode::Hardlink;
+
+    let actual = filter_by_mode(vec![t1.clone()], action_mode);
+
+    assert_eq!(actual.contains(&t1), expected);
+}
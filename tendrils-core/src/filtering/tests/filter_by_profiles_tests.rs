@@ -1,4 +1,4 @@
-use crate::filtering::filter_by_profiles;
+use crate::filtering::{exclude_by_profiles, filter_by_profiles};
 use crate::filtering::tests::filter_tendrils_tests::{
     string_filter_empty_tests,
     string_filter_match_tests,
@@ -6,7 +6,8 @@ use crate::filtering::tests::filter_tendrils_tests::{
     supported_asterisk_literals,
     supported_weird_values,
 };
-use crate::RawTendril;
+use crate::profiles::ProfileGroups;
+use crate::{RawTendril, SetupError};
 use rstest::rstest;
 use rstest_reuse::{self, apply};
 
@@ -14,7 +15,8 @@ use rstest_reuse::{self, apply};
 fn empty_tendril_list_returns_empty(#[case] filters: &[String]) {
     let tendrils = vec![];
 
-    let actual = filter_by_profiles(tendrils, filters);
+    let actual =
+        filter_by_profiles(tendrils, filters, &ProfileGroups::new()).unwrap();
 
     assert!(actual.is_empty())
 }
@@ -30,7 +32,8 @@ fn tendril_with_empty_profiles_list_included_in_all(
     assert!(t2.profiles.is_empty());
     let tendrils = vec![t1.clone(), t2.clone()];
 
-    let actual = filter_by_profiles(tendrils, filters);
+    let actual =
+        filter_by_profiles(tendrils, filters, &ProfileGroups::new()).unwrap();
 
     assert_eq!(actual, vec![t1, t2]);
 }
@@ -44,7 +47,8 @@ fn tendril_only_included_if_any_profile_matches(
     t1.profiles = vec!["v1".to_string(), "v2".to_string()];
     let tendrils = vec![t1.clone()];
 
-    let actual = filter_by_profiles(tendrils, filters);
+    let actual =
+        filter_by_profiles(tendrils, filters, &ProfileGroups::new()).unwrap();
 
     assert_eq!(actual, vec![t1.clone()]);
     // Check that at least one of the expected profile
@@ -63,7 +67,8 @@ fn tendril_not_included_if_not_empty_and_no_profile_matches(
     assert!(t2.profiles.is_empty());
     let tendrils = vec![t1.clone(), t2.clone()];
 
-    let actual = filter_by_profiles(tendrils, filters);
+    let actual =
+        filter_by_profiles(tendrils, filters, &ProfileGroups::new()).unwrap();
 
     assert_eq!(actual, vec![t2]);
 }
@@ -77,7 +82,8 @@ fn duplicate_filter_profiles_only_returns_tendril_once() {
     let tendrils = vec![t1.clone(), t2.clone()];
     let filters = ["P1".to_string(), "P1".to_string(), "P1".to_string()];
 
-    let actual = filter_by_profiles(tendrils, &filters);
+    let actual = filter_by_profiles(tendrils, &filters, &ProfileGroups::new())
+        .unwrap();
 
     assert_eq!(actual, vec![t1]);
 }
@@ -91,7 +97,8 @@ fn duplicate_tendril_profiles_only_returns_tendril_once() {
     let tendrils = vec![t1.clone(), t2.clone()];
     let filters = ["P1".to_string()];
 
-    let actual = filter_by_profiles(tendrils, &filters);
+    let actual = filter_by_profiles(tendrils, &filters, &ProfileGroups::new())
+        .unwrap();
 
     assert_eq!(actual, vec![t1]);
 }
@@ -105,7 +112,8 @@ fn duplicate_tendrils_returns_all_instances() {
     let tendrils = vec![t1.clone(), t1.clone(), t1.clone(), t2.clone()];
     let filters = ["P1".to_string()];
 
-    let actual = filter_by_profiles(tendrils, &filters);
+    let actual = filter_by_profiles(tendrils, &filters, &ProfileGroups::new())
+        .unwrap();
 
     assert_eq!(actual, vec![t1.clone(), t1.clone(), t1]);
 }
@@ -119,7 +127,8 @@ fn filter_supports_weird_profiles(#[case] profile: String) {
     let tendrils = vec![t1.clone(), t2.clone()];
 
     let filter = profile.replace('\\', "\\\\");
-    let actual = filter_by_profiles(tendrils, &[filter]);
+    let actual = filter_by_profiles(tendrils, &[filter], &ProfileGroups::new())
+        .unwrap();
 
     assert_eq!(actual, vec![t1]);
 }
@@ -135,7 +144,8 @@ fn filter_supports_asterisk_literals(
     t2.profiles = vec!["P2".to_string()];
     let tendrils = vec![t1.clone(), t2.clone()];
 
-    let actual = filter_by_profiles(tendrils, &[filter]);
+    let actual = filter_by_profiles(tendrils, &[filter], &ProfileGroups::new())
+        .unwrap();
 
     assert_eq!(actual, vec![t1]);
 }
@@ -148,7 +158,267 @@ fn empty_filters_list_returns_all_tendrils() {
     t2.profiles = vec![];
     let tendrils = vec![t1.clone(), t2.clone()];
 
-    let actual = filter_by_profiles(tendrils, &[]);
+    let actual =
+        filter_by_profiles(tendrils, &[], &ProfileGroups::new()).unwrap();
 
     assert_eq!(actual, vec![t1, t2]);
 }
+
+fn tendril_w_profiles(profiles: &[&str]) -> RawTendril {
+    let mut tendril = RawTendril::new("SomeLocal");
+    tendril.profiles = profiles.iter().map(|p| p.to_string()).collect();
+    tendril
+}
+
+#[rstest]
+#[case("p1 & p2", &["p1", "p2"], true)]
+#[case("p1 & p2", &["p1"], false)]
+#[case("p1 & p2", &["p1", "p3"], false)]
+#[case("p1 | p2", &["p2"], true)]
+#[case("p1 | p2", &["p3"], false)]
+#[case("(!p1)", &["p2"], true)]
+#[case("(!p1)", &["p1", "p2"], false)]
+#[case("p* & !p3", &["p1"], true)]
+#[case("p* & !p3", &["p1", "p3"], false)]
+#[case("(p1 | p2) & !p3", &["p2", "p4"], true)]
+fn filter_expression_is_evaluated_against_all_tendril_profiles(
+    #[case] filter: &str,
+    #[case] profiles: &[&str],
+    #[case] exp_match: bool,
+) {
+    let t1 = tendril_w_profiles(profiles);
+
+    let actual = filter_by_profiles(
+        vec![t1.clone()],
+        &[filter.to_string()],
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual.contains(&t1), exp_match);
+}
+
+#[test]
+fn filter_expressions_and_globs_can_be_mixed() {
+    let t1 = tendril_w_profiles(&["p1", "p2"]);
+    let t2 = tendril_w_profiles(&["p1"]);
+    let t3 = tendril_w_profiles(&["p3"]);
+    let tendrils = vec![t1.clone(), t2.clone(), t3.clone()];
+    let filters = ["p1 & p2".to_string(), "p3".to_string()];
+
+    let actual = filter_by_profiles(tendrils, &filters, &ProfileGroups::new())
+        .unwrap();
+
+    assert_eq!(actual, vec![t1, t3]);
+}
+
+#[rstest]
+#[case("p1 &")]
+#[case("(p1")]
+#[case("p1 p2)")]
+fn invalid_filter_expression_returns_error(#[case] filter: &str) {
+    let tendrils = vec![tendril_w_profiles(&["p1"])];
+
+    let actual = filter_by_profiles(
+        tendrils,
+        &[filter.to_string()],
+        &ProfileGroups::new(),
+    );
+
+    match actual {
+        Err(SetupError::InvalidProfileFilter { filter: f, msg }) => {
+            assert_eq!(f, filter);
+            assert!(!msg.is_empty());
+        }
+        _ => panic!("Expected an invalid filter error, got {:?}", actual),
+    }
+}
+
+#[test]
+fn invalid_filter_expression_returns_error_even_if_no_tendrils() {
+    let actual = filter_by_profiles(
+        vec![],
+        &["p1 &".to_string()],
+        &ProfileGroups::new(),
+    );
+
+    assert!(actual.is_err());
+}
+
+#[rstest]
+#[case("p1 & p2", &["p1", "p2"], true)]
+#[case("p1 & p2", &["p1"], false)]
+#[case("p1 | p2", &["p2"], true)]
+#[case("!p1", &["p2"], true)]
+#[case("!p1", &["p1"], false)]
+#[case("!p1", &["p1", "p2"], false)]
+#[case("p1 & !p2", &["p1"], true)]
+#[case("p1 & !p2", &["p1", "p2"], false)]
+#[case("p*", &["p1"], false)]
+fn tendril_expression_is_evaluated_against_filtered_profiles(
+    #[case] tendril_expr: &str,
+    #[case] filters: &[&str],
+    #[case] exp_match: bool,
+) {
+    let t1 = tendril_w_profiles(&[tendril_expr]);
+    let filters: Vec<String> = filters.iter().map(|f| f.to_string()).collect();
+
+    let actual = filter_by_profiles(
+        vec![t1.clone()],
+        &filters,
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual.contains(&t1), exp_match);
+}
+
+#[test]
+fn tendril_matches_if_any_name_or_expression_matches() {
+    let t1 = tendril_w_profiles(&["p1", "p2 & p3"]);
+    let filters_name = ["p1".to_string()];
+    let filters_expr = ["p2".to_string(), "p3".to_string()];
+    let filters_none = ["p2".to_string()];
+
+    let actual_name = filter_by_profiles(
+        vec![t1.clone()],
+        &filters_name,
+        &ProfileGroups::new(),
+    ).unwrap();
+    let actual_expr = filter_by_profiles(
+        vec![t1.clone()],
+        &filters_expr,
+        &ProfileGroups::new(),
+    ).unwrap();
+    let actual_none = filter_by_profiles(
+        vec![t1.clone()],
+        &filters_none,
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual_name, vec![t1.clone()]);
+    assert_eq!(actual_expr, vec![t1]);
+    assert!(actual_none.is_empty());
+}
+
+fn groups() -> ProfileGroups {
+    let mut groups = ProfileGroups::new();
+    let desktop = vec!["home-pc".to_string(), "work-pc".to_string()];
+    groups.insert("desktop".to_string(), desktop);
+    groups
+}
+
+#[rstest]
+#[case("desktop", &["home-pc"], true)]
+#[case("desktop", &["work-pc"], true)]
+#[case("desktop", &["desktop"], true)]
+#[case("desktop", &["laptop"], false)]
+#[case("home-pc", &["desktop"], true)]
+#[case("laptop", &["desktop"], false)]
+#[case("desktop & !work-pc", &["home-pc"], true)]
+#[case("desktop & !work-pc", &["work-pc"], false)]
+fn groups_are_expanded_in_filters_and_tendril_profiles(
+    #[case] filter: &str,
+    #[case] profiles: &[&str],
+    #[case] exp_match: bool,
+) {
+    let t1 = tendril_w_profiles(profiles);
+
+    let actual = filter_by_profiles(
+        vec![t1.clone()],
+        &[filter.to_string()],
+        &groups(),
+    ).unwrap();
+
+    assert_eq!(actual.contains(&t1), exp_match);
+}
+
+#[rstest]
+#[case(&["home-pc"], true)]
+#[case(&["desktop"], true)]
+#[case(&["laptop"], false)]
+fn groups_are_expanded_in_tendril_expressions(
+    #[case] filters: &[&str],
+    #[case] exp_match: bool,
+) {
+    let t1 = tendril_w_profiles(&["desktop & !laptop"]);
+    let filters: Vec<String> = filters.iter().map(|f| f.to_string()).collect();
+
+    let actual = filter_by_profiles(vec![t1.clone()], &filters, &groups())
+        .unwrap();
+
+    assert_eq!(actual.contains(&t1), exp_match);
+}
+
+#[rstest]
+#[case(&["!v1"], &["v1", "v2"], false)]
+#[case(&["!v1"], &["v2"], true)]
+#[case(&["!v*"], &["v2"], false)]
+#[case(&["v2", "!v1"], &["v2"], true)]
+#[case(&["v2", "!v1"], &["v1", "v2"], false)]
+#[case(&["v3", "!v1"], &["v2"], false)]
+#[case(&["!v1", "!v2"], &["v3"], true)]
+#[case(&["v1 | v2", "!v2"], &["v1", "v2"], false)]
+fn negated_profile_excludes_tendrils_with_any_matching_profile(
+    #[case] filters: &[&str],
+    #[case] profiles: &[&str],
+    #[case] exp_match: bool,
+) {
+    let t1 = tendril_w_profiles(profiles);
+    let filters: Vec<String> = filters.iter().map(|f| f.to_string()).collect();
+
+    let actual = filter_by_profiles(
+        vec![t1.clone()],
+        &filters,
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual.contains(&t1), exp_match);
+}
+
+#[test]
+fn negated_profile_does_not_exclude_tendrils_without_profiles() {
+    let t1 = RawTendril::new("SomeLocal");
+    assert!(t1.profiles.is_empty());
+
+    let actual = filter_by_profiles(
+        vec![t1.clone()],
+        &["!*".to_string()],
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual, vec![t1]);
+}
+
+#[rstest]
+#[case("v1", &["v1", "v2"], false)]
+#[case("v1", &["v2"], true)]
+#[case("v1 & v2", &["v1", "v2"], false)]
+#[case("v1 & v2", &["v1"], true)]
+#[case("desktop", &["home-pc"], false)]
+fn exclude_by_profiles_omits_tendrils_that_match(
+    #[case] exclude: &str,
+    #[case] profiles: &[&str],
+    #[case] exp_match: bool,
+) {
+    let t1 = tendril_w_profiles(profiles);
+    let t2 = RawTendril::new("SomeLocal");
+
+    let actual = exclude_by_profiles(
+        vec![t1.clone(), t2.clone()],
+        &[exclude.to_string()],
+        &groups(),
+    ).unwrap();
+
+    assert_eq!(actual.contains(&t1), exp_match);
+    assert!(actual.contains(&t2));
+}
+
+#[test]
+fn exclude_by_profiles_invalid_expression_returns_error() {
+    let actual = exclude_by_profiles(
+        vec![],
+        &["p1 &".to_string()],
+        &ProfileGroups::new(),
+    );
+
+    assert!(actual.is_err());
+}
@@ -1,4 +1,4 @@
-use crate::filtering::filter_by_remotes;
+use crate::filtering::{exclude_by_remotes, filter_by_remotes};
 use crate::filtering::tests::filter_tendrils_tests::{
     string_filter_empty_tests,
     string_filter_match_tests,
@@ -126,3 +126,83 @@ fn empty_filters_list_returns_all_tendrils() {
 
     assert_eq!(actual, tendrils);
 }
+
+#[test]
+fn tendril_included_if_any_fallback_matches() {
+    let mut t1 = RawTendril::new("SomeLocal");
+    let mut t2 = RawTendril::new("SomeLocal");
+    t1.remote = "r1".to_string();
+    t1.fallbacks = vec!["r1_alt1".to_string(), "r1_alt2".to_string()];
+    t2.remote = "r2".to_string();
+    t2.fallbacks = vec!["r2_alt1".to_string()];
+    let tendrils = vec![t1.clone(), t2.clone()];
+    let filters = ["*_alt2".to_string()];
+
+    let actual = filter_by_remotes(tendrils, &filters);
+
+    assert_eq!(actual, vec![t1]);
+}
+
+#[rstest]
+#[case(&["!v1".to_string()], &["v2"])]
+#[case(&["!v2".to_string()], &["v1"])]
+#[case(&["!v3".to_string()], &["v1", "v2"])]
+#[case(&["v*".to_string(), "!v1".to_string()], &["v2"])]
+#[case(&["!v1".to_string(), "v*".to_string()], &["v2"])]
+#[case(&["v1".to_string(), "!v1".to_string()], &[])]
+#[case(&["!v1".to_string(), "!v2".to_string()], &[])]
+#[case(&["!*".to_string()], &[])]
+fn negated_remote_excludes_matching_tendrils(
+    #[case] filters: &[String],
+    #[case] exp_matches: &[&str],
+) {
+    let mut t1 = RawTendril::new("SomeLocal");
+    let mut t2 = RawTendril::new("SomeLocal");
+    t1.remote = "v1".to_string();
+    t2.remote = "v2".to_string();
+    let tendrils = vec![t1.clone(), t2.clone()];
+
+    let actual = filter_by_remotes(tendrils, filters);
+
+    let expected = match exp_matches {
+        [] => vec![],
+        ["v1"] => vec![t1],
+        ["v2"] => vec![t2],
+        ["v1", "v2"] => vec![t1, t2],
+        _ => panic!(),
+    };
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn negated_remote_excludes_tendril_if_any_fallback_matches() {
+    let mut t1 = RawTendril::new("SomeLocal");
+    let mut t2 = RawTendril::new("SomeLocal");
+    t1.remote = "r1".to_string();
+    t1.fallbacks = vec!["r1_alt1".to_string(), "r1_alt2".to_string()];
+    t2.remote = "r2".to_string();
+    t2.fallbacks = vec!["r2_alt1".to_string()];
+    let tendrils = vec![t1.clone(), t2.clone()];
+    let filters = ["!*_alt2".to_string()];
+
+    let actual = filter_by_remotes(tendrils, &filters);
+
+    assert_eq!(actual, vec![t2]);
+}
+
+#[test]
+fn exclude_by_remotes_omits_tendrils_if_remote_or_any_fallback_matches() {
+    let mut t1 = RawTendril::new("SomeLocal");
+    let mut t2 = RawTendril::new("SomeLocal");
+    let mut t3 = RawTendril::new("SomeLocal");
+    t1.remote = "r1".to_string();
+    t2.remote = "r2".to_string();
+    t2.fallbacks = vec!["r2_alt1".to_string()];
+    t3.remote = "r3".to_string();
+    let tendrils = vec![t1.clone(), t2.clone(), t3.clone()];
+    let excludes = ["r1".to_string(), "*_alt1".to_string()];
+
+    let actual = exclude_by_remotes(tendrils, &excludes);
+
+    assert_eq!(actual, vec![t3]);
+}
@@ -0,0 +1,56 @@
+use crate::filtering::filter_by_tendril_modes;
+use crate::{RawTendril, TendrilMode};
+use rstest::rstest;
+
+fn samples() -> Vec<RawTendril> {
+    let mut t1 = RawTendril::new("SomeLocal");
+    t1.mode = TendrilMode::DirMerge;
+    let mut t2 = RawTendril::new("SomeLocal");
+    t2.mode = TendrilMode::Link;
+    let mut t3 = RawTendril::new("SomeLocal");
+    t3.mode = TendrilMode::DirOverwrite;
+    vec![t1, t2, t3]
+}
+
+#[test]
+fn empty_tendril_list_returns_empty() {
+    let tendrils = vec![];
+
+    let actual = filter_by_tendril_modes(tendrils, &[TendrilMode::Link]);
+
+    assert!(actual.is_empty())
+}
+
+#[test]
+fn empty_filters_list_returns_all_tendrils() {
+    let tendrils = samples();
+
+    let actual = filter_by_tendril_modes(tendrils.clone(), &[]);
+
+    assert_eq!(actual, tendrils);
+}
+
+#[rstest]
+#[case(TendrilMode::DirMerge, 0)]
+#[case(TendrilMode::Link, 1)]
+#[case(TendrilMode::DirOverwrite, 2)]
+fn only_includes_tendrils_with_given_mode(
+    #[case] mode: TendrilMode,
+    #[case] exp_idx: usize,
+) {
+    let tendrils = samples();
+
+    let actual = filter_by_tendril_modes(tendrils.clone(), &[mode]);
+
+    assert_eq!(actual, vec![tendrils[exp_idx].clone()]);
+}
+
+#[test]
+fn includes_tendrils_with_any_given_mode() {
+    let tendrils = samples();
+    let modes = [TendrilMode::DirOverwrite, TendrilMode::DirMerge];
+
+    let actual = filter_by_tendril_modes(tendrils.clone(), &modes);
+
+    assert_eq!(actual, vec![tendrils[0].clone(), tendrils[2].clone()]);
+}
@@ -1,7 +1,11 @@
 use crate::{ActionMode, RawTendril, TendrilMode};
-use crate::config::LazyCachedGlobalConfig;
+use crate::config::{GlobalConfig, LazyCachedGlobalConfig};
+use crate::enums::OneOrMany;
 use crate::filtering::{filter_tendrils, FilterSpec};
+use crate::path_ext::UniPath;
+use crate::profiles::{AutoProfiles, ProfileGroups, ProfileRule};
 use rstest_reuse::{self, template};
+use std::path::Path;
 
 #[template]
 #[rstest]
@@ -31,7 +35,6 @@ fn string_filter_empty_tests(#[case] filters: &[String]) {}
 #[case(&["??".to_string()], &["v1", "v2"])]
 #[case(&["*1".to_string()], &["v1"])]
 #[case(&["?1".to_string()], &["v1"])]
-#[case(&["!v1".to_string()], &["v2"])]
 #[case(&["v[12]".to_string()], &["v1", "v2"])]
 #[case(&["v{!1,2}".to_string()], &["v2"])]
 fn string_filter_match_tests(
@@ -109,10 +112,22 @@ fn empty_tendril_list_returns_empty() {
         locals: vec![],
         remotes: vec![],
         profiles: None,
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        tendril_modes: vec![],
+        fso_types: vec![],
     };
     let mut cfg = LazyCachedGlobalConfig::mock_w_parse_err();
 
-    let actual = filter_tendrils(tendrils, filter, &mut cfg);
+    let actual = filter_tendrils(
+        tendrils,
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &AutoProfiles::new(),
+        &ProfileGroups::new(),
+    ).unwrap();
 
     assert!(actual.is_empty())
 }
@@ -125,10 +140,22 @@ fn mode_filter_is_none_does_not_filter_by_mode() {
         locals: vec!["l0".to_string(), "l1".to_string(), "l2".to_string()],
         remotes: vec!["r0".to_string(), "r1".to_string(), "r2".to_string()],
         profiles: Some(vec!["p1".to_string(), "p2".to_string()]),
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        tendril_modes: vec![],
+        fso_types: vec![],
     };
     let mut cfg = LazyCachedGlobalConfig::mock_w_parse_err();
 
-    let actual = filter_tendrils(tendrils.clone(), filter, &mut cfg);
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &AutoProfiles::new(),
+        &ProfileGroups::new(),
+    ).unwrap();
 
     assert_eq!(actual, vec![
         tendrils[0].clone(), // Copy type
@@ -145,10 +172,22 @@ fn locals_filter_is_empty_does_not_filter_by_locals() {
         locals: vec![],
         remotes: vec!["r0".to_string(), "r1".to_string(), "r3".to_string()],
         profiles: Some(vec!["p1".to_string(), "p3".to_string()]),
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        tendril_modes: vec![],
+        fso_types: vec![],
     };
     let mut cfg = LazyCachedGlobalConfig::mock_w_parse_err();
 
-    let actual = filter_tendrils(tendrils.clone(), filter, &mut cfg);
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &AutoProfiles::new(),
+        &ProfileGroups::new(),
+    ).unwrap();
 
     assert_eq!(actual, vec![
         tendrils[0].clone(),
@@ -165,10 +204,22 @@ fn parent_filter_is_empty_does_not_filter_by_parent() {
         locals: vec!["l0".to_string(), "l1".to_string(), "l3".to_string()],
         remotes: vec![],
         profiles: Some(vec!["p1".to_string(), "p3".to_string()]),
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        tendril_modes: vec![],
+        fso_types: vec![],
     };
     let mut cfg = LazyCachedGlobalConfig::mock_w_parse_err();
 
-    let actual = filter_tendrils(tendrils.clone(), filter, &mut cfg);
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &AutoProfiles::new(),
+        &ProfileGroups::new(),
+    ).unwrap();
 
     assert_eq!(actual, vec![
         tendrils[0].clone(),
@@ -185,10 +236,22 @@ fn profile_filter_is_empty_does_not_filter_by_profile() {
         locals: vec!["l0".to_string(), "l1".to_string(), "l3".to_string()],
         remotes: vec!["r0".to_string(), "r1".to_string(), "r3".to_string()],
         profiles: Some(vec![]),
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        tendril_modes: vec![],
+        fso_types: vec![],
     };
     let mut cfg = LazyCachedGlobalConfig::mock_w_parse_err();
 
-    let actual = filter_tendrils(tendrils.clone(), filter, &mut cfg);
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &AutoProfiles::new(),
+        &ProfileGroups::new(),
+    ).unwrap();
 
     assert_eq!(actual, vec![
         tendrils[0].clone(),
@@ -223,10 +286,132 @@ fn all_filters_are_cumulative() {
             "p3".to_string(),
             "p5".to_string(),
         ]), // Eliminates t4
+        exclude_locals: vec![],
+        exclude_remotes: vec![],
+        exclude_profiles: vec![],
+        tendril_modes: vec![],
+        fso_types: vec![],
     };
     let mut cfg = LazyCachedGlobalConfig::mock_w_parse_err();
 
-    let actual = filter_tendrils(tendrils.clone(), filter, &mut cfg);
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &AutoProfiles::new(),
+        &ProfileGroups::new(),
+    ).unwrap();
 
     assert_eq!(actual, vec![tendrils[3].clone()]);
 }
+
+fn global_cfg_w_profiles() -> GlobalConfig {
+    let mut auto_profiles = AutoProfiles::new();
+    let always = OneOrMany::One(ProfileRule::default());
+    auto_profiles.insert("p2".to_string(), always);
+    auto_profiles.insert("p3".to_string(), OneOrMany::One(ProfileRule {
+        os: Some("NonExistentOs".to_string()),
+        ..Default::default()
+    }));
+
+    GlobalConfig {
+        schema: None,
+        version: None,
+        default_repo_path: None,
+        default_profiles: Some(vec!["p1".to_string()]),
+        auto_profiles: Some(auto_profiles),
+    }
+}
+
+#[test]
+fn profile_filter_is_none_uses_default_and_active_auto_profiles() {
+    let tendrils = samples();
+    let filter = FilterSpec::new();
+    let mut cfg = LazyCachedGlobalConfig::mock(global_cfg_w_profiles());
+    let mut repo_auto_profiles = AutoProfiles::new();
+    let always = OneOrMany::One(ProfileRule::default());
+    repo_auto_profiles.insert("p4".to_string(), always);
+
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &repo_auto_profiles,
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual, vec![
+        tendrils[0].clone(),
+        tendrils[1].clone(), // Default profile
+        tendrils[2].clone(), // Global auto profile
+        tendrils[4].clone(), // Repo auto profile
+    ]);
+}
+
+#[test]
+fn profile_filter_is_given_ignores_default_and_auto_profiles() {
+    let tendrils = samples();
+    let mut filter = FilterSpec::new();
+    filter.profiles = Some(vec!["p5".to_string()]);
+    let mut cfg = LazyCachedGlobalConfig::mock(global_cfg_w_profiles());
+    let mut repo_auto_profiles = AutoProfiles::new();
+    let always = OneOrMany::One(ProfileRule::default());
+    repo_auto_profiles.insert("p4".to_string(), always);
+
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &repo_auto_profiles,
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual, vec![tendrils[0].clone(), tendrils[5].clone()]);
+}
+
+#[test]
+fn exclude_filters_are_cumulative_with_include_filters() {
+    let mut tendrils = samples();
+    tendrils[0].mode = TendrilMode::Link;
+    let mut filter = FilterSpec::new();
+    filter.locals = vec!["l*".to_string()];
+    filter.profiles = Some(vec![]);
+    filter.exclude_locals = vec!["l1".to_string()]; // Eliminates t1
+    filter.exclude_remotes = vec!["r2".to_string()]; // Eliminates t2
+    filter.exclude_profiles = vec!["p3".to_string()]; // Eliminates t3
+    filter.tendril_modes = vec![TendrilMode::DirOverwrite]; // Eliminates t0
+    let mut cfg = LazyCachedGlobalConfig::mock_w_parse_err();
+
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &AutoProfiles::new(),
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual, vec![tendrils[4].clone(), tendrils[5].clone()]);
+}
+
+#[test]
+fn exclude_profiles_applies_to_default_and_auto_profiles() {
+    let tendrils = samples();
+    let mut filter = FilterSpec::new();
+    filter.exclude_profiles = vec!["p2".to_string()];
+    let mut cfg = LazyCachedGlobalConfig::mock(global_cfg_w_profiles());
+
+    let actual = filter_tendrils(
+        tendrils.clone(),
+        filter,
+        &UniPath::from(Path::new("/SomeRepo")),
+        &mut cfg,
+        &AutoProfiles::new(),
+        &ProfileGroups::new(),
+    ).unwrap();
+
+    assert_eq!(actual, vec![tendrils[0].clone(), tendrils[1].clone()]);
+}
@@ -1,5 +1,7 @@
+mod filter_by_fso_types_tests;
 mod filter_by_locals_tests;
 mod filter_by_mode_tests;
 mod filter_by_profiles_tests;
 mod filter_by_remotes_tests;
+mod filter_by_tendril_modes_tests;
 mod filter_tendrils_tests;
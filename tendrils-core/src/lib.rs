@@ -1,45 +1,76 @@
 //! - Provides core functionality for the [`tendrils-cli`](https://crates.io/crates/tendrils-cli) crate and its `td` CLI tool
 //! - See documentation at <https://github.com/TendrilApps/tendrils-cli>
 
+mod checks;
+use checks::check_tendrils;
+pub use checks::{CheckFinding, CheckKind, Severity};
 mod config;
+pub use config::{config_schema, CONFIG_VERSION};
+mod doctor;
+use doctor::diagnose_repo;
+pub use doctor::Diagnosis;
 mod enums;
-use config::{get_config, LazyCachedGlobalConfig};
+use config::{
+    convert_repo_config,
+    fmt_repo_config,
+    get_config,
+    get_global_config,
+    migrate_global_config,
+    migrate_repo_config,
+    LazyCachedGlobalConfig,
+};
 pub use enums::{
     ActionMode,
+    ConfigFormat,
     ConfigType,
+    CopyMethod,
     FsoType,
     GetConfigError,
     GetTendrilsRepoError,
     InitError,
     InvalidTendrilError,
+    LinkStatus,
     Location,
+    NestedSymlinks,
+    ParseErrorLocation,
+    ReflinkMode,
     TendrilActionError,
     TendrilActionSuccess,
     SetupError,
     TendrilMode,
 };
 mod env_ext;
-use env_ext::can_symlink;
+use env_ext::{can_symlink, HostFacts};
 mod filtering;
 use filtering::filter_tendrils;
 pub use filtering::FilterSpec;
 mod path_ext;
 use path_ext::PathExt;
 pub use path_ext::UniPath;
-use std::fs::{create_dir_all, remove_dir_all, remove_file};
+mod profiles;
+use profiles::{eval_auto_profiles, AutoProfiles};
+pub use profiles::{AutoProfileLog, ConditionLog, HostCondition};
+use same_file::is_same_file;
+use std::ffi::OsString;
+use std::fs::{create_dir_all, read_link, remove_dir_all, remove_file};
 use std::path::{Path, PathBuf};
 mod tendril;
-use tendril::Tendril;
+use tendril::{canonicalize_existing, Tendril};
 pub use tendril::RawTendril;
 mod tendril_report;
 pub use tendril_report::{
     ActionLog,
     CallbackUpdater,
+    CopyProgress,
     ListLog,
+    RepoReports,
     TendrilLog,
     TendrilReport,
     UpdateHandler
 };
+mod validation;
+use validation::validate_repo_config;
+pub use validation::{ConfigIssue, ConfigIssueKind};
 
 #[cfg(test)]
 mod tests;
@@ -53,18 +84,45 @@ pub mod test_utils;
 /// for testing. The actual API implementation should have little to no state.
 pub trait TendrilsApi {
     /// Returns the `default-repo-path` value stored in
-    /// `~/.tendrils/global-config.json` or any [errors](GetConfigError) that
+    /// the `global-config.json` or any [errors](GetConfigError) that
     /// occur. Returns `None` if the value is blank or absent, or if the config
     /// file does not exist. Note: This does *not* check whether the folder
     /// [is a tendrils repo](`TendrilsApi::is_tendrils_repo`).
+    /// If several default repos are listed, only the first is returned (see
+    /// [`TendrilsApi::get_default_repo_paths`]).
     fn get_default_repo_path(&self) -> Result<Option<PathBuf>, GetConfigError>;
 
+    /// Returns each of the paths listed in the `default-repo-path` value
+    /// stored in the `global-config.json` or any
+    /// [errors](GetConfigError) that occur. The value may be either a single
+    /// path or a list of paths. Returns an empty list if the value is absent,
+    /// or if the config file does not exist.
+    fn get_default_repo_paths(&self) -> Result<Vec<PathBuf>, GetConfigError>;
+
     /// Returns the `default-profiles` stored in
-    /// `~/.tendrils/global-config.json` or any [errors](GetConfigError) that
+    /// the `global-config.json` or any [errors](GetConfigError) that
     /// occur. Returns `None` if the value is blank or absent, or if the config
     /// file does not exist.
     fn get_default_profiles(&self) -> Result<Option<Vec<String>>, GetConfigError>;
 
+    /// Evaluates each of the `auto-profiles` rules against the current host
+    /// and returns the result of each rule. Rules from
+    /// the `global-config.json` are listed first, followed by those
+    /// in the Tendrils repo's config file. A profile is activated if any
+    /// of its rules match, and is then used in addition to the
+    /// [default profiles](`TendrilsApi::get_default_profiles`) whenever the
+    /// profiles are not explicitly given in the [`FilterSpec`].
+    ///
+    /// # Arguments
+    /// - `td_repo` - The Tendrils repo whose rules are also evaluated. If
+    ///   given `None`, the [default repo](`TendrilsApi::get_default_repo_path`)
+    ///   is used if it is a valid Tendrils repo, otherwise only the global
+    ///   rules are evaluated.
+    fn get_auto_profiles(
+        &self,
+        td_repo: Option<&UniPath>,
+    ) -> Result<Vec<AutoProfileLog>, SetupError>;
+
     /// Initializes a Tendrils repo with a `.tendrils` folder and a
     /// pre-populated `tendrils.json` file. This will fail if the folder is
     /// already a Tendrils repo or if there are general file-system errors.
@@ -78,16 +136,158 @@ pub trait TendrilsApi {
     /// Returns `true` if the given folder is a Tendrils repo, otherwise
     /// `false`.
     /// - A Tendrils repo is defined by having a `.tendrils` subfolder with
-    /// a `tendrils.json` file in it.
-    /// - Note: This does *not* check that the `tendrils.json` contents are valid.
+    /// a `tendrils.json`, `tendrils.jsonc` or `tendrils.toml` file in it.
+    /// - Note: This does *not* check that the file contents are valid.
+    /// - Note: This does *not* check that only one of these files exists.
     fn is_tendrils_repo(&self, dir: &UniPath) -> bool;
 
+    /// Searches the given folder, and then each of its parent folders, for a
+    /// Tendrils repo (as defined by [`TendrilsApi::is_tendrils_repo`]) and
+    /// returns the nearest one. Returns `None` if none of them are a
+    /// Tendrils repo.
+    fn find_tendrils_repo(&self, dir: &UniPath) -> Option<UniPath>;
+
     fn list_tendrils(
         &self,
         td_repo: Option<&UniPath>,
         filter: FilterSpec,
     ) -> Result<Vec<TendrilReport<ListLog>>, SetupError>;
 
+    /// Checks the tendrils in the given Tendrils repo that match the filter
+    /// for duplicate remotes, nested locals, and nested remotes. These can
+    /// cause the outcome of the tendril actions to depend on the order in
+    /// which they are performed. Each [`CheckFinding`] has a [`Severity`], and
+    /// the tendril actions are not performed if there are any
+    /// [`Severity::Error`] findings (see [`SetupError::TendrilConflict`]),
+    /// unless they are forced.
+    ///
+    /// # Arguments
+    /// - `td_repo` - The Tendrils repo to check. If given `None`, the
+    ///   [default repo](`TendrilsApi::get_default_repo_path`) is used.
+    /// - `filter` - Only tendrils matching this filter will be checked. The
+    ///   severities are for the action mode of the filter, or the highest for
+    ///   any action if it has none.
+    fn check_tendrils(
+        &self,
+        td_repo: Option<&UniPath>,
+        filter: FilterSpec,
+    ) -> Result<Vec<CheckFinding>, SetupError>;
+
+    /// Checks the config files in the given Tendrils repo for problems that
+    /// do not prevent them from being parsed, such as unknown keys (which are
+    /// otherwise silently ignored), empty `remotes` lists, empty profile
+    /// names, duplicate remotes within a tendril set, and invalid local
+    /// paths. Returns an empty list if there are no problems.
+    ///
+    /// # Arguments
+    /// - `td_repo` - The Tendrils repo to check. If given `None`, the
+    ///   [default repo](`TendrilsApi::get_default_repo_path`) is used.
+    fn validate_config(
+        &self,
+        td_repo: Option<&UniPath>,
+    ) -> Result<Vec<ConfigIssue>, SetupError>;
+
+    /// Converts the repo config file (`tendrils.json`, `tendrils.jsonc` or
+    /// `tendrils.toml`) in the given Tendrils repo to the given `format`, and
+    /// returns the format that it was converted from. The new file replaces
+    /// the original. Nothing is changed if it is already in that `format`.
+    ///
+    /// Only the repo config file is converted, any files that it includes are
+    /// left as-is. Unknown keys are not carried over. The original file is
+    /// only removed once the new file has been written.
+    ///
+    /// # Arguments
+    /// - `td_repo` - The Tendrils repo to convert. If given `None`, the
+    ///   [default repo](`TendrilsApi::get_default_repo_path`) is used.
+    /// - `format` - The format to convert to.
+    /// - `drop_comments` - Whether a JSONC or TOML file that contains
+    ///   comments may be converted without them. Otherwise
+    ///   [`GetConfigError::CommentsWouldBeLost`] is returned for such a file.
+    fn convert_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        format: ConfigFormat,
+        drop_comments: bool,
+    ) -> Result<ConfigFormat, SetupError>;
+
+    /// Rewrites the repo config file in the given Tendrils repo in the
+    /// layout of the [`CONFIG_VERSION`], and sets its `version`. Returns the
+    /// version of the file before it was migrated, where files without a
+    /// version are version 1. Nothing is changed if the file is already at
+    /// the [`CONFIG_VERSION`], whether or not it has a version.
+    ///
+    /// Older files are also migrated in memory whenever they are read, so
+    /// this is only needed to update the file itself. Only the repo config
+    /// file is rewritten, any files that it includes are left as-is.
+    /// Unknown keys are not carried over when the file is rewritten.
+    ///
+    /// # Arguments
+    /// - `td_repo` - The Tendrils repo to migrate. If given `None`, the
+    ///   [default repo](`TendrilsApi::get_default_repo_path`) is used.
+    /// - `drop_comments` - Whether a JSONC or TOML file that contains
+    ///   comments may be rewritten without them. Otherwise
+    ///   [`GetConfigError::CommentsWouldBeLost`] is returned for such a file.
+    fn migrate_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        drop_comments: bool,
+    ) -> Result<u32, SetupError>;
+
+    /// Rewrites the `global-config.json` file in the layout of the
+    /// [`CONFIG_VERSION`] (see [`TendrilsApi::migrate_config`]). Returns a
+    /// [`std::io::ErrorKind::NotFound`] error if the file does not exist.
+    fn migrate_global_config(&self) -> Result<u32, GetConfigError>;
+
+    /// Rewrites the repo config file in the given Tendrils repo in a
+    /// canonical form. Lists with a single item are written as that item,
+    /// backslashes in the locals and remotes are replaced by forward slashes
+    /// (except in UNC and verbatim paths), and values that are the same as
+    /// their defaults are removed. Returns `true` if the file was not already
+    /// formatted.
+    ///
+    /// TOML files are formatted in place, so their comments and unknown keys
+    /// are kept. Other files are rewritten, so their unknown keys are not
+    /// carried over.
+    ///
+    /// Only the repo config file is formatted, any files that it includes are
+    /// left as-is.
+    ///
+    /// # Arguments
+    /// - `td_repo` - The Tendrils repo to format. If given `None`, the
+    ///   [default repo](`TendrilsApi::get_default_repo_path`) is used.
+    /// - `sort_keys` - Whether to sort the tendrils, automatic profiles,
+    ///   profile groups and variables by name. Otherwise they keep their
+    ///   order.
+    /// - `check` - If `true`, the file is only checked and is not changed.
+    /// - `drop_comments` - Whether a JSONC file that contains comments may
+    ///   be rewritten without them. Otherwise
+    ///   [`GetConfigError::CommentsWouldBeLost`] is returned for such a file.
+    fn fmt_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        sort_keys: bool,
+        check: bool,
+        drop_comments: bool,
+    ) -> Result<bool, SetupError>;
+
+    /// Checks the environment and the given Tendrils repo for problems that
+    /// would otherwise only be found when performing the tendril actions,
+    /// such as invalid config files, unset environment variables, broken
+    /// symlinks and folders that cannot be written to. Returns an empty list
+    /// if there are no problems.
+    ///
+    /// Note: Write access to the Tendrils repo and to the nearest existing
+    /// folder of each remote is checked without modifying them. On Unix,
+    /// their file system must not be mounted read-only (using `statvfs`) and
+    /// the current user must have write access to them (using `access`).
+    /// On other platforms this is not checked, and
+    /// [`Diagnosis::WriteAccessNotChecked`] is returned instead.
+    ///
+    /// # Arguments
+    /// - `td_repo` - The Tendrils repo to check. If given `None`, the
+    ///   [default repo](`TendrilsApi::get_default_repo_path`) is used.
+    fn diagnose(&self, td_repo: Option<&UniPath>) -> Vec<Diagnosis>;
+
     /// Reads the `tendrils.json` file in the given Tendrils repo, and
     /// performs the action on each tendril that matches the
     /// filter.
@@ -128,8 +328,11 @@ pub trait TendrilsApi {
     /// [`TendrilActionSuccess::Overwrite`] if successful.
     /// - `force`
     ///     - `true` will ignore any type mismatches and will force the operation.
+    ///       Any conflicts between the tendrils are also ignored.
     ///     - `false` will simply return [`TendrilActionError::TypeMismatch`] if
     /// there is a type mismatch.
+    ///       Returns [`SetupError::TendrilConflict`] if there are any conflicts
+    ///       (see [`check_tendrils`](`TendrilsApi::check_tendrils`)).
     ///
     /// # Returns
     /// A [`TendrilReport`] containing an [`ActionLog`] for each tendril action.
@@ -158,19 +361,104 @@ pub trait TendrilsApi {
         dry_run: bool,
         force: bool,
     ) -> Result<Vec<TendrilReport<ActionLog>>, SetupError>;
+
+    /// Same behaviour as [`list_tendrils`](`TendrilsApi::list_tendrils`)
+    /// except it lists the tendrils in each of the given `td_repos`. The
+    /// reports are grouped by repo, in the order the repos are given. If
+    /// `td_repos` is empty, each of the
+    /// [default repos](`TendrilsApi::get_default_repo_paths`) is used.
+    ///
+    /// Returns [`SetupError::RemoteConflict`] if tendrils from two different
+    /// repos share a remote path, or any other [`SetupError`] from any of the
+    /// repos.
+    fn list_tendrils_multi(
+        &self,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+    ) -> Result<Vec<RepoReports<ListLog>>, SetupError>;
+
+    /// Same behaviour as
+    /// [`tendril_action_updating`](`TendrilsApi::tendril_action_updating`)
+    /// except it performs the actions on each of the given `td_repos`. The
+    /// `updater` is given the total count of tendrils across all repos, then
+    /// is updated for each tendril in the order the repos are given. If
+    /// `td_repos` is empty, each of the
+    /// [default repos](`TendrilsApi::get_default_repo_paths`) is used.
+    ///
+    /// All repos are set up before any actions are performed, so no actions
+    /// are performed if a [`SetupError`] occurs in any of the repos.
+    /// Returns [`SetupError::RemoteConflict`] if tendrils from two different
+    /// repos share a remote path.
+    ///
+    /// # Returns
+    /// The same reports given to the `updater`, grouped by repo.
+    fn tendril_action_multi_updating<U>(
+        &self,
+        updater: U,
+        mode: ActionMode,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<Vec<RepoReports<ActionLog>>, SetupError>
+    where
+        U: UpdateHandler<ActionLog>;
+
+    /// Same behaviour as
+    /// [`tendril_action_multi_updating`](`TendrilsApi::tendril_action_multi_updating`)
+    /// except reports are only returned once all actions have completed.
+    fn tendril_action_multi(
+        &self,
+        mode: ActionMode,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<Vec<RepoReports<ActionLog>>, SetupError>;
 }
 
 pub struct TendrilsActor {}
 
 impl TendrilsApi for TendrilsActor {
     fn get_default_repo_path(&self) -> Result<Option<PathBuf>, GetConfigError> {
-        Ok(config::get_global_config()?.default_repo_path)
+        Ok(config::get_global_config()?.default_repo_paths().into_iter().next())
+    }
+
+    fn get_default_repo_paths(&self) -> Result<Vec<PathBuf>, GetConfigError> {
+        Ok(config::get_global_config()?.default_repo_paths())
     }
 
     fn get_default_profiles(&self) -> Result<Option<Vec<String>>, GetConfigError> {
         Ok(config::get_global_config()?.default_profiles)
     }
 
+    fn get_auto_profiles(
+        &self,
+        td_repo: Option<&UniPath>,
+    ) -> Result<Vec<AutoProfileLog>, SetupError> {
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        let global_auto_profiles =
+            global_cfg.eval()?.auto_profiles.unwrap_or_default();
+        let (repo_auto_profiles, repo_format) =
+            match get_tendrils_repo(td_repo, &mut global_cfg) {
+                Ok(v) => {
+                    let cfg = get_config(&v)?;
+                    (cfg.auto_profiles, cfg.format)
+                }
+                Err(_) if td_repo.is_none() => {
+                    (AutoProfiles::new(), ConfigFormat::Json)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+        Ok(eval_auto_profiles(
+            &global_auto_profiles,
+            &repo_auto_profiles,
+            repo_format,
+            &HostFacts::current(),
+        ))
+    }
+
     fn init_tendrils_repo(&self, dir: &UniPath, force: bool) -> Result<(), InitError> {
         if !dir.inner().exists() {
             return Err(InitError::IoError { kind: std::io::ErrorKind::NotFound });
@@ -194,6 +482,10 @@ impl TendrilsApi for TendrilsActor {
         is_tendrils_repo(dir)
     }
 
+    fn find_tendrils_repo(&self, dir: &UniPath) -> Option<UniPath> {
+        find_tendrils_repo(dir)
+    }
+
     fn list_tendrils(
         &self,
         td_repo: Option<&UniPath>,
@@ -201,14 +493,122 @@ impl TendrilsApi for TendrilsActor {
     ) -> Result<Vec<TendrilReport<ListLog>>, SetupError> {
         let mut global_cfg = LazyCachedGlobalConfig::new();
         let td_repo= get_tendrils_repo(td_repo, &mut global_cfg)?;
-        let all_tendrils = get_config(&td_repo)?.raw_tendrils;
-        let filtered_tendrils =
-            filter_tendrils(all_tendrils, filter, &mut global_cfg);
+        let config = get_config(&td_repo)?;
+        let filtered_tendrils = filter_tendrils(
+            config.raw_tendrils,
+            filter,
+            &td_repo,
+            &mut global_cfg,
+            &config.auto_profiles,
+            &config.profile_groups,
+        )?;
 
         let reports = list_tendrils_inner(&td_repo, filtered_tendrils);
         Ok(reports)
     }
 
+    fn check_tendrils(
+        &self,
+        td_repo: Option<&UniPath>,
+        filter: FilterSpec,
+    ) -> Result<Vec<CheckFinding>, SetupError> {
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        let td_repo = get_tendrils_repo(td_repo, &mut global_cfg)?;
+        let config = get_config(&td_repo)?;
+        let mode = filter.mode.clone();
+        let filtered_tendrils = filter_tendrils(
+            config.raw_tendrils,
+            filter,
+            &td_repo,
+            &mut global_cfg,
+            &config.auto_profiles,
+            &config.profile_groups,
+        )?;
+
+        Ok(check_tendrils(&td_repo, &filtered_tendrils, mode.as_ref()))
+    }
+
+    fn validate_config(
+        &self,
+        td_repo: Option<&UniPath>,
+    ) -> Result<Vec<ConfigIssue>, SetupError> {
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        let td_repo = get_tendrils_repo(td_repo, &mut global_cfg)?;
+
+        // Any parse errors are reported the same way as for other commands
+        get_config(&td_repo)?;
+        Ok(validate_repo_config(&td_repo)?)
+    }
+
+    fn convert_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        format: ConfigFormat,
+        drop_comments: bool,
+    ) -> Result<ConfigFormat, SetupError> {
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        let td_repo = get_tendrils_repo(td_repo, &mut global_cfg)?;
+
+        Ok(convert_repo_config(&td_repo, format, drop_comments)?)
+    }
+
+    fn migrate_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        drop_comments: bool,
+    ) -> Result<u32, SetupError> {
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        let td_repo = get_tendrils_repo(td_repo, &mut global_cfg)?;
+
+        Ok(migrate_repo_config(&td_repo, drop_comments)?)
+    }
+
+    fn migrate_global_config(&self) -> Result<u32, GetConfigError> {
+        migrate_global_config()
+    }
+
+    fn fmt_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        sort_keys: bool,
+        check: bool,
+        drop_comments: bool,
+    ) -> Result<bool, SetupError> {
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        let td_repo = get_tendrils_repo(td_repo, &mut global_cfg)?;
+
+        Ok(fmt_repo_config(&td_repo, sort_keys, check, drop_comments)?)
+    }
+
+    fn diagnose(&self, td_repo: Option<&UniPath>) -> Vec<Diagnosis> {
+        let mut diagnoses = vec![];
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        if let Err(e) = global_cfg.eval() {
+            diagnoses.push(Diagnosis::GlobalConfig(e));
+        }
+
+        let td_repo = match get_tendrils_repo(td_repo, &mut global_cfg) {
+            Ok(v) => v,
+            // Already reported above
+            Err(GetTendrilsRepoError::ConfigError(_)) => return diagnoses,
+            Err(e) => {
+                diagnoses.push(Diagnosis::NoValidTendrilsRepo(e));
+                return diagnoses;
+            }
+        };
+        match get_config(&td_repo) {
+            Ok(config) => diagnoses.extend(
+                diagnose_repo(&td_repo, &config.raw_tendrils, can_symlink())
+            ),
+            Err(e) => diagnoses.push(Diagnosis::RepoConfig(e)),
+        }
+        if !cfg!(unix) {
+            diagnoses.push(Diagnosis::WriteAccessNotChecked);
+        }
+
+        diagnoses
+    }
+
     fn tendril_action_updating<U>(
         &self,
         updater: U,
@@ -224,13 +624,22 @@ impl TendrilsApi for TendrilsActor {
         let mut global_cfg = LazyCachedGlobalConfig::new();
         let td_repo= get_tendrils_repo(td_repo, &mut global_cfg)?;
         let config = config::get_config(&td_repo)?;
-        let all_tendrils = config.raw_tendrils;
-
-        let filtered_tendrils =
-            filter_tendrils(all_tendrils, filter, &mut global_cfg);
-        if mode == ActionMode::Link && !filtered_tendrils.is_empty() && !can_symlink() {
+        let filtered_tendrils = filter_tendrils(
+            config.raw_tendrils,
+            filter,
+            &td_repo,
+            &mut global_cfg,
+            &config.auto_profiles,
+            &config.profile_groups,
+        )?;
+        if (mode == ActionMode::Link || mode == ActionMode::Relink)
+            && filtered_tendrils.iter().any(|t| t.mode == TendrilMode::Link)
+            && !can_symlink() {
             return Err(SetupError::CannotSymlink);
         }
+        if !force {
+            check_tendril_conflicts(&td_repo, &filtered_tendrils, &mode)?;
+        }
 
         batch_tendril_action(updater, mode, &td_repo, filtered_tendrils, dry_run, force);
         Ok(())
@@ -257,9 +666,134 @@ impl TendrilsApi for TendrilsActor {
         self.tendril_action_updating(updater, mode, td_repo, filter, dry_run, force)?;
         Ok(reports)
     }
+
+    fn list_tendrils_multi(
+        &self,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+    ) -> Result<Vec<RepoReports<ListLog>>, SetupError> {
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        let repo_tendrils = filter_repos(td_repos, filter, &mut global_cfg)?;
+
+        Ok(repo_tendrils
+            .into_iter()
+            .map(|(td_repo, tendrils)| RepoReports {
+                reports: list_tendrils_inner(&td_repo, tendrils),
+                td_repo,
+            })
+            .collect())
+    }
+
+    fn tendril_action_multi_updating<U>(
+        &self,
+        mut updater: U,
+        mode: ActionMode,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<Vec<RepoReports<ActionLog>>, SetupError>
+    where
+        U: UpdateHandler<ActionLog>,
+    {
+        let mut global_cfg = LazyCachedGlobalConfig::new();
+        let repo_tendrils = filter_repos(td_repos, filter, &mut global_cfg)?;
+        if (mode == ActionMode::Link || mode == ActionMode::Relink)
+            && repo_tendrils
+                .iter()
+                .flat_map(|(_, t)| t.iter())
+                .any(|t| t.mode == TendrilMode::Link)
+            && !can_symlink() {
+            return Err(SetupError::CannotSymlink);
+        }
+        for (td_repo, tendrils) in repo_tendrils.iter().filter(|_| !force) {
+            check_tendril_conflicts(td_repo, tendrils, &mode)?;
+        }
+
+        let total: usize = repo_tendrils.iter().map(|(_, t)| t.len()).sum();
+        updater.count(total as i32);
+
+        let mut repo_reports = Vec::with_capacity(repo_tendrils.len());
+        for (td_repo, tendrils) in repo_tendrils {
+            let mut reports = vec![];
+            let repo_updater = RepoUpdater {
+                updater: &mut updater,
+                reports: &mut reports,
+            };
+
+            batch_tendril_action(
+                repo_updater,
+                mode.clone(),
+                &td_repo,
+                tendrils,
+                dry_run,
+                force,
+            );
+            repo_reports.push(RepoReports { td_repo, reports });
+        }
+
+        Ok(repo_reports)
+    }
+
+    fn tendril_action_multi(
+        &self,
+        mode: ActionMode,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<Vec<RepoReports<ActionLog>>, SetupError> {
+        let updater = CallbackUpdater::<_, _, _, ActionLog>::new(
+            |_| {},
+            |_| {},
+            |_| {},
+        );
+
+        self.tendril_action_multi_updating(
+            updater,
+            mode,
+            td_repos,
+            filter,
+            dry_run,
+            force,
+        )
+    }
+}
+
+/// Forwards the updates of a single repo's actions to the `updater` of a
+/// multi-repo action, and collects that repo's reports. The count is ignored
+/// as the `updater` is given the total across all repos.
+struct RepoUpdater<'a, U> {
+    updater: &'a mut U,
+    reports: &'a mut Vec<TendrilReport<ActionLog>>,
+}
+
+impl<U> UpdateHandler<ActionLog> for RepoUpdater<'_, U>
+where
+    U: UpdateHandler<ActionLog>,
+{
+    fn count(&mut self, _value: i32) {}
+
+    fn before(&mut self, raw: RawTendril) {
+        self.updater.before(raw)
+    }
+
+    fn after(&mut self, report: TendrilReport<ActionLog>) {
+        self.reports.push(report.clone());
+        self.updater.after(report)
+    }
+
+    fn progress(&mut self, progress: CopyProgress) {
+        self.updater.progress(progress)
+    }
+
+    fn wants_progress(&self) -> bool {
+        self.updater.wants_progress()
+    }
 }
 
 const INIT_TD_TENDRILS_JSON: &str = r#"{
+    "version": 2,
     "tendrils": {
         "SomeApp/SomeFile.ext": {
             "remotes": "/path/to/SomeFile.ext"
@@ -271,9 +805,11 @@ const INIT_TD_TENDRILS_JSON: &str = r#"{
                 "~/path/in/home/dir/SomeFolder",
                 "/path/using/<MY-ENV-VAR>/SomeFolder"
             ],
-            "dir-merge": false,
             "link": true,
-            "profiles": ["home", "work"]
+            "profiles": [
+                "home",
+                "work"
+            ]
         },
         "SomeApp3/file.txt": [
             {
@@ -286,7 +822,6 @@ const INIT_TD_TENDRILS_JSON: &str = r#"{
                     "~/windows/specific/path/file.txt",
                     "~/windows/another-specific/path/file.txt"
                 ],
-                "link": false,
                 "profiles": "windows"
             }
         ]
@@ -295,92 +830,470 @@ const INIT_TD_TENDRILS_JSON: &str = r#"{
 "#;
 
 fn is_tendrils_repo(dir: &UniPath) -> bool {
-    dir.inner().join(".tendrils/tendrils.json").is_file()
+    let dot_td_dir = dir.inner().join(".tendrils");
+    ConfigFormat::ALL
+        .iter()
+        .any(|f| dot_td_dir.join(ConfigType::Repo(*f).file_name()).is_file())
+}
+
+fn find_tendrils_repo(dir: &UniPath) -> Option<UniPath> {
+    dir.inner()
+        .ancestors()
+        .map(UniPath::from)
+        .find(is_tendrils_repo)
+}
+
+/// The outcome of a [`copy_fso`].
+struct CopyLog {
+    result: Result<TendrilActionSuccess, TendrilActionError>,
+
+    /// See [`ActionLog::copy_method`].
+    method: Option<CopyMethod>,
+
+    /// See [`ActionLog::outside_symlinks`].
+    outside_symlinks: Vec<PathBuf>,
+}
+
+/// The size of the chunks in which files are copied, between each of which
+/// the progress is reported.
+const COPY_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Copies files and folders according to the settings of a tendril,
+/// reporting its [`CopyProgress`] along the way if there is a `progress`
+/// callback.
+struct Copier<'a> {
+    dir_merge: bool,
+    reflink: ReflinkMode,
+    nested_symlinks: NestedSymlinks,
+    progress: Option<&'a mut dyn FnMut(CopyProgress)>,
+
+    /// The total number of bytes copied so far.
+    copied: u64,
+
+    /// Reused between chunked copies. Empty until the first one.
+    buf: Vec<u8>,
+}
+
+impl<'a> Copier<'a> {
+    fn new(
+        tendril: &Tendril,
+        progress: Option<&'a mut dyn FnMut(CopyProgress)>,
+    ) -> Copier<'a> {
+        Copier {
+            dir_merge: tendril.mode == TendrilMode::DirMerge,
+            reflink: tendril.reflink,
+            nested_symlinks: tendril.nested_symlinks,
+            progress,
+            copied: 0,
+            buf: vec![],
+        }
+    }
+
+    fn report(&mut self, progress: CopyProgress) {
+        if let Some(callback) = self.progress.as_mut() {
+            callback(progress);
+        }
+    }
+
+    /// Returns the number of files inside the `dir` folder that will be
+    /// copied by [`Copier::copy_dir`], and their combined size in bytes. Any
+    /// entries that cannot be read are not counted.
+    fn measure_dir(
+        &self,
+        dir: &Path,
+        ancestors: &mut Vec<PathBuf>,
+    ) -> (u64, u64) {
+        let Ok(canon_dir) = dir.canonicalize() else {
+            return (0, 0);
+        };
+        if ancestors.contains(&canon_dir) {
+            return (0, 0);
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return (0, 0);
+        };
+        ancestors.push(canon_dir);
+
+        let (mut files, mut bytes) = (0, 0);
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_symlink =
+                entry.file_type().is_ok_and(|t| t.is_symlink());
+            if is_symlink && self.nested_symlinks != NestedSymlinks::Follow {
+                continue;
+            }
+            else if path.is_dir() {
+                let (sub_files, sub_bytes) =
+                    self.measure_dir(&path, ancestors);
+                files += sub_files;
+                bytes += sub_bytes;
+            }
+            else {
+                files += 1;
+                bytes += path.metadata().map_or(0, |md| md.len());
+            }
+        }
+
+        ancestors.pop();
+        (files, bytes)
+    }
+
+    /// Copies the `from` file to `to`, which must not already exist. The
+    /// file is reflinked according to the `reflink` mode, and the method
+    /// that was used is returned.
+    ///
+    /// If the `reflink` mode is [`ReflinkMode::Always`] and the file system
+    /// does not support reflinks, [`CopyError::ReflinkUnsupported`] is
+    /// returned.
+    fn copy_file(
+        &mut self,
+        from: &Path,
+        to: &Path,
+    ) -> Result<CopyMethod, CopyError> {
+        use std::io::ErrorKind::{
+            AlreadyExists,
+            CrossesDevices,
+            NotFound,
+            PermissionDenied,
+            Unsupported,
+        };
+
+        self.report(CopyProgress::File { path: from.to_path_buf() });
+        let reflinked = match self.reflink {
+            ReflinkMode::Never => {
+                self.copy_contents(from, to)?;
+                false
+            }
+            ReflinkMode::Auto => match reflink_copy::reflink(from, to) {
+                Ok(()) => true,
+                Err(e) if matches!(
+                    e.kind(),
+                    AlreadyExists | NotFound | PermissionDenied
+                ) => return Err(e.into()),
+                Err(_) => {
+                    self.copy_contents(from, to)?;
+                    false
+                }
+            },
+            ReflinkMode::Always => match reflink_copy::reflink(from, to) {
+                Ok(()) => true,
+                // Includes EOPNOTSUPP
+                Err(e) if matches!(e.kind(), Unsupported | CrossesDevices) => {
+                    return Err(CopyError::ReflinkUnsupported);
+                }
+                Err(e) => return Err(e.into()),
+            },
+        };
+
+        if reflinked {
+            // Unlike a normal copy, the permissions are not carried over
+            let md = std::fs::metadata(from)?;
+            std::fs::set_permissions(to, md.permissions())?;
+            self.copied += md.len();
+            self.report(CopyProgress::Bytes { copied: self.copied });
+            Ok(CopyMethod::Reflink)
+        }
+        else {
+            Ok(CopyMethod::Copy)
+        }
+    }
+
+    /// Copies the contents and permissions of the `from` file to `to`. The
+    /// file is only copied in chunks (see [`Copier::copy_chunked`]) if its
+    /// progress is reported, since [`std::fs::copy`] is faster otherwise.
+    fn copy_contents(&mut self, from: &Path, to: &Path) -> std::io::Result<()> {
+        if self.progress.is_some() {
+            self.copy_chunked(from, to)
+        }
+        else {
+            std::fs::copy(from, to).map(|_| ())
+        }
+    }
+
+    /// Copies the contents and permissions of the `from` file to `to`,
+    /// reporting the progress after each chunk.
+    fn copy_chunked(
+        &mut self,
+        from: &Path,
+        to: &Path,
+    ) -> std::io::Result<()> {
+        use std::io::{Read, Write};
+
+        let mut reader = std::fs::File::open(from)?;
+        let mut writer = std::fs::File::create(to)?;
+        if self.buf.is_empty() {
+            self.buf = vec![0; COPY_CHUNK_SIZE];
+        }
+        loop {
+            let len = match reader.read(&mut self.buf) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            writer.write_all(&self.buf[..len])?;
+            self.copied += len as u64;
+            self.report(CopyProgress::Bytes { copied: self.copied });
+        }
+        writer.set_permissions(reader.metadata()?.permissions())
+    }
+
+    /// Copies the contents of the `from` folder into the `to` folder, which
+    /// is created if needed. Each file is copied using
+    /// [`Copier::copy_file`], overwriting any file of the same name in `to`.
+    /// Any symlinks within are handled according to the `nested_symlinks`
+    /// policy. Returns [`CopyMethod::Reflink`] only if every file was
+    /// reflinked.
+    ///
+    /// The `ancestors` are the canonical paths of the folders that are
+    /// already being copied. [`CopyError::SymlinkLoop`] is returned if a
+    /// followed symlink leads back to any of them.
+    fn copy_dir(
+        &mut self,
+        from: &Path,
+        to: &Path,
+        ancestors: &mut Vec<PathBuf>,
+    ) -> Result<CopyMethod, CopyError> {
+        let canon_from = from.canonicalize()?;
+        if ancestors.contains(&canon_from) {
+            return Err(CopyError::SymlinkLoop(from.to_path_buf()));
+        }
+        ancestors.push(canon_from);
+        create_dir_all(to)?;
+
+        let mut method = CopyMethod::Reflink;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            let from_child = entry.path();
+            let to_child = to.join(entry.file_name());
+            let is_symlink = entry.file_type()?.is_symlink();
+
+            let child_method = match self.nested_symlinks {
+                NestedSymlinks::Skip if is_symlink => continue,
+                NestedSymlinks::Preserve if is_symlink => {
+                    clear_copy_dest(&to_child, false)?;
+                    copy_symlink(&from_child, &to_child)?;
+                    continue;
+                }
+                _ if from_child.is_dir() => {
+                    clear_copy_dest(&to_child, true)?;
+                    self.copy_dir(&from_child, &to_child, ancestors)?
+                }
+                _ => {
+                    clear_copy_dest(&to_child, false)?;
+                    self.copy_file(&from_child, &to_child)?
+                }
+            };
+            if child_method == CopyMethod::Copy {
+                method = CopyMethod::Copy;
+            }
+        }
+
+        ancestors.pop();
+        Ok(method)
+    }
 }
 
 fn copy_fso(
     from: &Path,
     from_type: &Option<FsoType>,
-    mut to: &Path,
+    to: &Path,
     to_type: &Option<FsoType>,
-    dir_merge: bool,
+    copier: &mut Copier,
     dry_run: bool,
     force: bool,
-) -> Result<TendrilActionSuccess, TendrilActionError> {
-    use std::io::ErrorKind::{NotFound, PermissionDenied};
+) -> CopyLog {
     let to_existed = to_type.is_some();
 
-    check_copy_types(from_type, to_type, force)?;
+    if let Err(e) = check_copy_types(from_type, to_type, force) {
+        return CopyLog { result: Err(e), method: None, outside_symlinks: vec![] };
+    }
+
+    let is_dir = matches!(
+        from_type,
+        Some(FsoType::Dir | FsoType::SymDir | FsoType::BrokenSym),
+    );
+    let outside_symlinks = match copier.nested_symlinks {
+        NestedSymlinks::Skip => vec![],
+        _ if is_dir => find_outside_symlinks(from),
+        _ => vec![],
+    };
+    let success = match (dry_run, to_existed) {
+        (true, true) => TendrilActionSuccess::OverwriteSkipped,
+        (true, false) => TendrilActionSuccess::NewSkipped,
+        (false, true) => TendrilActionSuccess::Overwrite,
+        (false, false) => TendrilActionSuccess::New,
+    };
+    if dry_run {
+        return CopyLog { result: Ok(success), method: None, outside_symlinks };
+    }
 
-    match (dry_run, to_existed) {
-        (true, true) => return Ok(TendrilActionSuccess::OverwriteSkipped),
-        (true, false) => return Ok(TendrilActionSuccess::NewSkipped),
-        _ => {}
+    // Any outside symlinks are still reported if the copy fails
+    match copy_fso_contents(from, from_type, to, to_type, copier) {
+        Ok(method) => CopyLog {
+            result: Ok(success),
+            method: (copier.reflink != ReflinkMode::Never).then_some(method),
+            outside_symlinks,
+        },
+        Err(e) => CopyLog { result: Err(e), method: None, outside_symlinks },
     }
+}
+
+/// Copies the `from` file or folder to `to`, after any existing `to` has been
+/// prepared, and returns the method that was used.
+fn copy_fso_contents(
+    from: &Path,
+    from_type: &Option<FsoType>,
+    to: &Path,
+    to_type: &Option<FsoType>,
+    copier: &mut Copier,
+) -> Result<CopyMethod, TendrilActionError> {
+    use std::io::ErrorKind::NotFound;
+
     match from_type {
         Some(FsoType::Dir | FsoType::SymDir | FsoType::BrokenSym) => {
-            prepare_dest(to, to_type, dir_merge)?;
-
-            to = to.parent().unwrap_or(to);
-
-            let mut copy_opts = fs_extra::dir::CopyOptions::new();
-            copy_opts.overwrite = true;
-            copy_opts.skip_exist = false;
-            match (fs_extra::dir::copy(from, to, &copy_opts), to_existed) {
-                (Ok(_v), true) => Ok(TendrilActionSuccess::Overwrite),
-                (Ok(_v), false) => Ok(TendrilActionSuccess::New),
-                (Err(e), _) => match e.kind {
-                    // Convert fs_extra::errors
-                    fs_extra::error::ErrorKind::Io(e) => {
-                        if is_rofs_err(&e.kind()) {
-                            Err(TendrilActionError::IoError {
-                                kind: e.kind(),
-                                loc: Location::Dest,
-                            })
-                        }
-                        else {
-                            Err(TendrilActionError::from(e))
+            if copier.progress.is_some() {
+                let (files, bytes) = copier.measure_dir(from, &mut vec![]);
+                copier.report(CopyProgress::Start { files, bytes });
+            }
+            prepare_dest(to, to_type, copier.dir_merge)?;
+            copier.copy_dir(from, to, &mut vec![])
+        }
+        Some(FsoType::File | FsoType::SymFile) => {
+            if copier.progress.is_some() {
+                let bytes = from.metadata().map_or(0, |md| md.len());
+                copier.report(CopyProgress::Start { files: 1, bytes });
+            }
+            prepare_dest(to, to_type, false)?;
+            copier.copy_file(from, to)
+        }
+        None => {
+            return Err(TendrilActionError::IoError {
+                kind: NotFound,
+                loc: Location::Source,
+            });
+        }
+    }.map_err(|e| to_copy_err(e, to))
+}
+
+/// Removes the file system object at the `path`, if any, so that something
+/// can be copied in its place. If `keep_dir` is `true`, a folder is kept so
+/// that it can be copied into instead. Symlinks are always removed, rather
+/// than anything being copied to their targets.
+fn clear_copy_dest(path: &Path, keep_dir: bool) -> std::io::Result<()> {
+    match path.symlink_metadata() {
+        Ok(md) if md.is_dir() && keep_dir => Ok(()),
+        Ok(md) if md.is_dir() => remove_dir_all(path),
+        Ok(md) if md.is_symlink() => remove_symlink(path),
+        Ok(_) => remove_file(path),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Creates a symlink at `to` that points to the same target as the `from`
+/// symlink, exactly as written (i.e. relative targets stay relative).
+fn copy_symlink(from: &Path, to: &Path) -> std::io::Result<()> {
+    let target = read_link(from)?;
+
+    #[cfg(windows)]
+    if from.is_dir() {
+        std::os::windows::fs::symlink_dir(target, to)
+    }
+    else {
+        std::os::windows::fs::symlink_file(target, to)
+    }
+
+    #[cfg(not(windows))]
+    std::os::unix::fs::symlink(target, to)
+}
+
+/// Returns the symlinks anywhere inside the `root` folder that point outside
+/// of it, sorted by path. Symlinks to folders are not searched, and the
+/// targets are compared lexically (see [`PathExt::normalize`]).
+fn find_outside_symlinks(root: &Path) -> Vec<PathBuf> {
+    let norm_root = root.normalize();
+    let mut outside = vec![];
+    let mut dirs = vec![root.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(t) if t.is_dir() => dirs.push(path),
+                Ok(t) if t.is_symlink() => {
+                    let Ok(target) = read_link(&path) else {
+                        continue;
+                    };
+                    let target = match path.parent() {
+                        Some(parent) if target.is_relative() => {
+                            parent.join(target)
                         }
+                        _ => target,
+                    };
+                    if !target.normalize().starts_with(&norm_root) {
+                        outside.push(path);
                     }
-                    fs_extra::error::ErrorKind::PermissionDenied => {
-                        let loc = which_copy_perm_failed(to);
-                        Err(TendrilActionError::IoError {
-                            kind: PermissionDenied,
-                            loc,
-                        })
-                    }
-                    _ => {
-                        Err(TendrilActionError::from(std::io::ErrorKind::Other))
-                    }
-                },
+                }
+                _ => {}
             }
         }
-        Some(FsoType::File | FsoType::SymFile) => {
-            prepare_dest(to, to_type, false)?;
+    }
 
-            match (std::fs::copy(from, to), to_existed) {
-                (Ok(_v), true) => Ok(TendrilActionSuccess::Overwrite),
-                (Ok(_v), false) => Ok(TendrilActionSuccess::New),
-                (Err(e), _) if e.kind() == PermissionDenied => {
-                    let loc = which_copy_perm_failed(to);
-                    Err(TendrilActionError::IoError {
-                        kind: PermissionDenied,
-                        loc,
-                    })
-                }
-                (Err(e), _) if is_rofs_err(&e.kind()) => {
-                    Err(TendrilActionError::IoError {
-                        kind: e.kind(),
-                        loc: Location::Dest,
-                    })
-                }
-                (Err(e), _) => Err(TendrilActionError::from(e)),
-            }
+    outside.sort();
+    outside
+}
+
+/// An error from a [`Copier`].
+#[derive(Debug)]
+enum CopyError {
+    Io(std::io::Error),
+
+    /// See [`TendrilActionError::ReflinkUnsupported`].
+    ReflinkUnsupported,
+
+    /// See [`TendrilActionError::SymlinkLoop`].
+    SymlinkLoop(PathBuf),
+}
+
+impl From<std::io::Error> for CopyError {
+    fn from(err: std::io::Error) -> Self {
+        CopyError::Io(err)
+    }
+}
+
+/// Converts an error from copying a file system object to `to`, determining
+/// whether the source or destination is to blame where possible.
+fn to_copy_err(e: CopyError, to: &Path) -> TendrilActionError {
+    let e = match e {
+        CopyError::Io(e) => e,
+        CopyError::ReflinkUnsupported => {
+            return TendrilActionError::ReflinkUnsupported;
         }
-        None => Err(TendrilActionError::IoError {
-            kind: NotFound,
-            loc: Location::Source,
-        }),
+        CopyError::SymlinkLoop(symlink) => {
+            return TendrilActionError::SymlinkLoop { symlink };
+        }
+    };
+
+    if e.kind() == std::io::ErrorKind::PermissionDenied {
+        TendrilActionError::IoError {
+            kind: e.kind(),
+            loc: which_copy_perm_failed(to),
+        }
+    }
+    else if is_rofs_err(&e.kind()) {
+        TendrilActionError::IoError {
+            kind: e.kind(),
+            loc: Location::Dest,
+        }
+    }
+    else {
+        TendrilActionError::from(e)
     }
 }
 
@@ -503,8 +1416,10 @@ fn is_rofs_err(e_kind: &std::io::ErrorKind) -> bool {
 /// [`GetTendrilsRepoError::DefaultInvalid`] is returned
 ///     - If it is not set,
 /// [`GetTendrilsRepoError::DefaultNotSet`] is returned.
-// TODO: Recursively look through all parent folders before
-// checking global config?
+///
+/// Searching the parent folders of the current directory is left to the
+/// caller (see [`TendrilsApi::find_tendrils_repo`]), which can then pass the
+/// result as the `starting_path`.
 fn get_tendrils_repo(
     starting_path: Option<&UniPath>,
     global_cfg: &mut LazyCachedGlobalConfig,
@@ -520,7 +1435,7 @@ fn get_tendrils_repo(
                 })
             }
         }
-        None => match global_cfg.eval()?.default_repo_path {
+        None => match global_cfg.eval()?.default_repo_paths().into_iter().next() {
             Some(v) => {
                 let u_path = UniPath::from(v);
                 if is_tendrils_repo(&u_path) {
@@ -537,6 +1452,130 @@ fn get_tendrils_repo(
     }
 }
 
+/// Looks for each of the given Tendrils repos (as defined by
+/// [`TendrilsApi::is_tendrils_repo`]), or each of the default repos if none are
+/// given. Any duplicate repos are only returned once.
+/// - If any of the given repos are not a Tendrils repo,
+///   [`GetTendrilsRepoError::GivenInvalid`] is returned.
+/// - If any of the default repos are not a Tendrils repo,
+///   [`GetTendrilsRepoError::DefaultInvalid`] is returned.
+/// - If none are given and the default repo is not set,
+///   [`GetTendrilsRepoError::DefaultNotSet`] is returned.
+fn get_tendrils_repos(
+    td_repos: &[UniPath],
+    global_cfg: &mut LazyCachedGlobalConfig,
+) -> Result<Vec<UniPath>, GetTendrilsRepoError> {
+    let mut valid_repos: Vec<UniPath> = vec![];
+    if td_repos.is_empty() {
+        let default_repos = global_cfg.eval()?.default_repo_paths();
+        if default_repos.is_empty() {
+            return Err(GetTendrilsRepoError::DefaultNotSet);
+        }
+
+        for default_repo in default_repos {
+            let u_path = UniPath::from(default_repo);
+            if !is_tendrils_repo(&u_path) {
+                return Err(GetTendrilsRepoError::DefaultInvalid {
+                    path: PathBuf::from(u_path.inner()),
+                });
+            }
+            if !valid_repos.contains(&u_path) {
+                valid_repos.push(u_path);
+            }
+        }
+    }
+    else {
+        for td_repo in td_repos {
+            let u_path = get_tendrils_repo(Some(td_repo), global_cfg)?;
+            if !valid_repos.contains(&u_path) {
+                valid_repos.push(u_path);
+            }
+        }
+    }
+
+    Ok(valid_repos)
+}
+
+/// Filters the tendrils in each of the given (or default) Tendrils repos.
+/// Returns [`SetupError::RemoteConflict`] if any of the remaining tendrils
+/// from different repos share a remote path.
+fn filter_repos(
+    td_repos: &[UniPath],
+    filter: FilterSpec,
+    global_cfg: &mut LazyCachedGlobalConfig,
+) -> Result<Vec<(UniPath, Vec<RawTendril>)>, SetupError> {
+    let td_repos = get_tendrils_repos(td_repos, global_cfg)?;
+
+    let mut repo_tendrils = Vec::with_capacity(td_repos.len());
+    for td_repo in td_repos {
+        let config = get_config(&td_repo)?;
+        let filtered_tendrils = filter_tendrils(
+            config.raw_tendrils,
+            filter.clone(),
+            &td_repo,
+            global_cfg,
+            &config.auto_profiles,
+            &config.profile_groups,
+        )?;
+        repo_tendrils.push((td_repo, filtered_tendrils));
+    }
+
+    check_remote_conflicts(&repo_tendrils)?;
+    Ok(repo_tendrils)
+}
+
+/// Returns [`SetupError::RemoteConflict`] for the first remote (or fallback)
+/// that is shared by tendrils from two different repos. Remotes are compared
+/// after [resolving](UniPath) them. Tendrils within the same repo may share a
+/// remote.
+fn check_remote_conflicts(
+    repo_tendrils: &[(UniPath, Vec<RawTendril>)],
+) -> Result<(), SetupError> {
+    let mut claimed: Vec<(PathBuf, &UniPath)> = vec![];
+
+    for (td_repo, tendrils) in repo_tendrils {
+        let mut repo_remotes: Vec<PathBuf> = vec![];
+        let all_remotes = tendrils
+            .iter()
+            .flat_map(|t| std::iter::once(&t.remote).chain(&t.fallbacks));
+        for remote in all_remotes {
+            let resolved =
+                PathBuf::from(UniPath::from(Path::new(remote)).inner());
+            let other_claim = claimed.iter().find(|(r, _)| r == &resolved);
+            if let Some((_, other_repo)) = other_claim {
+                return Err(SetupError::RemoteConflict {
+                    remote: resolved,
+                    first_repo: PathBuf::from(other_repo.inner()),
+                    second_repo: PathBuf::from(td_repo.inner()),
+                });
+            }
+            if !repo_remotes.contains(&resolved) {
+                repo_remotes.push(resolved);
+            }
+        }
+
+        claimed.extend(repo_remotes.into_iter().map(|r| (r, td_repo)));
+    }
+
+    Ok(())
+}
+
+/// Returns [`SetupError::TendrilConflict`] for the first
+/// [`Severity::Error`] finding from [`check_tendrils`] for the action `mode`.
+fn check_tendril_conflicts(
+    td_repo: &UniPath,
+    raw_tendrils: &[RawTendril],
+    mode: &ActionMode,
+) -> Result<(), SetupError> {
+    match check_tendrils(td_repo, raw_tendrils, Some(mode))
+        .into_iter()
+        .find(|f| f.severity == Severity::Error)
+    {
+        Some(f) => Err(SetupError::TendrilConflict(Box::new(f))),
+        None => Ok(()),
+    }
+}
+
 fn link_tendril(
     tendril: &Tendril,
     dry_run: bool,
@@ -583,10 +1622,10 @@ fn link_tendril(
             log.remote_type(),
             &target,
             &None,
-            false,
+            &mut Copier::new(tendril, None),
             dry_run,
             false,
-        ) {
+        ).result {
             log.result = Err(e);
             return log;
         };
@@ -597,14 +1636,215 @@ fn link_tendril(
         local_type = log.local_type();
     }
 
+    if let Err(e) = check_symlink_types(local_type, log.remote_type(), force) {
+        log.result = Err(e);
+        return log;
+    }
+    let old_link = link_status(log.resolved_path(), target, tendril.local());
+    if let Some((LinkStatus::Linked, _)) = old_link {
+        let link_target =
+            link_target(log.resolved_path(), target, tendril.relative_link);
+        let is_same_form = read_link(log.resolved_path())
+            .is_ok_and(|p| p.is_relative() == link_target.is_relative());
+        if is_same_form {
+            log.result = Ok(TendrilActionSuccess::AlreadyLinked);
+            return log;
+        }
+    }
+
     log.result = symlink(
         log.resolved_path(),
         log.remote_type(),
-        &target,
+        target,
         local_type,
+        tendril.relative_link,
+        dry_run,
+        force,
+    ).map(|r| with_old_link(r, old_link));
+
+    log
+}
+
+/// Replaces the symlink at the remote of the `tendril` if it does not point
+/// to the local (see [`LinkStatus`]). Correct symlinks are left as-is, even
+/// if they are not in the form given by [`Tendril::relative_link`]. Unlike
+/// [`link_tendril`], remotes that are not symlinks are never replaced, and
+/// the local is never copied from the remote.
+fn relink_tendril(
+    tendril: &Tendril,
+    dry_run: bool,
+    force: bool,
+) -> ActionLog {
+    let target = tendril.local_abs();
+    let create_at = tendril.remote().inner();
+
+    let mut log = ActionLog::new(
+        target.get_type(),
+        create_at.get_type(),
+        create_at.to_path_buf(),
+        Ok(TendrilActionSuccess::New), // Init only value
+    );
+    if tendril.mode != TendrilMode::Link {
+        log.result = Err(TendrilActionError::ModeMismatch);
+        return log;
+    }
+
+    let old_link = match link_status(create_at, target, tendril.local()) {
+        Some((LinkStatus::Linked, _)) => {
+            log.result = Ok(TendrilActionSuccess::AlreadyLinked);
+            return log;
+        }
+        Some(v) => v,
+        None => {
+            log.result = match log.remote_type() {
+                None => Err(TendrilActionError::IoError {
+                    kind: std::io::ErrorKind::NotFound,
+                    loc: Location::Dest,
+                }),
+                Some(t) => Err(TendrilActionError::TypeMismatch {
+                    mistype: t.clone(),
+                    loc: Location::Dest,
+                }),
+            };
+            return log;
+        }
+    };
+
+    log.result = symlink(
+        log.resolved_path(),
+        log.remote_type(),
+        target,
+        log.local_type(),
+        tendril.relative_link,
         dry_run,
         force,
+    ).map(|r| with_old_link(r, Some(old_link)));
+
+    log
+}
+
+/// Replaces an overwrite `result` with the matching relink variant if the
+/// `old_link` that was overwritten did not point to the local.
+fn with_old_link(
+    result: TendrilActionSuccess,
+    old_link: Option<(LinkStatus, PathBuf)>,
+) -> TendrilActionSuccess {
+    match (result, old_link) {
+        (TendrilActionSuccess::Overwrite, Some((status, old_target)))
+            if status != LinkStatus::Linked => {
+            TendrilActionSuccess::Relinked { old_target, status }
+        }
+        (TendrilActionSuccess::OverwriteSkipped, Some((status, old_target)))
+            if status != LinkStatus::Linked => {
+            TendrilActionSuccess::RelinkSkipped { old_target, status }
+        }
+        (r, _) => r,
+    }
+}
+
+/// Creates a hard link at the remote of the `tendril` to its local file. If
+/// the local does not exist, it is created as a hard link to the remote
+/// instead. A remote that is already a hard link to the local is left as-is.
+/// Any other file at the remote is only replaced if `force` is `true`, as it
+/// may contain changes that are not in the local (i.e. if an app replaced the
+/// hard link with a new file when saving). If the `tendril` has a
+/// [`copy_fallback`](Tendril::copy_fallback) and the remote is on a different
+/// file system, the remote is expected to be a copy, so it is refreshed with
+/// the local regardless.
+fn hardlink_tendril(
+    tendril: &Tendril,
+    dry_run: bool,
+    force: bool,
+) -> ActionLog {
+    let target = tendril.local_abs();
+    let create_at = tendril.remote().inner();
+
+    let mut log = ActionLog::new(
+        target.get_type(),
+        create_at.get_type(),
+        create_at.to_path_buf(),
+        Ok(TendrilActionSuccess::New), // Init only value
     );
+    if tendril.mode != TendrilMode::Hardlink {
+        log.result = Err(TendrilActionError::ModeMismatch);
+        return log;
+    }
+
+    match (log.local_type(), log.remote_type()) {
+        (Some(FsoType::File), _) => {}
+        (None, Some(FsoType::File)) => {
+            // Local does not exist - link it to the remote instead
+            log.result = if dry_run {
+                Ok(TendrilActionSuccess::OverwriteSkipped)
+            }
+            else {
+                create_dir_all(target.parent().unwrap_or(target))
+                    .and_then(|_| {
+                        hard_link_or_copy(
+                            create_at,
+                            target,
+                            tendril.copy_fallback,
+                        )
+                    })
+                    .map(|copied| match copied {
+                        true => TendrilActionSuccess::CopiedInsteadOfLinked,
+                        false => TendrilActionSuccess::Overwrite,
+                    })
+                    .map_err(|e| TendrilActionError::IoError {
+                        kind: e.kind(),
+                        loc: Location::Source,
+                    })
+            };
+            return log;
+        }
+        (None, None) => {
+            log.result = Err(TendrilActionError::IoError {
+                kind: std::io::ErrorKind::NotFound,
+                loc: Location::Source,
+            });
+            return log;
+        }
+        (None, Some(t)) => {
+            log.result = Err(TendrilActionError::TypeMismatch {
+                mistype: t.clone(),
+                loc: Location::Dest,
+            });
+            return log;
+        }
+        (Some(t), _) => {
+            log.result = Err(TendrilActionError::TypeMismatch {
+                mistype: t.clone(),
+                loc: Location::Source,
+            });
+            return log;
+        }
+    }
+
+    log.result = match log.remote_type() {
+        Some(FsoType::File)
+            if is_same_file(target, create_at).unwrap_or(false) => {
+            Ok(TendrilActionSuccess::AlreadyLinked)
+        }
+        Some(FsoType::File)
+            if tendril.copy_fallback
+            && is_on_other_device(target, create_at) => {
+            hardlink(create_at, log.remote_type(), target, true, dry_run)
+        }
+        Some(t @ (FsoType::File | FsoType::Dir | FsoType::SymDir))
+            if !force => {
+            Err(TendrilActionError::TypeMismatch {
+                mistype: t.clone(),
+                loc: Location::Dest,
+            })
+        }
+        _ => hardlink(
+            create_at,
+            log.remote_type(),
+            target,
+            tendril.copy_fallback,
+            dry_run,
+        ),
+    };
 
     log
 }
@@ -616,13 +1856,13 @@ fn list_tendrils_inner(
     let mut reports = Vec::with_capacity(raw_tendrils.len());
 
     for raw_tendril in raw_tendrils {
-        let log = match raw_tendril.resolve(&td_repo) {
+        let log = match raw_tendril.resolve(&td_repo, None) {
             Ok(v) => {
                 Ok(ListLog::new(
                     v.local_abs().get_type(),
                     v.remote().inner().get_type(),
                     v.remote().inner().into()
-                ))
+                ).with_remote_idx(v.remote_idx()))
             }
             Err(e) => Err(e),
         };
@@ -637,10 +1877,23 @@ fn list_tendrils_inner(
 }
 
 
+/// Same as [`pull_tendril_inner`], without reporting any progress.
+#[cfg(test)]
 fn pull_tendril(
     tendril: &Tendril,
     dry_run: bool,
     force: bool,
+) -> ActionLog {
+    pull_tendril_inner(tendril, dry_run, force, None)
+}
+
+/// Reports the [`CopyProgress`] of the copy to the `progress` callback, if
+/// there is one.
+fn pull_tendril_inner(
+    tendril: &Tendril,
+    dry_run: bool,
+    force: bool,
+    progress: Option<&mut dyn FnMut(CopyProgress)>,
 ) -> ActionLog {
     let dest = tendril.local_abs();
     let source = tendril.remote().inner();
@@ -652,29 +1905,42 @@ fn pull_tendril(
         Ok(TendrilActionSuccess::New), // Init only value
     );
 
-    if tendril.mode == TendrilMode::Link {
+    if tendril.mode.is_link_type() {
         log.result = Err(TendrilActionError::ModeMismatch);
         return log;
     }
 
-    let dir_merge = tendril.mode == TendrilMode::DirMerge;
-    log.result = copy_fso(
+    let copied = copy_fso(
         log.resolved_path(),
         log.remote_type(),
         &dest,
         log.local_type(),
-        dir_merge,
+        &mut Copier::new(tendril, progress),
         dry_run,
         force,
     );
-
-    log
+    log.result = copied.result;
+    log.with_copy_method(copied.method)
+        .with_outside_symlinks(copied.outside_symlinks)
 }
 
+/// Same as [`push_tendril_inner`], without reporting any progress.
+#[cfg(test)]
 fn push_tendril(
     tendril: &Tendril,
     dry_run: bool,
     force: bool,
+) -> ActionLog {
+    push_tendril_inner(tendril, dry_run, force, None)
+}
+
+/// Reports the [`CopyProgress`] of the copy to the `progress` callback, if
+/// there is one.
+fn push_tendril_inner(
+    tendril: &Tendril,
+    dry_run: bool,
+    force: bool,
+    progress: Option<&mut dyn FnMut(CopyProgress)>,
 ) -> ActionLog {
     let source = tendril.local_abs();
     let dest = tendril.remote().inner();
@@ -685,23 +1951,23 @@ fn push_tendril(
         dest.to_path_buf(),
         Ok(TendrilActionSuccess::New), // Init only value
     );
-    if tendril.mode == TendrilMode::Link {
+    if tendril.mode.is_link_type() {
         log.result = Err(TendrilActionError::ModeMismatch);
         return log;
     }
 
-    let dir_merge = tendril.mode == TendrilMode::DirMerge;
-    log.result = copy_fso(
+    let copied = copy_fso(
         &source,
         log.local_type(),
         log.resolved_path(),
         log.remote_type(),
-        dir_merge,
+        &mut Copier::new(tendril, progress),
         dry_run,
         force,
     );
-
-    log
+    log.result = copied.result;
+    log.with_copy_method(copied.method)
+        .with_outside_symlinks(copied.outside_symlinks)
 }
 
 /// Returns [`Err(TendrilActionError::TypeMismatch)`](TendrilActionError::TypeMismatch)
@@ -753,16 +2019,21 @@ fn check_symlink_types(
     }
 }
 
+/// Creates a symlink at `create_at` that points to the `target`, replacing
+/// anything that is already at `create_at`. If `relative` is `true`, the
+/// symlink is relative (see [`link_target`]).
 fn symlink(
     create_at: &Path,
     create_at_type: &Option<FsoType>,
     target: &Path,
     target_type: &Option<FsoType>,
+    relative: bool,
     dry_run: bool,
     force: bool,
 ) -> Result<TendrilActionSuccess, TendrilActionError> {
     check_symlink_types(target_type, create_at_type, force)?;
 
+    let link_target = link_target(create_at, target, relative);
     let del_result = match (dry_run, &create_at_type) {
         (true, Some(_)) => return Ok(TendrilActionSuccess::OverwriteSkipped),
         (true, None) => return Ok(TendrilActionSuccess::NewSkipped),
@@ -793,9 +2064,9 @@ fn symlink(
     };
 
     #[cfg(windows)]
-    let sym_result = symlink_win(create_at, target);
+    let sym_result = symlink_win(create_at, target, &link_target);
     #[cfg(unix)]
-    let sym_result = symlink_unix(create_at, target);
+    let sym_result = symlink_unix(create_at, &link_target);
     match sym_result {
         Err(TendrilActionError::IoError { kind: k, loc: _ }) => {
             Err(TendrilActionError::IoError { kind: k, loc: Location::Dest })
@@ -811,6 +2082,111 @@ fn symlink(
     }
 }
 
+/// Creates a hard link at `create_at` to the `target` file, replacing
+/// whatever is at `create_at`. The hard link is created next to `create_at`
+/// first and then moved into place, so that `create_at` is left as-is if the
+/// hard link cannot be created. Returns
+/// [`TendrilActionSuccess::CopiedInsteadOfLinked`] if `target` was copied
+/// because of the `copy_fallback`.
+fn hardlink(
+    create_at: &Path,
+    create_at_type: &Option<FsoType>,
+    target: &Path,
+    copy_fallback: bool,
+    dry_run: bool,
+) -> Result<TendrilActionSuccess, TendrilActionError> {
+    let to_dest_err = |e: std::io::Error| TendrilActionError::IoError {
+        kind: e.kind(),
+        loc: Location::Dest,
+    };
+    match (dry_run, create_at_type) {
+        (true, Some(_)) => return Ok(TendrilActionSuccess::OverwriteSkipped),
+        (true, None) => return Ok(TendrilActionSuccess::NewSkipped),
+        (false, None) => {
+            let copied = create_dir_all(create_at.parent().unwrap_or(create_at))
+                .and_then(|_| {
+                    hard_link_or_copy(target, create_at, copy_fallback)
+                })
+                .map_err(to_dest_err)?;
+            return match copied {
+                true => Ok(TendrilActionSuccess::CopiedInsteadOfLinked),
+                false => Ok(TendrilActionSuccess::New),
+            };
+        }
+        (false, Some(_)) => {}
+    }
+
+    let mut tmp_name = OsString::from(".");
+    tmp_name.push(create_at.file_name().unwrap_or_default());
+    tmp_name.push(".tendrils-tmp");
+    let tmp = create_at.with_file_name(tmp_name);
+    let _ = remove_file(&tmp);
+    let copied =
+        hard_link_or_copy(target, &tmp, copy_fallback).map_err(to_dest_err)?;
+
+    let del_result = match create_at_type {
+        Some(FsoType::BrokenSym) => remove_symlink(create_at),
+        Some(FsoType::Dir | FsoType::SymDir) => remove_dir_all(create_at),
+        // Files and symlinks to files are replaced when renaming
+        _ => Ok(()),
+    };
+    if let Err(e) = del_result.and_then(|_| std::fs::rename(&tmp, create_at)) {
+        let _ = remove_file(&tmp);
+        return Err(to_dest_err(e));
+    }
+
+    match copied {
+        true => Ok(TendrilActionSuccess::CopiedInsteadOfLinked),
+        false => Ok(TendrilActionSuccess::Overwrite),
+    }
+}
+
+/// Creates a hard link at `to` to the `from` file. If `copy_fallback` is
+/// `true`, `from` is copied instead if the two are on different file
+/// systems. Returns whether it was copied.
+fn hard_link_or_copy(
+    from: &Path,
+    to: &Path,
+    copy_fallback: bool,
+) -> std::io::Result<bool> {
+    match std::fs::hard_link(from, to) {
+        Err(e) if copy_fallback
+            && e.kind() == std::io::ErrorKind::CrossesDevices => {
+            std::fs::copy(from, to).map(|_| true)
+        }
+        result => result.map(|_| false),
+    }
+}
+
+/// Returns `true` if the `local` and the `remote` are on different file
+/// systems, in which case they cannot be hard linked. If either does not
+/// exist, its nearest existing parent folder is checked instead.
+#[cfg(unix)]
+fn is_on_other_device(local: &Path, remote: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    let device = |path: &Path| {
+        path.ancestors().find_map(|p| p.metadata().ok()).map(|md| md.dev())
+    };
+    match (device(local), device(remote)) {
+        (Some(l), Some(r)) => l != r,
+        _ => false,
+    }
+}
+
+/// Returns `true` if the `local` and the `remote` are on different drives,
+/// in which case they cannot be hard linked.
+#[cfg(windows)]
+fn is_on_other_device(local: &Path, remote: &Path) -> bool {
+    let drive = |path: &Path| match path.components().next() {
+        Some(std::path::Component::Prefix(p)) => {
+            Some(p.as_os_str().to_ascii_lowercase())
+        }
+        _ => None,
+    };
+    drive(local) != drive(remote)
+}
+
 #[cfg(unix)]
 fn symlink_unix(
     create_at: &Path,
@@ -821,23 +2197,117 @@ fn symlink_unix(
     Ok(())
 }
 
+/// The `link_target` is what the symlink points to, which may be relative to
+/// the parent folder of `create_at`. The `target` is its absolute form, which
+/// is used to determine the type of symlink to create.
 #[cfg(windows)]
 fn symlink_win(
     create_at: &Path,
     target: &Path,
+    link_target: &Path,
 ) -> Result<(), TendrilActionError> {
     use std::os::windows::fs::{symlink_dir, symlink_file};
 
     if target.is_dir() {
-        symlink_dir(target, create_at)?;
+        symlink_dir(link_target, create_at)?;
     }
     else {
-        symlink_file(target, create_at)?;
+        symlink_file(link_target, create_at)?;
     }
 
     Ok(())
 }
 
+/// Returns the path that a symlink at `create_at` should point to in order to
+/// reach the `target`. If `relative` is `true`, this is the path from the
+/// parent folder of `create_at` to the `target`, with any symlinks in both of
+/// their parent folders resolved as far as they exist. Otherwise, or if there
+/// is no such relative path (i.e. they are on different drives on Windows),
+/// the `target` is returned as-is.
+fn link_target(create_at: &Path, target: &Path, relative: bool) -> PathBuf {
+    if !relative {
+        return target.to_path_buf();
+    }
+
+    let (Some(create_at_dir), Some(target_dir), Some(target_name)) =
+        (create_at.parent(), target.parent(), target.file_name())
+    else {
+        return target.to_path_buf();
+    };
+    canonicalize_existing(target_dir)
+        .join(target_name)
+        .relative_from(&canonicalize_existing(create_at_dir))
+        .unwrap_or_else(|| target.to_path_buf())
+}
+
+/// Returns where the symlink at `link` points, along with its [`LinkStatus`]
+/// compared to the `target`, or `None` if `link` is not a symlink. The
+/// `local` is the path of the `target` relative to its Tendrils repo, which
+/// is used to recognize symlinks to an old location of the repo. The
+/// returned path is absolute, even if the symlink is relative to its parent
+/// folder.
+///
+/// A symlink points to the `target` if both paths are the same once any
+/// symlinks in their parent folders are resolved as far as they exist, so
+/// this accepts absolute and relative symlinks alike. The last component is
+/// not resolved, so a symlink to wherever the `target` itself links to does
+/// not count.
+fn link_status(
+    link: &Path,
+    target: &Path,
+    local: &Path,
+) -> Option<(LinkStatus, PathBuf)> {
+    let dest = read_link(link).ok()?;
+    let dest = match link.parent() {
+        Some(parent) if dest.is_relative() => parent.join(dest).normalize(),
+        _ => dest,
+    };
+
+    let status = if canonicalize_parent(&dest) == canonicalize_parent(target) {
+        LinkStatus::Linked
+    }
+    else if is_old_repo_target(&dest, local) {
+        LinkStatus::OldRepo
+    }
+    else if dest.get_type().is_none() {
+        LinkStatus::Broken
+    }
+    else {
+        LinkStatus::WrongTarget
+    };
+    Some((status, dest))
+}
+
+/// Canonicalizes the parent folder of the `path` as far as it exists (see
+/// [`canonicalize_existing`]), and appends the last component as-is.
+fn canonicalize_parent(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => canonicalize_existing(parent).join(name),
+        _ => canonicalize_existing(path),
+    }
+}
+
+/// Returns `true` if the `dest` is the same `local` in another Tendrils
+/// repo, i.e. if it ends with the `local` and the rest of it is a known
+/// Tendrils repo. That is either an existing Tendrils repo, or one of the
+/// default repos in the `global-config.json` file, which covers repos that
+/// have since been moved away from there.
+fn is_old_repo_target(dest: &Path, local: &Path) -> bool {
+    if local.as_os_str().is_empty() || !dest.ends_with(local) {
+        return false;
+    }
+
+    let mut old_repo = dest.to_path_buf();
+    for _ in local.components() {
+        old_repo.pop();
+    }
+    let old_repo = UniPath::from(old_repo);
+    is_tendrils_repo(&old_repo)
+        || get_global_config().is_ok_and(|cfg| {
+            cfg.default_repo_paths().iter().any(|p| UniPath::from(p) == old_repo)
+        })
+}
+
 fn batch_tendril_action<U>(
     mut updater: U,
     mode: ActionMode,
@@ -849,29 +2319,60 @@ fn batch_tendril_action<U>(
 where
     U: UpdateHandler<ActionLog>,
 {
-    let can_symlink =
-        (mode == ActionMode::Link || mode == ActionMode::Out) && can_symlink();
+    let can_symlink = (
+        mode == ActionMode::Link
+        || mode == ActionMode::Out
+        || mode == ActionMode::Relink
+    ) && can_symlink();
 
     updater.count(raw_tendrils.len() as i32);
+    let wants_progress = updater.wants_progress();
 
     for raw_tendril in raw_tendrils.into_iter() {
         updater.before(raw_tendril.clone());
-        let tendril = raw_tendril.resolve(td_repo);
+        let tendril = raw_tendril.resolve(td_repo, Some(&mode));
+        let mut report_progress = |p| updater.progress(p);
+        let progress: Option<&mut dyn FnMut(CopyProgress)> = match wants_progress {
+            true => Some(&mut report_progress),
+            false => None,
+        };
 
         let log = match (tendril, &mode, can_symlink) {
             (Ok(v), ActionMode::Pull, _) => {
-                Ok(pull_tendril(&v, dry_run, force))
+                Ok(pull_tendril_inner(
+                    &v,
+                    dry_run,
+                    force,
+                    progress,
+                ))
             }
             (Ok(v), ActionMode::Push, _) => {
-                Ok(push_tendril(&v, dry_run, force))
+                Ok(push_tendril_inner(
+                    &v,
+                    dry_run,
+                    force,
+                    progress,
+                ))
+            }
+            (Ok(v), ActionMode::Out, _) if !v.mode.is_link_type() => {
+                Ok(push_tendril_inner(
+                    &v,
+                    dry_run,
+                    force,
+                    progress,
+                ))
             }
-            (Ok(v), ActionMode::Out, _) if v.mode != TendrilMode::Link => {
-                Ok(push_tendril(&v, dry_run, force))
+            (Ok(v), ActionMode::Out | ActionMode::Link, _)
+                if v.mode == TendrilMode::Hardlink => {
+                Ok(hardlink_tendril(&v, dry_run, force))
             }
             (Ok(v), ActionMode::Out | ActionMode::Link, true) => {
                 Ok(link_tendril(&v, dry_run, force))
             }
-            (Ok(v), ActionMode::Link | ActionMode::Out, false) => {
+            (Ok(v), ActionMode::Relink, true) => {
+                Ok(relink_tendril(&v, dry_run, force))
+            }
+            (Ok(v), ActionMode::Link | ActionMode::Out | ActionMode::Relink, false) => {
                 // Do not attempt to symlink if it has already been
                 // determined that the process
                 // does not have the required permissions.
@@ -1,7 +1,7 @@
-use crate::enums::FsoType;
-use crate::env_ext::get_home_dir;
+use crate::enums::{FsoType, InvalidTendrilError};
+use crate::env_ext::{get_home_dir, get_user_home_dir, get_xdg_default};
 use std::ffi::OsString;
-use std::path::{Path, PathBuf, MAIN_SEPARATOR_STR};
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR_STR};
 
 #[cfg(test)]
 mod tests;
@@ -24,28 +24,57 @@ pub(crate) trait PathExt {
     /// Replaces all forward slashes (`/`) with backslashes (`\`).
     fn replace_dir_seps(&self) -> PathBuf;
 
-    /// Replaces the first instance of `~` with the `HOME` variable
+    /// Replaces a leading `~` with the `HOME` variable
     /// and returns the replaced string. If `HOME` doesn't exist,
     /// `HOMEDRIVE` and `HOMEPATH` will be combined provided they both exist,
     /// otherwise it returns `self`. This fallback is mainly a Windows specific
     /// issue, but is supported on all platforms either way.
+    ///
+    /// A leading `~username` is replaced with the home directory of that user
+    /// as listed in the system user database. If the user cannot be found,
+    /// or on Windows, it returns `self`. See [`PathExt::try_resolve_tilde`]
+    /// to treat this as an error instead.
+    ///
+    /// In both cases the tilde must be followed by a directory separator
+    /// (`/` or `\`) or the end of the path.
     fn resolve_tilde(&self) -> PathBuf;
 
-    /// Replaces all environment variables in the format `<varname>` in the
-    /// given path with their values. If the variable is not found, the
-    /// `<varname>` is left as-is in the path.
+    /// Same as [`PathExt::resolve_tilde`] except it returns
+    /// [`InvalidTendrilError::UnknownUser`] if a leading `~username` cannot
+    /// be resolved.
+    fn try_resolve_tilde(&self) -> Result<PathBuf, InvalidTendrilError>;
+
+    /// Lexically removes any `.` components, and removes any `..` components
+    /// along with the component before them. A `..` directly after the root
+    /// is removed since it cannot go any higher. Other than that, the path is
+    /// unchanged, including any repeated or trailing directory separators.
+    /// This does not access the file system, so it does not account for
+    /// symlinks. Verbatim paths (`\\?\`) are returned unchanged on Windows.
+    fn normalize(&self) -> PathBuf;
+
+    /// Replaces all environment variables in the given path with their
+    /// values. The supported forms are:
+    /// - `<NAME>`
+    /// - `$NAME`, where the name only contains ASCII letters, digits and
+    ///   underscores, and does not start with a digit
+    /// - `${NAME}`
+    /// - `${NAME:-default}`, which uses the `default` if the variable is
+    ///   unset or empty
+    /// - `${NAME:?message}`, which is only resolved if the variable is set
+    ///   and not empty
     ///
-    /// # Limitations
-    /// If the path contains the `<pattern>` and the pattern corresponds to
-    /// an environment variable, there is no way to escape the brackets
-    /// to force it to use the raw path. This should only be an issue
-    /// on Unix (as Windows doesn't allow `<` or `>` in paths anyways),
-    /// and only when the variable exists (otherwise it uses the raw
-    /// path). A work-around is to set the variable value to `<pattern>`.
-    /// In the future, an escape character such as `|` could be
-    /// implemented, but this added complexity was avoided for now.
+    /// A literal `<` or `$` can be written as `<<` or `$$` respectively. A `<`
+    /// or `$` that does not start one of the forms above is also kept as-is.
+    /// If a variable cannot be resolved, it is left as-is in the path. See
+    /// [`PathExt::try_resolve_env_variables`] to treat this as an error
+    /// instead.
     fn resolve_env_variables(&self) -> PathBuf;
 
+    /// Same as [`PathExt::resolve_env_variables`] except it returns
+    /// [`InvalidTendrilError::UnresolvedVariable`] for the first variable that
+    /// cannot be resolved.
+    fn try_resolve_env_variables(&self) -> Result<PathBuf, InvalidTendrilError>;
+
     /// Converts a non-rooted path to rooted by prepending it with the given
     /// `root`. If the given `root` is not rooted either, then the default root
     /// of `/` on Unix or `\` on Windows is used. Returns `self` if the path is
@@ -53,6 +82,14 @@ pub(crate) trait PathExt {
     /// `C:\Path` and `\\MyServer\Share\Path` are rooted on Windows but not on
     /// Unix. This function does *not* take the current directory into account.
     fn root(&self, root: &Path) -> PathBuf;
+
+    /// Returns the relative path that leads from the `base` folder to `self`,
+    /// using `..` components to step out of the `base` where needed. Both
+    /// paths should be absolute and normalized. This does not access the
+    /// file system, so it does not account for symlinks. Returns `None` if
+    /// only one of the paths is absolute, or if they have a different prefix
+    /// (i.e. they are on different drives on Windows).
+    fn relative_from(&self, base: &Path) -> Option<PathBuf>;
 }
 
 impl PathExt for Path {
@@ -129,70 +166,129 @@ impl PathExt for Path {
     }
 
     fn resolve_tilde(&self) -> PathBuf {
-        let path_bytes = self.as_os_str().as_encoded_bytes();
-
-        if path_bytes == &['~' as u8]
-            || path_bytes.starts_with(&['~' as u8, '/' as u8])
-            || path_bytes.starts_with(&['~' as u8, '\\' as u8]) {
-            // Continue
+        match self.try_resolve_tilde() {
+            Ok(v) => v,
+            Err(_) => PathBuf::from(self),
         }
-        else {
-            return PathBuf::from(self);
+    }
+
+    fn try_resolve_tilde(&self) -> Result<PathBuf, InvalidTendrilError> {
+        let path_bytes = self.as_os_str().as_encoded_bytes();
+        if path_bytes.first() != Some(&b'~') {
+            return Ok(PathBuf::from(self));
         }
 
-        match get_home_dir() {
+        let name_len = path_bytes[1..]
+            .iter()
+            .position(|b| *b == b'/' || *b == b'\\')
+            .unwrap_or(path_bytes.len() - 1);
+        let username = &path_bytes[1..=name_len];
+        let home_dir = match name_len {
+            0 => get_home_dir(),
+            _ => match get_user_home_dir(username) {
+                Some(v) => Some(v),
+                None => return Err(InvalidTendrilError::UnknownUser {
+                    username: String::from_utf8_lossy(username).to_string(),
+                }),
+            },
+        };
+
+        match home_dir {
             Some(mut v) => {
                 let trimmed_str;
                 unsafe {
-                    // All bytes were originally from an OsString so this call
-                    // is safe.
+                    // All bytes were originally from an OsString and the
+                    // split is at an ASCII character, so this call is safe.
                     trimmed_str = OsString::from_encoded_bytes_unchecked(
-                        path_bytes[1..].to_vec()
+                        path_bytes[1 + name_len..].to_vec()
                     );
                 }
 
                 v.push(trimmed_str);
-                PathBuf::from(v)
+                Ok(PathBuf::from(v))
             }
-            None => PathBuf::from(self),
+            None => Ok(PathBuf::from(self)),
         }
     }
 
-    fn resolve_env_variables(&self) -> PathBuf {
-        let given_bytes = self.as_os_str().as_encoded_bytes();
-        let mut search_start_idx = 0;
-        let mut resolved_bytes: Vec<u8> = vec![];
-
-        while let Some(next) = next_env_var(given_bytes, search_start_idx) {
-            let var_no_brkts = &given_bytes[next.0 + 1..next.1];
-            let var_name_no_brkts = unsafe {
-                // All bytes were originally from an OsString so this call
-                // is safe.
-                OsString::from_encoded_bytes_unchecked(var_no_brkts.to_vec())
-            };
-            if let Some(v) = std::env::var_os(var_name_no_brkts) {
-                resolved_bytes.extend(&given_bytes[search_start_idx..next.0]);
-                resolved_bytes.extend(v.as_encoded_bytes());
+    fn normalize(&self) -> PathBuf {
+        let bytes = self.as_os_str().as_encoded_bytes();
+        let prefix_len = match self.components().next() {
+            #[cfg(windows)]
+            Some(std::path::Component::Prefix(p)) => {
+                if p.kind().is_verbatim() {
+                    return PathBuf::from(self);
+                }
+                p.as_os_str().as_encoded_bytes().len()
             }
-            else {
-                resolved_bytes.extend(&given_bytes[search_start_idx..next.1 + 1]);
+            _ => 0,
+        };
+
+        let (prefix, rest) = bytes.split_at(prefix_len);
+        let root_len = rest
+            .iter()
+            .position(|b| !is_dir_sep(*b))
+            .unwrap_or(rest.len());
+        let (root, rest) = rest.split_at(root_len);
+        let is_rooted = !prefix.is_empty() || !root.is_empty();
+
+        // Each component along with the separators that follow it
+        let mut comps: Vec<(&[u8], &[u8])> = vec![];
+        let mut is_changed = false;
+        for (comp, seps) in split_dir_comps(rest) {
+            match comp {
+                b"." => is_changed = true,
+                b".." => {
+                    match comps.last() {
+                        Some((last, _)) if **last != *b".." => {
+                            comps.pop();
+                        }
+                        None if is_rooted => {}
+                        _ => {
+                            comps.push((comp, seps));
+                            continue;
+                        }
+                    }
+                    is_changed = true;
+                }
+                _ => comps.push((comp, seps)),
             }
-            search_start_idx = next.1 + 1;
         }
-
-        if search_start_idx == 0 {
+        if !is_changed {
             return PathBuf::from(self);
         }
-        else {
-            resolved_bytes.extend(&given_bytes[search_start_idx..]);
 
-            let resolved_str = unsafe {
-                OsString::from_encoded_bytes_unchecked(resolved_bytes)
-            };
-            PathBuf::from(resolved_str)
+        let mut normalized = Vec::with_capacity(bytes.len());
+        normalized.extend(prefix);
+        normalized.extend(root);
+        for (comp, seps) in comps {
+            normalized.extend(comp);
+            normalized.extend(seps);
+        }
+        if normalized.is_empty() {
+            normalized.push(b'.');
+        }
+
+        unsafe {
+            // All bytes were originally from an OsString, and were only split
+            // at ASCII directory separators, so this call is safe.
+            OsString::from_encoded_bytes_unchecked(normalized)
+        }.into()
+    }
+
+    fn resolve_env_variables(&self) -> PathBuf {
+        match resolve_vars_inner(self, false) {
+            Ok(v) => v,
+            // Unresolved variables are kept as-is rather than returned, so
+            // this is not expected, but the raw path is the safe fallback
+            Err(_) => PathBuf::from(self),
         }
     }
 
+    fn try_resolve_env_variables(&self) -> Result<PathBuf, InvalidTendrilError> {
+        resolve_vars_inner(self, true)
+    }
+
     fn root(&self, root: &Path) -> PathBuf {
         if self.has_root() {
             PathBuf::from(self)
@@ -204,43 +300,326 @@ impl PathExt for Path {
             Path::new(MAIN_SEPARATOR_STR).join_raw(self)
         }
     }
+
+    fn relative_from(&self, base: &Path) -> Option<PathBuf> {
+        if self.has_root() != base.has_root() {
+            return None;
+        }
+
+        let mut self_comps = self.components().peekable();
+        let mut base_comps = base.components().peekable();
+        while let (Some(a), Some(b)) = (self_comps.peek(), base_comps.peek()) {
+            if a != b {
+                break;
+            }
+            self_comps.next();
+            base_comps.next();
+        }
+
+        let mut relative = PathBuf::new();
+        for comp in base_comps {
+            match comp {
+                Component::Normal(_) => relative.push(".."),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        for comp in self_comps {
+            match comp {
+                Component::Prefix(_) | Component::RootDir => return None,
+                _ => relative.push(comp),
+            }
+        }
+        if relative.as_os_str().is_empty() {
+            relative.push(".");
+        }
+
+        Some(relative)
+    }
+}
+
+/// Returns `true` if the given byte is a directory separator on the current
+/// platform.
+fn is_dir_sep(b: u8) -> bool {
+    #[cfg(windows)]
+    return b == b'/' || b == b'\\';
+
+    #[cfg(not(windows))]
+    return b == b'/';
+}
+
+/// Splits the given (non-rooted) path bytes into each of its components, along
+/// with the directory separators that follow each of them.
+fn split_dir_comps(bytes: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut comps = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let comp_end = bytes[i..]
+            .iter()
+            .position(|b| is_dir_sep(*b))
+            .map_or(bytes.len(), |len| i + len);
+        let seps_end = bytes[comp_end..]
+            .iter()
+            .position(|b| !is_dir_sep(*b))
+            .map_or(bytes.len(), |len| comp_end + len);
+
+        comps.push((&bytes[i..comp_end], &bytes[comp_end..seps_end]));
+        i = seps_end;
+    }
+    comps
+}
+
+fn resolve_vars_inner(
+    path: &Path,
+    strict: bool,
+) -> Result<PathBuf, InvalidTendrilError> {
+    let given_bytes = path.as_os_str().as_encoded_bytes();
+    let tokens = tokenize_vars(given_bytes);
+    if tokens.iter().all(|t| matches!(t, PathToken::Text(_))) {
+        return Ok(PathBuf::from(path));
+    }
+
+    let mut resolved_bytes: Vec<u8> = Vec::with_capacity(given_bytes.len());
+    for token in tokens {
+        match token {
+            PathToken::Text(text) => resolved_bytes.extend(text),
+            PathToken::Escaped(raw) => resolved_bytes.push(raw[0]),
+            PathToken::Var(var) => match var.lookup() {
+                Some(v) => resolved_bytes.extend(v.as_encoded_bytes()),
+                None if strict => return Err(var.unresolved_err()),
+                None => resolved_bytes.extend(var.raw),
+            },
+        }
+    }
+
+    let resolved_str = unsafe {
+        // All bytes were originally from an OsString, or are ASCII
+        // characters from the original OsString, so this call is safe.
+        OsString::from_encoded_bytes_unchecked(resolved_bytes)
+    };
+    Ok(PathBuf::from(resolved_str))
+}
+
+/// A segment of a path, as split by [`tokenize_vars`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum PathToken<'a> {
+    /// Text that is used as-is.
+    Text(&'a [u8]),
+
+    /// An escaped `<` or `$`, written as `<<` or `$$`. Contains the raw
+    /// (two character) text.
+    Escaped(&'a [u8]),
+
+    /// A variable to be replaced with its value.
+    Var(PathVar<'a>),
+}
+
+/// A variable within a path. See [`PathExt::resolve_env_variables`] for the
+/// supported forms.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct PathVar<'a> {
+    /// The full text of the variable, including any brackets.
+    pub raw: &'a [u8],
+
+    /// The variable name, without any brackets.
+    pub name: &'a [u8],
+
+    /// What to do if the variable is unset (or empty).
+    pub fallback: VarFallback<'a>,
 }
 
-/// Returns the `(start index, end index)` of the next environment variable
-/// name, including the surrounding brackets, starting the search from the
-/// `search_start_idx`. Returns `None` if no variables remain at or after the
-/// start index.
-fn next_env_var(bytes: &[u8], search_start_idx: usize) -> Option<(usize, usize)> {
-    let mut var_start = 0;
-    let mut has_start = false;
+/// Indicates what to do if a [`PathVar`] is unset (or empty).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum VarFallback<'a> {
+    /// The variable is unresolved if it is unset. An empty value is still
+    /// used.
+    None,
+
+    /// The given default is used if the variable is unset or empty.
+    Default(&'a [u8]),
+
+    /// The variable is unresolved if it is unset or empty, and the given
+    /// message describes why it is required.
+    Required(&'a [u8]),
+}
 
-    for (i, b) in bytes[search_start_idx..].iter().enumerate() {
-        if *b == '<' as u8 {
-            var_start = i;
-            has_start = true;
+impl PathVar<'_> {
+    /// Returns the name as an [`OsString`].
+    pub fn name_os(&self) -> OsString {
+        unsafe {
+            // All bytes were originally from an OsString and the name is
+            // bounded by ASCII characters, so this call is safe.
+            OsString::from_encoded_bytes_unchecked(self.name.to_vec())
         }
-        else if *b == '>' as u8 && has_start {
-            return Some((search_start_idx + var_start, search_start_idx + i))
+    }
+
+    /// Returns the value of the environment variable (or the default),
+    /// or `None` if it cannot be resolved. The XDG base directories are
+    /// always resolved, using their [defaults](get_xdg_default) if unset.
+    pub fn lookup(&self) -> Option<OsString> {
+        let name = self.name_os();
+        let value = match (std::env::var_os(&name), get_xdg_default(&name)) {
+            // Unset or empty XDG base directories use their defaults
+            (Some(v), Some(d)) if v.is_empty() => Some(d),
+            (None, d) => d,
+            (v, _) => v,
+        };
+        match (&self.fallback, value) {
+            (VarFallback::None, v) => v,
+            (_, Some(v)) if !v.is_empty() => Some(v),
+            (VarFallback::Default(d), _) => Some(unsafe {
+                // As above
+                OsString::from_encoded_bytes_unchecked(d.to_vec())
+            }),
+            (VarFallback::Required(_), _) => None,
         }
     }
 
-    None
+    fn unresolved_err(&self) -> InvalidTendrilError {
+        let msg = match self.fallback {
+            VarFallback::Required(m) if !m.is_empty() => {
+                Some(String::from_utf8_lossy(m).to_string())
+            }
+            _ => None,
+        };
+
+        InvalidTendrilError::UnresolvedVariable {
+            name: self.name_os().to_string_lossy().to_string(),
+            msg,
+        }
+    }
+}
+
+/// Splits the given path into text, escapes and variables. See
+/// [`PathExt::resolve_env_variables`] for the supported forms.
+pub(crate) fn tokenize_vars(bytes: &[u8]) -> Vec<PathToken<'_>> {
+    let mut tokens = vec![];
+    let mut text_start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let next = match bytes[i] {
+            b'<' | b'$' if bytes.get(i + 1) == Some(&bytes[i]) => {
+                Some((PathToken::Escaped(&bytes[i..i + 2]), i + 2))
+            }
+            b'<' => next_angle_var(bytes, i),
+            b'$' if bytes.get(i + 1) == Some(&b'{') => next_braced_var(bytes, i),
+            b'$' => next_bare_var(bytes, i),
+            _ => None,
+        };
+
+        match next {
+            Some((token, end)) => {
+                if text_start < i {
+                    tokens.push(PathToken::Text(&bytes[text_start..i]));
+                }
+                tokens.push(token);
+                text_start = end;
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+
+    if text_start < bytes.len() {
+        tokens.push(PathToken::Text(&bytes[text_start..]));
+    }
+    tokens
+}
+
+/// Parses a `<NAME>` variable starting at the `start` index. Returns the
+/// token and the index after its end, or `None` if the `<` does not start a
+/// variable (i.e. if it is not closed before the next `<`, or the name is
+/// empty).
+fn next_angle_var(bytes: &[u8], start: usize) -> Option<(PathToken<'_>, usize)> {
+    let len = bytes[start + 1..].iter().position(|b| *b == b'<' || *b == b'>')?;
+    let end = start + 1 + len;
+    if bytes[end] != b'>' || len == 0 {
+        return None;
+    }
+
+    Some((
+        PathToken::Var(PathVar {
+            raw: &bytes[start..=end],
+            name: &bytes[start + 1..end],
+            fallback: VarFallback::None,
+        }),
+        end + 1,
+    ))
+}
+
+/// Parses a `${NAME}`, `${NAME:-default}` or `${NAME:?message}` variable
+/// starting at the `start` index. Returns the token and the index after its
+/// end, or `None` if it is not closed or the name is empty.
+fn next_braced_var(bytes: &[u8], start: usize) -> Option<(PathToken<'_>, usize)> {
+    let content_start = start + 2;
+    let len = bytes[content_start..].iter().position(|b| *b == b'}')?;
+    let end = content_start + len;
+    let content = &bytes[content_start..end];
+
+    let (name, fallback) = match content.windows(2).position(|w| w[0] == b':') {
+        Some(idx) if content[idx + 1] == b'-' => {
+            (&content[..idx], VarFallback::Default(&content[idx + 2..]))
+        }
+        Some(idx) if content[idx + 1] == b'?' => {
+            (&content[..idx], VarFallback::Required(&content[idx + 2..]))
+        }
+        _ => (content, VarFallback::None),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((
+        PathToken::Var(PathVar { raw: &bytes[start..=end], name, fallback }),
+        end + 1,
+    ))
+}
+
+/// Parses a `$NAME` variable starting at the `start` index. Returns the token
+/// and the index after its end, or `None` if the `$` is not followed by a
+/// valid name.
+fn next_bare_var(bytes: &[u8], start: usize) -> Option<(PathToken<'_>, usize)> {
+    let name_start = start + 1;
+    match bytes.get(name_start) {
+        Some(b) if b.is_ascii_alphabetic() || *b == b'_' => {}
+        _ => return None,
+    }
+
+    let len = bytes[name_start..]
+        .iter()
+        .position(|b| !(b.is_ascii_alphanumeric() || *b == b'_'))
+        .unwrap_or(bytes.len() - name_start);
+    let end = name_start + len;
+
+    Some((
+        PathToken::Var(PathVar {
+            raw: &bytes[start..end],
+            name: &bytes[name_start..end],
+            fallback: VarFallback::None,
+        }),
+        end,
+    ))
 }
 
 #[cfg(test)]
 pub fn contains_env_var(input: &Path) -> bool {
-    next_env_var(input.as_os_str().as_encoded_bytes(), 0).is_some()
+    tokenize_vars(input.as_os_str().as_encoded_bytes())
+        .iter()
+        .any(|t| matches!(t, PathToken::Var(_)))
 }
 
 /// A [`PathBuf`] wrapper that guarantees that the path has been resolved in
 /// this particular order:
-///     1. Any environment variables have been resolved
+///     1. Any environment variables have been resolved (see
+/// [`PathExt::resolve_env_variables`])
 ///     2. A leading tilde has been resolved
 ///     3. A non-rooted path has been rooted. The default conversion to rooted
 /// occurs by prepending `/` on Unix or `\` on Windows. A different root can be
 /// provided by constructing the [`UniPath`] using [`UniPath::new_with_root`].
 ///     4. Unix style path separators (`/`) have been replaced with `\` (Windows
 /// only)
+///     5. Any `.` and `..` components have been [normalized](PathExt::normalize)
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UniPath(PathBuf);
 
@@ -250,21 +629,42 @@ impl UniPath {
     /// `root`. If the given `root` is not absolute either, it will default to
     /// using `/` on Unix and `\` on Windows.
     pub fn new_with_root(path: &Path, root: &Path) -> Self {
+        Self::from_tilde_resolved(
+            &path.resolve_env_variables().resolve_tilde(),
+            root,
+        )
+    }
+
+    /// Same as [`UniPath::from`], except it returns
+    /// [`InvalidTendrilError::UnresolvedVariable`] if any environment
+    /// variables cannot be resolved, or
+    /// [`InvalidTendrilError::UnknownUser`] if a leading `~username` cannot be
+    /// resolved, rather than leaving them in the path.
+    pub(crate) fn try_from_strict(
+        path: &Path,
+    ) -> Result<Self, InvalidTendrilError> {
+        Ok(Self::from_tilde_resolved(
+            &path.try_resolve_env_variables()?.try_resolve_tilde()?,
+            Path::new(MAIN_SEPARATOR_STR),
+        ))
+    }
+
+    /// Applies the remaining steps to a path whose environment variables and
+    /// tilde have already been resolved.
+    fn from_tilde_resolved(path: &Path, root: &Path) -> Self {
         #[cfg(windows)]
         return UniPath(
             path
-                .resolve_env_variables()
-                .resolve_tilde()
                 .root(root)
                 .replace_dir_seps()
+                .normalize()
         );
 
         #[cfg(not(windows))]
         return UniPath(
             path
-                .resolve_env_variables()
-                .resolve_tilde()
                 .root(root)
+                .normalize()
         );
     }
 
@@ -1,8 +1,12 @@
 mod join_raw_tests;
+mod normalize_tests;
+mod relative_from_tests;
 #[cfg(windows)]
 mod replace_dir_seps_tests;
 mod resolve_env_variable_tests;
 mod resolve_tilde_tests;
 mod root_tests;
+mod try_resolve_env_variables_tests;
+mod try_resolve_tilde_tests;
 mod test_paths;
 mod uni_path_tests;
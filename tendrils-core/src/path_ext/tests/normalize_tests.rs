@@ -0,0 +1,89 @@
+use crate::path_ext::PathExt;
+use crate::test_utils::non_utf_8_text;
+use rstest::rstest;
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+#[rstest]
+#[case("")]
+#[case("/")]
+#[case("Plain")]
+#[case("/some/path")]
+#[case("/trailing/sep/")]
+#[case("//repeated//seps//")]
+#[case("...")]
+#[case("/.../path")]
+#[case("/.hidden/..hidden/hidden..")]
+#[case("../relative")]
+#[case("../../relative")]
+fn nothing_to_normalize_returns_given(#[case] given: PathBuf) {
+    let actual = given.normalize();
+
+    assert_eq!(actual.as_os_str(), given.as_os_str());
+}
+
+#[rstest]
+#[case(".", ".")]
+#[case("./", ".")]
+#[case("/.", "/")]
+#[case("/./", "/")]
+#[case("/..", "/")]
+#[case("/../..", "/")]
+#[case("/a/.", "/a/")]
+#[case("/a/./b", "/a/b")]
+#[case("/a/.//b", "/a/b")]
+#[case("/a/b/..", "/a/")]
+#[case("/a/b/../", "/a/")]
+#[case("/a/b/../c", "/a/c")]
+#[case("/a/b/../../c", "/c")]
+#[case("/a/../../c", "/c")]
+#[case("a/..", ".")]
+#[case("a/../b", "b")]
+#[case("./a", "a")]
+#[case("../a/..", "../")]
+#[case("../a/../b", "../b")]
+#[case("a/../../b", "../b")]
+#[case("/a//b//..//c", "/a//c")]
+#[case("//a/./b", "//a/b")]
+fn dots_are_normalized(#[case] given: PathBuf, #[case] expected: &str) {
+    let actual = given.normalize();
+
+    assert_eq!(actual.to_string_lossy(), expected);
+}
+
+#[rstest]
+#[cfg_attr(not(windows), case("/a\\.\\b", "/a\\.\\b"))]
+#[cfg_attr(not(windows), case("/a/..\\b", "/a/..\\b"))]
+#[cfg_attr(windows, case("\\a\\.\\b", "\\a\\b"))]
+#[cfg_attr(windows, case("\\a\\b\\..\\c", "\\a\\c"))]
+#[cfg_attr(windows, case("C:\\a\\..\\..\\b", "C:\\b"))]
+#[cfg_attr(windows, case("\\\\Server\\Share\\..\\b", "\\\\Server\\Share\\b"))]
+#[cfg_attr(windows, case("\\\\?\\C:\\a\\..\\b", "\\\\?\\C:\\a\\..\\b"))]
+fn only_platform_dir_seps_separate_components(
+    #[case] given: PathBuf,
+    #[case] expected: &str,
+) {
+    let actual = given.normalize();
+
+    assert_eq!(actual.to_string_lossy(), expected);
+}
+
+#[test]
+fn non_utf8_is_preserved() {
+    let mut given_str = OsString::from("/");
+    given_str.push(non_utf_8_text());
+    given_str.push("/./");
+    given_str.push(non_utf_8_text());
+    given_str.push("/Nested/..");
+    let given = PathBuf::from(given_str);
+
+    let mut expected_str = OsString::from("/");
+    expected_str.push(non_utf_8_text());
+    expected_str.push("/");
+    expected_str.push(non_utf_8_text());
+    expected_str.push("/");
+
+    let actual = given.normalize();
+
+    assert_eq!(actual.as_os_str(), expected_str);
+}
@@ -0,0 +1,59 @@
+use crate::path_ext::PathExt;
+use rstest::rstest;
+use std::path::{Path, PathBuf};
+
+#[rstest]
+#[case("/a/b/c", "/a/b", "c")]
+#[case("/a/b/c/d", "/a/b", "c/d")]
+#[case("/a/b", "/a/b/c", "..")]
+#[case("/a/b", "/a/b/c/d", "../..")]
+#[case("/a/x/y", "/a/b/c", "../../x/y")]
+#[case("/x", "/a/b", "../../x")]
+#[case("/a/b", "/", "a/b")]
+#[case("/", "/a/b", "../..")]
+#[case("a/b", "a/c", "../b")]
+fn returns_relative_path(
+    #[case] given: &str,
+    #[case] base: &str,
+    #[case] exp: PathBuf,
+) {
+    let actual = Path::new(given).relative_from(Path::new(base));
+
+    assert_eq!(actual, Some(exp));
+}
+
+#[rstest]
+#[case("/a/b")]
+#[case("/a/b/")]
+#[case("/a/./b")]
+fn same_path_returns_cur_dir(#[case] base: &str) {
+    let actual = Path::new("/a/b").relative_from(Path::new(base));
+
+    assert_eq!(actual, Some(PathBuf::from(".")));
+}
+
+#[rstest]
+#[case("/a/b", "a")]
+#[case("a/b", "/a")]
+fn only_one_absolute_returns_none(#[case] given: &str, #[case] base: &str) {
+    let actual = Path::new(given).relative_from(Path::new(base));
+
+    assert_eq!(actual, None);
+}
+
+#[test]
+fn base_with_parent_dir_after_common_part_returns_none() {
+    let actual = Path::new("/a/b").relative_from(Path::new("/a/c/../d"));
+
+    assert_eq!(actual, None);
+}
+
+#[cfg(windows)]
+#[rstest]
+#[case("C:\\a\\b", "D:\\a")]
+#[case("\\\\MyServer\\Share\\a", "C:\\a")]
+fn different_prefix_returns_none(#[case] given: &str, #[case] base: &str) {
+    let actual = Path::new(given).relative_from(Path::new(base));
+
+    assert_eq!(actual, None);
+}
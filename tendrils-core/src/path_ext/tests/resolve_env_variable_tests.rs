@@ -62,8 +62,8 @@ fn wrong_capitalization_of_var_name_returns_raw_path(#[case] given: PathBuf) {
 #[case("<mut-testing>LeadingVar", "valueLeadingVar")]
 #[case("Path with <mut-testing> spaces", "Path with value spaces")]
 #[case("TrailingVar<mut-testing>", "TrailingVarvalue")]
-#[case("nested<<mut-testing>>arrows", "nested<value>arrows")]
-#[case("offset<<mut-testing>arrows", "offset<valuearrows")]
+#[case("nested<<<mut-testing>>arrows", "nested<value>arrows")]
+#[case("offset<<<mut-testing>arrows", "offset<valuearrows")]
 #[case("offset<mut-testing>>arrows", "offsetvalue>arrows")]
 #[case("<mut-testing>/<mut-testing>", "value/value")]
 #[case("<mut-testing>/mut-testing", "value/mut-testing")]
@@ -263,4 +263,168 @@ fn var_value_is_absolute_path_adds_raw_value(#[case] var_value: &str) {
     assert_eq!(actual.to_string_lossy(), expected_str);
 }
 
+#[rstest]
+#[case("$mut_testing", "value")]
+#[case("some/$mut_testing/path", "some/value/path")]
+#[case("$mut_testing.txt", "value.txt")]
+#[case("$mut_testing-suffix", "value-suffix")]
+#[case("${mut_testing}", "value")]
+#[case("${mut_testing}suffix", "valuesuffix")]
+#[case("${mut-testing}", "value")]
+#[case("${mut_testing:-default}", "value")]
+#[case("${I_do_not_exist:-default}", "default")]
+#[case("${I_do_not_exist:-}", "")]
+#[case("${I_do_not_exist:-some/default/path}", "some/default/path")]
+#[case("${mut_testing:?Some message}", "value")]
+#[case("<mut_testing>/$mut_testing/${mut_testing}", "value/value/value")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn dollar_sign_vars_are_replaced_with_value(
+    #[case] given: PathBuf,
+    #[case] expected: PathBuf,
+) {
+    std::env::set_var("mut_testing", "value");
+    std::env::set_var("mut-testing", "value");
+    std::env::remove_var("I_do_not_exist");
+
+    let actual = given.resolve_env_variables();
+
+    assert_eq!(actual, expected);
+}
+
+#[rstest]
+#[case("${mut_empty}", "")]
+#[case("$mut_empty", "")]
+#[case("${mut_empty:-default}", "default")]
+#[case("${mut_empty:?Some message}", "${mut_empty:?Some message}")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn empty_var_value_uses_default_or_is_unresolved_if_required(
+    #[case] given: PathBuf,
+    #[case] expected: PathBuf,
+) {
+    std::env::set_var("mut_empty", "");
+
+    let actual = given.resolve_env_variables();
+
+    assert_eq!(actual, expected);
+}
+
+#[rstest]
+#[case("$")]
+#[case("$$")]
+#[case("$1abc")]
+#[case("C$\\Windows")]
+#[case("Trailing$")]
+#[case("$-abc")]
+#[case("${}")]
+#[case("${:-default}")]
+#[case("${unclosed")]
+#[case("$I_do_not_exist")]
+#[case("${I_do_not_exist}")]
+#[case("${I_do_not_exist:?Some message}")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn dollar_sign_without_resolvable_var_is_kept(#[case] given: &str) {
+    let expected = PathBuf::from(given.replace("$$", "$"));
+    std::env::remove_var("I_do_not_exist");
+
+    let actual = PathBuf::from(given).resolve_env_variables();
+
+    assert_eq!(actual, expected);
+}
+
+#[rstest]
+#[case("<<", "<")]
+#[case("$$", "$")]
+#[case("<<mut_testing>", "<mut_testing>")]
+#[case("<<<mut_testing>", "<value")]
+#[case("$$mut_testing", "$mut_testing")]
+#[case("$${mut_testing}", "${mut_testing}")]
+#[case("$$$mut_testing", "$value")]
+#[case("<<$$", "<$")]
+#[case("a<<b$$c", "a<b$c")]
+#[case(">>", ">>")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn escaped_chars_are_replaced_with_literal(
+    #[case] given: PathBuf,
+    #[case] expected: PathBuf,
+) {
+    std::env::set_var("mut_testing", "value");
+
+    let actual = given.resolve_env_variables();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn escapes_in_var_value_are_not_decoded() {
+    let given = PathBuf::from("<mut_testing>");
+    let expected = PathBuf::from("<<$$");
+    std::env::set_var("mut_testing", "<<$$");
+
+    let actual = given.resolve_env_variables();
+
+    assert_eq!(actual, expected);
+}
+
+#[rstest]
+#[case("<XDG_CONFIG_HOME>", ".config")]
+#[case("$XDG_DATA_HOME", ".local/share")]
+#[case("${XDG_STATE_HOME}", ".local/state")]
+#[case("${XDG_CACHE_HOME:-default}", ".cache")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn xdg_base_dir_unset_uses_default_in_home_dir(
+    #[case] given: PathBuf,
+    #[case] exp_subdir: &str,
+) {
+    for var in ["XDG_CONFIG_HOME", "XDG_DATA_HOME", "XDG_STATE_HOME", "XDG_CACHE_HOME"] {
+        std::env::remove_var(var);
+    }
+    std::env::set_var("HOME", "MyHome");
+    let expected = PathBuf::from("MyHome").join(exp_subdir);
+
+    let actual = given.resolve_env_variables();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn xdg_base_dir_empty_uses_default_in_home_dir() {
+    let given = PathBuf::from("<XDG_CONFIG_HOME>");
+    std::env::set_var("XDG_CONFIG_HOME", "");
+    std::env::set_var("HOME", "MyHome");
+
+    let actual = given.resolve_env_variables();
+    std::env::remove_var("XDG_CONFIG_HOME");
+
+    assert_eq!(actual, PathBuf::from("MyHome").join(".config"));
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn xdg_base_dir_set_uses_value() {
+    let given = PathBuf::from("<XDG_CONFIG_HOME>/app");
+    std::env::set_var("XDG_CONFIG_HOME", "/custom/config");
+    std::env::set_var("HOME", "MyHome");
+
+    let actual = given.resolve_env_variables();
+    std::env::remove_var("XDG_CONFIG_HOME");
+
+    assert_eq!(actual, PathBuf::from("/custom/config/app"));
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn xdg_base_dir_unset_and_home_unset_is_unresolved() {
+    let given = PathBuf::from("<XDG_CONFIG_HOME>");
+    std::env::remove_var("XDG_CONFIG_HOME");
+    std::env::remove_var("HOME");
+    std::env::remove_var("HOMEDRIVE");
+    std::env::remove_var("HOMEPATH");
+
+    let actual = given.resolve_env_variables();
+
+    assert_eq!(actual, given);
+}
+
 // TODO: TEst with UNC and verbatim strings
@@ -264,3 +264,35 @@ fn home_path_and_homedrive_values_are_misc_returns_raw_value_appended(
 
     assert_eq!(actual, PathBuf::from(expected_str));
 }
+
+#[rstest]
+#[case("~root", "/root")]
+#[case("~root/", "/root/")]
+#[case("~root/Some/Path", "/root/Some/Path")]
+#[case("~root\\Some\\Path", "/root\\Some\\Path")]
+#[cfg(target_os = "linux")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn leading_tilde_with_username_is_replaced_with_users_home(
+    #[case] given: PathBuf,
+    #[case] expected_str: &str,
+) {
+    // Assumes the typical home folder for root
+    std::env::set_var("HOME", "MyHome");
+
+    let actual = given.resolve_tilde();
+
+    assert_eq!(actual, PathBuf::from(expected_str));
+}
+
+#[rstest]
+#[case("~I_do_not_exist")]
+#[case("~I_do_not_exist/Some/Path")]
+#[case("~I_do_not_exist\\Some\\Path")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn leading_tilde_with_unknown_username_returns_given(#[case] given: PathBuf) {
+    std::env::set_var("HOME", "MyHome");
+
+    let actual = given.resolve_tilde();
+
+    assert_eq!(actual, given);
+}
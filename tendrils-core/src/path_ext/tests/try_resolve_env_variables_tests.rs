@@ -0,0 +1,101 @@
+use crate::enums::InvalidTendrilError;
+use crate::path_ext::PathExt;
+use rstest::rstest;
+use serial_test::serial;
+use std::path::PathBuf;
+
+#[rstest]
+#[case("", "")]
+#[case("some/path", "some/path")]
+#[case("<mut_testing>", "value")]
+#[case("$mut_testing/${mut_testing}", "value/value")]
+#[case("${I_do_not_exist:-default}", "default")]
+#[case("<<I_do_not_exist>", "<I_do_not_exist>")]
+#[case("$$I_do_not_exist", "$I_do_not_exist")]
+#[case("C$\\Windows", "C$\\Windows")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn all_vars_resolved_returns_resolved_path(
+    #[case] given: PathBuf,
+    #[case] expected: PathBuf,
+) {
+    std::env::set_var("mut_testing", "value");
+    std::env::remove_var("I_do_not_exist");
+
+    let actual = given.try_resolve_env_variables();
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[rstest]
+#[case("<I_do_not_exist>")]
+#[case("$I_do_not_exist")]
+#[case("${I_do_not_exist}")]
+#[case("some/<mut_testing>/<I_do_not_exist>/path")]
+#[case("<I_do_not_exist>/<I_do_not_exist2>")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn unset_var_returns_unresolved_variable_err_for_first_unset(
+    #[case] given: PathBuf,
+) {
+    std::env::set_var("mut_testing", "value");
+    std::env::remove_var("I_do_not_exist");
+    std::env::remove_var("I_do_not_exist2");
+
+    let actual = given.try_resolve_env_variables();
+
+    assert_eq!(
+        actual,
+        Err(InvalidTendrilError::UnresolvedVariable {
+            name: "I_do_not_exist".to_string(),
+            msg: None,
+        }),
+    );
+}
+
+#[rstest]
+#[case("${mut_var:?Must be set}", Some("Must be set"))]
+#[case("${mut_var:?}", None)]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn required_var_is_unset_returns_unresolved_variable_err_with_msg(
+    #[case] given: PathBuf,
+    #[case] exp_msg: Option<&str>,
+) {
+    std::env::remove_var("mut_var");
+
+    let actual = given.try_resolve_env_variables();
+
+    assert_eq!(
+        actual,
+        Err(InvalidTendrilError::UnresolvedVariable {
+            name: "mut_var".to_string(),
+            msg: exp_msg.map(|m| m.to_string()),
+        }),
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn required_var_is_empty_returns_unresolved_variable_err() {
+    let given = PathBuf::from("${mut_var:?Must be set}");
+    std::env::set_var("mut_var", "");
+
+    let actual = given.try_resolve_env_variables();
+
+    assert_eq!(
+        actual,
+        Err(InvalidTendrilError::UnresolvedVariable {
+            name: "mut_var".to_string(),
+            msg: Some("Must be set".to_string()),
+        }),
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn var_is_empty_without_fallback_returns_empty_value() {
+    let given = PathBuf::from("a<mut_var>b");
+    std::env::set_var("mut_var", "");
+
+    let actual = given.try_resolve_env_variables();
+
+    assert_eq!(actual, Ok(PathBuf::from("ab")));
+}
@@ -0,0 +1,57 @@
+use crate::enums::InvalidTendrilError;
+use crate::path_ext::PathExt;
+use rstest::rstest;
+use serial_test::serial;
+use std::path::PathBuf;
+
+#[rstest]
+#[case("", "")]
+#[case("NoTilde", "NoTilde")]
+#[case("~", "MyHome")]
+#[case("~/Some/Path", "MyHome/Some/Path")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn resolvable_or_no_tilde_returns_resolved_path(
+    #[case] given: PathBuf,
+    #[case] expected: PathBuf,
+) {
+    std::env::set_var("HOME", "MyHome");
+
+    let actual = given.try_resolve_tilde();
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[rstest]
+#[case("~root", "/root")]
+#[case("~root/Some/Path", "/root/Some/Path")]
+#[cfg(target_os = "linux")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn known_username_returns_users_home(
+    #[case] given: PathBuf,
+    #[case] expected: PathBuf,
+) {
+    // Assumes the typical home folder for root
+    std::env::set_var("HOME", "MyHome");
+
+    let actual = given.try_resolve_tilde();
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[rstest]
+#[case("~I_do_not_exist")]
+#[case("~I_do_not_exist/Some/Path")]
+#[case("~I_do_not_exist\\Some\\Path")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn unknown_username_returns_unknown_user_err(#[case] given: PathBuf) {
+    std::env::set_var("HOME", "MyHome");
+
+    let actual = given.try_resolve_tilde();
+
+    assert_eq!(
+        actual,
+        Err(InvalidTendrilError::UnknownUser {
+            username: "I_do_not_exist".to_string(),
+        }),
+    );
+}
@@ -56,15 +56,16 @@ fn roots_with_dir_sep_on_init() {
 
 #[test]
 #[serial(SERIAL_MUT_ENV_VARS)]
-fn resolves_vars_then_tilde_then_dir_seps_then_roots() {
+fn resolves_vars_then_tilde_then_dir_seps_then_roots_then_normalizes() {
     let given = PathBuf::from("<var>\\misc.txt");
     std::env::set_var("HOME", "~/Home/.//<var>\\");
     std::env::set_var("var", "~/./value\\\\");
+    // The `.` components are then normalized
     #[cfg(not(windows))]
-    let expected_str = "/~/Home/.//<var>\\/./value\\\\\\misc.txt";
+    let expected_str = "/~/Home/<var>\\/value\\\\\\misc.txt";
     #[cfg(windows)]
     let expected_str =
-        "\\~\\Home\\.\\\\<var>\\\\.\\value\\\\\\misc.txt";
+        "\\~\\Home\\<var>\\\\value\\\\\\misc.txt";
 
     let actual = UniPath::from(given);
 
@@ -72,8 +73,8 @@ fn resolves_vars_then_tilde_then_dir_seps_then_roots() {
 }
 
 #[rstest]
-#[case(".", &format!("{SEP}."))]
-#[case("..", &format!("{SEP}.."))]
+#[case(".", SEP_STR)]
+#[case("..", SEP_STR)]
 #[case("/Path", &format!("{SEP}Path"))]
 #[cfg_attr(not(windows), case("\\Path", "/\\Path"))]
 #[cfg_attr(windows, case("\\Path", "\\Path"))]
@@ -93,8 +94,8 @@ fn resolves_tilde_then_replaces_seps_on_win_then_roots_with_dir_sep(
 }
 
 #[rstest]
-#[case(".", &format!("{SEP}."))]
-#[case("..", &format!("{SEP}.."))]
+#[case(".", SEP_STR)]
+#[case("..", SEP_STR)]
 #[case("/Path", &format!("{SEP}Path"))]
 #[cfg_attr(not(windows), case("\\Path", "/\\Path"))]
 #[cfg_attr(windows, case("\\Path", "\\Path"))]
@@ -172,3 +173,33 @@ fn non_utf8_is_preserved() {
 
     assert_eq!(actual.inner().as_os_str(), expected_str);
 }
+
+#[rstest]
+#[case("/a/./b", "/a/b")]
+#[case("/a/b/..", "/a/")]
+#[case("/a/b/../c", "/a/c")]
+#[case("/a/../../b", "/b")]
+#[case("/../a", "/a")]
+#[case("/a/./../b/./", "/b/")]
+#[case("a/../b", "/b")]
+#[case("/a//b//..//c", "/a//c")]
+#[case("/a/.../b", "/a/.../b")]
+#[case("/a/..b/.c", "/a/..b/.c")]
+fn normalizes_dots_on_init(#[case] given: &str, #[case] expected: &str) {
+    let actual = UniPath::from(PathBuf::from(given));
+
+    assert_eq!(actual.inner(), Path::new(&expected.replace('/', SEP_STR)));
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn normalizes_dots_from_resolved_vars_and_tilde_on_init() {
+    let given = PathBuf::from("~/<var>/../misc.txt");
+    std::env::set_var("HOME", "/MyHome/./Nested/..");
+    std::env::set_var("var", "Value");
+    let expected = format!("{SEP}MyHome{SEP}misc.txt");
+
+    let actual = UniPath::from(given);
+
+    assert_eq!(actual.inner().to_string_lossy(), expected);
+}
@@ -0,0 +1,485 @@
+use crate::{ConfigFormat, ConfigType};
+use crate::enums::OneOrMany;
+use crate::env_ext::HostFacts;
+use crate::path_ext::UniPath;
+use glob_match::glob_match;
+use indexmap::IndexMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests;
+
+/// Maps each profile name to one or more [`ProfileRule`]s. The profile
+/// is activated automatically if *any* of its rules match the current host.
+pub(crate) type AutoProfiles = IndexMap<String, OneOrMany<ProfileRule>>;
+
+/// A set of conditions describing a host. The rule matches only if *all* of
+/// the given conditions are met. A rule without any conditions always
+/// matches.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[schemars(rename = "profileRule", deny_unknown_fields)]
+pub(crate) struct ProfileRule {
+    /// Glob pattern matched against the host name. The comparison is case
+    /// insensitive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+
+    /// Glob pattern matched against either the operating system
+    /// (e.g. `linux`, `macos`, `windows`) or the OS family (`unix`,
+    /// `windows`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os: Option<String>,
+
+    /// Glob pattern matched against the CPU architecture
+    /// (e.g. `x86_64`, `aarch64`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arch: Option<String>,
+
+    /// Glob pattern matched against the name of the current user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// Name of an environment variable that must be set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<String>,
+
+    /// A path that must exist. Environment variables and a leading tilde
+    /// are resolved the same way as in a tendril's remote path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl ProfileRule {
+    fn eval(&self, facts: &HostFacts) -> Vec<ConditionLog> {
+        let mut logs = vec![];
+
+        if let Some(pattern) = &self.hostname {
+            let matched = match &facts.hostname {
+                Some(h) => glob_match(
+                    &pattern.to_lowercase(),
+                    &h.to_lowercase(),
+                ),
+                None => false,
+            };
+            logs.push(ConditionLog {
+                condition: HostCondition::Hostname(pattern.clone()),
+                actual: facts.hostname.clone(),
+                matched,
+            });
+        }
+        if let Some(pattern) = &self.os {
+            let matched = glob_match(pattern, &facts.os)
+                || glob_match(pattern, &facts.family);
+            logs.push(ConditionLog {
+                condition: HostCondition::Os(pattern.clone()),
+                actual: Some(facts.os.clone()),
+                matched,
+            });
+        }
+        if let Some(pattern) = &self.arch {
+            logs.push(ConditionLog {
+                condition: HostCondition::Arch(pattern.clone()),
+                actual: Some(facts.arch.clone()),
+                matched: glob_match(pattern, &facts.arch),
+            });
+        }
+        if let Some(pattern) = &self.username {
+            let matched = match &facts.username {
+                Some(u) => glob_match(pattern, u),
+                None => false,
+            };
+            logs.push(ConditionLog {
+                condition: HostCondition::Username(pattern.clone()),
+                actual: facts.username.clone(),
+                matched,
+            });
+        }
+        if let Some(var) = &self.env {
+            let actual = std::env::var_os(var)
+                .map(|v| v.to_string_lossy().to_string());
+            logs.push(ConditionLog {
+                condition: HostCondition::EnvVar(var.clone()),
+                matched: actual.is_some(),
+                actual,
+            });
+        }
+        if let Some(path) = &self.path {
+            let resolved = UniPath::from(PathBuf::from(path));
+            let exists = resolved.inner().exists()
+                || resolved.inner().is_symlink();
+            let actual = match exists {
+                true => Some(resolved.inner().to_string_lossy().to_string()),
+                false => None,
+            };
+            logs.push(ConditionLog {
+                condition: HostCondition::PathExists(path.clone()),
+                actual,
+                matched: exists,
+            });
+        }
+
+        logs
+    }
+}
+
+/// A single condition of an automatic profile rule.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HostCondition {
+    /// The host name matches the glob pattern.
+    Hostname(String),
+
+    /// The operating system or OS family matches the glob pattern.
+    Os(String),
+
+    /// The CPU architecture matches the glob pattern.
+    Arch(String),
+
+    /// The current user's name matches the glob pattern.
+    Username(String),
+
+    /// The environment variable is set.
+    EnvVar(String),
+
+    /// The path exists.
+    PathExists(String),
+}
+
+impl ToString for HostCondition {
+    fn to_string(&self) -> String {
+        match self {
+            HostCondition::Hostname(v) => format!("hostname \"{v}\""),
+            HostCondition::Os(v) => format!("os \"{v}\""),
+            HostCondition::Arch(v) => format!("arch \"{v}\""),
+            HostCondition::Username(v) => format!("username \"{v}\""),
+            HostCondition::EnvVar(v) => format!("env \"{v}\""),
+            HostCondition::PathExists(v) => format!("path \"{v}\""),
+        }
+    }
+}
+
+/// The result of evaluating a single [`HostCondition`] on the current host.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConditionLog {
+    pub condition: HostCondition,
+
+    /// The value found on the current host, if any. For
+    /// [`HostCondition::EnvVar`] this is the value of the variable, and for
+    /// [`HostCondition::PathExists`] it is the resolved path if it exists.
+    pub actual: Option<String>,
+
+    /// Whether the condition is met.
+    pub matched: bool,
+}
+
+/// The result of evaluating a single automatic profile rule on the current
+/// host.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AutoProfileLog {
+    /// The profile that the rule activates.
+    pub profile: String,
+
+    /// The configuration file that the rule is defined in.
+    pub cfg_type: ConfigType,
+
+    /// The results of each condition in the rule.
+    pub conditions: Vec<ConditionLog>,
+}
+
+impl AutoProfileLog {
+    /// Returns `true` if all conditions in the rule are met.
+    pub fn is_match(&self) -> bool {
+        self.conditions.iter().all(|c| c.matched)
+    }
+}
+
+/// Evaluates every rule in the `global` and `repo` automatic profiles, in that
+/// order, against the given host `facts`. The `repo_format` is the format of
+/// the repo config file that the `repo` profiles were defined in.
+pub(crate) fn eval_auto_profiles(
+    global: &AutoProfiles,
+    repo: &AutoProfiles,
+    repo_format: ConfigFormat,
+    facts: &HostFacts,
+) -> Vec<AutoProfileLog> {
+    let sources = [
+        (global, ConfigType::Global),
+        (repo, ConfigType::Repo(repo_format)),
+    ];
+    let mut logs = vec![];
+
+    for (auto_profiles, cfg_type) in sources.iter() {
+        for (profile, rules) in auto_profiles.iter() {
+            let rules: Vec<ProfileRule> = rules.clone().into();
+            for rule in rules.iter() {
+                logs.push(AutoProfileLog {
+                    profile: profile.clone(),
+                    cfg_type: cfg_type.clone(),
+                    conditions: rule.eval(facts),
+                });
+            }
+        }
+    }
+
+    logs
+}
+
+/// Returns the names of the profiles that were activated by any of the
+/// given rules, without duplicates.
+pub(crate) fn active_profiles(logs: &[AutoProfileLog]) -> Vec<String> {
+    let mut profiles: Vec<String> = vec![];
+    for log in logs.iter().filter(|l| l.is_match()) {
+        if !profiles.contains(&log.profile) {
+            profiles.push(log.profile.clone());
+        }
+    }
+
+    profiles
+}
+
+/// Maps each group name to the profiles it contains.
+pub(crate) type ProfileGroups = IndexMap<String, Vec<String>>;
+
+/// Returns the given `profiles` along with the members of any of them that
+/// are [groups](ProfileGroups), recursively, without duplicates.
+pub(crate) fn expand_groups(
+    profiles: &[String],
+    groups: &ProfileGroups,
+) -> Vec<String> {
+    let mut expanded: Vec<String> = vec![];
+    let mut pending: Vec<String> = profiles.to_vec();
+    pending.reverse();
+
+    while let Some(p) = pending.pop() {
+        if expanded.contains(&p) {
+            continue;
+        }
+        if let Some(members) = groups.get(&p) {
+            pending.extend(members.iter().rev().cloned());
+        }
+        expanded.push(p);
+    }
+
+    expanded
+}
+
+/// A boolean expression over profiles, such as `work & (linux | !laptop)`.
+/// - `&` - Both sides must be true
+/// - `|` - Either side must be true
+/// - `!` - Negates the following term
+/// - `( )` - Groups terms together
+///
+/// `!` has the highest precedence, followed by `&`, then `|`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ProfileExpr {
+    /// A single profile, or glob pattern.
+    Atom(String),
+    Not(Box<ProfileExpr>),
+    And(Box<ProfileExpr>, Box<ProfileExpr>),
+    Or(Box<ProfileExpr>, Box<ProfileExpr>),
+}
+
+impl ProfileExpr {
+    /// Parses the given expression, or returns a message describing why it is
+    /// invalid.
+    pub fn parse(expr: &str) -> Result<ProfileExpr, String> {
+        let tokens = tokenize(expr)?;
+        let mut parser = ExprParser { tokens, idx: 0 };
+        let parsed = parser.parse_or()?;
+
+        match parser.tokens.get(parser.idx) {
+            None => Ok(parsed),
+            Some((pos, Token::Close)) => {
+                Err(format!("Unexpected \")\" at position {pos}"))
+            }
+            Some((pos, t)) => Err(format!(
+                "Expected \"&\" or \"|\" before {} at position {pos}",
+                t.describe(),
+            )),
+        }
+    }
+
+    /// Evaluates the expression, using the `atom` function to determine
+    /// whether each individual [`ProfileExpr::Atom`] is true.
+    pub fn eval(&self, atom: &dyn Fn(&str) -> bool) -> bool {
+        match self {
+            ProfileExpr::Atom(v) => atom(v),
+            ProfileExpr::Not(v) => !v.eval(atom),
+            ProfileExpr::And(l, r) => l.eval(atom) && r.eval(atom),
+            ProfileExpr::Or(l, r) => l.eval(atom) || r.eval(atom),
+        }
+    }
+}
+
+/// Returns `true` if the entry in a tendril's `profiles` is an expression
+/// rather than the name of a profile.
+pub(crate) fn is_tendril_profile_expr(profile: &str) -> bool {
+    profile.chars().any(|c| "&|!()".contains(c))
+}
+
+/// Returns `true` if the entry in the profiles filter is an expression rather
+/// than a single glob pattern. Unlike [`is_tendril_profile_expr`], a `!` on
+/// its own is not enough, as it already negates a glob pattern. Any operators
+/// inside of `[]` or `{}` are also considered part of the glob pattern.
+pub(crate) fn is_filter_profile_expr(filter: &str) -> bool {
+    let mut depth = 0;
+    for c in filter.chars() {
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' if depth > 0 => depth -= 1,
+            '&' | '|' | '(' | ')' if depth == 0 => return true,
+            _ => {}
+        }
+    }
+
+    false
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Atom(String),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::And => String::from("\"&\""),
+            Token::Or => String::from("\"|\""),
+            Token::Not => String::from("\"!\""),
+            Token::Open => String::from("\"(\""),
+            Token::Close => String::from("\")\""),
+            Token::Atom(v) => format!("\"{v}\""),
+        }
+    }
+}
+
+/// Splits the expression into its tokens, each paired with its (1-based)
+/// character position in the expression.
+fn tokenize(expr: &str) -> Result<Vec<(usize, Token)>, String> {
+    let mut tokens = vec![];
+    let mut chars = expr.chars().enumerate().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let pos = i + 1;
+        let token = match c {
+            _ if c.is_whitespace() => continue,
+            '&' => Token::And,
+            '|' => Token::Or,
+            '!' => Token::Not,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            _ => {
+                let mut atom = String::from(c);
+                let mut depth = if c == '[' || c == '{' { 1 } else { 0 };
+                while let Some((_, next)) = chars.peek() {
+                    let next = *next;
+                    if depth == 0
+                        && (next.is_whitespace() || "&|!()".contains(next)) {
+                        break;
+                    }
+                    match next {
+                        '[' | '{' => depth += 1,
+                        ']' | '}' if depth > 0 => depth -= 1,
+                        _ => {}
+                    }
+                    atom.push(next);
+                    chars.next();
+                }
+                if depth > 0 {
+                    return Err(format!(
+                        "Unclosed bracket in \"{atom}\" at position {pos}"
+                    ));
+                }
+                Token::Atom(atom)
+            }
+        };
+        tokens.push((pos, token));
+    }
+
+    Ok(tokens)
+}
+
+struct ExprParser {
+    tokens: Vec<(usize, Token)>,
+    idx: usize,
+}
+
+impl ExprParser {
+    fn parse_or(&mut self) -> Result<ProfileExpr, String> {
+        let mut expr = self.parse_and()?;
+        while self.next_is(&Token::Or) {
+            self.idx += 1;
+            expr = ProfileExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<ProfileExpr, String> {
+        let mut expr = self.parse_not()?;
+        while self.next_is(&Token::And) {
+            self.idx += 1;
+            expr = ProfileExpr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> Result<ProfileExpr, String> {
+        if self.next_is(&Token::Not) {
+            self.idx += 1;
+            return Ok(ProfileExpr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_term()
+    }
+
+    fn parse_term(&mut self) -> Result<ProfileExpr, String> {
+        let (pos, token) = match self.tokens.get(self.idx) {
+            Some(v) => v.clone(),
+            None => return Err(self.expected_term_msg()),
+        };
+
+        match token {
+            Token::Atom(v) => {
+                self.idx += 1;
+                Ok(ProfileExpr::Atom(v))
+            }
+            Token::Open => {
+                self.idx += 1;
+                let expr = self.parse_or()?;
+                if self.next_is(&Token::Close) {
+                    self.idx += 1;
+                    Ok(expr)
+                }
+                else {
+                    Err(format!(
+                        "Missing \")\" to close the \"(\" at position {pos}"
+                    ))
+                }
+            }
+            _ => Err(self.expected_term_msg()),
+        }
+    }
+
+    fn next_is(&self, token: &Token) -> bool {
+        matches!(self.tokens.get(self.idx), Some((_, t)) if t == token)
+    }
+
+    fn expected_term_msg(&self) -> String {
+        let found = match self.tokens.get(self.idx) {
+            Some((pos, t)) => format!("found {} at position {pos}", t.describe()),
+            None => String::from("reached the end of the expression"),
+        };
+        match self.idx.checked_sub(1).and_then(|i| self.tokens.get(i)) {
+            Some((_, prev)) => format!(
+                "Expected a profile or \"(\" after {}, but {found}",
+                prev.describe(),
+            ),
+            None => format!("Expected a profile or \"(\", but {found}"),
+        }
+    }
+}
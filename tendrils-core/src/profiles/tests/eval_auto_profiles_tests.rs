@@ -0,0 +1,324 @@
+use crate::{ConfigFormat, ConfigType};
+use crate::env_ext::HostFacts;
+use crate::enums::OneOrMany;
+use crate::profiles::{
+    active_profiles,
+    eval_auto_profiles,
+    AutoProfiles,
+    ConditionLog,
+    HostCondition,
+    ProfileRule,
+};
+use crate::test_utils::Setup;
+use rstest::rstest;
+use serial_test::serial;
+
+fn facts() -> HostFacts {
+    HostFacts {
+        hostname: Some("Work-PC".to_string()),
+        os: "linux".to_string(),
+        family: "unix".to_string(),
+        arch: "x86_64".to_string(),
+        username: Some("alice".to_string()),
+    }
+}
+
+fn auto_profiles(rules: Vec<(&str, Vec<ProfileRule>)>) -> AutoProfiles {
+    rules
+        .into_iter()
+        .map(|(p, r)| (p.to_string(), OneOrMany::from(r)))
+        .collect()
+}
+
+fn eval_single(rule: ProfileRule, facts: &HostFacts) -> Vec<ConditionLog> {
+    let global = auto_profiles(vec![("p", vec![rule])]);
+    let mut logs = eval_auto_profiles(
+        &global,
+        &AutoProfiles::new(),
+        ConfigFormat::Json,
+        facts,
+    );
+    assert_eq!(logs.len(), 1);
+    logs.remove(0).conditions
+}
+
+#[test]
+fn no_rules_returns_empty() {
+    let actual = eval_auto_profiles(
+        &AutoProfiles::new(),
+        &AutoProfiles::new(),
+        ConfigFormat::Json,
+        &facts(),
+    );
+
+    assert!(actual.is_empty());
+}
+
+#[test]
+fn rule_without_conditions_always_matches() {
+    let global = auto_profiles(vec![("p", vec![ProfileRule::default()])]);
+
+    let actual = eval_auto_profiles(
+        &global,
+        &AutoProfiles::new(),
+        ConfigFormat::Json,
+        &facts(),
+    );
+
+    assert_eq!(actual.len(), 1);
+    assert!(actual[0].conditions.is_empty());
+    assert!(actual[0].is_match());
+}
+
+#[rstest]
+#[case("Work-PC", true)]
+#[case("work-pc", true)]
+#[case("WORK-*", true)]
+#[case("*-pc", true)]
+#[case("Home-PC", false)]
+#[case("Work", false)]
+fn hostname_is_glob_matched_case_insensitively(
+    #[case] pattern: &str,
+    #[case] exp_match: bool,
+) {
+    let rule = ProfileRule {
+        hostname: Some(pattern.to_string()),
+        ..Default::default()
+    };
+
+    let actual = eval_single(rule, &facts());
+
+    assert_eq!(actual, vec![ConditionLog {
+        condition: HostCondition::Hostname(pattern.to_string()),
+        actual: Some("Work-PC".to_string()),
+        matched: exp_match,
+    }]);
+}
+
+#[test]
+fn hostname_does_not_match_if_unknown() {
+    let rule = ProfileRule {
+        hostname: Some("*".to_string()),
+        ..Default::default()
+    };
+    let mut facts = facts();
+    facts.hostname = None;
+
+    let actual = eval_single(rule, &facts);
+
+    assert_eq!(actual[0].actual, None);
+    assert!(!actual[0].matched);
+}
+
+#[rstest]
+#[case("linux", true)]
+#[case("unix", true)]
+#[case("lin*", true)]
+#[case("windows", false)]
+#[case("macos", false)]
+fn os_is_glob_matched_against_os_or_family(
+    #[case] pattern: &str,
+    #[case] exp_match: bool,
+) {
+    let rule = ProfileRule {
+        os: Some(pattern.to_string()),
+        ..Default::default()
+    };
+
+    let actual = eval_single(rule, &facts());
+
+    assert_eq!(actual, vec![ConditionLog {
+        condition: HostCondition::Os(pattern.to_string()),
+        actual: Some("linux".to_string()),
+        matched: exp_match,
+    }]);
+}
+
+#[rstest]
+#[case("x86_64", true)]
+#[case("x86*", true)]
+#[case("aarch64", false)]
+fn arch_is_glob_matched(#[case] pattern: &str, #[case] exp_match: bool) {
+    let rule = ProfileRule {
+        arch: Some(pattern.to_string()),
+        ..Default::default()
+    };
+
+    let actual = eval_single(rule, &facts());
+
+    assert_eq!(actual[0].condition, HostCondition::Arch(pattern.to_string()));
+    assert_eq!(actual[0].matched, exp_match);
+}
+
+#[rstest]
+#[case("alice", true)]
+#[case("a*", true)]
+#[case("Alice", false)]
+#[case("bob", false)]
+fn username_is_glob_matched(#[case] pattern: &str, #[case] exp_match: bool) {
+    let rule = ProfileRule {
+        username: Some(pattern.to_string()),
+        ..Default::default()
+    };
+
+    let actual = eval_single(rule, &facts());
+
+    assert_eq!(actual[0].condition, HostCondition::Username(pattern.to_string()));
+    assert_eq!(actual[0].actual, Some("alice".to_string()));
+    assert_eq!(actual[0].matched, exp_match);
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn env_matches_only_if_var_is_set() {
+    let rule = ProfileRule {
+        env: Some("TENDRILS_AUTO_PROFILE_VAR".to_string()),
+        ..Default::default()
+    };
+
+    std::env::remove_var("TENDRILS_AUTO_PROFILE_VAR");
+    let unset_actual = eval_single(rule.clone(), &facts());
+    std::env::set_var("TENDRILS_AUTO_PROFILE_VAR", "value");
+    let set_actual = eval_single(rule, &facts());
+    std::env::remove_var("TENDRILS_AUTO_PROFILE_VAR");
+
+    assert_eq!(unset_actual, vec![ConditionLog {
+        condition: HostCondition::EnvVar("TENDRILS_AUTO_PROFILE_VAR".to_string()),
+        actual: None,
+        matched: false,
+    }]);
+    assert_eq!(set_actual, vec![ConditionLog {
+        condition: HostCondition::EnvVar("TENDRILS_AUTO_PROFILE_VAR".to_string()),
+        actual: Some("value".to_string()),
+        matched: true,
+    }]);
+}
+
+#[test]
+fn path_matches_only_if_path_exists() {
+    let setup = Setup::new();
+    setup.make_remote_file();
+    let exists = setup.remote_file.to_string_lossy().to_string();
+    let missing = setup.remote_dir.to_string_lossy().to_string();
+    let rule_exists = ProfileRule {
+        path: Some(exists.clone()),
+        ..Default::default()
+    };
+    let rule_missing = ProfileRule {
+        path: Some(missing.clone()),
+        ..Default::default()
+    };
+
+    let exists_actual = eval_single(rule_exists, &facts());
+    let missing_actual = eval_single(rule_missing, &facts());
+
+    assert_eq!(exists_actual, vec![ConditionLog {
+        condition: HostCondition::PathExists(exists.clone()),
+        actual: Some(exists),
+        matched: true,
+    }]);
+    assert_eq!(missing_actual, vec![ConditionLog {
+        condition: HostCondition::PathExists(missing),
+        actual: None,
+        matched: false,
+    }]);
+}
+
+#[rstest]
+#[case("Work-PC", "linux", true)]
+#[case("Work-PC", "windows", false)]
+#[case("Home-PC", "linux", false)]
+#[case("Home-PC", "windows", false)]
+fn all_conditions_in_rule_must_match(
+    #[case] hostname: &str,
+    #[case] os: &str,
+    #[case] exp_match: bool,
+) {
+    let rule = ProfileRule {
+        hostname: Some(hostname.to_string()),
+        os: Some(os.to_string()),
+        ..Default::default()
+    };
+    let global = auto_profiles(vec![("p", vec![rule])]);
+
+    let actual = eval_auto_profiles(
+        &global,
+        &AutoProfiles::new(),
+        ConfigFormat::Json,
+        &facts(),
+    );
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].conditions.len(), 2);
+    assert_eq!(actual[0].is_match(), exp_match);
+}
+
+#[test]
+fn each_rule_of_a_profile_is_evaluated_separately() {
+    let rule1 = ProfileRule {
+        hostname: Some("Home-PC".to_string()),
+        ..Default::default()
+    };
+    let rule2 = ProfileRule {
+        os: Some("linux".to_string()),
+        ..Default::default()
+    };
+    let global = auto_profiles(vec![("p", vec![rule1, rule2])]);
+
+    let actual = eval_auto_profiles(
+        &global,
+        &AutoProfiles::new(),
+        ConfigFormat::Json,
+        &facts(),
+    );
+
+    assert_eq!(actual.len(), 2);
+    assert!(!actual[0].is_match());
+    assert!(actual[1].is_match());
+    assert_eq!(active_profiles(&actual), vec!["p".to_string()]);
+}
+
+#[test]
+fn global_rules_are_listed_before_repo_rules() {
+    let global = auto_profiles(vec![
+        ("g1", vec![ProfileRule::default()]),
+        ("g2", vec![ProfileRule::default()]),
+    ]);
+    let repo = auto_profiles(vec![
+        ("r1", vec![ProfileRule::default()]),
+    ]);
+
+    let actual =
+        eval_auto_profiles(&global, &repo, ConfigFormat::Toml, &facts());
+
+    let summary: Vec<(&str, ConfigType)> = actual
+        .iter()
+        .map(|l| (l.profile.as_str(), l.cfg_type.clone()))
+        .collect();
+    assert_eq!(summary, vec![
+        ("g1", ConfigType::Global),
+        ("g2", ConfigType::Global),
+        ("r1", ConfigType::Repo(ConfigFormat::Toml)),
+    ]);
+}
+
+#[test]
+fn active_profiles_only_includes_matches_without_duplicates() {
+    let non_match = ProfileRule {
+        os: Some("windows".to_string()),
+        ..Default::default()
+    };
+    let global = auto_profiles(vec![
+        ("p1", vec![ProfileRule::default(), ProfileRule::default()]),
+        ("p2", vec![non_match]),
+    ]);
+    let repo = auto_profiles(vec![
+        ("p1", vec![ProfileRule::default()]),
+        ("p3", vec![ProfileRule::default()]),
+    ]);
+    let logs = eval_auto_profiles(&global, &repo, ConfigFormat::Json, &facts());
+
+    let actual = active_profiles(&logs);
+
+    assert_eq!(actual, vec!["p1".to_string(), "p3".to_string()]);
+}
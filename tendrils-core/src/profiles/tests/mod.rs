@@ -0,0 +1,2 @@
+mod eval_auto_profiles_tests;
+mod profile_expr_tests;
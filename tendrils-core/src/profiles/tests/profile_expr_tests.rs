@@ -0,0 +1,180 @@
+use crate::profiles::{
+    expand_groups,
+    is_filter_profile_expr,
+    is_tendril_profile_expr,
+    ProfileExpr,
+    ProfileGroups,
+};
+use rstest::rstest;
+
+fn atom(v: &str) -> Box<ProfileExpr> {
+    Box::new(ProfileExpr::Atom(v.to_string()))
+}
+
+#[rstest]
+#[case("work", ProfileExpr::Atom("work".to_string()))]
+#[case(" work ", ProfileExpr::Atom("work".to_string()))]
+#[case("!work", ProfileExpr::Not(atom("work")))]
+#[case("!!work", ProfileExpr::Not(Box::new(ProfileExpr::Not(atom("work")))))]
+#[case("a & b", ProfileExpr::And(atom("a"), atom("b")))]
+#[case("a&b", ProfileExpr::And(atom("a"), atom("b")))]
+#[case("a | b", ProfileExpr::Or(atom("a"), atom("b")))]
+#[case("(a)", ProfileExpr::Atom("a".to_string()))]
+#[case("((a))", ProfileExpr::Atom("a".to_string()))]
+#[case("*-pc & v[!1]", ProfileExpr::And(atom("*-pc"), atom("v[!1]")))]
+#[case("v{a,b} | c", ProfileExpr::Or(atom("v{a,b}"), atom("c")))]
+#[case("v[&|] & c", ProfileExpr::And(atom("v[&|]"), atom("c")))]
+fn parse_valid_expression(#[case] given: &str, #[case] expected: ProfileExpr) {
+    let actual = ProfileExpr::parse(given);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+fn parse_and_has_precedence_over_or() {
+    let actual = ProfileExpr::parse("a | b & c").unwrap();
+
+    assert_eq!(
+        actual,
+        ProfileExpr::Or(
+            atom("a"),
+            Box::new(ProfileExpr::And(atom("b"), atom("c"))),
+        ),
+    );
+}
+
+#[test]
+fn parse_not_has_precedence_over_and() {
+    let actual = ProfileExpr::parse("!a & b").unwrap();
+
+    assert_eq!(
+        actual,
+        ProfileExpr::And(Box::new(ProfileExpr::Not(atom("a"))), atom("b")),
+    );
+}
+
+#[test]
+fn parse_parentheses_override_precedence() {
+    let actual = ProfileExpr::parse("!(a | b) & c").unwrap();
+
+    assert_eq!(
+        actual,
+        ProfileExpr::And(
+            Box::new(ProfileExpr::Not(
+                Box::new(ProfileExpr::Or(atom("a"), atom("b")))
+            )),
+            atom("c"),
+        ),
+    );
+}
+
+#[rstest]
+#[case("", "Expected a profile or \"(\", but reached the end of the expression")]
+#[case("  ", "Expected a profile or \"(\", but reached the end of the expression")]
+#[case("a &", "Expected a profile or \"(\" after \"&\", but reached the end of the expression")]
+#[case("a | | b", "Expected a profile or \"(\" after \"|\", but found \"|\" at position 5")]
+#[case("& a", "Expected a profile or \"(\", but found \"&\" at position 1")]
+#[case("!", "Expected a profile or \"(\" after \"!\", but reached the end of the expression")]
+#[case("()", "Expected a profile or \"(\" after \"(\", but found \")\" at position 2")]
+#[case("(a & b", "Missing \")\" to close the \"(\" at position 1")]
+#[case("a & b)", "Unexpected \")\" at position 6")]
+#[case("a b", "Expected \"&\" or \"|\" before \"b\" at position 3")]
+#[case("a (b)", "Expected \"&\" or \"|\" before \"(\" at position 3")]
+#[case("a & v[12", "Unclosed bracket in \"v[12\" at position 5")]
+#[case("a & v{1,2", "Unclosed bracket in \"v{1,2\" at position 5")]
+fn parse_invalid_expression_returns_message(
+    #[case] given: &str,
+    #[case] exp_msg: &str,
+) {
+    let actual = ProfileExpr::parse(given);
+
+    assert_eq!(actual, Err(exp_msg.to_string()));
+}
+
+#[rstest]
+#[case("a", &["a"], true)]
+#[case("a", &["b"], false)]
+#[case("!a", &["a"], false)]
+#[case("!a", &["b"], true)]
+#[case("a & b", &["a", "b"], true)]
+#[case("a & b", &["a"], false)]
+#[case("a | b", &["b"], true)]
+#[case("a | b", &[], false)]
+#[case("a & !b", &["a"], true)]
+#[case("a & !b", &["a", "b"], false)]
+#[case("!(a | b) | c", &["c"], true)]
+#[case("!(a | b) | c", &["a"], false)]
+#[case("!(a | b) | c", &[], true)]
+fn eval_uses_given_fn_for_atoms(
+    #[case] given: &str,
+    #[case] active: &[&str],
+    #[case] expected: bool,
+) {
+    let expr = ProfileExpr::parse(given).unwrap();
+
+    let actual = expr.eval(&|a| active.contains(&a));
+
+    assert_eq!(actual, expected);
+}
+
+#[rstest]
+#[case("work", false)]
+#[case("*-pc", false)]
+#[case("v[12]", false)]
+#[case("!work", true)]
+#[case("a & b", true)]
+#[case("a|b", true)]
+#[case("(a)", true)]
+fn is_tendril_profile_expr_returns_true_if_contains_any_operator(
+    #[case] given: &str,
+    #[case] expected: bool,
+) {
+    assert_eq!(is_tendril_profile_expr(given), expected);
+}
+
+#[rstest]
+#[case("work", false)]
+#[case("!work", false)]
+#[case("v[!1]", false)]
+#[case("v{!1,2}", false)]
+#[case("v[&|()]", false)]
+#[case("a & b", true)]
+#[case("a|b", true)]
+#[case("(!a)", true)]
+#[case("v[12] & b", true)]
+#[case("v[12]|b", true)]
+fn is_filter_profile_expr_ignores_not_and_operators_in_brackets(
+    #[case] given: &str,
+    #[case] expected: bool,
+) {
+    assert_eq!(is_filter_profile_expr(given), expected);
+}
+
+fn groups() -> ProfileGroups {
+    let mut groups = ProfileGroups::new();
+    let desktop = vec!["home-pc".to_string(), "work-pc".to_string()];
+    let all = vec!["desktop".to_string(), "laptop".to_string()];
+    groups.insert("desktop".to_string(), desktop);
+    groups.insert("all".to_string(), all);
+    groups.insert("loop1".to_string(), vec!["loop2".to_string()]);
+    groups.insert("loop2".to_string(), vec!["loop1".to_string()]);
+    groups
+}
+
+#[rstest]
+#[case(&[], &[])]
+#[case(&["other"], &["other"])]
+#[case(&["desktop"], &["desktop", "home-pc", "work-pc"])]
+#[case(&["all"], &["all", "desktop", "home-pc", "work-pc", "laptop"])]
+#[case(&["home-pc", "desktop"], &["home-pc", "desktop", "work-pc"])]
+#[case(&["loop1"], &["loop1", "loop2"])]
+fn expand_groups_includes_members_recursively_without_duplicates(
+    #[case] given: &[&str],
+    #[case] expected: &[&str],
+) {
+    let given: Vec<String> = given.iter().map(|p| p.to_string()).collect();
+
+    let actual = expand_groups(&given, &groups());
+
+    assert_eq!(actual, expected);
+}
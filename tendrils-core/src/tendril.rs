@@ -1,4 +1,10 @@
-use crate::enums::{InvalidTendrilError, TendrilMode};
+use crate::enums::{
+    ActionMode,
+    InvalidTendrilError,
+    NestedSymlinks,
+    ReflinkMode,
+    TendrilMode,
+};
 use crate::path_ext::{PathExt, UniPath};
 use std::ffi::OsStr;
 use std::path::{Component, Path, PathBuf};
@@ -16,7 +22,23 @@ pub(crate) struct Tendril {
     local: PathBuf,
     local_abs: PathBuf,
     remote: UniPath,
+    remote_idx: usize,
     pub mode: TendrilMode,
+
+    /// Whether the symlink is relative (see [`RawTendril::relative_link`]).
+    pub relative_link: bool,
+
+    /// Whether the hard link may be replaced by a copy (see
+    /// [`RawTendril::copy_fallback`]).
+    pub copy_fallback: bool,
+
+    /// Whether files are reflinked when copied (see
+    /// [`RawTendril::reflink`]).
+    pub reflink: ReflinkMode,
+
+    /// How the symlinks inside of a folder are copied (see
+    /// [`RawTendril::nested_symlinks`]).
+    pub nested_symlinks: NestedSymlinks,
 }
 
 impl Tendril {
@@ -26,23 +48,7 @@ impl Tendril {
         remote: UniPath,
         mode: TendrilMode,
     ) -> Result<Tendril, InvalidTendrilError> {
-        if local.components().any(|c| c == Component::ParentDir) {
-            return Err(InvalidTendrilError::InvalidLocal);
-        }
-
-        let mut local_sub_comps = local.components();
-        if match local_sub_comps.next() {
-            Some(Component::Normal(c))
-                if Self::is_forbidden_dir(c) => true,
-            Some(Component::RootDir | Component::CurDir) => match local_sub_comps.next() {
-                Some(Component::Normal(c)) if Self::is_forbidden_dir(c) => true,
-                None => true,
-                _ => false,
-            }
-            None => true,
-            _ => false,
-        }
-        {
+        if !Self::is_valid_local(&local) {
             return Err(InvalidTendrilError::InvalidLocal);
         }
 
@@ -64,7 +70,17 @@ impl Tendril {
             .replace_dir_seps()
             .into();
 
-        Ok(Tendril { local, local_abs, remote, mode })
+        Ok(Tendril {
+            local,
+            local_abs,
+            remote,
+            remote_idx: 0,
+            mode,
+            relative_link: false,
+            copy_fallback: false,
+            reflink: ReflinkMode::Never,
+            nested_symlinks: NestedSymlinks::Follow,
+        })
     }
 
     #[cfg(any(test, feature = "_test_utils"))]
@@ -77,6 +93,12 @@ impl Tendril {
         Tendril::new(td_repo.as_ref(), local, remote, mode)
     }
 
+    /// The path to this file system object relative to the root of the
+    /// Tendrils repo.
+    pub fn local(&self) -> &Path {
+        &self.local
+    }
+
     /// The absolute path to this file system object inside the Tendrils repo.
     /// The combination of the given Tendrils repo and its `local`.
     pub fn local_abs(&self) -> &Path {
@@ -89,6 +111,35 @@ impl Tendril {
         &self.remote
     }
 
+    /// The index of the [`Self::remote`] within the combined list of the
+    /// [`RawTendril::remote`] followed by its [`RawTendril::fallbacks`].
+    /// This is always `0` for tendrils without any fallbacks.
+    pub fn remote_idx(&self) -> usize {
+        self.remote_idx
+    }
+
+    /// Returns `false` if the `local` path would be rejected with
+    /// [`InvalidTendrilError::InvalidLocal`], i.e. if it is empty, contains
+    /// a `..` component, or is (or is inside) the `.tendrils` folder.
+    pub(crate) fn is_valid_local(local: &Path) -> bool {
+        if local.components().any(|c| c == Component::ParentDir) {
+            return false;
+        }
+
+        let mut local_sub_comps = local.components();
+        !match local_sub_comps.next() {
+            Some(Component::Normal(c))
+                if Self::is_forbidden_dir(c) => true,
+            Some(Component::RootDir | Component::CurDir) => match local_sub_comps.next() {
+                Some(Component::Normal(c)) if Self::is_forbidden_dir(c) => true,
+                None => true,
+                _ => false,
+            }
+            None => true,
+            _ => false,
+        }
+    }
+
     fn is_forbidden_dir(path_comp: &OsStr) -> bool {
         match path_comp.to_string_lossy().to_lowercase().trim() {
             ".tendrils" => true,
@@ -99,13 +150,45 @@ impl Tendril {
         }
     }
 
+    /// Compares the (already normalized) paths, and then compares them
+    /// again with any symlinks resolved as far as the paths exist. The last
+    /// component of the `remote` is not resolved, as it may be a symlink to
+    /// the Tendrils repo in link mode.
     fn is_recursive(td_repo: &Path, remote: &Path) -> bool {
+        if Self::is_recursive_lexical(td_repo, remote) {
+            return true;
+        }
+
+        let canon_remote = match (remote.parent(), remote.file_name()) {
+            (Some(parent), Some(name)) => canonicalize_existing(parent).join(name),
+            _ => remote.to_path_buf(),
+        };
+        Self::is_recursive_lexical(&canonicalize_existing(td_repo), &canon_remote)
+    }
+
+    fn is_recursive_lexical(td_repo: &Path, remote: &Path) -> bool {
         td_repo == remote
             || td_repo.ancestors().any(|p| p == remote)
             || remote.ancestors().any(|p| p == td_repo)
     }
 }
 
+/// Canonicalizes the longest part of the `path` that exists, and appends the
+/// remainder as-is. Returns the `path` unchanged if no part of it can be
+/// canonicalized.
+pub(crate) fn canonicalize_existing(path: &Path) -> PathBuf {
+    for ancestor in path.ancestors() {
+        if let Ok(canon) = ancestor.canonicalize() {
+            return match path.strip_prefix(ancestor) {
+                Ok(rest) if rest.as_os_str().is_empty() => canon,
+                Ok(rest) => canon.join(rest),
+                Err(_) => path.to_path_buf(),
+            };
+        }
+    }
+    path.to_path_buf()
+}
+
 /// Contains the unresolved, unvalidated information to define a single
 /// tendril.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -118,11 +201,47 @@ pub struct RawTendril {
     /// which to recreate the [`Self::local`]. Each `local` and
     /// `remote` pair forms a tendril.
     pub remote: String,
+
+    /// Alternative remotes to the [`Self::remote`], in order of preference.
+    /// If not empty, only one of these remotes is used: the first that exists,
+    /// otherwise (unless pulling) the first whose parent folder exists,
+    /// otherwise the [`Self::remote`].
+    pub fallbacks: Vec<String>,
     pub mode: TendrilMode,
 
+    /// `true` indicates that link tendrils are created as symlinks relative
+    /// to the remote's parent folder, rather than to the absolute path of the
+    /// local. This keeps the links working if the Tendrils repo and the
+    /// remotes are moved together, i.e. on a removable drive or in a
+    /// container. This has no effect on the other modes.
+    pub relative_link: bool,
+
+    /// `true` indicates that hard link tendrils are copied instead if the
+    /// remote is on a different file system than the Tendrils repo, where
+    /// hard links cannot be created. Such a copy at the remote is then
+    /// refreshed each time it is linked, rather than being treated as a
+    /// replaced hard link. This has no effect on the other modes.
+    pub copy_fallback: bool,
+
+    /// Whether files are reflinked when they are copied to or from the
+    /// Tendrils repo. This is set for the whole repo rather than for each
+    /// tendril. This has no effect on link-type tendrils, except when the
+    /// local is first copied from the remote.
+    pub reflink: ReflinkMode,
+
+    /// How the symlinks inside of a folder tendril are handled when it is
+    /// copied to or from the Tendrils repo. This has no effect on file
+    /// tendrils, or on the folder itself if it is a symlink.
+    pub nested_symlinks: NestedSymlinks,
+
     /// A list of profiles to which this tendril belongs. If empty,
     /// this tendril is considered to be included in *all* profiles.
     pub profiles: Vec<String>,
+
+    /// The config file that this tendril was defined in, relative to the
+    /// `.tendrils` folder. `None` if it was defined in the repo config file
+    /// itself, whatever its format.
+    pub source: Option<PathBuf>,
 }
 
 impl RawTendril {
@@ -131,20 +250,99 @@ impl RawTendril {
         RawTendril {
             local: local.to_string(),
             remote: "".to_string(),
+            fallbacks: vec![],
             mode: TendrilMode::DirOverwrite,
+            relative_link: false,
+            copy_fallback: false,
+            reflink: ReflinkMode::Never,
+            nested_symlinks: NestedSymlinks::Follow,
             profiles: vec![],
+            source: None,
         }
     }
 
+    /// Resolves and validates this tendril. If there are any
+    /// [`Self::fallbacks`], the remote that is used is:
+    /// 1. The first of the [`Self::remote`] or [`Self::fallbacks`] that
+    ///    exists, otherwise
+    /// 2. The first whose parent folder exists, unless the `mode` is
+    ///    [`ActionMode::Pull`], otherwise
+    /// 3. The first whose environment variables can all be resolved
+    ///
+    /// Given `None`, the remote is chosen as for the actions that create the
+    /// remote, so that it matches the remote that a push or link would use.
+    ///
+    /// Remotes with unresolved environment variables are never used. If
+    /// none of the remotes can be resolved, the error for the
+    /// [`Self::remote`] is returned.
     pub(crate) fn resolve<'a>(
         &'a self,
         td_repo: &'a UniPath,
+        mode: Option<&ActionMode>,
     ) -> Result<Tendril, InvalidTendrilError> {
-        Tendril::new(
+        let (remote_idx, remote) = self.select_remote(mode)?;
+        let mut tendril = Tendril::new(
             td_repo,
             PathBuf::from(&self.local),
-            UniPath::from(PathBuf::from(&self.remote)),
+            remote,
             self.mode.clone(),
-        )
+        )?;
+
+        tendril.remote_idx = remote_idx;
+        tendril.relative_link = self.relative_link;
+        tendril.copy_fallback = self.copy_fallback;
+        tendril.reflink = self.reflink;
+        tendril.nested_symlinks = self.nested_symlinks;
+        Ok(tendril)
+    }
+
+    fn select_remote(
+        &self,
+        mode: Option<&ActionMode>,
+    ) -> Result<(usize, UniPath), InvalidTendrilError> {
+        let primary = UniPath::try_from_strict(Path::new(&self.remote));
+        if self.fallbacks.is_empty() {
+            return Ok((0, primary?));
+        }
+
+        let mut candidates: Vec<(usize, UniPath)> = vec![];
+        let primary_err = match primary {
+            Ok(p) => {
+                candidates.push((0, p));
+                None
+            }
+            Err(e) => Some(e),
+        };
+        candidates.extend(
+            self.fallbacks
+                .iter()
+                .enumerate()
+                .filter_map(|(i, f)| {
+                    UniPath::try_from_strict(Path::new(f))
+                        .ok()
+                        .map(|p| (i + 1, p))
+                })
+        );
+
+        if candidates.is_empty() {
+            return Err(primary_err.unwrap());
+        }
+
+        let can_create = mode != Some(&ActionMode::Pull);
+        let idx = candidates
+            .iter()
+            .position(|(_, c)| c.inner().get_type().is_some())
+            .or_else(|| {
+                if !can_create {
+                    return None;
+                }
+                candidates.iter().position(|(_, c)| match c.inner().parent() {
+                    Some(p) => p.is_dir(),
+                    None => false,
+                })
+            })
+            .unwrap_or(0);
+
+        Ok(candidates.swap_remove(idx))
     }
 }
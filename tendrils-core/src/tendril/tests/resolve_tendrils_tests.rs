@@ -1,4 +1,5 @@
-use crate::{RawTendril, Tendril, TendrilMode, UniPath};
+use crate::{ActionMode, InvalidTendrilError, RawTendril, Tendril, TendrilMode, UniPath};
+use crate::test_utils::Setup;
 use rstest::rstest;
 use serial_test::serial;
 use std::path::{Path, PathBuf};
@@ -9,7 +10,7 @@ fn invalid_tendril_returns_invalid_tendril() {
     let mut given = RawTendril::new("");
     given.remote = "SomeParentPath1".to_string();
 
-    let actual = given.resolve(&td_repo);
+    let actual = given.resolve(&td_repo, None);
 
     assert!(actual.is_err());
 }
@@ -37,28 +38,88 @@ fn vars_and_leading_tilde_in_remote_path_are_resolved(
     )
     .unwrap();
 
-    let actual = given.resolve(&td_repo).unwrap();
+    let actual = given.resolve(&td_repo, None).unwrap();
 
     assert_eq!(actual, expected);
 }
 
+#[rstest]
+#[case("<I_do_not_exist>")]
+#[case("$I_do_not_exist")]
+#[case("${I_do_not_exist}/misc.txt")]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn var_in_remote_path_doesnt_exist_returns_unresolved_variable_err(
+    #[case] remote: String,
+) {
+    let td_repo = UniPath::from(Path::new("/Repo"));
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = remote;
+    std::env::remove_var("I_do_not_exist");
+
+    let actual = given.resolve(&td_repo, None);
+
+    assert_eq!(
+        actual,
+        Err(InvalidTendrilError::UnresolvedVariable {
+            name: "I_do_not_exist".to_string(),
+            msg: None,
+        }),
+    );
+}
+
+#[rstest]
+#[case("~I_do_not_exist")]
+#[case("~I_do_not_exist/misc.txt")]
+fn unknown_user_in_remote_path_returns_unknown_user_err(
+    #[case] remote: String,
+) {
+    let td_repo = UniPath::from(Path::new("/Repo"));
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = remote;
+
+    let actual = given.resolve(&td_repo, None);
+
+    assert_eq!(
+        actual,
+        Err(InvalidTendrilError::UnknownUser {
+            username: "I_do_not_exist".to_string(),
+        }),
+    );
+}
+
 #[test]
-fn var_in_remote_path_doesnt_exist_returns_raw_path() {
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn required_var_in_remote_path_is_unset_returns_unresolved_variable_err() {
     let td_repo = UniPath::from(Path::new("/Repo"));
     let mut given = RawTendril::new("SomeLocal");
-    given.remote = "<I_do_not_exist>".to_string();
+    given.remote = "${mut-testing:?Set this first}/misc.txt".to_string();
+    std::env::remove_var("mut-testing");
 
-    let expected = Tendril::new_expose(
-        &td_repo,
-        "SomeLocal".into(),
-        PathBuf::from("<I_do_not_exist>").into(),
-        TendrilMode::DirOverwrite,
-    )
-    .unwrap();
+    let actual = given.resolve(&td_repo, None);
 
-    let actual = given.resolve(&td_repo).unwrap();
+    assert_eq!(
+        actual,
+        Err(InvalidTendrilError::UnresolvedVariable {
+            name: "mut-testing".to_string(),
+            msg: Some("Set this first".to_string()),
+        }),
+    );
+}
 
-    assert_eq!(actual, expected);
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn escaped_chars_in_remote_path_are_resolved() {
+    let td_repo = UniPath::from(Path::new("/Repo"));
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = "<<I_do_not_exist>/$$I_do_not_exist".to_string();
+    std::env::remove_var("I_do_not_exist");
+
+    let actual = given.resolve(&td_repo, None).unwrap();
+
+    assert_eq!(
+        actual.remote().inner(),
+        Path::new("/<I_do_not_exist>/$I_do_not_exist"),
+    );
 }
 
 #[test]
@@ -79,7 +140,7 @@ fn leading_tilde_in_remote_path_tilde_value_doesnt_exist_returns_raw_path() {
     )
     .unwrap();
 
-    let actual = given.resolve(&td_repo).unwrap();
+    let actual = given.resolve(&td_repo, None).unwrap();
 
     assert_eq!(actual, expected);
 }
@@ -104,7 +165,158 @@ fn resolves_tendril_mode_properly(
     )
     .unwrap();
 
-    let actual = given.resolve(&td_repo).unwrap();
+    let actual = given.resolve(&td_repo, None).unwrap();
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn no_fallbacks_uses_remote_even_if_it_does_not_exist() {
+    let setup = Setup::new();
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = setup.remote_file.to_string_lossy().to_string();
+    assert!(!setup.remote_file.exists());
+
+    let actual = given.resolve(&setup.uni_td_repo(), None).unwrap();
+
+    assert_eq!(actual.remote().inner(), setup.remote_file);
+    assert_eq!(actual.remote_idx(), 0);
+}
+
+#[test]
+fn fallbacks_uses_first_remote_that_exists() {
+    let setup = Setup::new();
+    setup.make_remote_subdir_dir();
+    setup.make_remote_dir();
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = setup.parent_dir.join("DNE").to_string_lossy().to_string();
+    given.fallbacks = vec![
+        setup.parent_dir.join("DNE2").to_string_lossy().to_string(),
+        setup.remote_subdir_dir.to_string_lossy().to_string(),
+        setup.remote_dir.to_string_lossy().to_string(),
+    ];
+
+    let actual = given.resolve(&setup.uni_td_repo(), None).unwrap();
+
+    assert_eq!(actual.remote().inner(), setup.remote_subdir_dir);
+    assert_eq!(actual.remote_idx(), 2);
+}
+
+#[test]
+fn fallbacks_prefers_remote_if_it_exists() {
+    let setup = Setup::new();
+    setup.make_remote_file();
+    setup.make_remote_subdir_file();
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = setup.remote_file.to_string_lossy().to_string();
+    given.fallbacks = vec![
+        setup.remote_subdir_file.to_string_lossy().to_string(),
+    ];
+
+    let actual = given.resolve(&setup.uni_td_repo(), None).unwrap();
+
+    assert_eq!(actual.remote().inner(), setup.remote_file);
+    assert_eq!(actual.remote_idx(), 0);
+}
+
+#[rstest]
+#[case(None)]
+#[case(Some(ActionMode::Push))]
+#[case(Some(ActionMode::Link))]
+#[case(Some(ActionMode::Out))]
+#[case(Some(ActionMode::Relink))]
+fn fallbacks_none_exist_and_not_pulling_uses_first_with_existing_parent(
+    #[case] mode: Option<ActionMode>,
+) {
+    let setup = Setup::new();
+    setup.make_remote_subdir_dir();
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = setup.parent_dir.join("DNE/misc.txt").to_string_lossy().to_string();
+    given.fallbacks = vec![
+        setup.remote_subdir_file.to_string_lossy().to_string(),
+        setup.remote_file.to_string_lossy().to_string(),
+    ];
+    assert!(!setup.remote_subdir_file.exists());
+    assert!(!setup.remote_file.exists());
+
+    let actual = given.resolve(&setup.uni_td_repo(), mode.as_ref()).unwrap();
+
+    assert_eq!(actual.remote().inner(), setup.remote_subdir_file);
+    assert_eq!(actual.remote_idx(), 1);
+}
+
+#[test]
+fn fallbacks_none_exist_and_pulling_ignores_existing_parents_and_uses_remote() {
+    let setup = Setup::new();
+    setup.make_remote_subdir_dir();
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = setup.parent_dir.join("DNE/misc.txt").to_string_lossy().to_string();
+    given.fallbacks = vec![
+        setup.remote_subdir_file.to_string_lossy().to_string(),
+        setup.remote_file.to_string_lossy().to_string(),
+    ];
+    assert!(!setup.remote_subdir_file.exists());
+    assert!(!setup.remote_file.exists());
+
+    let actual = given.resolve(&setup.uni_td_repo(), Some(&ActionMode::Pull)).unwrap();
+
+    assert_eq!(actual.remote().inner(), setup.parent_dir.join("DNE/misc.txt"));
+    assert_eq!(actual.remote_idx(), 0);
+}
+
+#[test]
+fn fallbacks_none_exist_and_no_parents_exist_uses_remote() {
+    let setup = Setup::new();
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = setup.parent_dir.join("DNE1/misc.txt").to_string_lossy().to_string();
+    given.fallbacks = vec![
+        setup.parent_dir.join("DNE2/misc.txt").to_string_lossy().to_string(),
+    ];
+
+    let actual = given.resolve(&setup.uni_td_repo(), None).unwrap();
+
+    assert_eq!(actual.remote().inner(), setup.parent_dir.join("DNE1/misc.txt"));
+    assert_eq!(actual.remote_idx(), 0);
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn fallbacks_skips_remotes_with_unresolved_vars() {
+    let setup = Setup::new();
+    setup.make_remote_file();
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = "<I_do_not_exist>/misc.txt".to_string();
+    given.fallbacks = vec![
+        setup.parent_dir.join("<I_do_not_exist>").to_string_lossy().to_string(),
+        setup.parent_dir.join("DNE1/misc.txt").to_string_lossy().to_string(),
+    ];
+    std::env::remove_var("I_do_not_exist");
+
+    let actual = given.resolve(&setup.uni_td_repo(), None).unwrap();
+
+    assert_eq!(actual.remote().inner(), setup.parent_dir.join("DNE1/misc.txt"));
+    assert_eq!(actual.remote_idx(), 2);
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn fallbacks_all_have_unresolved_vars_returns_err_for_remote() {
+    let setup = Setup::new();
+    let mut given = RawTendril::new("SomeLocal");
+    given.remote = "<I_do_not_exist>/misc.txt".to_string();
+    given.fallbacks = vec![
+        "${I_do_not_exist2:?Some message}".to_string(),
+    ];
+    std::env::remove_var("I_do_not_exist");
+    std::env::remove_var("I_do_not_exist2");
+
+    let actual = given.resolve(&setup.uni_td_repo(), None);
+
+    assert_eq!(
+        actual,
+        Err(InvalidTendrilError::UnresolvedVariable {
+            name: "I_do_not_exist".to_string(),
+            msg: None,
+        }),
+    );
+}
@@ -1,5 +1,5 @@
 use crate::{InvalidTendrilError, Tendril, TendrilMode, UniPath};
-use crate::test_utils::non_utf_8_text;
+use crate::test_utils::{non_utf_8_text, symlink_expose, Setup};
 use rstest::rstest;
 use rstest_reuse::{self, apply, template};
 use serial_test::serial;
@@ -194,11 +194,17 @@ fn remote_is_valid_returns_ok_or_recursive_2(#[case] remote: &str) {
     "\\\\Both\\\\",
     "\\Trailing\\\\\\\\Both\\\\"),
 )]
-#[case(
+// The `.` components in the repo are normalized, but not in the local
+#[cfg_attr(not(windows), case(
     "Parent///Slashes\\\\././",
     "Name//.\\Slashes\\\\.",
-    &format!("{SEP}Parent{SEP}{SEP}{SEP}Slashes\\\\.{SEP}.{SEP}Name{SEP}{SEP}.\\Slashes\\\\."),
-)]
+    "/Parent///Slashes\\\\./Name//.\\Slashes\\\\.",
+))]
+#[cfg_attr(windows, case(
+    "Parent///Slashes\\\\././",
+    "Name//.\\Slashes\\\\.",
+    "\\Parent\\\\\\Slashes\\\\Name\\\\.\\Slashes\\\\.",
+))]
 #[case("Plain", "C:\\Abs", &format!("{SEP}Plain{SEP}C:\\Abs"))]
 #[case("Trailing/", "C:\\Abs", &format!("{SEP}Trailing{SEP}C:\\Abs"))]
 #[cfg_attr(not(windows), case("Trailing\\", "C:\\Abs", "/Trailing\\/C:\\Abs"))]
@@ -313,9 +319,10 @@ fn remote_preserves_non_utf8() {
 #[case("/path/repo/../repo", "/path/repo")]
 #[case("/path/repo/nested/..", "/path/repo")]
 #[case("/path/./repo", "/path/repo")]
-// Not detected properly
-// #[case("/path/repo", "../path/repo")]
-// #[case("/otherpath/../path/repo", "/path/repo")]
+#[case("/path/repo", "../path/repo")]
+#[case("/path/repo", "/otherpath/../path/repo/misc.txt")]
+#[case("/otherpath/../path/repo", "/path/repo")]
+#[case("/path/repo", "/path/repo/nested/../../repo")]
 fn recursive_remote_returns_recursion_error(
     #[case] td_repo: PathBuf,
     #[case] remote: PathBuf,
@@ -341,3 +348,40 @@ fn remote_is_sibling_to_given_td_repo_proceeds_normally() {
 
     assert!(actual.is_ok());
 }
+
+#[test]
+#[cfg_attr(windows, ignore)] // Creating symlinks may require admin rights
+fn remote_inside_symlink_to_td_repo_returns_recursion_error() {
+    let setup = Setup::new();
+    setup.make_td_repo_dir();
+    let sym_to_repo = setup.temp_dir.path().join("SymToRepo");
+    symlink_expose(&sym_to_repo, &setup.td_repo, false, true).unwrap();
+
+    let actual = Tendril::new(
+        UniPath::from(setup.td_repo.as_path()),
+        "SomeLocal".into(),
+        UniPath::from(sym_to_repo.join("misc.txt").as_path()),
+        TendrilMode::DirOverwrite,
+    );
+
+    assert_eq!(actual, Err(InvalidTendrilError::Recursion));
+}
+
+#[test]
+#[cfg_attr(windows, ignore)] // Creating symlinks may require admin rights
+fn remote_is_symlink_to_td_repo_proceeds_normally() {
+    // The remote itself is not resolved as it may be a link to the local
+    let setup = Setup::new();
+    setup.make_td_repo_dir();
+    let sym_to_repo = setup.temp_dir.path().join("SymToRepo");
+    symlink_expose(&sym_to_repo, &setup.td_repo, false, true).unwrap();
+
+    let actual = Tendril::new(
+        UniPath::from(setup.td_repo.as_path()),
+        "SomeLocal".into(),
+        UniPath::from(sym_to_repo.as_path()),
+        TendrilMode::Link,
+    );
+
+    assert!(actual.is_ok());
+}
@@ -1,9 +1,11 @@
 use crate::{
+    CopyMethod,
     FsoType,
     InvalidTendrilError,
     RawTendril,
     TendrilActionError,
     TendrilActionSuccess,
+    UniPath,
 };
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -20,6 +22,17 @@ pub struct TendrilReport<T: TendrilLog> {
     pub log: Result<T, InvalidTendrilError>,
 }
 
+/// The reports from each of the tendrils in a single Tendrils repo, when
+/// operating on several repos at once
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RepoReports<T: TendrilLog> {
+    /// The Tendrils repo that the tendrils are defined in.
+    pub td_repo: UniPath,
+
+    /// The report for each of the tendrils in this repo.
+    pub reports: Vec<TendrilReport<T>>,
+}
+
 /// Generic log information for any operation on a tendril
 pub trait TendrilLog {
     /// The type of the file system object in the Tendrils repo.
@@ -44,6 +57,8 @@ pub struct ActionLog {
     resolved_path: PathBuf,
     /// Result of this individual action.
     pub result: Result<TendrilActionSuccess, TendrilActionError>,
+    copy_method: Option<CopyMethod>,
+    outside_symlinks: Vec<PathBuf>,
 }
 
 impl ActionLog {
@@ -53,7 +68,40 @@ impl ActionLog {
         resolved_path: PathBuf,
         result: Result<TendrilActionSuccess, TendrilActionError>,
     ) -> ActionLog {
-        ActionLog { local_type, remote_type, resolved_path, result }
+        ActionLog {
+            local_type,
+            remote_type,
+            resolved_path,
+            result,
+            copy_method: None,
+            outside_symlinks: vec![],
+        }
+    }
+
+    /// Copies the log with the updated `copy_method`
+    pub fn with_copy_method(self, copy_method: Option<CopyMethod>) -> ActionLog {
+        ActionLog { copy_method, ..self }
+    }
+
+    /// How the file system object was copied. This is only set if reflinks
+    /// are enabled for the Tendrils repo (see
+    /// [`ReflinkMode`](crate::ReflinkMode)) and something was copied, so it
+    /// is `None` for dry runs and for any failed actions.
+    pub fn copy_method(&self) -> Option<CopyMethod> {
+        self.copy_method
+    }
+
+    /// Copies the log with the updated `outside_symlinks`
+    pub fn with_outside_symlinks(self, outside_symlinks: Vec<PathBuf>) -> ActionLog {
+        ActionLog { outside_symlinks, ..self }
+    }
+
+    /// The symlinks inside of a copied folder that point outside of it. These
+    /// are found whether or not the action is a dry run, unless the
+    /// tendril's [`NestedSymlinks`](crate::NestedSymlinks) policy is to skip
+    /// them.
+    pub fn outside_symlinks(&self) -> &[PathBuf] {
+        &self.outside_symlinks
     }
 }
 
@@ -77,6 +125,7 @@ pub struct ListLog {
     local_type: Option<FsoType>,
     remote_type: Option<FsoType>,
     resolved_path: PathBuf,
+    remote_idx: usize,
 }
 
 impl ListLog {
@@ -85,7 +134,19 @@ impl ListLog {
         remote_type: Option<FsoType>,
         resolved_path: PathBuf,
     ) -> ListLog {
-        ListLog { local_type, remote_type, resolved_path }
+        ListLog { local_type, remote_type, resolved_path, remote_idx: 0 }
+    }
+
+    /// Copies the log with the updated `remote_idx`
+    pub fn with_remote_idx(self, remote_idx: usize) -> ListLog {
+        ListLog { remote_idx, ..self }
+    }
+
+    /// The index of the remote that was chosen from the combined list of the
+    /// [`RawTendril::remote`] followed by its [`RawTendril::fallbacks`].
+    /// This is always `0` for tendrils without any fallbacks.
+    pub fn remote_idx(&self) -> usize {
+        self.remote_idx
     }
 }
 
@@ -120,19 +181,55 @@ where
     /// Accepts `report` containing the result of the operation.
     /// This is called *after* processing.
     fn after(&mut self, report: TendrilReport<L>);
+
+    /// Accepts `progress` of the tendril that is being processed.
+    /// This is called any number of times between [`before`](Self::before)
+    /// and [`after`](Self::after), but only while copying (i.e. never on a
+    /// dry run or when linking), and only if
+    /// [`wants_progress`](Self::wants_progress) returns `true`. Progress is
+    /// ignored by default.
+    fn progress(&mut self, _progress: CopyProgress) {}
+
+    /// Whether [`progress`](Self::progress) should be called. Files are
+    /// copied more quickly when their progress is not reported, so this is
+    /// `false` by default.
+    fn wants_progress(&self) -> bool {
+        false
+    }
+}
+
+/// Progress of copying a single tendril, as reported to
+/// [`UpdateHandler::progress`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CopyProgress {
+    /// Sent once before anything is copied. Contains the total number of
+    /// files that will be copied and their combined size in bytes.
+    Start { files: u64, bytes: u64 },
+
+    /// Sent before the file at `path` is copied.
+    File { path: PathBuf },
+
+    /// Sent as the contents are copied. Contains the total number of bytes
+    /// copied so far for this tendril.
+    Bytes { copied: u64 },
 }
 
 /// Accepts callbacks to be called by the [`UpdateHandler`] methods.
-pub struct CallbackUpdater<A, B, C, L>
+/// Progress is ignored unless a callback is given with
+/// [`with_progress`](CallbackUpdater::with_progress).
+pub struct CallbackUpdater<A, B, C, L, P = fn(CopyProgress)>
 where
     A: FnMut(TendrilReport<L>),
     B: FnMut(RawTendril),
     C: FnMut(i32),
     L: TendrilLog,
+    P: FnMut(CopyProgress),
 {
     pub count: C,
     pub before: B,
     pub after: A,
+    pub progress: P,
+    wants_progress: bool,
     _marker: PhantomData<L>,
 }
 
@@ -148,6 +245,8 @@ where
             count,
             before,
             after,
+            progress: |_| {},
+            wants_progress: false,
             _marker: PhantomData,
         }
     }
@@ -158,17 +257,44 @@ where
             count: |_| {},
             before: |_| {},
             after: |_| {},
+            progress: |_| {},
+            wants_progress: false,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A, B, C, L, P> CallbackUpdater<A, B, C, L, P>
+where
+    A: FnMut(TendrilReport<L>),
+    B: FnMut(RawTendril),
+    C: FnMut(i32),
+    L: TendrilLog,
+    P: FnMut(CopyProgress),
+{
+    /// Replaces the callback for [`UpdateHandler::progress`].
+    pub fn with_progress<Q>(self, progress: Q) -> CallbackUpdater<A, B, C, L, Q>
+    where
+        Q: FnMut(CopyProgress),
+    {
+        CallbackUpdater {
+            count: self.count,
+            before: self.before,
+            after: self.after,
+            progress,
+            wants_progress: true,
             _marker: PhantomData,
         }
     }
 }
 
-impl<A, B, C, L> UpdateHandler<L> for CallbackUpdater<A, B, C, L>
+impl<A, B, C, L, P> UpdateHandler<L> for CallbackUpdater<A, B, C, L, P>
 where
     A: FnMut(TendrilReport<L>),
     B: FnMut(RawTendril),
     C: FnMut(i32),
     L: TendrilLog,
+    P: FnMut(CopyProgress),
 {
     fn count(&mut self, total: i32) {
         (self.count)(total)
@@ -181,4 +307,12 @@ where
     fn after(&mut self, report: TendrilReport<L>) {
         (self.after)(report)
     }
+
+    fn progress(&mut self, progress: CopyProgress) {
+        (self.progress)(progress)
+    }
+
+    fn wants_progress(&self) -> bool {
+        self.wants_progress
+    }
 }
@@ -2,12 +2,22 @@ use crate::{
     symlink,
     ActionMode,
     ActionLog,
+    AutoProfileLog,
+    CheckFinding,
+    ConfigFormat,
+    ConfigIssue,
+    CopyProgress,
+    Diagnosis,
+    CONFIG_VERSION,
     FilterSpec,
     InitError,
     ListLog,
+    NestedSymlinks,
     PathExt,
     SetupError,
     RawTendril,
+    ReflinkMode,
+    RepoReports,
     Tendril,
     TendrilMode,
     TendrilReport,
@@ -73,6 +83,13 @@ pub fn global_cfg_file() -> PathBuf {
     global_cfg_dir().join("global-config.json")
 }
 
+/// Path to the global `global-config.json` file in the default XDG config
+/// folder (`~/.config/tendrils`) using the current value of the `HOME`
+/// environment variable
+pub fn xdg_global_cfg_file() -> PathBuf {
+    home_dir().join(".config/tendrils/global-config.json")
+}
+
 /// Creates the json for a `global-config.json` file containing only a
 /// `default-repo-path` field
 pub fn default_repo_path_as_json(default_repo_path: &str) -> String {
@@ -117,6 +134,7 @@ pub fn symlink_expose(
         &create_at.get_type(),
         target,
         &target.get_type(),
+        false,
         dry_run,
         force,
     )
@@ -219,6 +237,10 @@ pub fn set_ra(path: &Path, can_read: bool) {
     }
 }
 
+type FindTendrilsRepoFn = dyn Fn(&UniPath) -> Option<UniPath>;
+type GetDefaultReposFn = dyn Fn() -> Result<Vec<PathBuf>, GetConfigError>;
+type ValidateFn = dyn Fn(Option<&UniPath>) -> Result<Vec<ConfigIssue>, SetupError>;
+
 pub struct MockTendrilsApi<'a> {
     pub init_const_rt: Result<(), InitError>,
     pub init_fn: Option<Box<dyn Fn(&UniPath, bool) -> Result<(), InitError>>>,
@@ -226,19 +248,50 @@ pub struct MockTendrilsApi<'a> {
     pub init_exp_force_arg: bool,
     pub is_tendrils_repo_const_rt: bool,
     pub is_tendrils_repo_fn: Option<Box<dyn Fn(&UniPath) -> bool>>,
+    pub find_tendrils_repo_fn: Option<Box<FindTendrilsRepoFn>>,
     pub get_default_repo_const_rt: Result<Option<PathBuf>, GetConfigError>,
     pub get_default_repo_fn: Option<Box<dyn Fn() -> Result<Option<PathBuf>, GetConfigError>>>,
+    pub get_default_repos_fn: Option<Box<GetDefaultReposFn>>,
     pub get_default_profiles_const_rt: Result<Option<Vec<String>>, GetConfigError>,
     pub get_default_profiles_fn: Option<Box<dyn Fn() -> Result<Option<Vec<String>>, GetConfigError>>>,
+    pub get_auto_profiles_const_rt: Result<Vec<AutoProfileLog>, SetupError>,
+    pub get_auto_profiles_exp_path: Option<&'a Path>,
     pub tau_const_count_updater_rt: i32,
     pub tau_const_before_updater_rts: Vec<RawTendril>,
     pub tau_const_after_updater_rts: Vec<TendrilReport<ActionLog>>,
+    /// Sent for the tendrils in order, unless empty.
+    pub tau_const_progress_updater_rts: Vec<Vec<CopyProgress>>,
     pub tau_const_rt: Result<(), SetupError>,
     pub list_const_rt: Result<Vec<TendrilReport<ListLog>>, SetupError>,
     pub list_fn: Option<Box<dyn Fn(Option<&UniPath>, FilterSpec)
         -> Result<Vec<TendrilReport<ListLog>>, SetupError>>>,
     pub list_exp_path: Option<&'a Path>,
     pub list_exp_filter: FilterSpec,
+    pub list_multi_const_rt: Result<Vec<RepoReports<ListLog>>, SetupError>,
+    pub list_multi_exp_paths: Vec<&'a Path>,
+    pub check_const_rt: Result<Vec<CheckFinding>, SetupError>,
+    pub check_exp_path: Option<&'a Path>,
+    pub check_exp_filter: FilterSpec,
+    pub validate_const_rt: Result<Vec<ConfigIssue>, SetupError>,
+    /// Replaces both the `validate_const_rt` and the `validate_exp_path`,
+    /// i.e. for commands that validate several repos.
+    pub validate_fn: Option<Box<ValidateFn>>,
+    pub validate_exp_path: Option<&'a Path>,
+    pub convert_const_rt: Result<ConfigFormat, SetupError>,
+    pub convert_exp_path: Option<&'a Path>,
+    pub convert_exp_format: ConfigFormat,
+    pub convert_exp_drop_comments: bool,
+    pub migrate_const_rt: Result<u32, SetupError>,
+    pub migrate_exp_path: Option<&'a Path>,
+    pub migrate_exp_drop_comments: bool,
+    pub migrate_global_const_rt: Result<u32, GetConfigError>,
+    pub fmt_const_rt: Result<bool, SetupError>,
+    pub fmt_exp_path: Option<&'a Path>,
+    pub fmt_exp_sort_keys: bool,
+    pub fmt_exp_check: bool,
+    pub fmt_exp_drop_comments: bool,
+    pub diagnose_const_rt: Vec<Diagnosis>,
+    pub diagnose_exp_path: Option<&'a Path>,
     pub ta_const_rt: Result<Vec<TendrilReport<ActionLog>>, SetupError>,
     pub ta_fn: Option<
         Box<
@@ -257,6 +310,8 @@ pub struct MockTendrilsApi<'a> {
     pub ta_exp_filter: FilterSpec,
     pub ta_exp_dry_run: bool,
     pub ta_exp_force: bool,
+    pub ta_multi_const_rt: Result<Vec<RepoReports<ActionLog>>, SetupError>,
+    pub ta_multi_exp_paths: Vec<&'a Path>,
 }
 
 impl<'a> MockTendrilsApi<'a> {
@@ -268,17 +323,45 @@ impl<'a> MockTendrilsApi<'a> {
             init_fn: None,
             is_tendrils_repo_const_rt: true,
             is_tendrils_repo_fn: None,
+            find_tendrils_repo_fn: None,
             get_default_repo_const_rt: Ok(None),
             get_default_repo_fn: None,
+            get_default_repos_fn: None,
             get_default_profiles_const_rt: Ok(None),
             get_default_profiles_fn: None,
+            get_auto_profiles_const_rt: Ok(vec![]),
+            get_auto_profiles_exp_path: None,
             list_const_rt: Ok(vec![]),
             list_fn: None,
             list_exp_path: None,
             list_exp_filter: FilterSpec::new(),
+            list_multi_const_rt: Ok(vec![]),
+            list_multi_exp_paths: vec![],
+            check_const_rt: Ok(vec![]),
+            check_exp_path: None,
+            check_exp_filter: FilterSpec::new(),
+            validate_const_rt: Ok(vec![]),
+            validate_fn: None,
+            validate_exp_path: None,
+            convert_const_rt: Ok(ConfigFormat::Json),
+            convert_exp_path: None,
+            convert_exp_format: ConfigFormat::Json,
+            convert_exp_drop_comments: false,
+            migrate_const_rt: Ok(CONFIG_VERSION),
+            migrate_exp_path: None,
+            migrate_exp_drop_comments: false,
+            migrate_global_const_rt: Ok(CONFIG_VERSION),
+            fmt_const_rt: Ok(false),
+            fmt_exp_path: None,
+            fmt_exp_sort_keys: false,
+            fmt_exp_check: false,
+            fmt_exp_drop_comments: false,
+            diagnose_const_rt: vec![],
+            diagnose_exp_path: None,
             tau_const_count_updater_rt: 0,
             tau_const_before_updater_rts: vec![],
             tau_const_after_updater_rts: vec![],
+            tau_const_progress_updater_rts: vec![],
             tau_const_rt: Ok(()),
             ta_const_rt: Ok(vec![]),
             ta_fn: None,
@@ -287,6 +370,8 @@ impl<'a> MockTendrilsApi<'a> {
             ta_exp_filter: FilterSpec::new(),
             ta_exp_dry_run: false,
             ta_exp_force: false,
+            ta_multi_const_rt: Ok(vec![]),
+            ta_multi_exp_paths: vec![],
         }
     }
 }
@@ -317,6 +402,20 @@ impl TendrilsApi for MockTendrilsApi<'_> {
         }
     }
 
+    /// Unless overridden, this only checks the given folder using
+    /// [`TendrilsApi::is_tendrils_repo`]
+    fn find_tendrils_repo(&self, dir: &UniPath) -> Option<UniPath> {
+        if let Some(f) = self.find_tendrils_repo_fn.as_ref() {
+            f(dir)
+        }
+        else if self.is_tendrils_repo(dir) {
+            Some(dir.clone())
+        }
+        else {
+            None
+        }
+    }
+
     fn get_default_repo_path(&self) -> Result<Option<PathBuf>, GetConfigError> {
         if let Some(f) = self.get_default_repo_fn.as_ref() {
             f()
@@ -326,6 +425,17 @@ impl TendrilsApi for MockTendrilsApi<'_> {
         }
     }
 
+    /// Unless overridden, this returns the result of
+    /// [`TendrilsApi::get_default_repo_path`] as a list
+    fn get_default_repo_paths(&self) -> Result<Vec<PathBuf>, GetConfigError> {
+        if let Some(f) = self.get_default_repos_fn.as_ref() {
+            f()
+        }
+        else {
+            Ok(self.get_default_repo_path()?.into_iter().collect())
+        }
+    }
+
     fn get_default_profiles(&self) -> Result<Option<Vec<String>>, GetConfigError> {
         if let Some(f) = self.get_default_profiles_fn.as_ref() {
             f()
@@ -335,6 +445,20 @@ impl TendrilsApi for MockTendrilsApi<'_> {
         }
     }
 
+    fn get_auto_profiles(
+        &self,
+        td_repo: Option<&UniPath>,
+    ) -> Result<Vec<AutoProfileLog>, SetupError> {
+        if let Some(p) = td_repo {
+            assert_eq!(p.inner(), self.get_auto_profiles_exp_path.unwrap());
+        }
+        else {
+            assert_eq!(None, self.get_auto_profiles_exp_path);
+        }
+
+        self.get_auto_profiles_const_rt.clone()
+    }
+
     fn list_tendrils(
         &self,
         td_repo: Option<&UniPath>,
@@ -356,6 +480,78 @@ impl TendrilsApi for MockTendrilsApi<'_> {
         }
     }
 
+    fn check_tendrils(
+        &self,
+        td_repo: Option<&UniPath>,
+        filter: FilterSpec,
+    ) -> Result<Vec<CheckFinding>, SetupError> {
+        assert_eq!(td_repo.map(|p| p.inner()), self.check_exp_path);
+        assert_eq!(filter, self.check_exp_filter);
+
+        self.check_const_rt.clone()
+    }
+
+    fn validate_config(
+        &self,
+        td_repo: Option<&UniPath>,
+    ) -> Result<Vec<ConfigIssue>, SetupError> {
+        if let Some(f) = self.validate_fn.as_ref() {
+            return f(td_repo);
+        }
+        assert_eq!(td_repo.map(|p| p.inner()), self.validate_exp_path);
+
+        self.validate_const_rt.clone()
+    }
+
+    fn convert_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        format: ConfigFormat,
+        drop_comments: bool,
+    ) -> Result<ConfigFormat, SetupError> {
+        assert_eq!(td_repo.map(|p| p.inner()), self.convert_exp_path);
+        assert_eq!(format, self.convert_exp_format);
+        assert_eq!(drop_comments, self.convert_exp_drop_comments);
+
+        self.convert_const_rt.clone()
+    }
+
+    fn migrate_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        drop_comments: bool,
+    ) -> Result<u32, SetupError> {
+        assert_eq!(td_repo.map(|p| p.inner()), self.migrate_exp_path);
+        assert_eq!(drop_comments, self.migrate_exp_drop_comments);
+
+        self.migrate_const_rt.clone()
+    }
+
+    fn migrate_global_config(&self) -> Result<u32, GetConfigError> {
+        self.migrate_global_const_rt.clone()
+    }
+
+    fn fmt_config(
+        &self,
+        td_repo: Option<&UniPath>,
+        sort_keys: bool,
+        check: bool,
+        drop_comments: bool,
+    ) -> Result<bool, SetupError> {
+        assert_eq!(td_repo.map(|p| p.inner()), self.fmt_exp_path);
+        assert_eq!(sort_keys, self.fmt_exp_sort_keys);
+        assert_eq!(check, self.fmt_exp_check);
+        assert_eq!(drop_comments, self.fmt_exp_drop_comments);
+
+        self.fmt_const_rt.clone()
+    }
+
+    fn diagnose(&self, td_repo: Option<&UniPath>) -> Vec<Diagnosis> {
+        assert_eq!(td_repo.map(|p| p.inner()), self.diagnose_exp_path);
+
+        self.diagnose_const_rt.clone()
+    }
+
     fn tendril_action_updating<U>(
         &self,
         mut updater: U,
@@ -400,6 +596,11 @@ impl TendrilsApi for MockTendrilsApi<'_> {
             // =========================
             // Action would be done here
             // =========================
+            if let Some(events) = self.tau_const_progress_updater_rts.get(i as usize) {
+                for progress in events.iter().cloned() {
+                    updater.progress(progress);
+                }
+            }
 
             updater.after(self.tau_const_after_updater_rts[i as usize].clone());
         }
@@ -433,6 +634,80 @@ impl TendrilsApi for MockTendrilsApi<'_> {
             self.ta_const_rt.clone()
         }
     }
+
+    fn list_tendrils_multi(
+        &self,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+    ) -> Result<Vec<RepoReports<ListLog>>, SetupError> {
+        let paths: Vec<&Path> = td_repos.iter().map(|p| p.inner()).collect();
+        assert_eq!(paths, self.list_multi_exp_paths);
+        assert_eq!(filter, self.list_exp_filter);
+
+        self.list_multi_const_rt.clone()
+    }
+
+    fn tendril_action_multi_updating<U>(
+        &self,
+        mut updater: U,
+        mode: ActionMode,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<Vec<RepoReports<ActionLog>>, SetupError>
+    where
+        U: UpdateHandler<ActionLog>
+    {
+        let paths: Vec<&Path> = td_repos.iter().map(|p| p.inner()).collect();
+        assert_eq!(paths, self.ta_multi_exp_paths);
+        assert_eq!(mode, self.ta_exp_mode);
+        assert_eq!(filter, self.ta_exp_filter);
+        assert_eq!(dry_run, self.ta_exp_dry_run);
+        assert_eq!(force, self.ta_exp_force);
+
+        let repo_reports = self.ta_multi_const_rt.clone()?;
+        let reports: Vec<&TendrilReport<ActionLog>> = repo_reports
+            .iter()
+            .flat_map(|r| r.reports.iter())
+            .collect();
+
+        updater.count(reports.len() as i32);
+        for (i, report) in reports.into_iter().enumerate() {
+            updater.before(report.raw_tendril.clone());
+
+            // =========================
+            // Action would be done here
+            // =========================
+            if let Some(events) = self.tau_const_progress_updater_rts.get(i) {
+                for progress in events.iter().cloned() {
+                    updater.progress(progress);
+                }
+            }
+
+            updater.after(report.clone());
+        }
+
+        Ok(repo_reports)
+    }
+
+    fn tendril_action_multi(
+        &self,
+        mode: ActionMode,
+        td_repos: &[UniPath],
+        filter: FilterSpec,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<Vec<RepoReports<ActionLog>>, SetupError> {
+        let paths: Vec<&Path> = td_repos.iter().map(|p| p.inner()).collect();
+        assert_eq!(paths, self.ta_multi_exp_paths);
+        assert_eq!(mode, self.ta_exp_mode);
+        assert_eq!(filter, self.ta_exp_filter);
+        assert_eq!(dry_run, self.ta_exp_dry_run);
+        assert_eq!(force, self.ta_exp_force);
+
+        self.ta_multi_const_rt.clone()
+    }
 }
 
 /// Abstractions for Tendrils test setups built around temporary directories.
@@ -539,8 +814,14 @@ impl Setup {
         let raw = RawTendril {
             local: "SomeApp/misc.txt".to_string(),
             remote: self.remote_file.to_string_lossy().to_string(),
+            fallbacks: vec![],
             mode: TendrilMode::DirOverwrite,
+            relative_link: false,
+            copy_fallback: false,
+            reflink: ReflinkMode::Never,
+            nested_symlinks: NestedSymlinks::Follow,
             profiles: vec![],
+            source: None,
         };
         raw
     }
@@ -605,6 +886,9 @@ impl Setup {
         self.make_dot_td_dir();
         let json = crate::config::serialize_config(Config {
             raw_tendrils: tendrils.to_vec(),
+            auto_profiles: indexmap::IndexMap::new(),
+            profile_groups: indexmap::IndexMap::new(),
+            format: ConfigFormat::Json,
         });
         write(&self.td_json_file, json).unwrap();
     }
@@ -705,12 +989,14 @@ impl Setup {
         write(global_cfg_file(), json).unwrap();
     }
 
-    /// Sets the home directory to the [`Setup::temp_dir`].
+    /// Sets the home directory to the [`Setup::temp_dir`], and unsets the
+    /// `XDG_CONFIG_HOME` so that it defaults to a folder within it.
     /// Note: This changes the `HOME` environment variable for the process
     /// so should not be run in parallel with other tests where this may
     /// interfere.
     pub fn set_home_dir(&self) {
         std::env::set_var("HOME", self.temp_dir.path());
+        std::env::remove_var("XDG_CONFIG_HOME");
     }
 
     pub fn td_json_file_contents(&self) -> String {
@@ -21,10 +21,10 @@ use crate::{
     TendrilMode,
     TendrilReport,
 };
-use fs_extra::file::read_to_string;
 use rstest::rstest;
+use same_file::is_same_file;
 use serial_test::serial;
-use std::fs::{create_dir_all, write};
+use std::fs::{create_dir_all, read_to_string, write};
 use std::path::PathBuf;
 use tempdir::TempDir;
 
@@ -784,6 +784,47 @@ fn out_returns_tendril_and_result_for_each_given_link_or_copy_type(
     }
 }
 
+#[rstest]
+#[case(ActionMode::Link)]
+#[case(ActionMode::Out)]
+fn hardlink_tendrils_are_hard_linked(
+    #[case] mode: ActionMode,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let mut given = setup.file_tendril_raw();
+    given.mode = TendrilMode::Hardlink;
+
+    let mut after_actual = vec![];
+    let updater = CallbackUpdater::<_, _, _, ActionLog>::new(
+        |_| {},
+        |_| {},
+        |report| after_actual.push(report),
+    );
+
+    batch_tendril_action(
+        updater,
+        mode,
+        &setup.uni_td_repo(),
+        vec![given.clone()],
+        false,
+        force,
+    );
+
+    assert_eq!(after_actual, vec![TendrilReport {
+        raw_tendril: given,
+        log: Ok(ActionLog::new(
+            Some(FsoType::File),
+            None,
+            setup.remote_file.clone(),
+            Ok(TendrilActionSuccess::New),
+        )),
+    }]);
+    assert!(!setup.remote_file.is_symlink());
+    assert!(is_same_file(&setup.local_file, &setup.remote_file).unwrap());
+}
+
 #[rstest]
 #[serial(SERIAL_MUT_ENV_VARS)]
 fn remote_path_vars_are_resolved(
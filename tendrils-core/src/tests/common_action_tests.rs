@@ -19,6 +19,7 @@ use crate::{
     push_tendril,
     ActionLog,
     FsoType,
+    LinkStatus,
     Location,
     Tendril,
     TendrilActionError,
@@ -32,7 +33,7 @@ use rstest::rstest;
 use rstest_reuse::{self, apply, template};
 use serial_test::serial;
 use std::fs::{create_dir_all, read_to_string, remove_dir, remove_file, write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[rstest]
 #[case(link_tendril)]
@@ -67,13 +68,12 @@ fn remote_is_sibling_to_given_td_repo_proceeds_normally(
 
     let actual = action(&tendril, dry_run, force);
 
-    let exp_result;
-    if dry_run {
-        exp_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-    }
-    else {
-        exp_result = Ok(TendrilActionSuccess::Overwrite);
-    }
+    let exp_result = exp_overwrite(
+        dry_run,
+        tendril.mode == TendrilMode::Link,
+        &setup.target_dir,
+        LinkStatus::WrongTarget,
+    );
     assert_eq!(
         actual,
         ActionLog::new(
@@ -380,13 +380,12 @@ fn var_in_local_uses_raw_path_even_if_var_exists(
 
     let actual = action(&tendril, dry_run, force);
 
-    let exp_result;
-    if dry_run {
-        exp_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-    }
-    else {
-        exp_result = Ok(TendrilActionSuccess::Overwrite);
-    }
+    let exp_result = exp_overwrite(
+        dry_run,
+        tendril.mode == TendrilMode::Link,
+        &setup.target_file,
+        LinkStatus::WrongTarget,
+    );
     assert_eq!(
         actual,
         ActionLog::new(
@@ -459,20 +458,26 @@ fn other_tendrils_in_same_group_dir_are_unchanged(
         some_other_local_nested_contents,
         "Another nested from the same group"
     );
-    let exp_result;
-    if dry_run {
-        exp_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-    }
-    else {
-        exp_result = Ok(TendrilActionSuccess::Overwrite);
-    }
+    let is_link = file_tendril.mode == TendrilMode::Link;
+    let exp_file_result = exp_overwrite(
+        dry_run,
+        is_link,
+        &setup.target_file,
+        LinkStatus::WrongTarget,
+    );
+    let exp_dir_result = exp_overwrite(
+        dry_run,
+        is_link,
+        &setup.target_dir,
+        LinkStatus::WrongTarget,
+    );
     assert_eq!(
         file_actual,
         ActionLog::new(
             Some(FsoType::File),
             exp_remote_type_file,
             setup.remote_file,
-            exp_result.clone(),
+            exp_file_result,
         )
     );
     assert_eq!(
@@ -481,7 +486,7 @@ fn other_tendrils_in_same_group_dir_are_unchanged(
             Some(FsoType::Dir),
             exp_remote_type_dir,
             setup.remote_dir,
-            exp_result,
+            exp_dir_result,
         )
     );
 }
@@ -799,8 +804,9 @@ fn remote_direct_parent_doesnt_exist_but_parent_does_should_create_subdirs_then_
 #[rstest]
 #[case(pull_tendril)]
 #[case(push_tendril)]
-fn link_mode_tendril_returns_mode_mismatch_error(
+fn link_type_mode_tendril_returns_mode_mismatch_error(
     #[case] action: fn(&Tendril, bool, bool) -> ActionLog,
+    #[values(TendrilMode::Link, TendrilMode::Hardlink)] mode: TendrilMode,
     #[values(true, false)] dry_run: bool,
     #[values(true, false)] force: bool,
 ) {
@@ -809,7 +815,7 @@ fn link_mode_tendril_returns_mode_mismatch_error(
     setup.make_local_file();
 
     let mut tendril = setup.file_tendril();
-    tendril.mode = TendrilMode::Link;
+    tendril.mode = mode;
 
     let actual = action(&tendril, dry_run, force);
 
@@ -882,20 +888,26 @@ fn local_is_unchanged(
     let file_actual = action(&file_tendril, dry_run, force);
     let dir_actual = action(&dir_tendril, dry_run, force);
 
-    let exp_result;
-    if dry_run {
-        exp_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-    }
-    else {
-        exp_result = Ok(TendrilActionSuccess::Overwrite);
-    }
+    let is_link = file_tendril.mode == TendrilMode::Link;
+    let exp_file_result = exp_overwrite(
+        dry_run,
+        is_link,
+        &setup.target_file,
+        LinkStatus::WrongTarget,
+    );
+    let exp_dir_result = exp_overwrite(
+        dry_run,
+        is_link,
+        &setup.target_dir,
+        LinkStatus::WrongTarget,
+    );
     assert_eq!(
         file_actual,
         ActionLog::new(
             Some(FsoType::File),
             exp_remote_type_file,
             setup.remote_file.clone(),
-            exp_result.clone(),
+            exp_file_result,
         )
     );
     assert_eq!(
@@ -904,7 +916,7 @@ fn local_is_unchanged(
             Some(FsoType::Dir),
             exp_remote_type_dir,
             setup.remote_dir.clone(),
-            exp_result,
+            exp_dir_result,
         )
     );
     assert_eq!(setup.local_file_contents(), "Local file contents");
@@ -970,13 +982,20 @@ fn local_symlink_is_unchanged(
             mistype: FsoType::SymDir,
         });
     }
-    else if dry_run {
-        exp_file_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-        exp_dir_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-    }
     else {
-        exp_file_result = Ok(TendrilActionSuccess::Overwrite);
-        exp_dir_result = Ok(TendrilActionSuccess::Overwrite);
+        let is_link = file_tendril.mode == TendrilMode::Link;
+        exp_file_result = exp_overwrite(
+            dry_run,
+            is_link,
+            &setup.target_file,
+            LinkStatus::WrongTarget,
+        );
+        exp_dir_result = exp_overwrite(
+            dry_run,
+            is_link,
+            &setup.target_dir,
+            LinkStatus::WrongTarget,
+        );
     }
     assert_eq!(
         file_actual,
@@ -1131,13 +1150,20 @@ fn remote_symlink_is_unchanged(
             mistype: FsoType::SymDir,
         });
     }
-    else if dry_run {
-        exp_file_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-        exp_dir_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-    }
     else {
-        exp_file_result = Ok(TendrilActionSuccess::Overwrite);
-        exp_dir_result = Ok(TendrilActionSuccess::Overwrite);
+        let is_link = file_tendril.mode == TendrilMode::Link;
+        exp_file_result = exp_overwrite(
+            dry_run,
+            is_link,
+            &setup.target_file,
+            LinkStatus::WrongTarget,
+        );
+        exp_dir_result = exp_overwrite(
+            dry_run,
+            is_link,
+            &setup.target_dir,
+            LinkStatus::WrongTarget,
+        );
     }
     assert_eq!(
         file_actual,
@@ -1221,14 +1247,36 @@ fn remote_is_broken_symlink_treats_as_if_it_doesnt_exist_if_forced_except_for_pu
         });
     }
     else if dry_run {
-        exp_file_result = Ok(TendrilActionSuccess::OverwriteSkipped);
-        exp_dir_result = Ok(TendrilActionSuccess::OverwriteSkipped);
+        let is_link = file_tendril.mode == TendrilMode::Link;
+        exp_file_result = exp_overwrite(
+            dry_run,
+            is_link,
+            &setup.target_file,
+            LinkStatus::Broken,
+        );
+        exp_dir_result = exp_overwrite(
+            dry_run,
+            is_link,
+            &setup.target_dir,
+            LinkStatus::Broken,
+        );
         assert_eq!(&setup.remote_file.get_type(), &Some(FsoType::BrokenSym));
         assert_eq!(&setup.remote_dir.get_type(), &Some(FsoType::BrokenSym));
     }
     else {
-        exp_file_result = Ok(TendrilActionSuccess::Overwrite);
-        exp_dir_result = Ok(TendrilActionSuccess::Overwrite);
+        let is_link = file_tendril.mode == TendrilMode::Link;
+        exp_file_result = exp_overwrite(
+            dry_run,
+            is_link,
+            &setup.target_file,
+            LinkStatus::Broken,
+        );
+        exp_dir_result = exp_overwrite(
+            dry_run,
+            is_link,
+            &setup.target_dir,
+            LinkStatus::Broken,
+        );
         assert_eq!(setup.remote_file_contents(), "Local file contents");
         assert_eq!(setup.remote_nested_file_contents(), "Local nested file contents");
     }
@@ -1576,3 +1624,23 @@ mod admin_container {
 // and a file (Windows only?)
 // TODO: Test when td_repo is in the ~/.tendrils folder and global config dir exists
 // TODO: Test when td_repo is the ~/.tendrils folder and global config dir exists
+
+/// Returns the expected result of an action that overwrites the remote. Link
+/// actions report it as relinked instead, since the symlink at the remote
+/// points to the `old_target` rather than to the local.
+fn exp_overwrite(
+    dry_run: bool,
+    is_link: bool,
+    old_target: &Path,
+    status: LinkStatus,
+) -> Result<TendrilActionSuccess, TendrilActionError> {
+    let old_target = old_target.to_path_buf();
+    match (is_link, dry_run) {
+        (true, true) => {
+            Ok(TendrilActionSuccess::RelinkSkipped { old_target, status })
+        }
+        (true, false) => Ok(TendrilActionSuccess::Relinked { old_target, status }),
+        (false, true) => Ok(TendrilActionSuccess::OverwriteSkipped),
+        (false, false) => Ok(TendrilActionSuccess::Overwrite),
+    }
+}
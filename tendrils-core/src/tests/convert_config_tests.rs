@@ -0,0 +1,230 @@
+use crate::config::get_config;
+use crate::test_utils::Setup;
+use crate::{
+    ConfigFormat,
+    ConfigType,
+    GetConfigError,
+    GetTendrilsRepoError,
+    SetupError,
+    TendrilsActor,
+    TendrilsApi,
+};
+use rstest::rstest;
+use std::fs::{read_to_string, write};
+
+const FULL_JSON: &str = r#"{
+    "$schema": "./tendrils.schema.json",
+    "tendrils": {
+        "SomeApp/file": {"remotes": "r1"},
+        "SomeApp/folder": [
+            {"remotes": ["r2", "r3"], "dir-merge": true, "profiles": "home"},
+            {"remotes": ["r4", "r5"], "link": true, "fallback": true}
+        ]
+    },
+    "auto-profiles": {
+        "home": [{"os": "linux"}, {"hostname": "h*", "path": "/p"}],
+        "work": {"env": "WORK"}
+    },
+    "profile-groups": {"all": ["home", "work"]},
+    "include": ["extra.json"],
+    "defaults": {"profiles": ["home"], "link": false},
+    "vars": {"DIR": "/some/dir"},
+    "var-priority": "env",
+    "reflink": "auto"
+}"#;
+
+#[test]
+fn given_td_repo_is_invalid_returns_no_valid_td_repo_err() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), ConfigFormat::Toml, false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::NoValidTendrilsRepo(GetTendrilsRepoError::GivenInvalid {
+            path: setup.td_repo.clone(),
+        })),
+    );
+}
+
+#[rstest]
+#[case(ConfigFormat::Jsonc)]
+#[case(ConfigFormat::Toml)]
+fn converts_to_format_and_removes_original_without_changing_config(
+    #[case] format: ConfigFormat,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, FULL_JSON).unwrap();
+    write(setup.dot_td_dir.join("extra.json"), "{}").unwrap();
+    let mut expected = get_config(&setup.uni_td_repo()).unwrap();
+    expected.format = format;
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), format, false);
+
+    let new_file = setup.dot_td_dir.join(ConfigType::Repo(format).file_name());
+    assert_eq!(actual, Ok(ConfigFormat::Json));
+    assert!(!setup.td_json_file.exists());
+    assert!(new_file.exists());
+    let converted = get_config(&setup.uni_td_repo()).unwrap();
+    assert_eq!(converted, expected);
+    assert!(read_to_string(new_file).unwrap().contains("./tendrils.schema.json"));
+}
+
+#[rstest]
+#[case(ConfigFormat::Json)]
+#[case(ConfigFormat::Jsonc)]
+fn converts_back_from_toml_without_changing_config(#[case] format: ConfigFormat) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, FULL_JSON).unwrap();
+    write(setup.dot_td_dir.join("extra.json"), "{}").unwrap();
+    let mut expected = get_config(&setup.uni_td_repo()).unwrap();
+    expected.format = format;
+    api.convert_config(Some(&setup.uni_td_repo()), ConfigFormat::Toml, false).unwrap();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), format, false);
+
+    assert_eq!(actual, Ok(ConfigFormat::Toml));
+    assert!(!setup.dot_td_dir.join("tendrils.toml").exists());
+    let converted = get_config(&setup.uni_td_repo()).unwrap();
+    assert_eq!(converted, expected);
+}
+
+#[test]
+fn already_in_format_returns_format_and_leaves_file_unchanged() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml_file = setup.dot_td_dir.join("tendrils.toml");
+    let toml = "# Comment\n[tendrils.l1]\nremotes = \"r1\"\n";
+    write(&toml_file, toml).unwrap();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), ConfigFormat::Toml, false);
+
+    assert_eq!(actual, Ok(ConfigFormat::Toml));
+    assert_eq!(read_to_string(toml_file).unwrap(), toml);
+}
+
+#[rstest]
+#[case(ConfigFormat::Json)]
+#[case(ConfigFormat::Toml)]
+fn jsonc_with_comments_returns_comments_would_be_lost_err_and_leaves_file(
+    #[case] format: ConfigFormat,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let jsonc_file = setup.dot_td_dir.join("tendrils.jsonc");
+    let jsonc = "{\n    // Comment\n    \"tendrils\": {\"l1\": {\"remotes\": \"r1\"}}\n}\n";
+    write(&jsonc_file, jsonc).unwrap();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), format, false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::CommentsWouldBeLost {
+            cfg_type: ConfigType::Repo(ConfigFormat::Jsonc),
+        })),
+    );
+    assert_eq!(read_to_string(jsonc_file).unwrap(), jsonc);
+    assert!(!setup.dot_td_dir.join(ConfigType::Repo(format).file_name()).exists());
+}
+
+#[test]
+fn toml_with_comments_returns_comments_would_be_lost_err_and_leaves_file() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml_file = setup.dot_td_dir.join("tendrils.toml");
+    let toml = "[tendrils.l1]\nremotes = \"r1\" # Comment\n";
+    write(&toml_file, toml).unwrap();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), ConfigFormat::Json, false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::CommentsWouldBeLost {
+            cfg_type: ConfigType::Repo(ConfigFormat::Toml),
+        })),
+    );
+    assert_eq!(read_to_string(toml_file).unwrap(), toml);
+    assert!(!setup.td_json_file.exists());
+}
+
+#[test]
+fn toml_with_hash_only_in_strings_is_converted() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml_file = setup.dot_td_dir.join("tendrils.toml");
+    let toml = "[tendrils.\"l#1\"]\nremotes = [\"r#1\", 'r#2', \"\"\"r\\\"#3\"\"\"]\n";
+    write(&toml_file, toml).unwrap();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), ConfigFormat::Json, false);
+
+    assert_eq!(actual, Ok(ConfigFormat::Toml));
+    assert!(!toml_file.exists());
+    let converted = get_config(&setup.uni_td_repo()).unwrap();
+    assert_eq!(converted.raw_tendrils[0].local, "l#1");
+    assert_eq!(converted.raw_tendrils[2].remote, "r\"#3");
+}
+
+#[rstest]
+#[case("tendrils.jsonc", "{\"tendrils\": {\"l1\": {\"remotes\": \"r1\"}}} // Comment\n")]
+#[case("tendrils.toml", "# Comment\n[tendrils.l1]\nremotes = \"r1\"\n")]
+fn with_comments_and_drop_comments_is_converted_without_comments(
+    #[case] file_name: &str,
+    #[case] contents: &str,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(setup.dot_td_dir.join(file_name), contents).unwrap();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), ConfigFormat::Json, true);
+
+    assert!(actual.is_ok());
+    assert!(!setup.dot_td_dir.join(file_name).exists());
+    let converted = get_config(&setup.uni_td_repo()).unwrap();
+    assert_eq!(converted.raw_tendrils.len(), 1);
+    assert!(!read_to_string(&setup.td_json_file).unwrap().contains("Comment"));
+}
+
+#[test]
+fn config_that_cannot_be_parsed_returns_parse_err_and_leaves_file() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "I'm not JSON").unwrap();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), ConfigFormat::Toml, false);
+
+    assert!(matches!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::ParseError { .. })),
+    ));
+    assert!(setup.td_json_file.exists());
+    assert!(!setup.dot_td_dir.join("tendrils.toml").exists());
+}
+
+#[test]
+fn multiple_repo_config_files_returns_multiple_repo_configs_err() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "{}").unwrap();
+    write(setup.dot_td_dir.join("tendrils.toml"), "").unwrap();
+
+    let actual = api.convert_config(Some(&setup.uni_td_repo()), ConfigFormat::Jsonc, false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::MultipleRepoConfigs {
+            formats: vec![ConfigFormat::Json, ConfigFormat::Toml],
+        })),
+    );
+}
@@ -0,0 +1,150 @@
+use crate::test_utils::{default_repo_path_as_json, global_cfg_file, Setup};
+use crate::{
+    ConfigFormat,
+    ConfigType,
+    Diagnosis,
+    GetConfigError,
+    GetTendrilsRepoError,
+    ParseErrorLocation,
+    TendrilsActor,
+    TendrilsApi,
+};
+use serial_test::serial;
+use std::fs::write;
+
+#[test]
+fn given_td_repo_is_valid_with_no_problems_returns_empty() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_parent_dir();
+    setup.make_td_json_file(&[setup.file_tendril_raw()]);
+
+    let actual = api.diagnose(Some(&setup.uni_td_repo()));
+
+    let expected = match cfg!(unix) {
+        true => vec![],
+        false => vec![Diagnosis::WriteAccessNotChecked],
+    };
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn given_td_repo_is_invalid_returns_no_valid_td_repo() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+
+    let actual = api.diagnose(Some(&setup.uni_td_repo()));
+
+    assert_eq!(
+        actual,
+        vec![Diagnosis::NoValidTendrilsRepo(GetTendrilsRepoError::GivenInvalid {
+            path: setup.td_repo.clone(),
+        })],
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn given_td_repo_is_none_default_td_repo_not_set_returns_no_valid_td_repo() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.set_home_dir();
+    assert!(!global_cfg_file().exists());
+
+    let actual = api.diagnose(None);
+
+    assert_eq!(
+        actual,
+        vec![Diagnosis::NoValidTendrilsRepo(GetTendrilsRepoError::DefaultNotSet)],
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn given_td_repo_is_none_default_td_repo_is_valid_checks_default_td_repo() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "I'm not JSON").unwrap();
+    setup.make_global_cfg_file(
+        default_repo_path_as_json(&setup.td_repo.to_string_lossy()),
+    );
+
+    let actual = api.diagnose(None);
+
+    assert_eq!(
+        actual,
+        vec![Diagnosis::RepoConfig(GetConfigError::ParseError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: "expected value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: "I'm not JSON".to_string(),
+            }),
+        })],
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn global_config_invalid_returns_global_config_and_still_checks_given_repo() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_global_cfg_file("I'm not JSON".to_string());
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "I'm not JSON").unwrap();
+
+    let actual = api.diagnose(Some(&setup.uni_td_repo()));
+
+    assert_eq!(
+        actual,
+        vec![
+            Diagnosis::GlobalConfig(GetConfigError::ParseError {
+                cfg_type: ConfigType::Global,
+                msg: "expected value".to_string(),
+                loc: Some(ParseErrorLocation {
+                    line: 1,
+                    column: 1,
+                    key_path: String::new(),
+                    line_text: "I'm not JSON".to_string(),
+                }),
+            }),
+            Diagnosis::RepoConfig(GetConfigError::ParseError {
+                cfg_type: ConfigType::Repo(ConfigFormat::Json),
+                msg: "expected value".to_string(),
+                loc: Some(ParseErrorLocation {
+                    line: 1,
+                    column: 1,
+                    key_path: String::new(),
+                    line_text: "I'm not JSON".to_string(),
+                }),
+            }),
+        ],
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn global_config_invalid_and_no_td_repo_given_only_returns_global_config() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_global_cfg_file("I'm not JSON".to_string());
+
+    let actual = api.diagnose(None);
+
+    assert_eq!(
+        actual,
+        vec![Diagnosis::GlobalConfig(GetConfigError::ParseError {
+            cfg_type: ConfigType::Global,
+            msg: "expected value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: "I'm not JSON".to_string(),
+            }),
+        })],
+    );
+}
@@ -0,0 +1,89 @@
+use crate::{TendrilsActor, TendrilsApi};
+use crate::test_utils::{get_disposable_dir, Setup};
+use std::fs::{create_dir_all, write};
+use tempdir::TempDir;
+
+#[test]
+fn given_dir_is_repo_returns_given_dir() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+
+    let actual = api.find_tendrils_repo(&setup.td_repo.clone().into());
+
+    assert_eq!(actual, Some(setup.td_repo.into()));
+}
+
+#[test]
+fn parent_dir_is_repo_returns_parent_dir() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_group_dir();
+
+    let actual = api.find_tendrils_repo(&setup.group_dir.into());
+
+    assert_eq!(actual, Some(setup.td_repo.into()));
+}
+
+#[test]
+fn distant_ancestor_is_repo_returns_ancestor() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    setup.make_local_subdir_dir();
+
+    let actual = api.find_tendrils_repo(&setup.local_subdir_dir.into());
+
+    assert_eq!(actual, Some(setup.td_repo.into()));
+}
+
+#[test]
+fn nested_repos_returns_nearest_repo() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+    let nested_repo = setup.group_dir.join("NestedRepo");
+    create_dir_all(nested_repo.join(".tendrils")).unwrap();
+    write(nested_repo.join(".tendrils/tendrils.json"), "").unwrap();
+    let nested_subdir = nested_repo.join("SubDir");
+    create_dir_all(&nested_subdir).unwrap();
+
+    let actual = api.find_tendrils_repo(&nested_subdir.into());
+
+    assert_eq!(actual, Some(nested_repo.into()));
+}
+
+#[test]
+fn child_dir_is_repo_returns_none() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+
+    let actual = api.find_tendrils_repo(&setup.parent_dir.into());
+
+    assert_eq!(actual, None);
+}
+
+#[test]
+fn no_repo_in_any_ancestor_returns_none() {
+    let api = TendrilsActor {};
+    let temp_dir = TempDir::new_in(get_disposable_dir(), "Temp").unwrap();
+    let sub_dir = temp_dir.path().join("SubDir");
+    create_dir_all(&sub_dir).unwrap();
+
+    let actual = api.find_tendrils_repo(&sub_dir.into());
+
+    assert_eq!(actual, None);
+}
+
+#[test]
+fn given_dir_does_not_exist_but_ancestor_is_repo_returns_ancestor() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_td_json_file(&[]);
+
+    let actual = api.find_tendrils_repo(&setup.local_dir.into());
+
+    assert_eq!(actual, Some(setup.td_repo.into()));
+}
@@ -0,0 +1,482 @@
+use crate::config::get_config;
+use crate::test_utils::Setup;
+use crate::{
+    ConfigFormat,
+    ConfigType,
+    GetConfigError,
+    GetTendrilsRepoError,
+    SetupError,
+    TendrilsActor,
+    TendrilsApi,
+};
+use rstest::rstest;
+use std::fs::{read_to_string, write};
+
+#[test]
+fn given_td_repo_is_invalid_returns_no_valid_td_repo_err() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, false, false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::NoValidTendrilsRepo(GetTendrilsRepoError::GivenInvalid {
+            path: setup.td_repo.clone(),
+        })),
+    );
+}
+
+#[rstest]
+#[case(true)]
+#[case(false)]
+fn already_formatted_returns_false_and_leaves_file_unchanged(#[case] check: bool) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = "{\n    \"tendrils\": {\n        \"SomeApp/file\": {\n            \
+        \"remotes\": \"r1\"\n        }\n    }\n}\n";
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, check, false);
+
+    assert_eq!(actual, Ok(false));
+    assert_eq!(read_to_string(&setup.td_json_file).unwrap(), json);
+}
+
+#[test]
+fn check_returns_true_and_leaves_file_unchanged() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{"tendrils": {"SomeApp/file": {"remotes": ["r1"]}}}"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, true, false);
+
+    assert_eq!(actual, Ok(true));
+    assert_eq!(read_to_string(&setup.td_json_file).unwrap(), json);
+}
+
+#[test]
+fn lists_with_single_item_are_written_as_that_item() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{
+        "tendrils": {
+            "SomeApp/file": [{"remotes": ["r1"], "profiles": ["p1"]}],
+            "SomeApp/folder": {"remotes": ["r2", "r3"], "profiles": []}
+        },
+        "auto-profiles": {"p1": [{"os": "linux"}]},
+        "profile-groups": {"g1": ["p1"]},
+        "defaults": {"profiles": ["p2"]}
+    }"#).unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, false, false);
+
+    assert_eq!(actual, Ok(true));
+    assert_eq!(read_to_string(&setup.td_json_file).unwrap(), r#"{
+    "tendrils": {
+        "SomeApp/file": {
+            "remotes": "r1",
+            "profiles": "p1"
+        },
+        "SomeApp/folder": {
+            "remotes": [
+                "r2",
+                "r3"
+            ],
+            "profiles": []
+        }
+    },
+    "auto-profiles": {
+        "p1": {
+            "os": "linux"
+        }
+    },
+    "profile-groups": {
+        "g1": [
+            "p1"
+        ]
+    },
+    "defaults": {
+        "profiles": "p2"
+    }
+}
+"#);
+}
+
+#[test]
+fn backslashes_are_replaced_except_in_vars_and_unc_paths() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{
+        "tendrils": {
+            "SomeApp\\file": {"remotes": [
+                "C:\\Users\\<USER>\\file",
+                "${DIR:-C:\\Default}\\file",
+                "\\\\MyServer\\Share\\file",
+                "\\\\?\\C:\\file"
+            ]}
+        }
+    }"#).unwrap();
+
+    api.fmt_config(Some(&setup.uni_td_repo()), false, false, false).unwrap();
+
+    let actual = read_to_string(&setup.td_json_file).unwrap();
+    assert!(actual.contains(r#""SomeApp/file": {"#));
+    assert!(actual.contains(r#""C:/Users/<USER>/file","#));
+    assert!(actual.contains(r#""${DIR:-C:\\Default}/file","#));
+    assert!(actual.contains(r#""\\\\MyServer\\Share\\file","#));
+    assert!(actual.contains(r#""\\\\?\\C:\\file""#));
+}
+
+#[test]
+fn older_version_is_not_migrated() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{
+        "tendrils": {"SomeApp/file": {"remotes": "/$Recycle.Bin"}}
+    }"#).unwrap();
+
+    api.fmt_config(Some(&setup.uni_td_repo()), false, false, false).unwrap();
+
+    let actual = read_to_string(&setup.td_json_file).unwrap();
+    assert!(actual.contains(r#""remotes": "/$Recycle.Bin""#));
+    assert!(!actual.contains("version"));
+}
+
+#[test]
+fn backslashes_in_local_are_kept_if_local_with_slashes_exists() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{
+        "tendrils": {
+            "SomeApp\\file": {"remotes": "r1"},
+            "SomeApp/file": {"remotes": "r2"}
+        }
+    }"#).unwrap();
+
+    api.fmt_config(Some(&setup.uni_td_repo()), false, false, false).unwrap();
+
+    let actual = get_config(&setup.uni_td_repo()).unwrap();
+    assert_eq!(actual.raw_tendrils[0].local, "SomeApp\\file");
+    assert_eq!(actual.raw_tendrils[1].local, "SomeApp/file");
+}
+
+#[test]
+fn values_same_as_defaults_are_removed() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{
+        "tendrils": {
+            "SomeApp/file": {
+                "remotes": "r1",
+                "dir-merge": true,
+                "link": false,
+                "profiles": "p1"
+            },
+            "SomeApp/folder": {
+                "remotes": "r2",
+                "dir-merge": false,
+                "link": true,
+                "nested-symlinks": "follow",
+                "profiles": []
+            }
+        },
+        "defaults": {"dir-merge": true, "link": false, "profiles": []},
+        "var-priority": "repo",
+        "reflink": "never"
+    }"#).unwrap();
+
+    api.fmt_config(Some(&setup.uni_td_repo()), false, false, false).unwrap();
+
+    assert_eq!(read_to_string(&setup.td_json_file).unwrap(), r#"{
+    "tendrils": {
+        "SomeApp/file": {
+            "remotes": "r1",
+            "profiles": "p1"
+        },
+        "SomeApp/folder": {
+            "remotes": "r2",
+            "dir-merge": false,
+            "link": true
+        }
+    },
+    "defaults": {
+        "dir-merge": true
+    }
+}
+"#);
+}
+
+#[rstest]
+#[case(true, &["a", "b", "c"])]
+#[case(false, &["c", "a", "b"])]
+fn sort_keys_sorts_by_name_otherwise_keeps_order(
+    #[case] sort_keys: bool,
+    #[case] exp_order: &[&str],
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{
+        "tendrils": {"c": {"remotes": "r"}, "a": {"remotes": "r"}, "b": {"remotes": "r"}},
+        "auto-profiles": {"c": {}, "a": {}, "b": {}},
+        "profile-groups": {"c": [], "a": [], "b": []},
+        "vars": {"c": "v", "a": "v", "b": "v"}
+    }"#).unwrap();
+
+    api.fmt_config(Some(&setup.uni_td_repo()), sort_keys, false, false).unwrap();
+
+    let actual = read_to_string(&setup.td_json_file).unwrap();
+    for section in ["tendrils", "auto-profiles", "profile-groups", "vars"].iter() {
+        let text = &actual[actual.find(&format!("\"{}\"", section)).unwrap()..];
+        let positions: Vec<usize> = exp_order
+            .iter()
+            .map(|k| text.find(&format!("\"{}\"", k)).unwrap())
+            .collect();
+        let mut sorted = positions.clone();
+        sorted.sort_unstable();
+        assert_eq!(positions, sorted, "{}", section);
+    }
+}
+
+#[rstest]
+#[case(ConfigFormat::Json)]
+#[case(ConfigFormat::Jsonc)]
+#[case(ConfigFormat::Toml)]
+fn does_not_change_config(#[case] format: ConfigFormat) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, r#"{
+        "tendrils": {
+            "SomeApp\\file": {"remotes": ["r1"], "link": false},
+            "SomeApp/folder": [
+                {"remotes": ["r2", "r3"], "dir-merge": true, "nested-symlinks": "skip", "profiles": ["home"]},
+                {"remotes": ["r4", "r5"], "link": true, "fallback": true}
+            ]
+        },
+        "auto-profiles": {"home": {"os": "linux"}, "work": {"env": "WORK"}},
+        "profile-groups": {"all": ["home", "work"]},
+        "defaults": {"profiles": ["home"], "link": false},
+        "vars": {"DIR": "/some/dir"},
+        "var-priority": "env",
+        "reflink": "auto"
+    }"#).unwrap();
+    api.convert_config(Some(&setup.uni_td_repo()), format, false).unwrap();
+    let mut expected = get_config(&setup.uni_td_repo()).unwrap();
+    expected.raw_tendrils[0].local = "SomeApp/file".to_string();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), true, false, false);
+
+    assert_eq!(actual, Ok(true));
+    let mut formatted = get_config(&setup.uni_td_repo()).unwrap();
+    formatted.raw_tendrils.sort_by(|a, b| a.local.cmp(&b.local));
+    expected.raw_tendrils.sort_by(|a, b| a.local.cmp(&b.local));
+    assert_eq!(formatted, expected);
+    assert_eq!(api.fmt_config(Some(&setup.uni_td_repo()), true, true, false), Ok(false));
+}
+
+#[test]
+fn formats_toml_file() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml_file = setup.dot_td_dir.join("tendrils.toml");
+    write(&toml_file, "[tendrils]\n\"SomeApp/file\" = [{ remotes = [\"r1\"] }]\n")
+        .unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, false, false);
+
+    assert_eq!(actual, Ok(true));
+    assert_eq!(
+        read_to_string(toml_file).unwrap(),
+        "[tendrils]\n\"SomeApp/file\" = { remotes = \"r1\" }\n",
+    );
+}
+
+#[test]
+fn toml_file_is_formatted_in_place_keeping_comments_and_unknown_keys() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml_file = setup.dot_td_dir.join("tendrils.toml");
+    write(&toml_file, r#"# My dotfiles
+unknown-key = "kept"
+
+[defaults]
+link = false # Not needed
+
+# The first tendril
+[[tendrils.'SomeApp\file']]
+remotes = ["r1"] # Trailing comment
+dir-merge = false
+profiles = [
+    "home", # Only at home
+]
+
+[[tendrils."SomeApp/folder"]]
+remotes = ["r2", 'C:\r3']
+fallback = false
+some-unknown-key = 1
+
+[[tendrils."SomeApp/folder"]]
+remotes = ["r4"]
+
+[auto-profiles]
+home = [{ os = "linux" }] # Rules
+"#).unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, false, false);
+
+    assert_eq!(actual, Ok(true));
+    assert_eq!(read_to_string(&toml_file).unwrap(), r#"# My dotfiles
+unknown-key = "kept"
+
+# The first tendril
+[tendrils."SomeApp/file"]
+remotes = "r1" # Trailing comment
+profiles = [
+    "home", # Only at home
+]
+
+[[tendrils."SomeApp/folder"]]
+remotes = ["r2", "C:/r3"]
+some-unknown-key = 1
+
+[[tendrils."SomeApp/folder"]]
+remotes = "r4"
+
+[auto-profiles]
+home = { os = "linux" } # Rules
+"#);
+    assert_eq!(
+        api.fmt_config(Some(&setup.uni_td_repo()), false, true, false),
+        Ok(false),
+    );
+}
+
+#[test]
+fn toml_sort_keys_moves_tables_with_their_keys() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml_file = setup.dot_td_dir.join("tendrils.toml");
+    write(&toml_file, r#"[vars]
+b = "v"
+a = "v"
+
+# Tendril b
+[tendrils.b]
+remotes = "r"
+
+# Tendril a
+[[tendrils.a]]
+remotes = "r1"
+
+[[tendrils.a]]
+remotes = "r2"
+"#).unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), true, false, false);
+
+    assert_eq!(actual, Ok(true));
+    assert_eq!(read_to_string(&toml_file).unwrap(), r#"[vars]
+a = "v"
+b = "v"
+
+# Tendril a
+[[tendrils.a]]
+remotes = "r1"
+
+[[tendrils.a]]
+remotes = "r2"
+
+# Tendril b
+[tendrils.b]
+remotes = "r"
+"#);
+}
+
+#[rstest]
+#[case(true)]
+#[case(false)]
+fn jsonc_file_with_comments_returns_comments_would_be_lost_err_and_leaves_file(
+    #[case] check: bool,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let jsonc_file = setup.dot_td_dir.join("tendrils.jsonc");
+    let jsonc = r#"{"tendrils": {"SomeApp/file": {"remotes": ["r1"]}}} // Comment"#;
+    write(&jsonc_file, jsonc).unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, check, false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::CommentsWouldBeLost {
+            cfg_type: ConfigType::Repo(ConfigFormat::Jsonc),
+        })),
+    );
+    assert_eq!(read_to_string(&jsonc_file).unwrap(), jsonc);
+}
+
+#[test]
+fn jsonc_file_with_comments_is_formatted_without_them_if_drop_comments() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let jsonc_file = setup.dot_td_dir.join("tendrils.jsonc");
+    write(&jsonc_file, r#"{
+        // Comment
+        "tendrils": {"SomeApp/file": {"remotes": ["r1"]}}, /* Comment */
+    }"#).unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, false, true);
+
+    assert_eq!(actual, Ok(true));
+    assert_eq!(
+        read_to_string(&jsonc_file).unwrap(),
+        "{\n    \"tendrils\": {\n        \"SomeApp/file\": {\n            \
+        \"remotes\": \"r1\"\n        }\n    }\n}\n",
+    );
+}
+
+#[test]
+fn jsonc_file_with_slashes_only_in_strings_is_formatted() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let jsonc_file = setup.dot_td_dir.join("tendrils.jsonc");
+    write(&jsonc_file, r#"{"tendrils": {"SomeApp//file": {"remotes": ["r/*1*/"]},}}"#)
+        .unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, false, false);
+
+    assert_eq!(actual, Ok(true));
+    assert!(read_to_string(&jsonc_file).unwrap().contains("\"remotes\": \"r/*1*/\""));
+}
+
+#[test]
+fn config_that_cannot_be_parsed_returns_parse_err_and_leaves_file() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "I'm not JSON").unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, false, false);
+
+    assert!(matches!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::ParseError { .. })),
+    ));
+    assert_eq!(read_to_string(&setup.td_json_file).unwrap(), "I'm not JSON");
+}
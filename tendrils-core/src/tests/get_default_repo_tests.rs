@@ -3,7 +3,7 @@ use crate::test_utils::{
     global_cfg_file,
     Setup,
 };
-use crate::{ConfigType, GetConfigError, TendrilsActor, TendrilsApi};
+use crate::{ConfigType, GetConfigError, ParseErrorLocation, TendrilsActor, TendrilsApi};
 use rstest::rstest;
 use serial_test::serial;
 use std::path::PathBuf;
@@ -34,7 +34,13 @@ fn invalid_json_returns_parse_error() {
         actual,
         Err(GetConfigError::ParseError {
             cfg_type: ConfigType::Global,
-            msg: "EOF while parsing a value at line 1 column 0".to_string(),
+            msg: "EOF while parsing a value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: String::new(),
+            }),
         }),
     );
 }
@@ -71,3 +77,67 @@ fn config_file_exists_returns_unaltered_path_even_if_invalid(
 
     assert_eq!(actual, Ok(Some(PathBuf::from(exp_field_contents))));
 }
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn config_file_has_list_of_paths_returns_first() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_global_cfg_file(
+        r#"{"default-repo-path": ["First/Path", "Second/Path"]}"#.to_string()
+    );
+
+    let actual = api.get_default_repo_path();
+
+    assert_eq!(actual, Ok(Some(PathBuf::from("First/Path"))));
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn config_file_has_empty_list_of_paths_returns_none() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_global_cfg_file(r#"{"default-repo-path": []}"#.to_string());
+
+    let actual = api.get_default_repo_path();
+
+    assert_eq!(actual, Ok(None));
+}
+
+#[rstest]
+#[case(r#"{}"#, vec![])]
+#[case(r#"{"default-repo-path": null}"#, vec![])]
+#[case(r#"{"default-repo-path": []}"#, vec![])]
+#[case(r#"{"default-repo-path": "Some/Path"}"#, vec!["Some/Path"])]
+#[case(r#"{"default-repo-path": ["Some/Path"]}"#, vec!["Some/Path"])]
+#[case(
+    r#"{"default-repo-path": ["First/Path", "Second/Path", "First/Path"]}"#,
+    vec!["First/Path", "Second/Path", "First/Path"],
+)]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn get_default_repo_paths_returns_each_path_unaltered(
+    #[case] json: &str,
+    #[case] expected: Vec<&str>,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_global_cfg_file(json.to_string());
+
+    let actual = api.get_default_repo_paths();
+
+    let expected = expected.into_iter().map(PathBuf::from).collect();
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn get_default_repo_paths_config_file_does_not_exist_returns_empty() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.set_home_dir();
+    assert!(!global_cfg_file().exists());
+
+    let actual = api.get_default_repo_paths();
+
+    assert_eq!(actual, Ok(vec![]));
+}
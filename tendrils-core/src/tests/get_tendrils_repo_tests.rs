@@ -288,7 +288,7 @@ fn leading_tilde_and_env_vars_in_default_path_are_resolved_in_error_path_and_dir
 }
 
 #[test]
-fn relative_given_path_is_absoluted_and_dots_preserved_in_returned_path() {
+fn relative_given_path_is_absoluted_and_dots_normalized_in_returned_path() {
     let setup = Setup::new();
     setup.make_td_json_file(&[]);
     create_dir_all(&setup.td_repo.join("SkipMe")).unwrap();
@@ -298,7 +298,7 @@ fn relative_given_path_is_absoluted_and_dots_preserved_in_returned_path() {
         PathBuf::from(".././").join_raw(&setup.td_repo).join(".///SkipMe/..");
     #[cfg(not(windows))]
     let expected_str = format!(
-        "/.././{}/TendrilsRepo/.///SkipMe/..",
+        "{}/TendrilsRepo/",
         setup.temp_dir.path().to_string_lossy(),
     );
     #[cfg(windows)]
@@ -306,7 +306,7 @@ fn relative_given_path_is_absoluted_and_dots_preserved_in_returned_path() {
         &setup.td_repo.join(".///SkipMe/..");
     #[cfg(windows)]
     let expected_str = format!(
-        "{}\\TendrilsRepo\\.\\\\\\SkipMe\\..",
+        "{}\\TendrilsRepo\\",
         setup.temp_dir.path().to_string_lossy(),
     );
 
@@ -317,7 +317,7 @@ fn relative_given_path_is_absoluted_and_dots_preserved_in_returned_path() {
 
 #[test]
 #[serial(SERIAL_MUT_ENV_VARS)]
-fn relative_default_path_is_absoluted_and_dots_preserved_in_returned_path() {
+fn relative_default_path_is_absoluted_and_dots_normalized_in_returned_path() {
     let setup = Setup::new();
     create_dir_all(&setup.td_repo.join("SkipMe")).unwrap();
 
@@ -326,7 +326,7 @@ fn relative_default_path_is_absoluted_and_dots_preserved_in_returned_path() {
         PathBuf::from(".././").join_raw(&setup.td_repo).join(".///SkipMe/..");
     #[cfg(not(windows))]
     let expected_str = format!(
-        "/.././{}/TendrilsRepo/.///SkipMe/..",
+        "{}/TendrilsRepo/",
         setup.temp_dir.path().to_string_lossy(),
     );
     #[cfg(windows)]
@@ -334,7 +334,7 @@ fn relative_default_path_is_absoluted_and_dots_preserved_in_returned_path() {
         &setup.td_repo.join(".///SkipMe/..");
     #[cfg(windows)]
     let expected_str = format!(
-        "{}\\TendrilsRepo\\.\\\\\\SkipMe\\..",
+        "{}\\TendrilsRepo\\",
         setup.temp_dir.path().to_string_lossy(),
     );
 
@@ -349,10 +349,10 @@ fn relative_default_path_is_absoluted_and_dots_preserved_in_returned_path() {
 }
 
 #[test]
-fn relative_given_path_is_absoluted_and_dots_preserved_in_error_path() {
+fn relative_given_path_is_absoluted_and_dots_normalized_in_error_path() {
     let starting_td_repo =
         UniPath::from(PathBuf::from(".././SomeRel/../Path"));
-    let expected_str = format!("{SEP}..{SEP}.{SEP}SomeRel{SEP}..{SEP}Path");
+    let expected_str = format!("{SEP}Path");
 
     let actual = get_tendrils_repo(Some(&starting_td_repo), &mut cfg());
 
@@ -366,12 +366,12 @@ fn relative_given_path_is_absoluted_and_dots_preserved_in_error_path() {
 
 #[test]
 #[serial(SERIAL_MUT_ENV_VARS)]
-fn relative_default_path_is_absoluted_and_dots_preserved_in_error_path() {
+fn relative_default_path_is_absoluted_and_dots_normalized_in_error_path() {
     let setup = Setup::new();
     setup.make_global_cfg_file(default_repo_path_as_json(
         ".././SomeRel/../Path",
     ));
-    let expected_str = format!("{SEP}..{SEP}.{SEP}SomeRel{SEP}..{SEP}Path");
+    let expected_str = format!("{SEP}Path");
 
     let actual = get_tendrils_repo(None, &mut cfg());
 
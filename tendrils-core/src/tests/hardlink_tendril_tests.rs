@@ -0,0 +1,392 @@
+//! Contains tests specific to hard link actions.
+//! See also [`crate::tests::link_tendril_tests`].
+
+use crate::test_utils::{symlink_expose, Setup};
+use crate::{
+    hardlink_tendril,
+    ActionLog,
+    FsoType,
+    Location,
+    Tendril,
+    TendrilActionError,
+    TendrilActionSuccess,
+    TendrilMode,
+};
+use rstest::rstest;
+use same_file::is_same_file;
+use std::fs::{read_to_string, write};
+#[cfg(target_os = "linux")]
+use tempdir::TempDir;
+
+#[rstest]
+fn non_hardlink_mode_tendril_returns_mode_mismatch_error(
+    #[values(TendrilMode::DirMerge, TendrilMode::DirOverwrite, TendrilMode::Link)]
+    mode: TendrilMode,
+
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = mode;
+
+    let actual = hardlink_tendril(&tendril, dry_run, force);
+
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            None,
+            None,
+            setup.remote_file,
+            Err(TendrilActionError::ModeMismatch),
+        )
+    );
+}
+
+#[rstest]
+fn remote_doesnt_exist_hard_link_to_local_is_created_unless_dry_run(
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_subdir_file();
+    let mut tendril = setup.subdir_file_tendril();
+    tendril.mode = TendrilMode::Hardlink;
+
+    let actual = hardlink_tendril(&tendril, dry_run, force);
+
+    let exp_result;
+    if dry_run {
+        exp_result = Ok(TendrilActionSuccess::NewSkipped);
+        assert!(!setup.remote_subdir_file.exists());
+    }
+    else {
+        exp_result = Ok(TendrilActionSuccess::New);
+        assert!(!setup.remote_subdir_file.is_symlink());
+        assert!(
+            is_same_file(&setup.local_subdir_file, &setup.remote_subdir_file)
+                .unwrap()
+        );
+    }
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            Some(FsoType::File),
+            None,
+            setup.remote_subdir_file.clone(),
+            exp_result,
+        )
+    );
+}
+
+#[test]
+fn changes_to_local_are_seen_at_remote() {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Hardlink;
+    hardlink_tendril(&tendril, false, false).result.unwrap();
+
+    write(&setup.local_file, "Updated local file contents").unwrap();
+
+    assert_eq!(setup.remote_file_contents(), "Updated local file contents");
+}
+
+#[rstest]
+fn remote_is_already_hard_link_to_local_returns_already_linked(
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_parent_dir();
+    std::fs::hard_link(&setup.local_file, &setup.remote_file).unwrap();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Hardlink;
+
+    let actual = hardlink_tendril(&tendril, dry_run, force);
+
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            Some(FsoType::File),
+            Some(FsoType::File),
+            setup.remote_file.clone(),
+            Ok(TendrilActionSuccess::AlreadyLinked),
+        )
+    );
+}
+
+#[rstest]
+#[case(true)]
+#[case(false)]
+fn remote_is_separate_file_returns_type_mismatch_error_unless_forced(
+    #[case] force: bool,
+    #[values(true, false)] dry_run: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_remote_file();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Hardlink;
+
+    let actual = hardlink_tendril(&tendril, dry_run, force);
+
+    let exp_result = match (force, dry_run) {
+        (false, _) => Err(TendrilActionError::TypeMismatch {
+            mistype: FsoType::File,
+            loc: Location::Dest,
+        }),
+        (true, true) => Ok(TendrilActionSuccess::OverwriteSkipped),
+        (true, false) => Ok(TendrilActionSuccess::Overwrite),
+    };
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            Some(FsoType::File),
+            Some(FsoType::File),
+            setup.remote_file.clone(),
+            exp_result,
+        )
+    );
+    if force && !dry_run {
+        assert!(is_same_file(&setup.local_file, &setup.remote_file).unwrap());
+    }
+    else {
+        assert_eq!(setup.remote_file_contents(), "Remote file contents");
+    }
+    assert_eq!(setup.local_file_contents(), "Local file contents");
+}
+
+#[rstest]
+#[case(true)]
+#[case(false)]
+fn remote_is_dir_returns_type_mismatch_error_unless_forced(
+    #[case] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_remote_nested_file();
+    let tendril = Tendril::new_expose(
+        setup.uni_td_repo(),
+        "SomeApp/misc.txt".into(),
+        setup.remote_dir.clone().into(),
+        TendrilMode::Hardlink,
+    ).unwrap();
+
+    let actual = hardlink_tendril(&tendril, false, force);
+
+    if force {
+        assert_eq!(actual.result, Ok(TendrilActionSuccess::Overwrite));
+        assert!(is_same_file(&setup.local_file, &setup.remote_dir).unwrap());
+    }
+    else {
+        assert_eq!(
+            actual.result,
+            Err(TendrilActionError::TypeMismatch {
+                mistype: FsoType::Dir,
+                loc: Location::Dest,
+            }),
+        );
+        assert_eq!(
+            setup.remote_nested_file_contents(),
+            "Remote nested file contents",
+        );
+    }
+}
+
+#[rstest]
+fn remote_is_symlink_is_replaced_by_hard_link(
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    symlink_expose(&setup.remote_file, &setup.local_file, false, true)
+        .unwrap();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Hardlink;
+
+    let actual = hardlink_tendril(&tendril, false, force);
+
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            Some(FsoType::File),
+            Some(FsoType::SymFile),
+            setup.remote_file.clone(),
+            Ok(TendrilActionSuccess::Overwrite),
+        )
+    );
+    assert!(!setup.remote_file.is_symlink());
+    assert!(is_same_file(&setup.local_file, &setup.remote_file).unwrap());
+}
+
+#[rstest]
+fn local_is_not_file_returns_type_mismatch_error(
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_nested_file();
+    let mut tendril = setup.dir_tendril();
+    tendril.mode = TendrilMode::Hardlink;
+
+    let actual = hardlink_tendril(&tendril, dry_run, force);
+
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            Some(FsoType::Dir),
+            None,
+            setup.remote_dir.clone(),
+            Err(TendrilActionError::TypeMismatch {
+                mistype: FsoType::Dir,
+                loc: Location::Source,
+            }),
+        )
+    );
+    assert!(!setup.remote_dir.exists());
+}
+
+#[rstest]
+fn local_doesnt_exist_is_hard_linked_to_remote_unless_dry_run(
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_remote_file();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Hardlink;
+
+    let actual = hardlink_tendril(&tendril, dry_run, force);
+
+    let exp_result;
+    if dry_run {
+        exp_result = Ok(TendrilActionSuccess::OverwriteSkipped);
+        assert!(!setup.local_file.exists());
+    }
+    else {
+        exp_result = Ok(TendrilActionSuccess::Overwrite);
+        assert!(is_same_file(&setup.local_file, &setup.remote_file).unwrap());
+    }
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            None,
+            Some(FsoType::File),
+            setup.remote_file.clone(),
+            exp_result,
+        )
+    );
+    assert_eq!(setup.remote_file_contents(), "Remote file contents");
+}
+
+#[rstest]
+fn local_and_remote_dont_exist_returns_io_error_not_found(
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Hardlink;
+
+    let actual = hardlink_tendril(&tendril, dry_run, force);
+
+    assert_eq!(
+        actual.result,
+        Err(TendrilActionError::IoError {
+            kind: std::io::ErrorKind::NotFound,
+            loc: Location::Source,
+        }),
+    );
+}
+
+/// `/dev/shm` is a separate in-memory file system on Linux
+#[cfg(target_os = "linux")]
+#[rstest]
+fn remote_on_other_file_system_returns_crosses_devices_error_unless_copy_fallback(
+    #[values(true, false)] copy_fallback: bool,
+    #[values(true, false)] remote_exists: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let other_fs_dir = TempDir::new_in("/dev/shm", "Remote").unwrap();
+    let remote = other_fs_dir.path().join("misc.txt");
+    if remote_exists {
+        write(&remote, "Remote file contents").unwrap();
+    }
+    let mut tendril = Tendril::new_expose(
+        setup.uni_td_repo(),
+        "SomeApp/misc.txt".into(),
+        remote.clone().into(),
+        TendrilMode::Hardlink,
+    ).unwrap();
+    tendril.copy_fallback = copy_fallback;
+
+    let actual = hardlink_tendril(&tendril, false, true);
+
+    let exp_result = match (copy_fallback, remote_exists) {
+        (false, _) => Err(TendrilActionError::IoError {
+            kind: std::io::ErrorKind::CrossesDevices,
+            loc: Location::Dest,
+        }),
+        (true, _) => Ok(TendrilActionSuccess::CopiedInsteadOfLinked),
+    };
+    assert_eq!(actual.result, exp_result);
+    if copy_fallback {
+        assert_eq!(
+            read_to_string(&remote).unwrap(),
+            "Local file contents",
+        );
+        assert!(!is_same_file(&setup.local_file, &remote).unwrap());
+    }
+    else if remote_exists {
+        assert_eq!(
+            read_to_string(&remote).unwrap(),
+            "Remote file contents",
+        );
+    }
+    else {
+        assert!(!remote.exists());
+    }
+    assert!(!other_fs_dir.path().join(".misc.txt.tendrils-tmp").exists());
+}
+
+/// `/dev/shm` is a separate in-memory file system on Linux
+#[cfg(target_os = "linux")]
+#[rstest]
+fn remote_copy_on_other_file_system_is_refreshed_without_force_if_copy_fallback(
+    #[values(true, false)] copy_fallback: bool,
+    #[values(true, false)] dry_run: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let other_fs_dir = TempDir::new_in("/dev/shm", "Remote").unwrap();
+    let remote = other_fs_dir.path().join("misc.txt");
+    write(&remote, "Remote file contents").unwrap();
+    let mut tendril = Tendril::new_expose(
+        setup.uni_td_repo(),
+        "SomeApp/misc.txt".into(),
+        remote.clone().into(),
+        TendrilMode::Hardlink,
+    ).unwrap();
+    tendril.copy_fallback = copy_fallback;
+
+    let actual = hardlink_tendril(&tendril, dry_run, false);
+
+    let exp_result = match (copy_fallback, dry_run) {
+        (false, _) => Err(TendrilActionError::TypeMismatch {
+            mistype: FsoType::File,
+            loc: Location::Dest,
+        }),
+        (true, true) => Ok(TendrilActionSuccess::OverwriteSkipped),
+        (true, false) => Ok(TendrilActionSuccess::CopiedInsteadOfLinked),
+    };
+    assert_eq!(actual.result, exp_result);
+    let exp_remote_contents = match copy_fallback && !dry_run {
+        true => "Local file contents",
+        false => "Remote file contents",
+    };
+    assert_eq!(read_to_string(&remote).unwrap(), exp_remote_contents);
+    assert_eq!(setup.local_file_contents(), "Local file contents");
+}
@@ -7,13 +7,17 @@ use crate::test_utils::{
 };
 use crate::{
     is_tendrils_repo,
+    ConfigFormat,
     InitError,
+    NestedSymlinks,
     RawTendril,
+    ReflinkMode,
     TendrilMode,
     TendrilsActor,
     TendrilsApi,
     UniPath,
 };
+use indexmap::IndexMap;
 use rstest::rstest;
 use serial_test::serial;
 use std::fs::{create_dir_all, read_to_string, write};
@@ -29,50 +33,98 @@ fn creates_dot_tendrils_dir_and_contents_in_empty_dir(#[case] force: bool) {
     let expected_t1 = RawTendril {
         local: "SomeApp/SomeFile.ext".to_string(),
         remote: "/path/to/SomeFile.ext".to_string(),
+        fallbacks: vec![],
         mode: TendrilMode::DirOverwrite,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let expected_t2 = RawTendril {
         local: "SomeApp2/SomeFolder".to_string(),
         remote: "/path/to/SomeFolder".to_string(),
+        fallbacks: vec![],
         mode: TendrilMode::Link,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["home".to_string(), "work".to_string()],
+        source: None,
     };
     let expected_t3 = RawTendril {
         local: "SomeApp2/SomeFolder".to_string(),
         remote: "/path/to/DifferentName".to_string(),
+        fallbacks: vec![],
         mode: TendrilMode::Link,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["home".to_string(), "work".to_string()],
+        source: None,
     };
     let expected_t4 = RawTendril {
         local: "SomeApp2/SomeFolder".to_string(),
         remote: "~/path/in/home/dir/SomeFolder".to_string(),
+        fallbacks: vec![],
         mode: TendrilMode::Link,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["home".to_string(), "work".to_string()],
+        source: None,
     };
     let expected_t5 = RawTendril {
         local: "SomeApp2/SomeFolder".to_string(),
         remote: "/path/using/<MY-ENV-VAR>/SomeFolder".to_string(),
+        fallbacks: vec![],
         mode: TendrilMode::Link,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["home".to_string(), "work".to_string()],
+        source: None,
     };
     let expected_t6 = RawTendril {
         local: "SomeApp3/file.txt".to_string(),
         remote: "~/unix/specific/path/file.txt".to_string(),
+        fallbacks: vec![],
         mode: TendrilMode::Link,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["unix".to_string()],
+        source: None,
     };
     let expected_t7 = RawTendril {
         local: "SomeApp3/file.txt".to_string(),
         remote: "~/windows/specific/path/file.txt".to_string(),
+        fallbacks: vec![],
         mode: TendrilMode::DirOverwrite,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["windows".to_string()],
+        source: None,
     };
     let expected_t8 = RawTendril {
         local: "SomeApp3/file.txt".to_string(),
         remote: "~/windows/another-specific/path/file.txt".to_string(),
+        fallbacks: vec![],
         mode: TendrilMode::DirOverwrite,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["windows".to_string()],
+        source: None,
     };
     let expected_tendrils = vec![
         expected_t1,
@@ -84,7 +136,12 @@ fn creates_dot_tendrils_dir_and_contents_in_empty_dir(#[case] force: bool) {
         expected_t7,
         expected_t8,
     ];
-    let expected = Config { raw_tendrils: expected_tendrils };
+    let expected = Config {
+        raw_tendrils: expected_tendrils,
+        auto_profiles: IndexMap::new(),
+        profile_groups: IndexMap::new(),
+        format: ConfigFormat::Json,
+    };
 
     let actual = api.init_tendrils_repo(&setup.uni_td_repo(), force);
 
@@ -97,6 +154,18 @@ fn creates_dot_tendrils_dir_and_contents_in_empty_dir(#[case] force: bool) {
     );
 }
 
+#[test]
+fn created_config_file_is_already_formatted() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_td_repo_dir();
+    api.init_tendrils_repo(&setup.uni_td_repo(), false).unwrap();
+
+    let actual = api.fmt_config(Some(&setup.uni_td_repo()), false, true, false);
+
+    assert_eq!(actual, Ok(false));
+}
+
 #[rstest]
 #[case(true)]
 #[case(false)]
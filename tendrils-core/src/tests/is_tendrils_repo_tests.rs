@@ -6,6 +6,7 @@ use crate::test_utils::{
     home_dir,
     Setup
 };
+use rstest::rstest;
 use serial_test::serial;
 use std::fs::{create_dir_all, write};
 use tempdir::TempDir;
@@ -97,6 +98,42 @@ fn valid_tendrils_json_file_returns_true() {
     assert!(api.is_tendrils_repo(&td_dir.path().into()));
 }
 
+#[rstest]
+#[case("tendrils.jsonc")]
+#[case("tendrils.toml")]
+fn other_config_format_file_returns_true(#[case] file_name: &str) {
+    let api = TendrilsActor {};
+    let td_dir = TempDir::new_in(get_disposable_dir(), "Temp").unwrap();
+    let dot_tendrils_dir = td_dir.path().join(".tendrils");
+    create_dir_all(&dot_tendrils_dir).unwrap();
+    write(dot_tendrils_dir.join(file_name), "").unwrap();
+
+    assert!(api.is_tendrils_repo(&td_dir.path().into()));
+}
+
+#[test]
+fn multiple_config_files_returns_true() {
+    let api = TendrilsActor {};
+    let td_dir = TempDir::new_in(get_disposable_dir(), "Temp").unwrap();
+    let dot_tendrils_dir = td_dir.path().join(".tendrils");
+    create_dir_all(&dot_tendrils_dir).unwrap();
+    write(dot_tendrils_dir.join("tendrils.json"), "").unwrap();
+    write(dot_tendrils_dir.join("tendrils.toml"), "").unwrap();
+
+    assert!(api.is_tendrils_repo(&td_dir.path().into()));
+}
+
+#[test]
+fn other_config_file_extension_returns_false() {
+    let api = TendrilsActor {};
+    let td_dir = TempDir::new_in(get_disposable_dir(), "Temp").unwrap();
+    let dot_tendrils_dir = td_dir.path().join(".tendrils");
+    create_dir_all(&dot_tendrils_dir).unwrap();
+    write(dot_tendrils_dir.join("tendrils.yaml"), "").unwrap();
+
+    assert!(!api.is_tendrils_repo(&td_dir.path().into()));
+}
+
 #[test]
 #[serial(SERIAL_MUT_ENV_VARS)]
 fn home_dir_with_global_cfg_file_and_td_json_returns_true() {
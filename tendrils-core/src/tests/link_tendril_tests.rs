@@ -3,6 +3,7 @@
 
 use crate::path_ext::{PathExt, UniPath};
 use crate::test_utils::{
+    default_repo_path_as_json,
     set_ra,
     symlink_expose,
     Setup,
@@ -12,6 +13,7 @@ use crate::{
     link_tendril,
     ActionLog,
     FsoType,
+    LinkStatus,
     Location,
     Tendril,
     TendrilActionError,
@@ -19,7 +21,14 @@ use crate::{
     TendrilMode,
 };
 use rstest::rstest;
-use std::fs::{create_dir_all, metadata, set_permissions};
+use serial_test::serial;
+use std::fs::{
+    create_dir_all,
+    metadata,
+    remove_dir_all,
+    set_permissions,
+    write,
+};
 use std::path::{Path, PathBuf};
 
 /// See also [`crate::tests::common_action_tests::remote_is_unchanged`] for
@@ -316,7 +325,7 @@ fn local_is_symlink_returns_type_mismatch_error_unless_forced(
 #[rstest]
 #[case(true)]
 #[case(false)]
-fn existing_symlinks_at_remote_are_overwritten(#[case] force: bool) {
+fn existing_symlinks_at_remote_are_relinked(#[case] force: bool) {
     let setup = Setup::new();
     setup.make_local_file();
     setup.make_local_nested_file();
@@ -343,7 +352,10 @@ fn existing_symlinks_at_remote_are_overwritten(#[case] force: bool) {
             Some(FsoType::File),
             Some(FsoType::SymFile),
             setup.remote_file.clone(),
-            Ok(TendrilActionSuccess::Overwrite),
+            Ok(TendrilActionSuccess::Relinked {
+                old_target: setup.target_file.clone(),
+                status: LinkStatus::WrongTarget,
+            }),
         )
     );
     assert_eq!(
@@ -352,7 +364,10 @@ fn existing_symlinks_at_remote_are_overwritten(#[case] force: bool) {
             Some(FsoType::Dir),
             Some(FsoType::SymDir),
             setup.remote_dir.clone(),
-            Ok(TendrilActionSuccess::Overwrite),
+            Ok(TendrilActionSuccess::Relinked {
+                old_target: setup.target_dir.clone(),
+                status: LinkStatus::WrongTarget,
+            }),
         )
     );
     assert!(setup.remote_file.is_symlink());
@@ -373,8 +388,7 @@ fn existing_symlinks_at_remote_are_overwritten(#[case] force: bool) {
 }
 
 #[rstest]
-#[cfg_attr(windows, ignore)] // The Windows syscall reduces these paths
-fn symlink_uses_repo_path_exactly_as_given(
+fn symlink_uses_normalized_repo_path(
     #[values(true, false)] force: bool,
     #[values(true, false)] remote_exists: bool,
 ) {
@@ -400,7 +414,10 @@ fn symlink_uses_repo_path_exactly_as_given(
             .unwrap();
 
         exp_remote_type = Some(FsoType::SymFile);
-        exp_success = Ok(TendrilActionSuccess::Overwrite);
+        exp_success = Ok(TendrilActionSuccess::Relinked {
+            old_target: setup.target_file.clone(),
+            status: LinkStatus::WrongTarget,
+        });
     }
     else {
         exp_remote_type = None;
@@ -421,9 +438,7 @@ fn symlink_uses_repo_path_exactly_as_given(
 
     assert_eq!(
         std::fs::read_link(setup.remote_file).unwrap(),
-        setup.temp_dir.path().join_raw(
-            Path::new("./././TendrilsRepo/../TendrilsRepo/SomeApp/misc.txt")
-        )
+        setup.local_file,
     );
 }
 
@@ -651,3 +666,139 @@ fn local_doesnt_exist_copies_remote_to_local_then_links_unless_dryrun(
         ActionLog::new(None, Some(FsoType::Dir), setup.remote_dir, exp_result,)
     );
 }
+
+#[rstest]
+fn relative_link_creates_symlink_relative_to_remote_parent(
+    #[values(true, false)] force: bool,
+    #[values(true, false)] as_dir: bool,
+) {
+    let setup = Setup::new();
+    let remote;
+    let exp_target;
+    let mut tendril;
+    if as_dir {
+        setup.make_local_subdir_nested_file();
+        remote = setup.remote_subdir_dir.clone();
+        exp_target = PathBuf::from("../TendrilsRepo/SomeApp/SubDir/misc");
+        tendril = setup.subdir_dir_tendril();
+    }
+    else {
+        setup.make_local_subdir_file();
+        remote = setup.remote_subdir_file.clone();
+        exp_target = PathBuf::from("../TendrilsRepo/SomeApp/SubDir/misc.txt");
+        tendril = setup.subdir_file_tendril();
+    }
+    tendril.mode = TendrilMode::Link;
+    tendril.relative_link = true;
+
+    let actual = link_tendril(&tendril, false, force);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+    assert_eq!(std::fs::read_link(&remote).unwrap(), exp_target);
+    if as_dir {
+        assert_eq!(
+            setup.remote_subdir_nested_file_contents(),
+            "Local subdir nested file contents"
+        );
+    }
+    else {
+        assert_eq!(
+            setup.remote_subdir_file_contents(),
+            "Local subdir file contents"
+        );
+    }
+}
+
+#[test]
+fn relative_link_still_resolves_after_repo_and_remote_are_moved() {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+    tendril.relative_link = true;
+    link_tendril(&tendril, false, false).result.unwrap();
+
+    let moved_dir = setup.parent_dir.join("Moved");
+    create_dir_all(&moved_dir).unwrap();
+    std::fs::rename(&setup.td_repo, moved_dir.join("TendrilsRepo")).unwrap();
+    std::fs::rename(&setup.remote_file, moved_dir.join("misc.txt")).unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(moved_dir.join("misc.txt")).unwrap(),
+        "Local file contents",
+    );
+}
+
+#[rstest]
+#[case(true)]
+#[case(false)]
+fn existing_link_to_local_is_replaced_with_configured_form(
+    #[case] relative: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+    tendril.relative_link = !relative;
+    link_tendril(&tendril, false, false).result.unwrap();
+    tendril.relative_link = relative;
+
+    let actual = link_tendril(&tendril, false, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::Overwrite));
+    let link_target = std::fs::read_link(&setup.remote_file).unwrap();
+    assert_eq!(link_target.is_relative(), relative);
+    assert_eq!(setup.remote_file_contents(), "Local file contents");
+}
+
+#[rstest]
+#[case(true)]
+#[case(false)]
+fn existing_link_to_local_in_configured_form_returns_already_linked(
+    #[case] relative: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+    tendril.relative_link = relative;
+    link_tendril(&tendril, false, false).result.unwrap();
+    let exp_target = std::fs::read_link(&setup.remote_file).unwrap();
+
+    let actual = link_tendril(&tendril, false, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::AlreadyLinked));
+    assert_eq!(std::fs::read_link(&setup.remote_file).unwrap(), exp_target);
+    assert_eq!(setup.remote_file_contents(), "Local file contents");
+}
+
+#[rstest]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn existing_link_to_same_local_in_old_repo_is_relinked(
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let old_repo = setup.parent_dir.join("OldRepo");
+    let old_target = old_repo.join("SomeApp/misc.txt");
+    create_dir_all(old_repo.join("SomeApp")).unwrap();
+    write(&old_target, "Old repo file contents").unwrap();
+    symlink_expose(&setup.remote_file, &old_target, false, true).unwrap();
+    remove_dir_all(&old_repo).unwrap();
+    setup.make_global_cfg_file(
+        default_repo_path_as_json(&old_repo.to_string_lossy()),
+    );
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+
+    let actual = link_tendril(&tendril, false, force);
+
+    assert_eq!(
+        actual.result,
+        Ok(TendrilActionSuccess::Relinked {
+            old_target,
+            status: LinkStatus::OldRepo,
+        }),
+    );
+    assert_eq!(setup.remote_file_contents(), "Local file contents");
+}
@@ -7,7 +7,7 @@ use crate::test_utils::{
     Setup,
 };
 use crate::{
-    FsoType, InvalidTendrilError, ListLog, RawTendril, TendrilMode, TendrilReport, UniPath, list_tendrils_inner
+    FsoType, InvalidTendrilError, ListLog, NestedSymlinks, RawTendril, ReflinkMode, TendrilMode, TendrilReport, UniPath, list_tendrils_inner
 };
 use rstest::rstest;
 use core::assert_eq;
@@ -48,50 +48,98 @@ fn returns_fso_types_and_resolved_paths_for_all_in_given_order(
     let raw_file_tendril = RawTendril {
         local: "SomeApp/misc.txt".to_string(),
         remote: setup.remote_file.to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let raw_dir_tendril = RawTendril {
         local: "SomeApp/misc".to_string(),
         remote: setup.remote_dir.to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["p3".to_string()],
+        source: None,
     };
     let raw_file_link_tendril = RawTendril {
         local: "SomeApp/misc.txt".to_string(),
         remote: setup.parent_dir.join("misc_link.txt").to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let raw_dir_link_tendril = RawTendril {
         local: "SomeApp/misc".to_string(),
         remote: setup.parent_dir.join("misc_link").to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let raw_wrong_link_tendril = RawTendril {
         local: "SomeApp/misc.txt".to_string(),
         remote: setup.parent_dir.join("wrong_link.txt").to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let raw_missing_link_tendril = RawTendril {
         local: "I don't exist".to_string(),
         remote: setup.parent_dir.join("missing_link.txt").to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let raw_dne_tendril = RawTendril {
         local: "I don't exist".to_string(),
         remote: setup.parent_dir.join("I don't exist").to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec!["p1".to_string(), "p3".to_string()],
+        source: None,
     };
     let raw_invalid_tendril = RawTendril {
         local: "".to_string(),
         remote: setup.parent_dir.join("I don't exist").to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let given = vec![
         raw_file_tendril.clone(),
@@ -192,8 +240,14 @@ fn no_read_access_from_local_or_remote_file_returns_proper_fso_type(
     let raw_tendril = RawTendril {
         local: "SomeApp/nra.txt".to_string(),
         remote: setup.remote_nra_file.to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let given = vec![raw_tendril.clone()];
 
@@ -228,8 +282,14 @@ fn no_read_access_from_local_or_remote_dir_returns_proper_fso_type(
     let raw_tendril = RawTendril {
         local: "SomeApp/nra".to_string(),
         remote: setup.remote_nra_dir.to_string_lossy().into(),
+        fallbacks: vec![],
         mode,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
         profiles: vec![],
+        source: None,
     };
     let given = vec![raw_tendril.clone()];
 
@@ -250,3 +310,81 @@ fn no_read_access_from_local_or_remote_dir_returns_proper_fso_type(
 
     assert_eq!(actual, exp);
 }
+
+#[test]
+fn tendril_with_fallbacks_returns_index_of_chosen_remote() {
+    let setup = Setup::new();
+    setup.make_td_repo_dir();
+    setup.make_remote_subdir_file();
+
+    let raw_tendril = RawTendril {
+        local: "SomeApp/misc.txt".to_string(),
+        remote: setup.parent_dir.join("DNE/misc.txt").to_string_lossy().into(),
+        fallbacks: vec![
+            setup.remote_subdir_file.to_string_lossy().into(),
+            setup.remote_file.to_string_lossy().into(),
+        ],
+        mode: TendrilMode::DirOverwrite,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
+        profiles: vec![],
+        source: None,
+    };
+    let given = vec![raw_tendril.clone()];
+
+    let actual = list_tendrils_inner(&setup.td_repo.into(), given);
+
+    let exp = vec![
+        TendrilReport {
+            raw_tendril,
+            log: Ok(ListLog::new(
+                None,
+                Some(FsoType::File),
+                setup.remote_subdir_file,
+            ).with_remote_idx(1)),
+        },
+    ];
+
+    assert_eq!(actual, exp);
+}
+
+#[test]
+fn tendril_with_fallbacks_none_exist_returns_index_that_push_would_use() {
+    let setup = Setup::new();
+    setup.make_td_repo_dir();
+    setup.make_remote_subdir_dir();
+
+    let raw_tendril = RawTendril {
+        local: "SomeApp/misc.txt".to_string(),
+        remote: setup.parent_dir.join("DNE/misc.txt").to_string_lossy().into(),
+        fallbacks: vec![
+            setup.remote_subdir_file.to_string_lossy().into(),
+            setup.remote_file.to_string_lossy().into(),
+        ],
+        mode: TendrilMode::DirOverwrite,
+        relative_link: false,
+        copy_fallback: false,
+        reflink: ReflinkMode::Never,
+        nested_symlinks: NestedSymlinks::Follow,
+        profiles: vec![],
+        source: None,
+    };
+    let given = vec![raw_tendril.clone()];
+
+    let actual = list_tendrils_inner(&setup.td_repo.into(), given);
+
+    let exp = vec![
+        TendrilReport {
+            raw_tendril,
+            log: Ok(ListLog::new(
+                None,
+                None,
+                setup.remote_subdir_file,
+            ).with_remote_idx(1)),
+        },
+    ];
+
+    assert_eq!(actual, exp);
+}
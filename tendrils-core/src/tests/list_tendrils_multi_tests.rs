@@ -0,0 +1,242 @@
+use crate::test_utils::Setup;
+use crate::{
+    FilterSpec,
+    GetTendrilsRepoError,
+    SetupError,
+    TendrilsActor,
+    TendrilsApi,
+};
+use serial_test::serial;
+
+#[test]
+fn reports_are_grouped_by_repo_in_given_order() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    let mut t2a = setup2.file_tendril_raw();
+    let mut t2b = setup2.file_tendril_raw();
+    t2a.local = "SomeApp/misc1.txt".to_string();
+    t2b.local = "SomeApp/misc2.txt".to_string();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[t2a.clone(), t2b.clone()]);
+
+    let actual = api.list_tendrils_multi(
+        &[setup2.uni_td_repo(), setup1.uni_td_repo()],
+        FilterSpec::new(),
+    )
+    .unwrap();
+
+    assert_eq!(actual.len(), 2);
+    assert_eq!(actual[0].td_repo, setup2.uni_td_repo());
+    assert_eq!(actual[1].td_repo, setup1.uni_td_repo());
+    let actual_raws: Vec<_> =
+        actual[0].reports.iter().map(|r| r.raw_tendril.clone()).collect();
+    assert_eq!(actual_raws, vec![t2a, t2b]);
+    assert_eq!(actual[1].reports.len(), 1);
+    assert_eq!(actual[1].reports[0].raw_tendril, setup1.file_tendril_raw());
+}
+
+#[test]
+fn duplicate_repos_are_only_listed_once() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_td_json_file(&[setup.file_tendril_raw()]);
+
+    let actual = api.list_tendrils_multi(
+        &[setup.uni_td_repo(), setup.uni_td_repo()],
+        FilterSpec::new(),
+    )
+    .unwrap();
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].reports.len(), 1);
+}
+
+#[test]
+fn filter_is_applied_to_each_repo() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    let mut t2 = setup2.file_tendril_raw();
+    t2.local = "OtherApp/misc.txt".to_string();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[setup2.file_tendril_raw(), t2]);
+    let mut filter = FilterSpec::new();
+    filter.locals = vec!["SomeApp/*".to_string()];
+
+    let actual = api.list_tendrils_multi(
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        filter,
+    )
+    .unwrap();
+
+    assert_eq!(actual[0].reports.len(), 1);
+    assert_eq!(actual[1].reports.len(), 1);
+    assert_eq!(actual[1].reports[0].raw_tendril, setup2.file_tendril_raw());
+}
+
+#[test]
+fn any_given_repo_invalid_returns_given_invalid_err() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_td_json_file(&[]);
+
+    let actual = api.list_tendrils_multi(
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        FilterSpec::new(),
+    );
+
+    assert_eq!(
+        actual,
+        Err(SetupError::NoValidTendrilsRepo(
+            GetTendrilsRepoError::GivenInvalid { path: setup2.td_repo.clone() }
+        )),
+    );
+}
+
+#[test]
+fn same_remote_in_two_repos_returns_remote_conflict_err() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[setup1.file_tendril_raw()]);
+
+    let actual = api.list_tendrils_multi(
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        FilterSpec::new(),
+    );
+
+    assert_eq!(
+        actual,
+        Err(SetupError::RemoteConflict {
+            remote: setup1.remote_file.clone(),
+            first_repo: setup1.td_repo.clone(),
+            second_repo: setup2.td_repo.clone(),
+        }),
+    );
+}
+
+#[test]
+fn fallback_matching_remote_in_other_repo_returns_remote_conflict_err() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    let mut t2 = setup2.file_tendril_raw();
+    t2.fallbacks = vec![setup1.remote_file.to_string_lossy().to_string()];
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[t2]);
+
+    let actual = api.list_tendrils_multi(
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        FilterSpec::new(),
+    );
+
+    assert_eq!(
+        actual,
+        Err(SetupError::RemoteConflict {
+            remote: setup1.remote_file.clone(),
+            first_repo: setup1.td_repo.clone(),
+            second_repo: setup2.td_repo.clone(),
+        }),
+    );
+}
+
+#[test]
+fn same_remote_in_two_repos_but_filtered_out_returns_ok() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    let mut t2 = setup1.file_tendril_raw();
+    t2.local = "OtherApp/misc.txt".to_string();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[t2]);
+    let mut filter = FilterSpec::new();
+    filter.locals = vec!["SomeApp/*".to_string()];
+
+    let actual = api.list_tendrils_multi(
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        filter,
+    )
+    .unwrap();
+
+    assert_eq!(actual[0].reports.len(), 1);
+    assert!(actual[1].reports.is_empty());
+}
+
+#[test]
+fn same_remote_twice_in_one_repo_returns_ok() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let mut t2 = setup.file_tendril_raw();
+    t2.local = "OtherApp/misc.txt".to_string();
+    setup.make_td_json_file(&[setup.file_tendril_raw(), t2]);
+
+    let actual = api.list_tendrils_multi(
+        &[setup.uni_td_repo()],
+        FilterSpec::new(),
+    )
+    .unwrap();
+
+    assert_eq!(actual[0].reports.len(), 2);
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn no_repos_given_uses_each_default_repo() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[setup2.file_tendril_raw()]);
+    let json = serde_json::json!({
+        "default-repo-path": [setup1.td_repo, setup2.td_repo],
+    });
+    setup1.make_global_cfg_file(json.to_string());
+
+    let actual = api.list_tendrils_multi(&[], FilterSpec::new()).unwrap();
+
+    assert_eq!(actual.len(), 2);
+    assert_eq!(actual[0].td_repo, setup1.uni_td_repo());
+    assert_eq!(actual[1].td_repo, setup2.uni_td_repo());
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn no_repos_given_and_any_default_invalid_returns_default_invalid_err() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_td_json_file(&[]);
+    let json = serde_json::json!({
+        "default-repo-path": [setup1.td_repo, setup2.td_repo],
+    });
+    setup1.make_global_cfg_file(json.to_string());
+
+    let actual = api.list_tendrils_multi(&[], FilterSpec::new());
+
+    assert_eq!(
+        actual,
+        Err(SetupError::NoValidTendrilsRepo(
+            GetTendrilsRepoError::DefaultInvalid { path: setup2.td_repo.clone() }
+        )),
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn no_repos_given_and_default_list_empty_returns_default_not_set_err() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_global_cfg_file(r#"{"default-repo-path": []}"#.to_string());
+
+    let actual = api.list_tendrils_multi(&[], FilterSpec::new());
+
+    assert_eq!(
+        actual,
+        Err(SetupError::NoValidTendrilsRepo(
+            GetTendrilsRepoError::DefaultNotSet
+        )),
+    );
+}
@@ -0,0 +1,237 @@
+use crate::config::{migrate_repo_config_with, Migration};
+use crate::test_utils::Setup;
+use crate::{
+    ConfigFormat,
+    ConfigType,
+    GetConfigError,
+    GetTendrilsRepoError,
+    SetupError,
+    TendrilsActor,
+    TendrilsApi,
+    CONFIG_VERSION,
+};
+use rstest::rstest;
+use serde_json::{json, Value};
+use std::fs::{read_to_string, write};
+
+#[test]
+fn given_td_repo_is_invalid_returns_no_valid_td_repo_err() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+
+    let actual = api.migrate_config(Some(&setup.uni_td_repo()), false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::NoValidTendrilsRepo(GetTendrilsRepoError::GivenInvalid {
+            path: setup.td_repo.clone(),
+        })),
+    );
+}
+
+#[test]
+fn unversioned_file_returns_first_version_and_escapes_dollars() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{
+        "tendrils": {
+            "l1": {"remotes": "C:\\$Recycle.Bin"},
+            "l2": [{"remotes": ["\\\\server\\share$\\x", "r2"]}]
+        },
+        "vars": {"v": "a$b"},
+        "auto-profiles": {"p": [{"path": "/$x"}, {"hostname": "h$"}]}
+    }"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = api.migrate_config(Some(&setup.uni_td_repo()), false);
+
+    assert_eq!(actual, Ok(1));
+    let migrated: Value = serde_json::from_str(&setup.td_json_file_contents()).unwrap();
+    assert_eq!(migrated["version"], json!(CONFIG_VERSION));
+    assert_eq!(migrated["tendrils"]["l1"]["remotes"], json!("C:\\$$Recycle.Bin"));
+    assert_eq!(
+        migrated["tendrils"]["l2"][0]["remotes"],
+        json!(["\\\\server\\share$$\\x", "r2"]),
+    );
+    assert_eq!(migrated["vars"], json!({"v": "a$$b"}));
+    assert_eq!(
+        migrated["auto-profiles"]["p"],
+        json!([{"path": "/$$x"}, {"hostname": "h$"}]),
+    );
+}
+
+#[rstest]
+#[case("tendrils.jsonc", ConfigFormat::Jsonc, r#"{"tendrils": {"l1": {"remotes": ["r1"]}}} // Comment"#)]
+#[case("tendrils.toml", ConfigFormat::Toml, "# Comment\n[tendrils.l1]\nremotes = [\"r1\"]\n")]
+fn unversioned_file_with_comments_returns_comments_would_be_lost_err_and_leaves_file(
+    #[case] file_name: &str,
+    #[case] format: ConfigFormat,
+    #[case] contents: &str,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let file = setup.dot_td_dir.join(file_name);
+    write(&file, contents).unwrap();
+
+    let actual = api.migrate_config(Some(&setup.uni_td_repo()), false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::CommentsWouldBeLost {
+            cfg_type: ConfigType::Repo(format),
+        })),
+    );
+    assert_eq!(read_to_string(file).unwrap(), contents);
+}
+
+#[test]
+fn latest_version_returns_version_and_leaves_file_unchanged() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let jsonc_file = setup.dot_td_dir.join("tendrils.jsonc");
+    let jsonc = format!("{{\"version\": {CONFIG_VERSION}}} // Comment");
+    write(&jsonc_file, &jsonc).unwrap();
+
+    let actual = api.migrate_config(Some(&setup.uni_td_repo()), false);
+
+    assert_eq!(actual, Ok(CONFIG_VERSION));
+    assert_eq!(read_to_string(jsonc_file).unwrap(), jsonc);
+}
+
+#[test]
+fn newer_version_returns_unsupported_version_err_and_leaves_file() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = format!("{{\"version\": {}}}", CONFIG_VERSION + 1);
+    write(&setup.td_json_file, &json).unwrap();
+
+    let actual = api.migrate_config(Some(&setup.uni_td_repo()), false);
+
+    assert_eq!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::UnsupportedVersion {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            version: CONFIG_VERSION + 1,
+            supported: CONFIG_VERSION,
+        })),
+    );
+    assert_eq!(setup.td_json_file_contents(), json);
+}
+
+/// Upgrades a test layout from version 1, where the tendrils were under
+/// `old-tendrils`, to version 2.
+fn rename_old_tendrils(value: &mut Value) {
+    if let Some(map) = value.as_object_mut() {
+        if let Some(tendrils) = map.remove("old-tendrils") {
+            map.insert(String::from("tendrils"), tendrils);
+        }
+    }
+}
+
+const TEST_MIGRATIONS: &[Migration] = &[rename_old_tendrils];
+
+#[test]
+fn older_version_is_migrated_and_rewritten_at_latest_version() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{"version": 1, "old-tendrils": {"l1": {"remotes": "r1"}}}"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = migrate_repo_config_with(&setup.uni_td_repo(), false, TEST_MIGRATIONS);
+
+    assert_eq!(actual, Ok(1));
+    let migrated: Value = serde_json::from_str(&setup.td_json_file_contents()).unwrap();
+    assert_eq!(migrated["version"], json!(2));
+    assert_eq!(migrated["tendrils"], json!({"l1": {"remotes": "r1"}}));
+    assert!(migrated.get("old-tendrils").is_none());
+}
+
+#[test]
+fn unversioned_toml_is_migrated_and_rewritten_as_toml() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml_file = setup.dot_td_dir.join("tendrils.toml");
+    write(&toml_file, "[old-tendrils.l1]\nremotes = \"r1\"\n").unwrap();
+
+    let actual = migrate_repo_config_with(&setup.uni_td_repo(), false, TEST_MIGRATIONS);
+
+    assert_eq!(actual, Ok(1));
+    let migrated = read_to_string(&toml_file).unwrap();
+    assert!(migrated.contains("version = 2"));
+    assert!(migrated.contains("[tendrils.l1]"));
+    assert!(!migrated.contains("old-tendrils"));
+}
+
+#[test]
+fn latest_version_of_migrations_leaves_file_unchanged() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{"version": 2, "tendrils": {"l1": {"remotes": "r1"}}}"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = migrate_repo_config_with(&setup.uni_td_repo(), false, TEST_MIGRATIONS);
+
+    assert_eq!(actual, Ok(2));
+    assert_eq!(setup.td_json_file_contents(), json);
+}
+
+#[test]
+fn newer_version_than_migrations_returns_unsupported_version_err() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{"version": 3}"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = migrate_repo_config_with(&setup.uni_td_repo(), false, TEST_MIGRATIONS);
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::UnsupportedVersion {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            version: 3,
+            supported: 2,
+        }),
+    );
+    assert_eq!(setup.td_json_file_contents(), json);
+}
+
+#[rstest]
+#[case("tendrils.jsonc", ConfigFormat::Jsonc, "{\"old-tendrils\": {\"l1\": {\"remotes\": \"r1\"}}} // Comment\n")]
+#[case("tendrils.toml", ConfigFormat::Toml, "# Comment\n[old-tendrils.l1]\nremotes = \"r1\"\n")]
+fn older_version_with_comments_returns_comments_would_be_lost_err_unless_drop_comments(
+    #[case] file_name: &str,
+    #[case] format: ConfigFormat,
+    #[case] contents: &str,
+    #[values(true, false)] drop_comments: bool,
+) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let file = setup.dot_td_dir.join(file_name);
+    write(&file, contents).unwrap();
+
+    let actual = migrate_repo_config_with(
+        &setup.uni_td_repo(),
+        drop_comments,
+        TEST_MIGRATIONS,
+    );
+
+    let migrated = read_to_string(&file).unwrap();
+    if drop_comments {
+        assert_eq!(actual, Ok(1));
+        assert!(!migrated.contains("Comment"));
+        assert!(!migrated.contains("old-tendrils"));
+    }
+    else {
+        assert_eq!(
+            actual,
+            Err(GetConfigError::CommentsWouldBeLost {
+                cfg_type: ConfigType::Repo(format),
+            }),
+        );
+        assert_eq!(migrated, contents);
+    }
+}
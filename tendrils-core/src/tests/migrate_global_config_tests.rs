@@ -0,0 +1,90 @@
+use crate::config::{migrate_global_config_with, Migration};
+use crate::test_utils::{global_cfg_file, Setup};
+use crate::{ConfigType, GetConfigError, TendrilsActor, TendrilsApi, CONFIG_VERSION};
+use serde_json::{json, Value};
+use serial_test::serial;
+use std::fs::read_to_string;
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn no_config_file_returns_io_not_found_err() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.set_home_dir();
+
+    let actual = api.migrate_global_config();
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::IoError {
+            cfg_type: ConfigType::Global,
+            kind: std::io::ErrorKind::NotFound,
+        }),
+    );
+    assert!(!global_cfg_file().exists());
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn unversioned_file_returns_first_version_and_escapes_dollars() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let json = r#"{
+        "default-repo-path": ["C:\\$Recycle.Bin", "r2"],
+        "default-profiles": ["p$"],
+        "auto-profiles": {"p": {"path": "/$x"}}
+    }"#;
+    setup.make_global_cfg_file(json.to_string());
+
+    let actual = api.migrate_global_config();
+
+    assert_eq!(actual, Ok(1));
+    let migrated: Value =
+        serde_json::from_str(&read_to_string(global_cfg_file()).unwrap()).unwrap();
+    assert_eq!(migrated["version"], json!(CONFIG_VERSION));
+    assert_eq!(migrated["default-repo-path"], json!(["C:\\$$Recycle.Bin", "r2"]));
+    assert_eq!(migrated["default-profiles"], json!(["p$"]));
+    assert_eq!(migrated["auto-profiles"], json!({"p": {"path": "/$$x"}}));
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn latest_version_returns_version_and_leaves_file_unchanged() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let json = format!("{{\"version\": {CONFIG_VERSION}}}");
+    setup.make_global_cfg_file(json.clone());
+
+    let actual = api.migrate_global_config();
+
+    assert_eq!(actual, Ok(CONFIG_VERSION));
+    assert_eq!(read_to_string(global_cfg_file()).unwrap(), json);
+}
+
+/// Upgrades a test layout from version 1, where the default profiles were
+/// under `old-profiles`, to version 2.
+fn rename_old_profiles(value: &mut Value) {
+    if let Some(map) = value.as_object_mut() {
+        if let Some(profiles) = map.remove("old-profiles") {
+            map.insert(String::from("default-profiles"), profiles);
+        }
+    }
+}
+
+const TEST_MIGRATIONS: &[Migration] = &[rename_old_profiles];
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn older_version_is_migrated_and_rewritten_at_latest_version() {
+    let setup = Setup::new();
+    setup.make_global_cfg_file(r#"{"old-profiles": ["p1"]}"#.to_string());
+
+    let actual = migrate_global_config_with(TEST_MIGRATIONS);
+
+    assert_eq!(actual, Ok(1));
+    let migrated: Value =
+        serde_json::from_str(&read_to_string(global_cfg_file()).unwrap()).unwrap();
+    assert_eq!(migrated["version"], json!(2));
+    assert_eq!(migrated["default-profiles"], json!(["p1"]));
+    assert!(migrated.get("old-profiles").is_none());
+}
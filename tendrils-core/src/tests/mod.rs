@@ -1,14 +1,27 @@
 pub mod sample_tendrils;
 
 mod common_action_tests;
+mod convert_config_tests;
+mod diagnose_tests;
+mod find_tendrils_repo_tests;
+mod fmt_config_tests;
 mod get_default_repo_tests;
 mod get_tendrils_repo_tests;
+mod hardlink_tendril_tests;
 mod init_tendrils_repo_tests;
 mod is_tendrils_repo_tests;
 mod link_tendril_tests;
 mod list_tendrils_inner_tests;
+mod list_tendrils_multi_tests;
+mod migrate_config_tests;
+mod migrate_global_config_tests;
+mod nested_symlinks_tests;
 mod pull_tendril_tests;
 mod push_tendril_tests;
+mod reflink_tests;
+mod relink_tendril_tests;
 mod batch_tendril_action_tests;
+mod tendril_action_multi_tests;
 mod tendril_action_tests;
 mod tendril_action_updating_tests;
+mod validate_config_tests;
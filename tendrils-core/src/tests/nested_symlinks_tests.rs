@@ -0,0 +1,292 @@
+//! Contains tests specific to symlinks nested within folder tendrils in push
+//! and pull actions.
+//! See also [`crate::tests::push_tendril_tests`] and
+//! [`crate::tests::pull_tendril_tests`].
+
+use crate::test_utils::{symlink_expose, Setup};
+use crate::{
+    pull_tendril,
+    push_tendril,
+    NestedSymlinks,
+    Tendril,
+    TendrilActionError,
+    TendrilActionSuccess,
+    TendrilMode,
+};
+use rstest::rstest;
+use std::fs::{create_dir_all, read_link, read_to_string};
+use std::path::{Path, PathBuf};
+
+/// Returns the source folder, the destination folder, and the folder tendril
+/// of the `setup` with the given `nested_symlinks` policy. The source
+/// contains its nested file.
+fn setup_dirs(
+    setup: &Setup,
+    push: bool,
+    nested_symlinks: NestedSymlinks,
+) -> (PathBuf, PathBuf, Tendril) {
+    let mut tendril = setup.dir_tendril();
+    tendril.nested_symlinks = nested_symlinks;
+    if push {
+        setup.make_local_nested_file();
+        (setup.local_dir.clone(), setup.remote_dir.clone(), tendril)
+    }
+    else {
+        setup.make_remote_nested_file();
+        (setup.remote_dir.clone(), setup.local_dir.clone(), tendril)
+    }
+}
+
+fn run(tendril: &Tendril, push: bool, dry_run: bool) -> crate::ActionLog {
+    if push {
+        push_tendril(tendril, dry_run, false)
+    }
+    else {
+        pull_tendril(tendril, dry_run, false)
+    }
+}
+
+fn is_symlink(path: &Path) -> bool {
+    path.symlink_metadata().map(|md| md.is_symlink()).unwrap_or(false)
+}
+
+#[rstest]
+fn preserve_copies_nested_symlinks_as_symlinks(
+    #[values(true, false)] push: bool,
+) {
+    let setup = Setup::new();
+    setup.make_target_file();
+    setup.make_target_nested_file();
+    let (source, dest, tendril) =
+        setup_dirs(&setup, push, NestedSymlinks::Preserve);
+    symlink_expose(&source.join("file.txt"), &setup.target_file, false, false)
+        .unwrap();
+    symlink_expose(&source.join("dir"), &setup.target_dir, false, false)
+        .unwrap();
+
+    let actual = run(&tendril, push, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+    assert_eq!(read_link(dest.join("file.txt")).unwrap(), setup.target_file);
+    assert_eq!(read_link(dest.join("dir")).unwrap(), setup.target_dir);
+    assert_eq!(setup.target_file_contents(), "Target file contents");
+}
+
+#[rstest]
+fn follow_copies_contents_of_nested_symlink_targets(
+    #[values(true, false)] push: bool,
+) {
+    let setup = Setup::new();
+    setup.make_target_file();
+    setup.make_target_nested_file();
+    let (source, dest, tendril) =
+        setup_dirs(&setup, push, NestedSymlinks::Follow);
+    symlink_expose(&source.join("file.txt"), &setup.target_file, false, false)
+        .unwrap();
+    symlink_expose(&source.join("dir"), &setup.target_dir, false, false)
+        .unwrap();
+
+    let actual = run(&tendril, push, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+    assert!(!is_symlink(&dest.join("file.txt")));
+    assert!(!is_symlink(&dest.join("dir")));
+    assert_eq!(
+        read_to_string(dest.join("file.txt")).unwrap(),
+        "Target file contents",
+    );
+    assert_eq!(
+        read_to_string(dest.join("dir").join("nested.txt")).unwrap(),
+        "Target nested file contents",
+    );
+}
+
+#[rstest]
+fn skip_leaves_out_nested_symlinks(#[values(true, false)] push: bool) {
+    let setup = Setup::new();
+    setup.make_target_file();
+    setup.make_target_nested_file();
+    let (source, dest, tendril) =
+        setup_dirs(&setup, push, NestedSymlinks::Skip);
+    symlink_expose(&source.join("file.txt"), &setup.target_file, false, false)
+        .unwrap();
+    symlink_expose(&source.join("dir"), &setup.target_dir, false, false)
+        .unwrap();
+
+    let actual = run(&tendril, push, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+    assert!(dest.join("nested.txt").exists());
+    assert!(dest.join("file.txt").symlink_metadata().is_err());
+    assert!(dest.join("dir").symlink_metadata().is_err());
+    assert!(actual.outside_symlinks().is_empty());
+}
+
+#[rstest]
+fn symlinks_pointing_outside_are_reported_unless_skipped(
+    #[values(true, false)] push: bool,
+    #[values(true, false)] dry_run: bool,
+
+    #[values(
+        NestedSymlinks::Preserve,
+        NestedSymlinks::Follow,
+        NestedSymlinks::Skip
+    )]
+    nested_symlinks: NestedSymlinks,
+) {
+    let setup = Setup::new();
+    setup.make_target_file();
+    let (source, _, tendril) = setup_dirs(&setup, push, nested_symlinks);
+    let subdir = source.join("SubDir");
+    create_dir_all(&subdir).unwrap();
+    let outside_link = subdir.join("outside.txt");
+    let inside_link = source.join("inside.txt");
+    symlink_expose(&outside_link, &setup.target_file, false, false).unwrap();
+    symlink_expose(&inside_link, &source.join("nested.txt"), false, false)
+        .unwrap();
+
+    let actual = run(&tendril, push, dry_run);
+
+    if nested_symlinks == NestedSymlinks::Skip {
+        assert!(actual.outside_symlinks().is_empty());
+    }
+    else {
+        assert_eq!(actual.outside_symlinks(), [outside_link]);
+    }
+}
+
+#[test]
+fn dry_run_copies_nothing_but_still_reports_outside_symlinks() {
+    let setup = Setup::new();
+    setup.make_target_file();
+    let (source, dest, tendril) =
+        setup_dirs(&setup, true, NestedSymlinks::Preserve);
+    let link = source.join("file.txt");
+    symlink_expose(&link, &setup.target_file, false, false).unwrap();
+
+    let actual = run(&tendril, true, true);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::NewSkipped));
+    assert_eq!(actual.outside_symlinks(), [link]);
+    assert!(!dest.exists());
+}
+
+#[cfg(unix)]
+#[rstest]
+fn preserve_keeps_relative_targets_relative(
+    #[values(true, false)] push: bool,
+) {
+    let setup = Setup::new();
+    let (source, dest, tendril) =
+        setup_dirs(&setup, push, NestedSymlinks::Preserve);
+    std::os::unix::fs::symlink("nested.txt", source.join("link.txt")).unwrap();
+
+    let actual = run(&tendril, push, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+    assert_eq!(
+        read_link(dest.join("link.txt")).unwrap(),
+        PathBuf::from("nested.txt"),
+    );
+    assert!(actual.outside_symlinks().is_empty());
+}
+
+#[rstest]
+fn follow_symlink_loop_returns_symlink_loop_error(
+    #[values(true, false)] push: bool,
+) {
+    let setup = Setup::new();
+    let (source, _, tendril) =
+        setup_dirs(&setup, push, NestedSymlinks::Follow);
+    symlink_expose(&source.join("loop"), &source, false, false).unwrap();
+
+    let actual = run(&tendril, push, false);
+
+    assert_eq!(
+        actual.result,
+        Err(TendrilActionError::SymlinkLoop { symlink: source.join("loop") }),
+    );
+}
+
+#[rstest]
+fn follow_symlink_loop_through_outside_folder_still_reports_outside_symlinks(
+    #[values(true, false)] push: bool,
+) {
+    let setup = Setup::new();
+    let (source, _, tendril) =
+        setup_dirs(&setup, push, NestedSymlinks::Follow);
+    let outside = setup.parent_dir.join("Outside");
+    create_dir_all(&outside).unwrap();
+    symlink_expose(&source.join("out"), &outside, false, false).unwrap();
+    symlink_expose(&outside.join("back"), &source, false, false).unwrap();
+
+    let actual = run(&tendril, push, false);
+
+    assert_eq!(
+        actual.result,
+        Err(TendrilActionError::SymlinkLoop {
+            symlink: source.join("out").join("back"),
+        }),
+    );
+    assert_eq!(actual.outside_symlinks(), &[source.join("out")]);
+}
+
+#[rstest]
+fn preserve_or_skip_symlink_loop_is_copied_without_error(
+    #[values(NestedSymlinks::Preserve, NestedSymlinks::Skip)]
+    nested_symlinks: NestedSymlinks,
+) {
+    let setup = Setup::new();
+    let (source, dest, tendril) =
+        setup_dirs(&setup, true, nested_symlinks);
+    symlink_expose(&source.join("loop"), &source, false, false).unwrap();
+
+    let actual = run(&tendril, true, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+    assert_eq!(
+        is_symlink(&dest.join("loop")),
+        nested_symlinks == NestedSymlinks::Preserve,
+    );
+}
+
+#[rstest]
+fn symlink_in_dest_is_replaced_rather_than_written_through(
+    #[values(
+        NestedSymlinks::Preserve,
+        NestedSymlinks::Follow,
+        NestedSymlinks::Skip
+    )]
+    nested_symlinks: NestedSymlinks,
+) {
+    let setup = Setup::new();
+    setup.make_target_file();
+    setup.make_target_nested_file();
+    setup.make_remote_nested_file();
+    let (source, dest, mut tendril) =
+        setup_dirs(&setup, true, nested_symlinks);
+    tendril.mode = TendrilMode::DirMerge;
+    create_dir_all(source.join("dir")).unwrap();
+    std::fs::write(source.join("dir").join("nested.txt"), "Local contents")
+        .unwrap();
+    std::fs::write(source.join("file.txt"), "Local contents").unwrap();
+    symlink_expose(&dest.join("dir"), &setup.target_dir, false, false)
+        .unwrap();
+    symlink_expose(&dest.join("file.txt"), &setup.target_file, false, false)
+        .unwrap();
+
+    let actual = run(&tendril, true, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::Overwrite));
+    assert!(!is_symlink(&dest.join("dir")));
+    assert!(!is_symlink(&dest.join("file.txt")));
+    assert_eq!(
+        read_to_string(dest.join("dir").join("nested.txt")).unwrap(),
+        "Local contents",
+    );
+    assert_eq!(setup.target_file_contents(), "Target file contents");
+    assert_eq!(
+        read_to_string(&setup.target_nested_file).unwrap(),
+        "Target nested file contents",
+    );
+}
@@ -0,0 +1,226 @@
+//! Contains tests specific to reflink copies in push and pull actions.
+//! See also [`crate::tests::push_tendril_tests`] and
+//! [`crate::tests::pull_tendril_tests`].
+
+use crate::test_utils::Setup;
+use crate::{
+    pull_tendril,
+    push_tendril,
+    CopyMethod,
+    ReflinkMode,
+    TendrilActionError,
+    TendrilActionSuccess,
+    TendrilMode,
+};
+use rstest::rstest;
+use std::fs::{read_to_string, write};
+
+/// Returns whether the file system of the `setup` supports reflinks.
+fn reflinks_supported(setup: &Setup) -> bool {
+    let from = setup.parent_dir.join("reflink-probe");
+    let to = setup.parent_dir.join("reflink-probe-copy");
+    write(&from, "Probe").unwrap();
+    let supported = reflink_copy::reflink(&from, &to).is_ok();
+    let _ = std::fs::remove_file(&from);
+    let _ = std::fs::remove_file(&to);
+    supported
+}
+
+#[rstest]
+fn never_copies_without_reporting_copy_method(
+    #[values(true, false)] push: bool,
+    #[values(true, false)] as_dir: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_nested_file();
+    setup.make_local_file();
+    setup.make_remote_nested_file();
+    setup.make_remote_file();
+    let mut tendril = if as_dir {
+        setup.dir_tendril()
+    }
+    else {
+        setup.file_tendril()
+    };
+    tendril.reflink = ReflinkMode::Never;
+
+    let actual = if push {
+        push_tendril(&tendril, false, false)
+    }
+    else {
+        pull_tendril(&tendril, false, false)
+    };
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::Overwrite));
+    assert_eq!(actual.copy_method(), None);
+}
+
+#[rstest]
+fn auto_copies_and_reports_reflink_if_supported_otherwise_copy(
+    #[values(true, false)] push: bool,
+    #[values(true, false)] as_dir: bool,
+) {
+    let setup = Setup::new();
+    let exp_method = if reflinks_supported(&setup) {
+        CopyMethod::Reflink
+    }
+    else {
+        CopyMethod::Copy
+    };
+    let mut tendril = if as_dir {
+        setup.dir_tendril()
+    }
+    else {
+        setup.file_tendril()
+    };
+    tendril.reflink = ReflinkMode::Auto;
+
+    let actual = if push {
+        setup.make_local_nested_file();
+        setup.make_local_file();
+        push_tendril(&tendril, false, false)
+    }
+    else {
+        setup.make_remote_nested_file();
+        setup.make_remote_file();
+        pull_tendril(&tendril, false, false)
+    };
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+    assert_eq!(actual.copy_method(), Some(exp_method));
+    match (push, as_dir) {
+        (true, true) => assert_eq!(
+            setup.remote_nested_file_contents(),
+            "Local nested file contents",
+        ),
+        (true, false) => {
+            assert_eq!(setup.remote_file_contents(), "Local file contents")
+        }
+        (false, true) => assert_eq!(
+            setup.local_nested_file_contents(),
+            "Remote nested file contents",
+        ),
+        (false, false) => {
+            assert_eq!(setup.local_file_contents(), "Remote file contents")
+        }
+    }
+}
+
+#[rstest]
+fn always_reflinks_if_supported_otherwise_returns_reflink_unsupported_error(
+    #[values(true, false)] as_dir: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_nested_file();
+    setup.make_local_file();
+    let mut tendril = if as_dir {
+        setup.dir_tendril()
+    }
+    else {
+        setup.file_tendril()
+    };
+    tendril.reflink = ReflinkMode::Always;
+
+    let actual = push_tendril(&tendril, false, false);
+
+    if reflinks_supported(&setup) {
+        assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+        assert_eq!(actual.copy_method(), Some(CopyMethod::Reflink));
+    }
+    else {
+        assert_eq!(
+            actual.result,
+            Err(TendrilActionError::ReflinkUnsupported),
+        );
+        assert_eq!(actual.copy_method(), None);
+        assert!(!setup.remote_file.exists());
+        assert!(!setup.remote_nested_file.exists());
+    }
+}
+
+#[rstest]
+fn dry_run_does_not_report_copy_method(
+    #[values(ReflinkMode::Auto, ReflinkMode::Always)] reflink: ReflinkMode,
+    #[values(true, false)] as_dir: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_nested_file();
+    setup.make_local_file();
+    let mut tendril = if as_dir {
+        setup.dir_tendril()
+    }
+    else {
+        setup.file_tendril()
+    };
+    tendril.reflink = reflink;
+
+    let actual = push_tendril(&tendril, true, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::NewSkipped));
+    assert_eq!(actual.copy_method(), None);
+    assert!(!setup.remote_file.exists());
+    assert!(!setup.remote_dir.exists());
+}
+
+#[rstest]
+#[case(TendrilMode::DirMerge, true)]
+#[case(TendrilMode::DirOverwrite, false)]
+fn auto_keeps_other_files_in_remote_dir_only_if_dir_merge(
+    #[case] mode: TendrilMode,
+    #[case] exp_kept: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_nested_file();
+    setup.make_remote_nested_file();
+    let other_file = setup.remote_dir.join("other.txt");
+    write(&other_file, "Other file contents").unwrap();
+    let mut tendril = setup.dir_tendril();
+    tendril.mode = mode;
+    tendril.reflink = ReflinkMode::Auto;
+
+    let actual = push_tendril(&tendril, false, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::Overwrite));
+    assert_eq!(
+        setup.remote_nested_file_contents(),
+        "Local nested file contents",
+    );
+    assert_eq!(other_file.exists(), exp_kept);
+}
+
+#[test]
+fn auto_copies_nested_folders() {
+    let setup = Setup::new();
+    setup.make_local_subdir_nested_file();
+    let mut tendril = setup.subdir_dir_tendril();
+    tendril.reflink = ReflinkMode::Auto;
+
+    let actual = push_tendril(&tendril, false, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::New));
+    assert_eq!(
+        read_to_string(&setup.remote_subdir_nested_file).unwrap(),
+        read_to_string(&setup.local_subdir_nested_file).unwrap(),
+    );
+}
+
+#[cfg(unix)]
+#[rstest]
+fn copy_keeps_file_permissions(
+    #[values(ReflinkMode::Auto, ReflinkMode::Never)] reflink: ReflinkMode,
+) {
+    use std::os::unix::fs::PermissionsExt;
+    let setup = Setup::new();
+    setup.make_local_file();
+    std::fs::set_permissions(
+        &setup.local_file,
+        std::fs::Permissions::from_mode(0o741),
+    ).unwrap();
+    let mut tendril = setup.file_tendril();
+    tendril.reflink = reflink;
+
+    push_tendril(&tendril, false, false).result.unwrap();
+
+    let actual = setup.remote_file.metadata().unwrap().permissions().mode();
+    assert_eq!(actual & 0o777, 0o741);
+}
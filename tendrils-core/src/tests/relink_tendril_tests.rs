@@ -0,0 +1,322 @@
+//! Contains tests specific to relink actions.
+//! See also [`crate::tests::link_tendril_tests`].
+
+use crate::path_ext::PathExt;
+use crate::test_utils::{default_repo_path_as_json, symlink_expose, Setup};
+use crate::{
+    relink_tendril,
+    ActionLog,
+    FsoType,
+    LinkStatus,
+    Location,
+    TendrilActionError,
+    TendrilActionSuccess,
+    TendrilMode,
+};
+use rstest::rstest;
+use serial_test::serial;
+use std::fs::{create_dir_all, read_link, remove_dir_all, write};
+
+#[rstest]
+fn non_link_mode_tendril_returns_mode_mismatch_error(
+    #[values(TendrilMode::DirMerge, TendrilMode::DirOverwrite)]
+    mode: TendrilMode,
+
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = mode;
+
+    let actual = relink_tendril(&tendril, dry_run, force);
+
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            None,
+            None,
+            setup.remote_file,
+            Err(TendrilActionError::ModeMismatch),
+        )
+    );
+}
+
+#[rstest]
+fn remote_doesnt_exist_returns_io_error_not_found(
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+
+    let actual = relink_tendril(&tendril, dry_run, force);
+
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            Some(FsoType::File),
+            None,
+            setup.remote_file.clone(),
+            Err(TendrilActionError::IoError {
+                kind: std::io::ErrorKind::NotFound,
+                loc: Location::Dest,
+            }),
+        )
+    );
+    assert!(!setup.remote_file.exists());
+}
+
+#[rstest]
+fn remote_is_not_symlink_returns_type_mismatch_error_even_if_forced(
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_local_nested_file();
+    setup.make_remote_file();
+    setup.make_remote_nested_file();
+    let mut file_tendril = setup.file_tendril();
+    file_tendril.mode = TendrilMode::Link;
+    let mut dir_tendril = setup.dir_tendril();
+    dir_tendril.mode = TendrilMode::Link;
+
+    let file_actual = relink_tendril(&file_tendril, dry_run, force);
+    let dir_actual = relink_tendril(&dir_tendril, dry_run, force);
+
+    assert_eq!(
+        file_actual,
+        ActionLog::new(
+            Some(FsoType::File),
+            Some(FsoType::File),
+            setup.remote_file.clone(),
+            Err(TendrilActionError::TypeMismatch {
+                mistype: FsoType::File,
+                loc: Location::Dest,
+            }),
+        )
+    );
+    assert_eq!(
+        dir_actual,
+        ActionLog::new(
+            Some(FsoType::Dir),
+            Some(FsoType::Dir),
+            setup.remote_dir.clone(),
+            Err(TendrilActionError::TypeMismatch {
+                mistype: FsoType::Dir,
+                loc: Location::Dest,
+            }),
+        )
+    );
+    assert_eq!(setup.remote_file_contents(), "Remote file contents");
+    assert_eq!(
+        setup.remote_nested_file_contents(),
+        "Remote nested file contents"
+    );
+}
+
+#[rstest]
+fn link_to_local_returns_already_linked_and_keeps_its_form(
+    #[values(true, false)] relative: bool,
+    #[values(true, false)] dry_run: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+    tendril.relative_link = relative;
+    crate::link_tendril(&tendril, false, false).result.unwrap();
+    let exp_target = read_link(&setup.remote_file).unwrap();
+    tendril.relative_link = !relative;
+
+    let actual = relink_tendril(&tendril, dry_run, false);
+
+    assert_eq!(actual.result, Ok(TendrilActionSuccess::AlreadyLinked));
+    assert_eq!(read_link(&setup.remote_file).unwrap(), exp_target);
+}
+
+#[rstest]
+fn link_to_wrong_target_is_relinked_to_local(
+    #[values(true, false)] force: bool,
+    #[values(true, false)] as_dir: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_local_nested_file();
+    setup.make_target_file();
+    setup.make_target_nested_file();
+    let (remote, local, target, mut tendril) = if as_dir {
+        (
+            &setup.remote_dir,
+            &setup.local_dir,
+            &setup.target_dir,
+            setup.dir_tendril(),
+        )
+    }
+    else {
+        (
+            &setup.remote_file,
+            &setup.local_file,
+            &setup.target_file,
+            setup.file_tendril(),
+        )
+    };
+    tendril.mode = TendrilMode::Link;
+    symlink_expose(remote, target, false, true).unwrap();
+    let exp_remote_type = remote.get_type();
+
+    let actual = relink_tendril(&tendril, false, force);
+
+    assert_eq!(
+        actual,
+        ActionLog::new(
+            local.get_type(),
+            exp_remote_type,
+            remote.clone(),
+            Ok(TendrilActionSuccess::Relinked {
+                old_target: target.clone(),
+                status: LinkStatus::WrongTarget,
+            }),
+        )
+    );
+    assert_eq!(&read_link(remote).unwrap(), local);
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn link_to_same_local_in_old_repo_is_relinked_with_old_repo_status(
+    #[case] old_repo_exists: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let old_repo = setup.parent_dir.join("OldRepo");
+    let old_target = old_repo.join("SomeApp/misc.txt");
+    create_dir_all(old_repo.join(".tendrils")).unwrap();
+    write(old_repo.join(".tendrils/tendrils.json"), "{}").unwrap();
+    create_dir_all(old_repo.join("SomeApp")).unwrap();
+    write(&old_target, "Old repo file contents").unwrap();
+    symlink_expose(&setup.remote_file, &old_target, false, true).unwrap();
+    if !old_repo_exists {
+        // Only recognized as an old repo since it is still listed
+        remove_dir_all(&old_repo).unwrap();
+        setup.make_global_cfg_file(
+            default_repo_path_as_json(&old_repo.to_string_lossy()),
+        );
+    }
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+
+    let actual = relink_tendril(&tendril, false, false);
+
+    assert_eq!(
+        actual.result,
+        Ok(TendrilActionSuccess::Relinked {
+            old_target,
+            status: LinkStatus::OldRepo,
+        }),
+    );
+    assert_eq!(read_link(&setup.remote_file).unwrap(), setup.local_file);
+    assert_eq!(setup.remote_file_contents(), "Local file contents");
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn link_to_same_local_in_missing_unknown_folder_is_broken() {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_global_cfg_file(default_repo_path_as_json("/SomeOtherRepo"));
+    let missing_dir = setup.parent_dir.join("Missing");
+    let missing_target = missing_dir.join("SomeApp/misc.txt");
+    create_dir_all(missing_dir.join("SomeApp")).unwrap();
+    write(&missing_target, "Missing file contents").unwrap();
+    symlink_expose(&setup.remote_file, &missing_target, false, true).unwrap();
+    remove_dir_all(&missing_dir).unwrap();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+
+    let actual = relink_tendril(&tendril, false, false);
+
+    assert_eq!(
+        actual.result,
+        Ok(TendrilActionSuccess::Relinked {
+            old_target: missing_target,
+            status: LinkStatus::Broken,
+        }),
+    );
+    assert_eq!(read_link(&setup.remote_file).unwrap(), setup.local_file);
+}
+
+#[test]
+fn link_to_same_local_in_existing_non_repo_folder_is_wrong_target() {
+    let setup = Setup::new();
+    setup.make_local_file();
+    let other_dir = setup.parent_dir.join("OtherDir");
+    let other_target = other_dir.join("SomeApp/misc.txt");
+    create_dir_all(other_dir.join("SomeApp")).unwrap();
+    write(&other_target, "Other file contents").unwrap();
+    symlink_expose(&setup.remote_file, &other_target, false, true).unwrap();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+
+    let actual = relink_tendril(&tendril, false, false);
+
+    assert_eq!(
+        actual.result,
+        Ok(TendrilActionSuccess::Relinked {
+            old_target: other_target,
+            status: LinkStatus::WrongTarget,
+        }),
+    );
+}
+
+#[test]
+fn broken_link_is_relinked_with_broken_status() {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_target_file();
+    symlink_expose(&setup.remote_file, &setup.target_file, false, true)
+        .unwrap();
+    std::fs::remove_file(&setup.target_file).unwrap();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+
+    let actual = relink_tendril(&tendril, false, false);
+
+    assert_eq!(
+        actual.result,
+        Ok(TendrilActionSuccess::Relinked {
+            old_target: setup.target_file.clone(),
+            status: LinkStatus::Broken,
+        }),
+    );
+    assert_eq!(setup.remote_file_contents(), "Local file contents");
+}
+
+#[rstest]
+fn dry_run_returns_relink_skipped_and_leaves_link_unchanged(
+    #[values(true, false)] force: bool,
+) {
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_target_file();
+    symlink_expose(&setup.remote_file, &setup.target_file, false, true)
+        .unwrap();
+    let mut tendril = setup.file_tendril();
+    tendril.mode = TendrilMode::Link;
+
+    let actual = relink_tendril(&tendril, true, force);
+
+    assert_eq!(
+        actual.result,
+        Ok(TendrilActionSuccess::RelinkSkipped {
+            old_target: setup.target_file.clone(),
+            status: LinkStatus::WrongTarget,
+        }),
+    );
+    assert_eq!(read_link(&setup.remote_file).unwrap(), setup.target_file);
+}
@@ -1,5 +1,5 @@
 use crate::RawTendril;
-use crate::enums::TendrilMode;
+use crate::enums::{NestedSymlinks, ReflinkMode, TendrilMode};
 
 pub struct SampleTendrils {}
 
@@ -9,8 +9,14 @@ impl SampleTendrils {
             RawTendril {
                 local: "settings.json".to_string(),
                 remote: "C:\\Users\\MyName\\AppData\\settings.json".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::DirOverwrite,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec![],
+                source: None,
             }
         ]
     }
@@ -32,8 +38,14 @@ impl SampleTendrils {
             RawTendril {
                 local: "SomeApp2/settings2.json".to_string(),
                 remote: "some/remote/path/settings2.json".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::DirMerge,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec!["win".to_string()],
+                source: None,
             }
         ]
     }
@@ -53,8 +65,14 @@ impl SampleTendrils {
             RawTendril {
                 local: "SomeApp/linkme.txt".to_string(),
                 remote: "some/remote/path3/linkme.txt".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::Link,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec!["mac".to_string()],
+                source: None,
             }
         ]
     }
@@ -74,8 +92,14 @@ impl SampleTendrils {
             RawTendril {
                 local: "SomeApp/localName.txt".to_string(),
                 remote: "some/remote/path4/remoteName.txt".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::DirOverwrite,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec!["mac".to_string(), "win".to_string()],
+                source: None,
             }
         ]
     }
@@ -95,8 +119,14 @@ impl SampleTendrils {
             RawTendril {
                 local: "misc.txt".to_string(),
                 remote: "some/remote/path5/misc.txt".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::DirOverwrite,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec!["mac".to_string()],
+                source: None,
             }
         ]
     }
@@ -116,14 +146,26 @@ impl SampleTendrils {
             RawTendril {
                 local: "SomeApp/misc.txt".to_string(),
                 remote: "some/remote/path6a/misc1.txt".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::DirOverwrite,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec!["mac".to_string(), "win".to_string()],
+                source: None,
             },
             RawTendril {
                 local: "SomeApp/misc.txt".to_string(),
                 remote: "some/remote/path6b/misc2.txt".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::DirOverwrite,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec!["mac".to_string(), "win".to_string()],
+                source: None,
             }            
         ]
     }
@@ -144,14 +186,26 @@ impl SampleTendrils {
             RawTendril {
                 local: "host-specific.txt".to_string(),
                 remote: "~/host1/specific/path/host1.txt".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::DirOverwrite,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec!["host1".to_string()],
+                source: None,
             },
             RawTendril {
                 local: "host-specific.txt".to_string(),
                 remote: "~/host2/specific/path/host2.txt".to_string(),
+                fallbacks: vec![],
                 mode: TendrilMode::Link,
+                relative_link: false,
+                copy_fallback: false,
+                reflink: ReflinkMode::Never,
+                nested_symlinks: NestedSymlinks::Follow,
                 profiles: vec!["host2".to_string()],
+                source: None,
             }
         ]
     }
@@ -0,0 +1,212 @@
+//! Tests that actions are set up and performed across several Tendrils repos.
+//! See [`super::tendril_action_tests`] for testing of the single repo setup.
+
+use crate::test_utils::Setup;
+use crate::{
+    ActionLog,
+    ActionMode,
+    CallbackUpdater,
+    CopyProgress,
+    FilterSpec,
+    GetTendrilsRepoError,
+    RawTendril,
+    SetupError,
+    TendrilActionSuccess,
+    TendrilsActor,
+    TendrilsApi,
+};
+use rstest::rstest;
+
+#[rstest]
+fn empty_tendrils_lists_return_empty_groups(
+    #[values(ActionMode::Push, ActionMode::Pull, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+    #[values(true, false)] force: bool,
+) {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_td_json_file(&[]);
+    setup2.make_td_json_file(&[]);
+
+    let actual = api.tendril_action_multi(
+        mode,
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        FilterSpec::new(),
+        dry_run,
+        force,
+    )
+    .unwrap();
+
+    assert_eq!(actual.len(), 2);
+    assert!(actual.iter().all(|r| r.reports.is_empty()));
+}
+
+#[rstest]
+fn performs_action_in_each_repo_and_groups_reports(
+    #[values(true, false)] force: bool,
+) {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_local_file();
+    setup2.make_local_file();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[setup2.file_tendril_raw()]);
+
+    let actual = api.tendril_action_multi(
+        ActionMode::Push,
+        &[setup2.uni_td_repo(), setup1.uni_td_repo()],
+        FilterSpec::new(),
+        false,
+        force,
+    )
+    .unwrap();
+
+    assert_eq!(actual.len(), 2);
+    assert_eq!(actual[0].td_repo, setup2.uni_td_repo());
+    assert_eq!(actual[1].td_repo, setup1.uni_td_repo());
+    for repo in actual.iter() {
+        assert_eq!(repo.reports.len(), 1);
+        assert_eq!(
+            repo.reports[0].log.as_ref().unwrap().result,
+            Ok(TendrilActionSuccess::New),
+        );
+    }
+    assert_eq!(setup1.remote_file_contents(), "Local file contents");
+    assert_eq!(setup2.remote_file_contents(), "Local file contents");
+}
+
+#[test]
+fn failure_in_one_repo_does_not_stop_other_repos() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup2.make_local_file();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[setup2.file_tendril_raw()]);
+
+    let actual = api.tendril_action_multi(
+        ActionMode::Push,
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        FilterSpec::new(),
+        false,
+        false,
+    )
+    .unwrap();
+
+    assert!(actual[0].reports[0].log.as_ref().unwrap().result.is_err());
+    assert_eq!(
+        actual[1].reports[0].log.as_ref().unwrap().result,
+        Ok(TendrilActionSuccess::New),
+    );
+    assert!(!setup1.remote_file.exists());
+    assert_eq!(setup2.remote_file_contents(), "Local file contents");
+}
+
+#[rstest]
+fn same_remote_in_two_repos_returns_remote_conflict_err_and_does_nothing(
+    #[values(ActionMode::Push, ActionMode::Pull, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] force: bool,
+) {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_local_file();
+    setup2.make_local_file();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[setup1.file_tendril_raw()]);
+
+    let actual = api.tendril_action_multi(
+        mode,
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        FilterSpec::new(),
+        false,
+        force,
+    );
+
+    assert_eq!(
+        actual,
+        Err(SetupError::RemoteConflict {
+            remote: setup1.remote_file.clone(),
+            first_repo: setup1.td_repo.clone(),
+            second_repo: setup2.td_repo.clone(),
+        }),
+    );
+    assert!(!setup1.remote_file.exists());
+}
+
+#[test]
+fn any_given_repo_invalid_returns_given_invalid_err_and_does_nothing() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_local_file();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+
+    let actual = api.tendril_action_multi(
+        ActionMode::Push,
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        FilterSpec::new(),
+        false,
+        false,
+    );
+
+    assert_eq!(
+        actual,
+        Err(SetupError::NoValidTendrilsRepo(
+            GetTendrilsRepoError::GivenInvalid { path: setup2.td_repo.clone() }
+        )),
+    );
+    assert!(!setup1.remote_file.exists());
+}
+
+#[test]
+fn updater_is_given_total_count_and_each_report_across_all_repos() {
+    let api = TendrilsActor {};
+    let setup1 = Setup::new();
+    let setup2 = Setup::new();
+    setup1.make_local_file();
+    setup2.make_local_file();
+    setup1.make_td_json_file(&[setup1.file_tendril_raw()]);
+    setup2.make_td_json_file(&[setup2.file_tendril_raw()]);
+    let mut count = 0;
+    let mut before_remotes = vec![];
+    let mut after_reports = vec![];
+    let mut progress_events = vec![];
+    let updater = CallbackUpdater::<_, _, _, ActionLog>::new(
+        |c| count = c,
+        |r: RawTendril| before_remotes.push(r.remote),
+        |r| after_reports.push(r),
+    )
+    .with_progress(|p| progress_events.push(p));
+
+    let actual = api.tendril_action_multi_updating(
+        updater,
+        ActionMode::Push,
+        &[setup1.uni_td_repo(), setup2.uni_td_repo()],
+        FilterSpec::new(),
+        false,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(count, 2);
+    assert_eq!(
+        before_remotes,
+        vec![
+            setup1.file_tendril_raw().remote,
+            setup2.file_tendril_raw().remote,
+        ],
+    );
+    let exp_reports: Vec<_> =
+        actual.into_iter().flat_map(|r| r.reports).collect();
+    assert_eq!(after_reports, exp_reports);
+    let starts = progress_events
+        .iter()
+        .filter(|p| matches!(p, CopyProgress::Start { .. }))
+        .count();
+    assert_eq!(starts, 2);
+}
@@ -11,13 +11,18 @@ use crate::test_utils::{
 use crate::{
     ActionLog,
     ActionMode,
+    CheckFinding,
+    CheckKind,
+    ConfigFormat,
     ConfigType,
     FilterSpec,
     FsoType,
     GetConfigError,
     GetTendrilsRepoError,
     Location,
+    ParseErrorLocation,
     SetupError,
+    Severity,
     TendrilActionError,
     TendrilActionSuccess,
     TendrilMode,
@@ -26,6 +31,7 @@ use crate::{
     TendrilsApi,
 };
 use rstest::rstest;
+use same_file::is_same_file;
 use serial_test::serial;
 use std::fs::write;
 use std::path::{MAIN_SEPARATOR_STR as SEP, PathBuf};
@@ -319,12 +325,240 @@ fn tendrils_json_invalid_returns_config_error(
     assert_eq!(
         actual,
         Err(SetupError::ConfigError(GetConfigError::ParseError {
-            cfg_type: ConfigType::Repo,
-            msg: "expected value at line 1 column 1".to_string(),
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: "expected value".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 1,
+                key_path: String::new(),
+                line_text: "I'm not JSON".to_string(),
+            }),
         })),
     );
 }
 
+#[rstest]
+fn conflicting_tendrils_returns_tendril_conflict_err(
+    #[values(ActionMode::Push, ActionMode::Out, ActionMode::Link)]
+    mode: ActionMode,
+    #[values(true, false)] dry_run: bool,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let t1 = setup.file_tendril_raw();
+    let mut t2 = t1.clone();
+    t2.local = "OtherApp/misc.txt".to_string();
+    setup.make_td_json_file(&[t1.clone(), t2.clone()]);
+    setup.make_local_file();
+    setup.make_remote_file();
+    let filter = FilterSpec::new();
+
+    let actual = api.tendril_action(
+        mode,
+        Some(&setup.uni_td_repo()),
+        filter,
+        dry_run,
+        false,
+    );
+
+    assert_eq!(
+        actual,
+        Err(SetupError::TendrilConflict(Box::new(CheckFinding {
+            kind: CheckKind::DuplicateRemote,
+            severity: Severity::Error,
+            outer: t1,
+            inner: t2,
+            outer_path: setup.remote_file.clone(),
+            inner_path: setup.remote_file.clone(),
+        }))),
+    );
+    assert_eq!(setup.local_file_contents(), "Local file contents");
+    assert_eq!(setup.remote_file_contents(), "Remote file contents");
+}
+
+#[test]
+fn duplicate_remote_with_different_locals_is_pulled_into_both() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let t1 = setup.file_tendril_raw();
+    let mut t2 = t1.clone();
+    t2.local = "OtherApp/misc.txt".to_string();
+    setup.make_td_json_file(&[t1, t2]);
+    setup.make_remote_file();
+
+    let actual = api.tendril_action(
+        ActionMode::Pull,
+        Some(&setup.uni_td_repo()),
+        FilterSpec::new(),
+        false,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(actual.len(), 2);
+    assert_eq!(setup.local_file_contents(), "Remote file contents");
+    assert_eq!(
+        std::fs::read_to_string(setup.td_repo.join("OtherApp/misc.txt"))
+            .unwrap(),
+        "Remote file contents",
+    );
+}
+
+#[rstest]
+fn hardlink_tendril_without_mode_filter_returns_mode_mismatch_when_copying(
+    #[values(ActionMode::Pull, ActionMode::Push)] mode: ActionMode,
+    #[values(true, false)] force: bool,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let mut given = setup.file_tendril_raw();
+    given.mode = TendrilMode::Hardlink;
+    setup.make_td_json_file(&[given.clone()]);
+    setup.make_local_file();
+    setup.make_parent_dir();
+    std::fs::hard_link(&setup.local_file, &setup.remote_file).unwrap();
+    let filter = FilterSpec::new();
+    assert!(filter.mode.is_none());
+
+    let actual = api.tendril_action(
+        mode,
+        Some(&setup.uni_td_repo()),
+        filter,
+        false,
+        force,
+    )
+    .unwrap();
+
+    assert_eq!(actual, vec![TendrilReport {
+        raw_tendril: given,
+        log: Ok(ActionLog::new(
+            Some(FsoType::File),
+            Some(FsoType::File),
+            setup.remote_file.clone(),
+            Err(TendrilActionError::ModeMismatch),
+        )),
+    }]);
+    assert!(is_same_file(&setup.local_file, &setup.remote_file).unwrap());
+    assert_eq!(setup.local_file_contents(), "Local file contents");
+}
+
+#[rstest]
+fn conflicting_tendrils_are_not_checked_if_forced(
+    #[values(ActionMode::Push, ActionMode::Out)] mode: ActionMode,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let mut t1 = setup.file_tendril_raw();
+    t1.local = "SomeApp/misc".to_string();
+    t1.remote = setup.remote_dir.to_string_lossy().to_string();
+    let mut t2 = t1.clone();
+    t2.local = "SomeApp/misc/nested.txt".to_string();
+    t2.remote = setup.parent_dir.join("nested.txt").to_string_lossy().to_string();
+    setup.make_td_json_file(&[t1, t2]);
+    setup.make_local_nested_file();
+
+    let actual = api.tendril_action(
+        mode,
+        Some(&setup.uni_td_repo()),
+        FilterSpec::new(),
+        false,
+        true,
+    )
+    .unwrap();
+
+    assert_eq!(actual.len(), 2);
+    assert_eq!(
+        setup.remote_nested_file_contents(),
+        "Local nested file contents",
+    );
+}
+
+#[rstest]
+fn nested_locals_returns_tendril_conflict_err_when_pushing(
+    #[values(ActionMode::Push, ActionMode::Out)] mode: ActionMode,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let mut t1 = setup.file_tendril_raw();
+    t1.local = "SomeApp/misc".to_string();
+    t1.remote = setup.remote_dir.to_string_lossy().to_string();
+    let mut t2 = t1.clone();
+    t2.local = "SomeApp/misc/nested.txt".to_string();
+    t2.remote = setup.parent_dir.join("nested.txt").to_string_lossy().to_string();
+    setup.make_td_json_file(&[t1, t2]);
+    setup.make_local_nested_file();
+
+    let actual = api.tendril_action(
+        mode,
+        Some(&setup.uni_td_repo()),
+        FilterSpec::new(),
+        false,
+        false,
+    );
+
+    match &actual {
+        Err(SetupError::TendrilConflict(f)) => {
+            assert_eq!(f.kind, CheckKind::NestedLocal);
+        }
+        _ => panic!("Expected a conflict error, got {:?}", actual),
+    }
+    assert!(!setup.remote_dir.exists());
+}
+
+#[rstest]
+fn conflicting_tendrils_filtered_out_does_not_return_conflict_err(
+    #[values(ActionMode::Push, ActionMode::Pull, ActionMode::Link)]
+    mode: ActionMode,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let t1 = setup.file_tendril_raw();
+    let mut t2 = t1.clone();
+    t2.local = "OtherApp/misc.txt".to_string();
+    setup.make_td_json_file(&[t1.clone(), t2]);
+    let mut filter = FilterSpec::new();
+    filter.locals = vec!["SomeApp/misc.txt".to_string()];
+
+    let actual = api.tendril_action(
+        mode,
+        Some(&setup.uni_td_repo()),
+        filter,
+        true,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].raw_tendril, t1);
+}
+
+#[rstest]
+fn tendrils_with_only_warnings_performs_action(
+    #[values(ActionMode::Push, ActionMode::Pull, ActionMode::Link)]
+    mode: ActionMode,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let t1 = setup.file_tendril_raw();
+    let mut t2 = t1.clone();
+    t2.profiles = vec!["p1".to_string()];
+    setup.make_td_json_file(&[t1.clone(), t2.clone()]);
+    let filter = FilterSpec::new();
+
+    let actual = api.tendril_action(
+        mode,
+        Some(&setup.uni_td_repo()),
+        filter,
+        true,
+        false,
+    )
+    .unwrap();
+
+    let actual_raws: Vec<_> =
+        actual.into_iter().map(|r| r.raw_tendril).collect();
+    assert_eq!(actual_raws, vec![t1, t2]);
+}
+
 #[rstest]
 fn tendrils_are_filtered_before_action(
     #[values(ActionMode::Push, ActionMode::Pull, ActionMode::Link)]
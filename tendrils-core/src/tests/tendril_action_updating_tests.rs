@@ -6,14 +6,20 @@ use crate::{
     ActionLog,
     ActionMode,
     CallbackUpdater,
+    CopyProgress,
     FilterSpec,
+    RawTendril,
+    ReflinkMode,
     FsoType,
     TendrilActionSuccess,
+    TendrilMode,
     TendrilReport,
     TendrilsActor,
     TendrilsApi,
+    UpdateHandler,
 };
 use rstest::rstest;
+use std::fs::write;
 
 #[rstest]
 fn empty_tendrils_list_returns_empty(
@@ -148,3 +154,146 @@ fn returns_result_after_each_operation(
     )
     .unwrap();
 }
+
+#[rstest]
+fn progress_is_reported_for_each_file_while_copying(
+    #[values(ActionMode::Push, ActionMode::Pull)] mode: ActionMode,
+    #[values(ReflinkMode::Never, ReflinkMode::Auto)] reflink: ReflinkMode,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    let (source_dir, source_nested_file) = if mode == ActionMode::Push {
+        setup.make_local_nested_file();
+        (&setup.local_dir, &setup.local_nested_file)
+    }
+    else {
+        setup.make_remote_nested_file();
+        (&setup.remote_dir, &setup.remote_nested_file)
+    };
+    let other_file = source_dir.join("other.txt");
+    write(&other_file, "Other file contents").unwrap();
+    let exp_bytes = source_nested_file.metadata().unwrap().len()
+        + other_file.metadata().unwrap().len();
+    let mut raw = setup.file_tendril_raw();
+    raw.local = "SomeApp/misc".to_string();
+    raw.remote = setup.remote_dir.to_string_lossy().to_string();
+    raw.reflink = reflink;
+    setup.make_td_json_file(&[raw]);
+
+    let mut progress_actual = vec![];
+    let updater = CallbackUpdater::<_, _, _, ActionLog>::new(|_| {}, |_| {}, |_| {})
+        .with_progress(|p| progress_actual.push(p));
+
+    api.tendril_action_updating(
+        updater,
+        mode,
+        Some(&setup.uni_td_repo()),
+        FilterSpec::new(),
+        false,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        progress_actual.first(),
+        Some(&CopyProgress::Start { files: 2, bytes: exp_bytes }),
+    );
+    assert_eq!(
+        progress_actual.last(),
+        Some(&CopyProgress::Bytes { copied: exp_bytes }),
+    );
+    let mut files_actual: Vec<_> = progress_actual
+        .iter()
+        .filter_map(|p| match p {
+            CopyProgress::File { path } => Some(path.clone()),
+            _ => None,
+        })
+        .collect();
+    files_actual.sort();
+    let mut exp_files = vec![source_nested_file.clone(), other_file];
+    exp_files.sort();
+    assert_eq!(files_actual, exp_files);
+}
+
+#[rstest]
+#[case(ActionMode::Push, true)]
+#[case(ActionMode::Pull, true)]
+#[case(ActionMode::Link, false)]
+#[case(ActionMode::Link, true)]
+fn progress_is_not_reported_on_dry_run_or_when_linking(
+    #[case] mode: ActionMode,
+    #[case] dry_run: bool,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_local_file();
+    setup.make_remote_file();
+    let mut raw = setup.file_tendril_raw();
+    if mode == ActionMode::Link {
+        raw.mode = TendrilMode::Link;
+    }
+    setup.make_td_json_file(&[raw]);
+
+    let mut progress_actual = vec![];
+    let updater = CallbackUpdater::<_, _, _, ActionLog>::new(|_| {}, |_| {}, |_| {})
+        .with_progress(|p| progress_actual.push(p));
+
+    api.tendril_action_updating(
+        updater,
+        mode,
+        Some(&setup.uni_td_repo()),
+        FilterSpec::new(),
+        dry_run,
+        true,
+    )
+    .unwrap();
+
+    assert!(progress_actual.is_empty());
+}
+
+/// Records any progress, without overriding
+/// [`UpdateHandler::wants_progress`].
+#[derive(Default)]
+struct DefaultProgressUpdater {
+    progress: Vec<CopyProgress>,
+}
+
+impl UpdateHandler<ActionLog> for &mut DefaultProgressUpdater {
+    fn count(&mut self, _: i32) {}
+
+    fn before(&mut self, _: RawTendril) {}
+
+    fn after(&mut self, _: TendrilReport<ActionLog>) {}
+
+    fn progress(&mut self, progress: CopyProgress) {
+        self.progress.push(progress);
+    }
+}
+
+#[rstest]
+fn progress_is_not_reported_if_updater_does_not_want_it(
+    #[values(ActionMode::Push, ActionMode::Pull)] mode: ActionMode,
+) {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_local_nested_file();
+    setup.make_remote_nested_file();
+    let mut raw = setup.file_tendril_raw();
+    raw.local = "SomeApp/misc".to_string();
+    raw.remote = setup.remote_dir.to_string_lossy().to_string();
+    setup.make_td_json_file(&[raw]);
+    let mut updater = DefaultProgressUpdater::default();
+
+    api.tendril_action_updating(
+        &mut updater,
+        mode,
+        Some(&setup.uni_td_repo()),
+        FilterSpec::new(),
+        false,
+        false,
+    )
+    .unwrap();
+
+    assert!(updater.progress.is_empty());
+    assert_eq!(setup.local_nested_file_contents(), setup.remote_nested_file_contents());
+}
@@ -0,0 +1,100 @@
+use crate::test_utils::{default_repo_path_as_json, Setup};
+use crate::{
+    ConfigIssue,
+    ConfigIssueKind,
+    ConfigFormat,
+    ConfigType,
+    GetConfigError,
+    GetTendrilsRepoError,
+    ParseErrorLocation,
+    SetupError,
+    Severity,
+    TendrilsActor,
+    TendrilsApi,
+};
+use serial_test::serial;
+use std::fs::write;
+
+#[test]
+fn given_td_repo_is_invalid_returns_no_valid_td_repo_err() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+
+    let actual = api.validate_config(Some(&setup.uni_td_repo()));
+
+    assert_eq!(
+        actual,
+        Err(SetupError::NoValidTendrilsRepo(GetTendrilsRepoError::GivenInvalid {
+            path: setup.td_repo.clone(),
+        })),
+    );
+}
+
+#[test]
+fn config_that_cannot_be_parsed_returns_parse_err() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "{\"tendrils\": []}").unwrap();
+
+    let actual = api.validate_config(Some(&setup.uni_td_repo()));
+
+    assert_eq!(
+        actual,
+        Err(SetupError::ConfigError(GetConfigError::ParseError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            msg: "invalid type: sequence, expected a map".to_string(),
+            loc: Some(ParseErrorLocation {
+                line: 1,
+                column: 14,
+                key_path: "tendrils".to_string(),
+                line_text: "{\"tendrils\": []}".to_string(),
+            }),
+        })),
+    );
+}
+
+#[test]
+fn given_td_repo_with_unknown_key_returns_issue() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "{\"tendrils\": {\"SomeApp\": {\"remotes\": \"r\", \"link_\": true}}}").unwrap();
+
+    let actual = api.validate_config(Some(&setup.uni_td_repo()));
+
+    assert_eq!(
+        actual,
+        Ok(vec![ConfigIssue {
+            kind: ConfigIssueKind::UnknownKey { suggestion: Some("link".to_string()) },
+            severity: Severity::Warning,
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            key_path: "tendrils.SomeApp.link_".to_string(),
+        }]),
+    );
+}
+
+#[test]
+#[serial(SERIAL_MUT_ENV_VARS)]
+fn given_td_repo_is_none_default_td_repo_is_valid_validates_default_td_repo() {
+    let api = TendrilsActor {};
+    let setup = Setup::new();
+    setup.set_home_dir();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "{\"tendrils\": {\"SomeApp\": {\"remotes\": []}}}").unwrap();
+    setup.make_global_cfg_file(
+        default_repo_path_as_json(&setup.td_repo.to_string_lossy()),
+    );
+
+    let actual = api.validate_config(None);
+
+    assert_eq!(
+        actual,
+        Ok(vec![ConfigIssue {
+            kind: ConfigIssueKind::EmptyRemotes,
+            severity: Severity::Warning,
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            key_path: "tendrils.SomeApp.remotes".to_string(),
+        }]),
+    );
+}
@@ -0,0 +1,406 @@
+use crate::config::{
+    deserialize_config,
+    find_include_files,
+    find_repo_config,
+    include_format,
+    join_key_path,
+    read_config_file,
+};
+use crate::tendril::Tendril;
+use crate::{ConfigFormat, ConfigType, GetConfigError, Severity, UniPath};
+use indexmap::IndexMap;
+use serde::de::{MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::path::Path;
+
+#[cfg(test)]
+mod tests;
+
+pub(crate) const CONFIG_KEYS: &[&str] = &[
+    "$schema",
+    "version",
+    "tendrils",
+    "auto-profiles",
+    "profile-groups",
+    "include",
+    "defaults",
+    "vars",
+    "var-priority",
+    "reflink",
+];
+pub(crate) const TENDRIL_SET_KEYS: &[&str] = &[
+    "remotes",
+    "dir-merge",
+    "link",
+    "relative-link",
+    "hardlink",
+    "copy-fallback",
+    "nested-symlinks",
+    "profiles",
+    "fallback",
+];
+pub(crate) const DEFAULTS_KEYS: &[&str] = &[
+    "dir-merge",
+    "link",
+    "relative-link",
+    "hardlink",
+    "copy-fallback",
+    "nested-symlinks",
+    "profiles",
+];
+pub(crate) const PROFILE_RULE_KEYS: &[&str] =
+    &["hostname", "os", "arch", "username", "env", "path"];
+
+/// The kind of problem found in a config file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigIssueKind {
+    /// The key is not part of the schema and is ignored, which usually
+    /// indicates a typo. Contains the most similar known key, if any.
+    UnknownKey { suggestion: Option<String> },
+
+    /// The `remotes` of a tendril set are an empty list, so the set does not
+    /// define any tendrils.
+    EmptyRemotes,
+
+    /// An entry in the `profiles` is empty or only whitespace.
+    EmptyProfile,
+
+    /// The same remote is listed more than once in a tendril set.
+    DuplicateRemote { remote: String },
+
+    /// The local path would be rejected with
+    /// [`InvalidTendrilError::InvalidLocal`](crate::InvalidTendrilError::InvalidLocal)
+    /// by every tendril using it.
+    InvalidLocal,
+}
+
+/// A problem in a config file that does not prevent it from being parsed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ConfigIssue {
+    pub kind: ConfigIssueKind,
+    pub severity: Severity,
+
+    /// The file that the problem is in.
+    pub cfg_type: ConfigType,
+
+    /// The path to the offending key or value, i.e.
+    /// `tendrils."SomeApp/file".remotes[2]`.
+    pub key_path: String,
+}
+
+impl ToString for ConfigIssue {
+    fn to_string(&self) -> String {
+        let loc = format!(
+            "at {} in the {} file",
+            self.key_path,
+            self.cfg_type.file_name(),
+        );
+        match &self.kind {
+            ConfigIssueKind::UnknownKey { suggestion: Some(s) } => {
+                format!("Unknown key {loc}, did you mean \"{s}\"?")
+            }
+            ConfigIssueKind::UnknownKey { suggestion: None } => {
+                format!("Unknown key {loc}")
+            }
+            ConfigIssueKind::EmptyRemotes => format!("Empty remotes list {loc}"),
+            ConfigIssueKind::EmptyProfile => format!("Empty profile name {loc}"),
+            ConfigIssueKind::DuplicateRemote { remote } => {
+                format!("Duplicate remote \"{remote}\" {loc}")
+            }
+            ConfigIssueKind::InvalidLocal => format!("Invalid local path {loc}"),
+        }
+    }
+}
+
+/// A JSON value that keeps the order of the keys in each object, so that the
+/// issues are reported in the order they appear in the file.
+enum JsonNode {
+    Object(IndexMap<String, JsonNode>),
+    List(Vec<JsonNode>),
+    String(String),
+    Other,
+}
+
+impl<'de> Deserialize<'de> for JsonNode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(JsonNodeVisitor)
+    }
+}
+
+struct JsonNodeVisitor;
+
+impl<'de> Visitor<'de> for JsonNodeVisitor {
+    type Value = JsonNode;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JsonNode, A::Error> {
+        let mut entries = IndexMap::new();
+        while let Some((k, v)) = map.next_entry::<String, JsonNode>()? {
+            entries.insert(k, v);
+        }
+        Ok(JsonNode::Object(entries))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JsonNode, A::Error> {
+        let mut items = vec![];
+        while let Some(v) = seq.next_element::<JsonNode>()? {
+            items.push(v);
+        }
+        Ok(JsonNode::List(items))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<JsonNode, E> {
+        Ok(JsonNode::String(v.to_string()))
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<JsonNode, E> {
+        Ok(JsonNode::Other)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<JsonNode, E> {
+        Ok(JsonNode::Other)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<JsonNode, E> {
+        Ok(JsonNode::Other)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<JsonNode, E> {
+        Ok(JsonNode::Other)
+    }
+
+    fn visit_unit<E>(self) -> Result<JsonNode, E> {
+        Ok(JsonNode::Other)
+    }
+}
+
+/// Checks the repo config file and each of its included files for
+/// unknown keys, empty `remotes` lists, empty profile names, duplicate remotes
+/// within a tendril set, and invalid local paths. The issues are in the order
+/// of the files, then in the order they appear within each file.
+pub(crate) fn validate_repo_config(
+    td_repo: &UniPath,
+) -> Result<Vec<ConfigIssue>, GetConfigError> {
+    let dot_td_dir = td_repo.inner().join(".tendrils");
+    let format = find_repo_config(&dot_td_dir)?;
+    let main_type = ConfigType::Repo(format);
+    let main = read_node(&dot_td_dir.join(main_type.file_name()), format, &main_type)?;
+    let include: Vec<String> = match &main {
+        JsonNode::Object(top) => match top.get("include") {
+            Some(JsonNode::List(v)) => v
+                .iter()
+                .filter_map(|i| match i {
+                    JsonNode::String(s) => Some(s.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+
+    let mut files = vec![(main_type, main)];
+    for path in find_include_files(&dot_td_dir, &include)? {
+        let cfg_type = ConfigType::RepoInclude(path.clone());
+        let node =
+            read_node(&dot_td_dir.join(&path), include_format(&path), &cfg_type)?;
+        files.push((cfg_type, node));
+    }
+
+    let mut issues = vec![];
+    for (cfg_type, node) in files.iter() {
+        let mut validator = Validator { cfg_type, issues: &mut issues };
+        validator.validate_config(node);
+    }
+    Ok(issues)
+}
+
+fn read_node(
+    path: &Path,
+    format: ConfigFormat,
+    cfg_type: &ConfigType,
+) -> Result<JsonNode, GetConfigError> {
+    let text = read_config_file(path, cfg_type)?;
+    deserialize_config(&text, format, cfg_type)
+}
+
+struct Validator<'a> {
+    cfg_type: &'a ConfigType,
+    issues: &'a mut Vec<ConfigIssue>,
+}
+
+impl Validator<'_> {
+    fn push(&mut self, kind: ConfigIssueKind, severity: Severity, key_path: String) {
+        self.issues.push(ConfigIssue {
+            kind,
+            severity,
+            cfg_type: self.cfg_type.clone(),
+            key_path,
+        });
+    }
+
+    fn validate_config(&mut self, node: &JsonNode) {
+        let JsonNode::Object(top) = node
+        else {
+            return;
+        };
+        self.check_keys(top, "", CONFIG_KEYS);
+
+        if let Some(JsonNode::Object(tendrils)) = top.get("tendrils") {
+            for (local, sets) in tendrils {
+                let local_path = join_key_path("tendrils", local);
+                if !Tendril::is_valid_local(Path::new(local)) {
+                    self.push(
+                        ConfigIssueKind::InvalidLocal,
+                        Severity::Error,
+                        local_path.clone(),
+                    );
+                }
+                match sets {
+                    JsonNode::List(sets) => {
+                        for (i, set) in sets.iter().enumerate() {
+                            self.validate_set(set, &format!("{local_path}[{i}]"));
+                        }
+                    }
+                    set => self.validate_set(set, &local_path),
+                }
+            }
+        }
+
+        if let Some(JsonNode::Object(defaults)) = top.get("defaults") {
+            self.check_keys(defaults, "defaults", DEFAULTS_KEYS);
+            if let Some(profiles) = defaults.get("profiles") {
+                self.check_profiles(profiles, "defaults.profiles");
+            }
+        }
+
+        if let Some(JsonNode::Object(auto_profiles)) = top.get("auto-profiles") {
+            for (name, rules) in auto_profiles {
+                let name_path = join_key_path("auto-profiles", name);
+                match rules {
+                    JsonNode::Object(rule) => {
+                        self.check_keys(rule, &name_path, PROFILE_RULE_KEYS);
+                    }
+                    JsonNode::List(rules) => {
+                        for (i, rule) in rules.iter().enumerate() {
+                            if let JsonNode::Object(rule) = rule {
+                                let rule_path = format!("{name_path}[{i}]");
+                                self.check_keys(rule, &rule_path, PROFILE_RULE_KEYS);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn validate_set(&mut self, set: &JsonNode, set_path: &str) {
+        let JsonNode::Object(set) = set
+        else {
+            return;
+        };
+        self.check_keys(set, set_path, TENDRIL_SET_KEYS);
+
+        let remotes_path = join_key_path(set_path, "remotes");
+        match set.get("remotes") {
+            Some(JsonNode::List(remotes)) if remotes.is_empty() => {
+                self.push(ConfigIssueKind::EmptyRemotes, Severity::Warning, remotes_path);
+            }
+            Some(JsonNode::List(remotes)) => {
+                for (i, remote) in remotes.iter().enumerate() {
+                    let JsonNode::String(remote) = remote
+                    else {
+                        continue;
+                    };
+                    let is_dup = remotes[..i]
+                        .iter()
+                        .any(|r| matches!(r, JsonNode::String(r) if r == remote));
+                    if is_dup {
+                        self.push(
+                            ConfigIssueKind::DuplicateRemote { remote: remote.clone() },
+                            Severity::Warning,
+                            format!("{remotes_path}[{i}]"),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(profiles) = set.get("profiles") {
+            self.check_profiles(profiles, &join_key_path(set_path, "profiles"));
+        }
+    }
+
+    fn check_keys(
+        &mut self,
+        object: &IndexMap<String, JsonNode>,
+        object_path: &str,
+        known: &[&str],
+    ) {
+        for key in object.keys().filter(|k| !known.contains(&k.as_str())) {
+            self.push(
+                ConfigIssueKind::UnknownKey { suggestion: suggest_key(key, known) },
+                Severity::Warning,
+                join_key_path(object_path, key),
+            );
+        }
+    }
+
+    fn check_profiles(&mut self, profiles: &JsonNode, profiles_path: &str) {
+        match profiles {
+            JsonNode::String(p) if p.trim().is_empty() => {
+                self.push(
+                    ConfigIssueKind::EmptyProfile,
+                    Severity::Warning,
+                    profiles_path.to_string(),
+                );
+            }
+            JsonNode::List(profiles) => {
+                for (i, p) in profiles.iter().enumerate() {
+                    if matches!(p, JsonNode::String(p) if p.trim().is_empty()) {
+                        self.push(
+                            ConfigIssueKind::EmptyProfile,
+                            Severity::Warning,
+                            format!("{profiles_path}[{i}]"),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the `known` key that is most similar to the unknown `key`, if
+/// any are within a couple of edits of it. Case and `_` versus `-` are
+/// ignored.
+fn suggest_key(key: &str, known: &[&str]) -> Option<String> {
+    let normalized = key.to_lowercase().replace('_', "-");
+    known
+        .iter()
+        .map(|k| (edit_distance(&normalized, k), k))
+        .filter(|(d, _)| *d <= 2 && *d < normalized.chars().count())
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k.to_string())
+}
+
+/// The number of single character insertions, deletions or substitutions
+/// needed to change `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr.push(substitution.min(prev[j + 1] + 1).min(curr[j] + 1));
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
@@ -0,0 +1 @@
+mod validate_repo_config_tests;
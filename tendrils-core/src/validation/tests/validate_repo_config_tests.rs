@@ -0,0 +1,324 @@
+use crate::test_utils::Setup;
+use crate::validation::validate_repo_config;
+use crate::{
+    ConfigFormat,
+    ConfigIssue,
+    ConfigIssueKind,
+    ConfigType,
+    GetConfigError,
+    Severity,
+};
+use rstest::rstest;
+use std::fs::{create_dir_all, write};
+use std::path::PathBuf;
+
+fn issue(kind: ConfigIssueKind, severity: Severity, key_path: &str) -> ConfigIssue {
+    ConfigIssue {
+        kind,
+        severity,
+        cfg_type: ConfigType::Repo(ConfigFormat::Json),
+        key_path: key_path.to_string(),
+    }
+}
+
+fn unknown_key(key_path: &str, suggestion: Option<&str>) -> ConfigIssue {
+    issue(
+        ConfigIssueKind::UnknownKey { suggestion: suggestion.map(String::from) },
+        Severity::Warning,
+        key_path,
+    )
+}
+
+#[test]
+fn valid_config_returns_empty() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{
+        "$schema": "./tendrils.schema.json",
+        "tendrils": {
+            "SomeApp/file": [
+                {"remotes": ["r1", "r2"], "dir-merge": true, "profiles": "p1"},
+                {"remotes": "r3", "link": true, "fallback": false}
+            ]
+        },
+        "defaults": {"dir-merge": false, "link": false, "profiles": []},
+        "auto-profiles": {"p1": [{"os": "linux"}, {"hostname": "h", "path": "/p"}]},
+        "profile-groups": {},
+        "include": [],
+        "vars": {},
+        "var-priority": "repo"
+    }"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    assert_eq!(actual, Ok(vec![]));
+}
+
+#[test]
+fn no_tendrils_json_file_returns_io_not_found_error() {
+    let setup = Setup::new();
+    setup.make_td_repo_dir();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::IoError {
+            cfg_type: ConfigType::Repo(ConfigFormat::Json),
+            kind: std::io::ErrorKind::NotFound,
+        }),
+    );
+}
+
+#[rstest]
+#[case("dir_merge", Some("dir-merge"))]
+#[case("Dir-Merge", Some("dir-merge"))]
+#[case("profile", Some("profiles"))]
+#[case("remote", Some("remotes"))]
+#[case("lnik", Some("link"))]
+#[case("somethingElse", None)]
+fn unknown_key_in_tendril_set_returns_warning_with_suggestion(
+    #[case] key: &str,
+    #[case] suggestion: Option<&str>,
+) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = format!(
+        r#"{{"tendrils": {{"SomeApp/file": {{"remotes": "r1", "{key}": true}}}}}}"#
+    );
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    let key_path = format!("tendrils.\"SomeApp/file\".{key}");
+    assert_eq!(actual, Ok(vec![unknown_key(&key_path, suggestion)]));
+}
+
+#[test]
+fn unknown_keys_in_each_section_are_returned_in_file_order() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{
+        "tendril": {},
+        "tendrils": {
+            "SomeApp/file": [{"remotes": "r1"}, {"remotes": "r2", "zzz": 1}],
+            "OtherApp": {"remotes": "r3", "aaa": 1}
+        },
+        "defaults": {"fallback": true},
+        "auto-profiles": {"p1": {"host": "h"}, "p2": [{}, {"oss": "linux"}]}
+    }"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Ok(vec![
+            unknown_key("tendril", Some("tendrils")),
+            unknown_key("tendrils.\"SomeApp/file\"[1].zzz", None),
+            unknown_key("tendrils.OtherApp.aaa", None),
+            unknown_key("defaults.fallback", None),
+            unknown_key("auto-profiles.p1.host", Some("os")),
+            unknown_key("auto-profiles.p2[1].oss", Some("os")),
+        ]),
+    );
+}
+
+#[test]
+fn empty_remotes_list_returns_warning() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{"tendrils": {"SomeApp/file": [{"remotes": "r1"}, {"remotes": []}]}}"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Ok(vec![issue(
+            ConfigIssueKind::EmptyRemotes,
+            Severity::Warning,
+            "tendrils.\"SomeApp/file\"[1].remotes",
+        )]),
+    );
+}
+
+#[test]
+fn duplicate_remotes_in_set_returns_warning_for_each_repeat() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{"tendrils": {
+        "SomeApp/file": {"remotes": ["r1", "r2", "r1", "R2", "r1"]},
+        "OtherApp/file": {"remotes": "r1"}
+    }}"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    let dup = || ConfigIssueKind::DuplicateRemote { remote: "r1".to_string() };
+    assert_eq!(
+        actual,
+        Ok(vec![
+            issue(dup(), Severity::Warning, "tendrils.\"SomeApp/file\".remotes[2]"),
+            issue(dup(), Severity::Warning, "tendrils.\"SomeApp/file\".remotes[4]"),
+        ]),
+    );
+}
+
+#[test]
+fn same_remote_in_different_sets_is_not_reported() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{"tendrils": {"SomeApp/file": [{"remotes": "r1"}, {"remotes": ["r1"]}]}}"#;
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    assert_eq!(actual, Ok(vec![]));
+}
+
+#[rstest]
+#[case(r#""""#, "")]
+#[case(r#""  ""#, "")]
+#[case(r#"["p1", "", "p2"]"#, "[1]")]
+fn empty_profile_returns_warning(#[case] profiles: &str, #[case] suffix: &str) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = format!(
+        r#"{{
+            "tendrils": {{"SomeApp/file": {{"remotes": "r1", "profiles": {profiles}}}}},
+            "defaults": {{"profiles": {profiles}}}
+        }}"#
+    );
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Ok(vec![
+            issue(
+                ConfigIssueKind::EmptyProfile,
+                Severity::Warning,
+                &format!("tendrils.\"SomeApp/file\".profiles{suffix}"),
+            ),
+            issue(
+                ConfigIssueKind::EmptyProfile,
+                Severity::Warning,
+                &format!("defaults.profiles{suffix}"),
+            ),
+        ]),
+    );
+}
+
+#[rstest]
+#[case("")]
+#[case("..")]
+#[case("SomeApp/../file")]
+#[case(".tendrils")]
+#[case(".Tendrils/file")]
+#[case("/")]
+fn invalid_local_returns_error(#[case] local: &str) {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = format!(r#"{{"tendrils": {{"{local}": {{"remotes": "r1"}}}}}}"#);
+    write(&setup.td_json_file, json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    let key_path = format!("tendrils.{local:?}");
+    assert_eq!(
+        actual,
+        Ok(vec![issue(ConfigIssueKind::InvalidLocal, Severity::Error, &key_path)]),
+    );
+}
+
+#[test]
+fn issues_in_included_files_are_returned_after_main_file() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let json = r#"{"include": ["extra.json"], "tendrils": {"A": {"remotes": [], "x": 1}}}"#;
+    write(&setup.td_json_file, json).unwrap();
+    let include_json = r#"{"tendrils": {"B": {"remotes": "r1", "profile": "p"}}}"#;
+    write(setup.dot_td_dir.join("extra.json"), include_json).unwrap();
+    create_dir_all(setup.dot_td_dir.join("tendrils.d")).unwrap();
+    let dir_json = r#"{"tendrils": {"C": {"remotes": "r1", "remotes2": []}}}"#;
+    write(setup.dot_td_dir.join("tendrils.d/more.json"), dir_json).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    let extra = ConfigType::RepoInclude(PathBuf::from("extra.json"));
+    let more = ConfigType::RepoInclude(PathBuf::from("tendrils.d").join("more.json"));
+    assert_eq!(
+        actual,
+        Ok(vec![
+            unknown_key("tendrils.A.x", None),
+            issue(ConfigIssueKind::EmptyRemotes, Severity::Warning, "tendrils.A.remotes"),
+            ConfigIssue { cfg_type: extra, ..unknown_key("tendrils.B.profile", Some("profiles")) },
+            ConfigIssue { cfg_type: more, ..unknown_key("tendrils.C.remotes2", Some("remotes")) },
+        ]),
+    );
+}
+
+#[test]
+fn issue_to_string_includes_key_path_and_file() {
+    let mut actual = unknown_key("tendrils.A.dir_merge", Some("dir-merge"));
+    actual.cfg_type = ConfigType::RepoInclude(PathBuf::from("extra.json"));
+
+    assert_eq!(
+        actual.to_string(),
+        "Unknown key at tendrils.A.dir_merge in the extra.json file, did you \
+        mean \"dir-merge\"?",
+    );
+}
+
+#[test]
+fn toml_config_returns_issues_with_toml_cfg_type() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    let toml = r#"
+        [tendrils."SomeApp/file"]
+        remotes = []
+        dir_merge = true
+    "#;
+    write(setup.dot_td_dir.join("tendrils.toml"), toml).unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    let toml_type = ConfigType::Repo(ConfigFormat::Toml);
+    assert_eq!(
+        actual,
+        Ok(vec![
+            ConfigIssue {
+                cfg_type: toml_type.clone(),
+                ..unknown_key("tendrils.\"SomeApp/file\".dir_merge", Some("dir-merge"))
+            },
+            ConfigIssue {
+                cfg_type: toml_type,
+                ..issue(
+                    ConfigIssueKind::EmptyRemotes,
+                    Severity::Warning,
+                    "tendrils.\"SomeApp/file\".remotes",
+                )
+            },
+        ]),
+    );
+}
+
+#[test]
+fn multiple_repo_config_files_returns_multiple_repo_configs_err() {
+    let setup = Setup::new();
+    setup.make_dot_td_dir();
+    write(&setup.td_json_file, "{}").unwrap();
+    write(setup.dot_td_dir.join("tendrils.jsonc"), "{}").unwrap();
+
+    let actual = validate_repo_config(&setup.uni_td_repo());
+
+    assert_eq!(
+        actual,
+        Err(GetConfigError::MultipleRepoConfigs {
+            formats: vec![ConfigFormat::Json, ConfigFormat::Jsonc],
+        }),
+    );
+}